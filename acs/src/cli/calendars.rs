@@ -0,0 +1,320 @@
+// CLI calendar commands: calendar list, show, add, remove
+
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::Client;
+use serde_json::Value;
+
+use super::{base_url, connection_error_message};
+use crate::models::calendar::{parse_ical, DateRange, Window};
+use crate::models::Calendar;
+
+/// Helper to handle reqwest errors and produce a user-friendly connection error.
+fn handle_request_error(err: reqwest::Error, host: &str, port: u16) -> anyhow::Error {
+    if err.is_connect() || err.is_timeout() {
+        anyhow::anyhow!("{}", connection_error_message(host, port))
+    } else {
+        anyhow::anyhow!("Request failed: {}", err)
+    }
+}
+
+/// Build the calendar of `acs calendar add` from its `--range`, `--window`
+/// and `--ics` options.
+pub fn build_calendar(
+    name: &str,
+    description: Option<&str>,
+    timezone: Option<&str>,
+    ranges: &[String],
+    windows: &[String],
+    ics_files: &[String],
+) -> anyhow::Result<Calendar> {
+    let mut calendar = Calendar {
+        name: name.to_string(),
+        description: description.map(|s| s.to_string()),
+        tz: timezone.map(|s| s.to_string()),
+        ranges: ranges
+            .iter()
+            .map(|r| parse_range(r, timezone))
+            .collect::<anyhow::Result<_>>()?,
+        windows: windows
+            .iter()
+            .map(|w| parse_window(w))
+            .collect::<anyhow::Result<_>>()?,
+    };
+    for path in ics_files {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", path, e))?;
+        calendar.ranges.extend(parse_ical(&content, timezone)?);
+    }
+    Ok(calendar)
+}
+
+/// Parse a `--range`: `START/END` as whole days, both included, or as times
+/// accepted by `--at`.
+fn parse_range(s: &str, timezone: Option<&str>) -> anyhow::Result<DateRange> {
+    let (start, end) = s
+        .split_once('/')
+        .ok_or_else(|| anyhow::anyhow!("Invalid range '{}': expected START/END", s))?;
+    let days = (
+        NaiveDate::parse_from_str(start, "%Y-%m-%d"),
+        NaiveDate::parse_from_str(end, "%Y-%m-%d"),
+    );
+    let (start, end) = match days {
+        (Ok(first), Ok(last)) => (
+            start_of_day(first, timezone)?,
+            start_of_day(last + chrono::Days::new(1), timezone)?,
+        ),
+        _ => (
+            super::jobs::parse_at(start, timezone)?,
+            super::jobs::parse_at(end, timezone)?,
+        ),
+    };
+    Ok(DateRange {
+        start,
+        end,
+        label: None,
+    })
+}
+
+/// Midnight at the start of `day` in `timezone` (UTC if unset).
+fn start_of_day(day: NaiveDate, timezone: Option<&str>) -> anyhow::Result<DateTime<Utc>> {
+    super::jobs::parse_at(&format!("{}T00:00", day), timezone)
+}
+
+/// Parse a `--window`: a cron expression followed by a duration.
+fn parse_window(s: &str) -> anyhow::Result<Window> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid window '{}': expected a cron expression and a duration, e.g. \"0 0 * * SAT 2d\"",
+            s
+        )
+    };
+    let (cron, duration) = s.trim().rsplit_once(' ').ok_or_else(invalid)?;
+    Ok(Window {
+        cron: cron.trim().to_string(),
+        duration: duration.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+        label: None,
+    })
+}
+
+/// acs calendar list
+pub async fn cmd_list(host: &str, port: u16, json: bool) -> anyhow::Result<()> {
+    let client = Client::new();
+    let url = format!("{}/api/calendars", base_url(host, port));
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let calendars: Vec<Calendar> = serde_json::from_value(body)?;
+    if calendars.is_empty() {
+        println!("No calendars found.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<20}{:<22}{:<8}{:<9}DESCRIPTION",
+        "NAME", "TIMEZONE", "RANGES", "WINDOWS"
+    );
+    for calendar in calendars {
+        println!(
+            "{:<20}{:<22}{:<8}{:<9}{}",
+            calendar.name,
+            calendar.tz.as_deref().unwrap_or("UTC"),
+            calendar.ranges.len(),
+            calendar.windows.len(),
+            calendar.description.as_deref().unwrap_or("")
+        );
+    }
+
+    Ok(())
+}
+
+/// acs calendar show
+pub async fn cmd_show(host: &str, port: u16, name: &str, json: bool) -> anyhow::Result<()> {
+    let client = Client::new();
+    let url = format!("{}/api/calendars/{}", base_url(host, port), name);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let calendar: Calendar = serde_json::from_value(body)?;
+    println!("Calendar: {}", calendar.name);
+    if let Some(ref description) = calendar.description {
+        println!("  Description: {}", description);
+    }
+    println!("  Timezone:    {}", calendar.tz.as_deref().unwrap_or("UTC"));
+    for range in &calendar.ranges {
+        println!(
+            "  Range:       {} to {}{}",
+            range.start.to_rfc3339(),
+            range.end.to_rfc3339(),
+            range
+                .label
+                .as_ref()
+                .map(|l| format!(" ({})", l))
+                .unwrap_or_default()
+        );
+    }
+    for window in &calendar.windows {
+        println!(
+            "  Window:      '{}' for {}{}",
+            window.cron,
+            window.duration,
+            window
+                .label
+                .as_ref()
+                .map(|l| format!(" ({})", l))
+                .unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+/// acs calendar add
+pub async fn cmd_add(host: &str, port: u16, calendar: &Calendar) -> anyhow::Result<()> {
+    let client = Client::new();
+    let url = format!("{}/api/calendars", base_url(host, port));
+
+    let response = client
+        .post(&url)
+        .json(calendar)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if status.is_success() {
+        println!("Calendar '{}' created successfully.", calendar.name);
+        println!("  Ranges:  {}", calendar.ranges.len());
+        println!("  Windows: {}", calendar.windows.len());
+    } else {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// acs calendar remove
+pub async fn cmd_remove(host: &str, port: u16, name: &str) -> anyhow::Result<()> {
+    let client = Client::new();
+    let url = format!("{}/api/calendars/{}", base_url(host, port), name);
+
+    let response = client
+        .delete(&url)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+
+    if status.as_u16() == 204 {
+        println!("Calendar '{}' removed.", name);
+    } else {
+        let body: Value = response
+            .json()
+            .await
+            .unwrap_or_else(|_| serde_json::json!({"message": "Unknown error"}));
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_build_calendar_ranges_and_windows() {
+        let calendar = build_calendar(
+            "holidays",
+            None,
+            Some("Europe/Berlin"),
+            &[
+                "2025-12-24/2025-12-26".to_string(),
+                "2025-07-01T22:00/2025-07-02T02:00".to_string(),
+            ],
+            &["0 0 * * SAT 2d".to_string()],
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            calendar.ranges[0].start,
+            Utc.with_ymd_and_hms(2025, 12, 23, 23, 0, 0).unwrap()
+        );
+        assert_eq!(
+            calendar.ranges[0].end,
+            Utc.with_ymd_and_hms(2025, 12, 26, 23, 0, 0).unwrap()
+        );
+        assert_eq!(
+            calendar.ranges[1].start,
+            Utc.with_ymd_and_hms(2025, 7, 1, 20, 0, 0).unwrap()
+        );
+        assert_eq!(calendar.windows[0].cron, "0 0 * * SAT");
+        assert_eq!(calendar.windows[0].duration.as_secs(), 2 * 86_400);
+    }
+
+    #[test]
+    fn test_build_calendar_rejects_malformed_options() {
+        let build = |ranges: &[&str], windows: &[&str]| {
+            let ranges: Vec<String> = ranges.iter().map(|s| s.to_string()).collect();
+            let windows: Vec<String> = windows.iter().map(|s| s.to_string()).collect();
+            build_calendar("c", None, None, &ranges, &windows, &[])
+        };
+        assert!(build(&["2025-12-24"], &[]).is_err());
+        assert!(build(&["yesterday/today"], &[]).is_err());
+        assert!(build(&[], &["0 0 * * SAT"]).is_err());
+        assert!(build(&[], &["weekend"]).is_err());
+    }
+}
@@ -1,14 +1,14 @@
-// CLI job commands: add, remove, list, enable, disable, trigger
+// CLI job commands: add, remove, list, enable, disable, trigger, kill, queue
 
 use std::io::{self, BufRead, Write};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use reqwest::Client;
 use serde_json::Value;
 
-use super::{base_url, connection_error_message, parse_env_vars};
+use super::{base_url, connection_error_message, parse_env_vars, parse_labels};
 use crate::models::job::ExecutionType;
-use crate::models::NewJob;
+use crate::models::{CatchupPolicy, ConcurrencyPolicy, Interval, NewJob, QueuedRun, Schedule};
 
 /// Helper to handle reqwest errors and produce a user-friendly connection error.
 fn handle_request_error(err: reqwest::Error, host: &str, port: u16) -> anyhow::Error {
@@ -50,20 +50,96 @@ fn format_relative_time(dt: &DateTime<Utc>) -> String {
     }
 }
 
+/// Build the schedule of `acs add` from its mutually exclusive `--schedule`,
+/// `--every`, `--at` and `--manual` options.
+pub fn build_schedule(
+    cron: Option<&str>,
+    every: Option<Interval>,
+    at: Option<&str>,
+    manual: bool,
+    timezone: Option<&str>,
+) -> anyhow::Result<Schedule> {
+    if timezone.is_some() && cron.is_none() && at.is_none() {
+        anyhow::bail!("--timezone only applies to --schedule and --at");
+    }
+    match (cron, every, at, manual) {
+        (Some(expr), None, None, false) => Ok(Schedule::Cron {
+            expr: expr.to_string(),
+            tz: timezone.map(|s| s.to_string()),
+        }),
+        (None, Some(interval), None, false) => Ok(Schedule::Every {
+            interval,
+            anchor: None,
+        }),
+        (None, None, Some(at), false) => Ok(Schedule::At {
+            timestamp: parse_at(at, timezone)?,
+        }),
+        (None, None, None, true) => Ok(Schedule::Manual),
+        _ => anyhow::bail!("Specify exactly one of --schedule, --every, --at or --manual"),
+    }
+}
+
+/// Parse the `--at` time: RFC 3339, or a local `YYYY-MM-DDTHH:MM[:SS]` in
+/// `timezone` (UTC if unset).
+pub(crate) fn parse_at(s: &str, timezone: Option<&str>) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+    .ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid time '{}': expected RFC 3339 or YYYY-MM-DDTHH:MM[:SS]",
+            s
+        )
+    })?;
+    let tz: chrono_tz::Tz = match timezone {
+        Some(tz) => tz
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid timezone '{}': {}", tz, e))?,
+        None => chrono_tz::UTC,
+    };
+    naive
+        .and_local_timezone(tz)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| anyhow::anyhow!("Time '{}' does not exist in {}", s, tz))
+}
+
 /// acs add
 #[allow(clippy::too_many_arguments)]
 pub async fn cmd_add(
     host: &str,
     port: u16,
     name: &str,
-    schedule: &str,
+    schedule: Schedule,
+    jitter_secs: u64,
     cmd: Option<&str>,
     script: Option<&str>,
-    timezone: Option<&str>,
     working_dir: Option<&str>,
     env: &[String],
     disabled: bool,
     log_env: bool,
+    concurrency: Option<ConcurrencyPolicy>,
+    catchup: Option<CatchupPolicy>,
+    include_calendars: &[String],
+    exclude_calendars: &[String],
+    start_at: Option<DateTime<Utc>>,
+    end_at: Option<DateTime<Utc>>,
+    max_runs: Option<u32>,
+    on_success: &[String],
+    on_failure: &[String],
+    forward_output_lines: Option<usize>,
+    pool: Option<&str>,
+    priority: i32,
+    tags: &[String],
+    labels: &[String],
 ) -> anyhow::Result<()> {
     let execution = match (cmd, script) {
         (Some(c), None) => ExecutionType::ShellCommand(c.to_string()),
@@ -79,17 +155,35 @@ pub async fn cmd_add(
         let parsed = parse_env_vars(env).map_err(|e| anyhow::anyhow!(e))?;
         Some(parsed)
     };
+    let labels = parse_labels(labels).map_err(|e| anyhow::anyhow!(e))?;
 
     let new_job = NewJob {
         name: name.to_string(),
-        schedule: schedule.to_string(),
+        schedule: schedule.clone(),
         execution,
         enabled: !disabled,
-        timezone: timezone.map(|s| s.to_string()),
+        jitter_secs,
+        timezone: None,
         working_dir: working_dir.map(|s| s.to_string()),
         env_vars,
         timeout_secs: 0,
         log_environment: log_env,
+        concurrency_policy: concurrency.unwrap_or_default(),
+        retry: Default::default(),
+        catchup: catchup.unwrap_or_default(),
+        include_calendars: include_calendars.to_vec(),
+        exclude_calendars: exclude_calendars.to_vec(),
+        start_at,
+        end_at,
+        max_runs,
+        on_success: on_success.to_vec(),
+        on_failure: on_failure.to_vec(),
+        forward_output_lines,
+        pool: pool.map(|s| s.to_string()),
+        priority,
+        tags: tags.to_vec(),
+        labels,
+        log_retention: Default::default(),
     };
 
     let client = Client::new();
@@ -113,6 +207,18 @@ pub async fn cmd_add(
         println!("  ID:       {}", body["id"].as_str().unwrap_or("unknown"));
         println!("  Schedule: {}", schedule);
         println!("  Enabled:  {}", !disabled);
+        if let Some(start_at) = start_at {
+            println!("  Starts:   {}", start_at.to_rfc3339());
+        }
+        if let Some(end_at) = end_at {
+            println!("  Ends:     {}", end_at.to_rfc3339());
+        }
+        if let Some(max_runs) = max_runs {
+            println!("  Max runs: {}", max_runs);
+        }
+        if !tags.is_empty() {
+            println!("  Tags:     {}", tags.join(", "));
+        }
     } else {
         let message = body["message"].as_str().unwrap_or("Unknown error");
         eprintln!("Error: {}", message);
@@ -163,25 +269,40 @@ pub async fn cmd_remove(host: &str, port: u16, job: &str, yes: bool) -> anyhow::
     Ok(())
 }
 
+/// Combine `--tag` filters and a `--selector` into one selector string.
+pub fn build_selector(tags: &[String], selector: Option<&str>) -> String {
+    tags.iter()
+        .map(|tag| format!("tag={}", tag))
+        .chain(selector.map(str::to_string))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 /// acs list
 pub async fn cmd_list(
     host: &str,
     port: u16,
     enabled: bool,
     disabled: bool,
+    selector: &str,
     json: bool,
 ) -> anyhow::Result<()> {
     let client = Client::new();
-    let mut url = format!("{}/api/jobs", base_url(host, port));
+    let url = format!("{}/api/jobs", base_url(host, port));
 
+    let mut query = Vec::new();
     if enabled {
-        url.push_str("?enabled=true");
+        query.push(("enabled", "true"));
     } else if disabled {
-        url.push_str("?enabled=false");
+        query.push(("enabled", "false"));
+    }
+    if !selector.is_empty() {
+        query.push(("selector", selector));
     }
 
     let response = client
         .get(&url)
+        .query(&query)
         .send()
         .await
         .map_err(|e| handle_request_error(e, host, port))?;
@@ -220,7 +341,9 @@ pub async fn cmd_list(
 
     for job in jobs {
         let name = job["name"].as_str().unwrap_or("?");
-        let schedule = job["schedule"].as_str().unwrap_or("?");
+        let schedule = serde_json::from_value::<Schedule>(job["schedule"].clone())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|_| "?".to_string());
         let job_enabled = job["enabled"].as_bool().unwrap_or(false);
 
         let last_run = match job["last_run_at"].as_str() {
@@ -263,7 +386,7 @@ pub async fn cmd_list(
             if schedule.len() > 15 {
                 format!("{}...", &schedule[..12])
             } else {
-                schedule.to_string()
+                schedule
             },
             job_enabled,
             last_run,
@@ -333,6 +456,65 @@ pub async fn cmd_disable(host: &str, port: u16, job: &str) -> anyhow::Result<()>
     Ok(())
 }
 
+/// acs enable/disable --selector: set `enabled` on every matching job.
+pub async fn cmd_set_enabled_matching(
+    host: &str,
+    port: u16,
+    selector: &str,
+    enabled: bool,
+) -> anyhow::Result<()> {
+    let action = if enabled { "enable" } else { "disable" };
+    let client = Client::new();
+    let url = format!("{}/api/jobs/bulk", base_url(host, port));
+
+    let response = client
+        .post(&url)
+        .json(&serde_json::json!({ "action": action, "selector": selector }))
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    let empty_vec = vec![];
+    let results = body["results"].as_array().unwrap_or(&empty_vec);
+    if results.is_empty() {
+        println!("No jobs match '{}'.", selector);
+        return Ok(());
+    }
+
+    for result in results {
+        let name = result["job_name"]
+            .as_str()
+            .or(result["target"].as_str())
+            .unwrap_or("?");
+        if result["ok"].as_bool().unwrap_or(false) {
+            println!("Job '{}' {}d.", name, action);
+        } else {
+            let message = result["error"]["message"]
+                .as_str()
+                .unwrap_or("Unknown error");
+            eprintln!("Error: failed to {} job '{}': {}", action, name, message);
+        }
+    }
+
+    if body["failed"].as_u64().unwrap_or(0) > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
 /// acs trigger
 #[allow(clippy::too_many_arguments)]
 pub async fn cmd_trigger(
@@ -445,6 +627,185 @@ pub async fn cmd_trigger(
 
     Ok(())
 }
+/// acs kill
+///
+/// `target` may be a run ID or a job name/UUID. Run IDs are tried first; for a
+/// job, every run that is currently running is cancelled.
+pub async fn cmd_kill(host: &str, port: u16, target: &str) -> anyhow::Result<()> {
+    let client = Client::new();
+
+    if uuid::Uuid::parse_str(target).is_ok() {
+        if let Some(run) = cancel_run(&client, host, port, target).await? {
+            print_killed_run(&run);
+            return Ok(());
+        }
+    }
+
+    let job_id = match resolve_job_id(&client, host, port, target).await {
+        Ok(id) => id,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Queued runs too, so none of them starts after the running ones are gone.
+    let mut run_ids = job_run_ids(&client, host, port, "runs/active", &job_id).await?;
+    run_ids.extend(job_run_ids(&client, host, port, "queue", &job_id).await?);
+
+    if run_ids.is_empty() {
+        println!("Job '{}' has no running or queued runs.", target);
+        return Ok(());
+    }
+
+    // Each cancel waits for its run to exit, so they are sent together.
+    let cancels = run_ids
+        .iter()
+        .map(|run_id| cancel_run(&client, host, port, run_id));
+    for (run_id, result) in run_ids
+        .iter()
+        .zip(futures_util::future::join_all(cancels).await)
+    {
+        match result? {
+            Some(run) => print_killed_run(&run),
+            // Finished on its own between listing and cancelling.
+            None => println!("Run {} already finished.", run_id),
+        }
+    }
+
+    Ok(())
+}
+
+/// IDs of the job's runs listed by `GET /api/{path}`, such as the active
+/// runs or the run queue.
+async fn job_run_ids(
+    client: &Client,
+    host: &str,
+    port: u16,
+    path: &str,
+    job_id: &str,
+) -> anyhow::Result<Vec<String>> {
+    let url = format!("{}/api/{}", base_url(host, port), path);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    Ok(body["runs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|r| r["job_id"].as_str() == Some(job_id))
+        .filter_map(|r| r["run_id"].as_str().map(String::from))
+        .collect())
+}
+
+/// acs queue
+pub async fn cmd_queue(host: &str, port: u16, json: bool) -> anyhow::Result<()> {
+    let url = format!("{}/api/queue", base_url(host, port));
+
+    let response = Client::new()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let running = body["running"].as_u64().unwrap_or(0);
+    match body["max_concurrent_runs"].as_u64().unwrap_or(0) {
+        0 => println!("Running: {}", running),
+        max => println!("Running: {}/{}", running, max),
+    }
+
+    let runs: Vec<QueuedRun> = serde_json::from_value(body["runs"].clone())?;
+    if runs.is_empty() {
+        println!("No queued runs.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<38}{:<20}{:<10}{:<12}{:<17}WAITING FOR",
+        "RUN ID", "JOB", "PRIORITY", "POOL", "QUEUED"
+    );
+    for run in runs {
+        println!(
+            "{:<38}{:<20}{:<10}{:<12}{:<17}{}",
+            run.run_id.to_string(),
+            run.job_name,
+            run.priority,
+            run.pool.as_deref().unwrap_or("-"),
+            format_relative_time(&run.queued_at),
+            run.waiting_for
+        );
+    }
+
+    Ok(())
+}
+
+/// Cancel a single run. Returns `None` if the daemon does not know of an
+/// active run with this ID; other API errors abort the command.
+async fn cancel_run(
+    client: &Client,
+    host: &str,
+    port: u16,
+    run_id: &str,
+) -> anyhow::Result<Option<Value>> {
+    let url = format!("{}/api/runs/{}/cancel", base_url(host, port), run_id);
+
+    let response = client
+        .post(&url)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if status.is_success() {
+        return Ok(Some(body));
+    }
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::CONFLICT {
+        return Ok(None);
+    }
+
+    let message = body["message"].as_str().unwrap_or("Unknown error");
+    eprintln!("Error: {}", message);
+    std::process::exit(1);
+}
+
+fn print_killed_run(run: &Value) {
+    let run_id = run["run_id"].as_str().unwrap_or("unknown");
+    let status = run["status"].as_str().unwrap_or("unknown").to_lowercase();
+    match run["signal"].as_str() {
+        Some(signal) => println!("Run {} {} ({}).", run_id, status, signal),
+        None => println!("Run {} {}.", run_id, status),
+    }
+}
+
 /// Resolve a job name or UUID to a job ID string.
 async fn resolve_job_id(
     client: &Client,
@@ -517,8 +878,13 @@ async fn follow_sse_stream(response: reqwest::Response) -> anyhow::Result<()> {
                     // data field is: {"event":"Output","data":{"job_id":"...","data":"the text",...}}
                     if let Ok(json) = serde_json::from_str::<Value>(&data) {
                         if let Some(output) = json["data"]["data"].as_str() {
-                            print!("{}", output);
-                            io::stdout().flush()?;
+                            if json["data"]["stream"].as_str() == Some("stderr") {
+                                eprint!("{}", output);
+                                io::stderr().flush()?;
+                            } else {
+                                print!("{}", output);
+                                io::stdout().flush()?;
+                            }
                         }
                     }
                 }
@@ -542,6 +908,19 @@ async fn follow_sse_stream(response: reqwest::Response) -> anyhow::Result<()> {
                     }
                     return Ok(());
                 }
+                "killed" => {
+                    if let Ok(json) = serde_json::from_str::<Value>(&data) {
+                        let reason = json["data"]["reason"].as_str().unwrap_or("unknown");
+                        eprintln!("\n--- Job killed ({}) ---", reason);
+                    }
+                    return Ok(());
+                }
+                "queued" => {
+                    if let Ok(json) = serde_json::from_str::<Value>(&data) {
+                        let reason = json["data"]["reason"].as_str().unwrap_or("a free slot");
+                        eprintln!("--- Run queued, waiting for {} ---", reason);
+                    }
+                }
                 "keepalive" | "" => {
                     // Ignore keepalive comments
                 }
@@ -561,6 +940,65 @@ async fn follow_sse_stream(response: reqwest::Response) -> anyhow::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_build_schedule() {
+        let schedule = build_schedule(Some("0 9 * * *"), None, None, false, Some("Europe/London"));
+        assert_eq!(
+            schedule.unwrap(),
+            Schedule::Cron {
+                expr: "0 9 * * *".to_string(),
+                tz: Some("Europe/London".to_string()),
+            }
+        );
+
+        let every = "15m".parse().unwrap();
+        let schedule = build_schedule(None, Some(every), None, false, None).unwrap();
+        assert_eq!(
+            schedule,
+            Schedule::Every {
+                interval: every,
+                anchor: None,
+            }
+        );
+        assert!(build_schedule(None, Some(every), None, false, Some("UTC")).is_err());
+
+        let schedule = build_schedule(None, None, Some("2026-11-01T09:00"), false, None);
+        assert_eq!(
+            schedule.unwrap(),
+            Schedule::At {
+                timestamp: Utc.with_ymd_and_hms(2026, 11, 1, 9, 0, 0).unwrap(),
+            }
+        );
+
+        assert_eq!(
+            build_schedule(None, None, None, true, None).unwrap(),
+            Schedule::Manual
+        );
+    }
+
+    #[test]
+    fn test_parse_at_formats() {
+        let expected = Utc.with_ymd_and_hms(2026, 11, 1, 9, 0, 0).unwrap();
+        assert_eq!(parse_at("2026-11-01T09:00:00Z", None).unwrap(), expected);
+        assert_eq!(
+            parse_at("2026-11-01T10:00:00+01:00", None).unwrap(),
+            expected
+        );
+        assert_eq!(parse_at("2026-11-01 09:00", None).unwrap(), expected);
+        // Local time in the given timezone (GMT in November)
+        assert_eq!(
+            parse_at("2026-11-01T09:00", Some("Europe/London")).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_at("2026-11-01T04:00", Some("America/New_York")).unwrap(),
+            expected
+        );
+        assert!(parse_at("next tuesday", None).is_err());
+        assert!(parse_at("2026-11-01T09:00", Some("Not/A/Zone")).is_err());
+    }
 
     #[test]
     fn test_format_relative_time_past() {
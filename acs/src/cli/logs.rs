@@ -1,11 +1,13 @@
-// CLI log commands: logs
+// CLI log commands: logs, history, maintenance compress
 
 use std::io::{self, Write};
 
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde_json::Value;
 
 use super::{base_url, connection_error_message};
+use crate::models::{Interval, LogCompression};
 
 /// Helper to handle reqwest errors and produce a user-friendly connection error.
 fn handle_request_error(err: reqwest::Error, host: &str, port: u16) -> anyhow::Error {
@@ -26,6 +28,7 @@ pub async fn cmd_logs(
     run: Option<&str>,
     last: Option<usize>,
     tail: Option<usize>,
+    stream: Option<&str>,
     json: bool,
 ) -> anyhow::Result<()> {
     let client = Client::new();
@@ -33,14 +36,17 @@ pub async fn cmd_logs(
     if follow {
         // Follow mode: first resolve job ID, then stream SSE
         let job_id = resolve_job_id(&client, host, port, job).await?;
-        let sse_url = format!("{}/api/events?job_id={}", base_url(host, port), job_id);
+        let mut sse_url = format!("{}/api/events?job_id={}", base_url(host, port), job_id);
+        if let Some(stream) = stream {
+            sse_url.push_str(&format!("&stream={}", stream));
+        }
         follow_sse_logs(&client, &sse_url).await?;
         return Ok(());
     }
 
     if let Some(run_id) = run {
         // Show a specific run's log
-        show_run_log(&client, host, port, run_id, tail, json).await?;
+        show_run_log(&client, host, port, run_id, tail, stream, json).await?;
     } else {
         // List runs (optionally limited by --last)
         let limit = last.unwrap_or(20);
@@ -136,11 +142,11 @@ async fn list_runs(
 
             // Print header
             println!(
-                "{:<38}{:<22}{:<12}{:<10}{:<12}",
-                "RUN ID", "STARTED", "STATUS", "EXIT", "SIZE"
+                "{:<38}{:<9}{:<22}{:<12}{:<10}{:<12}",
+                "RUN ID", "ATTEMPT", "STARTED", "STATUS", "EXIT", "SIZE"
             );
 
-            for run in runs {
+            for run in group_attempts(runs) {
                 let run_id = run["run_id"].as_str().unwrap_or("?");
                 let started = run["started_at"].as_str().unwrap_or("?");
                 let run_status = run["status"].as_str().unwrap_or("?");
@@ -149,6 +155,7 @@ async fn list_runs(
                     None => "-".to_string(),
                 };
                 let log_size = run["log_size_bytes"].as_u64().unwrap_or(0);
+                let attempt = run["attempt"].as_u64().unwrap_or(1);
 
                 // Format started time (show just the datetime part)
                 let started_display = if started.len() > 19 {
@@ -158,8 +165,9 @@ async fn list_runs(
                 };
 
                 println!(
-                    "{:<38}{:<22}{:<12}{:<10}{:<12}",
+                    "{:<38}{:<9}{:<22}{:<12}{:<10}{:<12}",
                     run_id,
+                    attempt,
                     started_display,
                     run_status,
                     exit_code,
@@ -175,6 +183,26 @@ async fn list_runs(
     Ok(())
 }
 
+/// Order runs so the attempts of a retried run are listed together, first
+/// attempt first. Groups keep the position of their most recent attempt.
+fn group_attempts(runs: &[Value]) -> Vec<&Value> {
+    let mut groups: Vec<(&str, Vec<&Value>)> = Vec::new();
+    for run in runs {
+        let key = run["parent_run_id"]
+            .as_str()
+            .or_else(|| run["run_id"].as_str())
+            .unwrap_or("?");
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(run),
+            None => groups.push((key, vec![run])),
+        }
+    }
+    for (_, group) in &mut groups {
+        group.sort_by_key(|run| run["attempt"].as_u64().unwrap_or(1));
+    }
+    groups.into_iter().flat_map(|(_, group)| group).collect()
+}
+
 /// Show a specific run's log output.
 async fn show_run_log(
     client: &Client,
@@ -182,16 +210,22 @@ async fn show_run_log(
     port: u16,
     run_id: &str,
     tail: Option<usize>,
+    stream: Option<&str>,
     json: bool,
 ) -> anyhow::Result<()> {
-    let mut url = format!("{}/api/runs/{}/log", base_url(host, port), run_id);
+    let url = format!("{}/api/runs/{}/log", base_url(host, port), run_id);
 
+    let mut query = Vec::new();
     if let Some(n) = tail {
-        url.push_str(&format!("?tail={}", n));
+        query.push(("tail", n.to_string()));
+    }
+    if let Some(stream) = stream {
+        query.push(("stream", stream.to_string()));
     }
 
     let response = client
         .get(&url)
+        .query(&query)
         .send()
         .await
         .map_err(|e| handle_request_error(e, host, port))?;
@@ -217,6 +251,7 @@ async fn show_run_log(
         // Wrap log content in JSON
         let output = serde_json::json!({
             "run_id": run_id,
+            "stream": stream,
             "log": body,
         });
         println!("{}", serde_json::to_string_pretty(&output)?);
@@ -279,8 +314,15 @@ async fn follow_sse_logs(client: &Client, url: &str) -> anyhow::Result<()> {
                 "output" => {
                     if let Ok(json) = serde_json::from_str::<Value>(&data) {
                         if let Some(output) = json["data"]["data"].as_str() {
-                            print!("{}", output);
-                            io::stdout().flush()?;
+                            // Mirror the job's own streams so `2>/dev/null`
+                            // and friends behave as expected.
+                            if json["data"]["stream"].as_str() == Some("stderr") {
+                                eprint!("{}", output);
+                                io::stderr().flush()?;
+                            } else {
+                                print!("{}", output);
+                                io::stdout().flush()?;
+                            }
                         }
                     }
                 }
@@ -299,6 +341,24 @@ async fn follow_sse_logs(client: &Client, url: &str) -> anyhow::Result<()> {
                         eprintln!("\n--- Job failed: {} ---", error);
                     }
                 }
+                "killed" => {
+                    if let Ok(json) = serde_json::from_str::<Value>(&data) {
+                        let reason = json["data"]["reason"].as_str().unwrap_or("unknown");
+                        eprintln!("\n--- Job killed ({}) ---", reason);
+                    }
+                }
+                "skipped" => {
+                    if let Ok(json) = serde_json::from_str::<Value>(&data) {
+                        let reason = json["data"]["reason"].as_str().unwrap_or("unknown");
+                        eprintln!("\n--- Run skipped: {} ---", reason);
+                    }
+                }
+                "queued" => {
+                    if let Ok(json) = serde_json::from_str::<Value>(&data) {
+                        let reason = json["data"]["reason"].as_str().unwrap_or("a free slot");
+                        eprintln!("\n--- Run queued, waiting for {} ---", reason);
+                    }
+                }
                 "keepalive" | "" => {
                     // Ignore keepalive comments
                 }
@@ -310,6 +370,206 @@ async fn follow_sse_logs(client: &Client, url: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Which runs `acs history` lists. Unset fields match any run.
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    pub job: Option<String>,
+    pub selector: Option<String>,
+    pub status: Option<String>,
+    pub exit_code: Option<i32>,
+    pub trigger: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub search: Option<String>,
+}
+
+impl HistoryFilter {
+    /// The `GET /api/runs` query parameters for the filter.
+    fn query(&self) -> anyhow::Result<Vec<(&'static str, String)>> {
+        let mut query = Vec::new();
+        let fields = [
+            ("job", &self.job),
+            ("selector", &self.selector),
+            ("status", &self.status),
+            ("trigger", &self.trigger),
+            ("q", &self.search),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                query.push((name, value.clone()));
+            }
+        }
+        if let Some(exit_code) = self.exit_code {
+            query.push(("exit_code", exit_code.to_string()));
+        }
+        if let Some(since) = &self.since {
+            query.push(("started_after", parse_time(since)?.to_rfc3339()));
+        }
+        if let Some(until) = &self.until {
+            query.push(("started_before", parse_time(until)?.to_rfc3339()));
+        }
+        Ok(query)
+    }
+}
+
+/// Parse an RFC 3339 time, or an interval such as `2h` meaning that long
+/// ago.
+fn parse_time(s: &str) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(ago) = s.parse::<Interval>() {
+        return Ok(Utc::now() - ago.to_duration());
+    }
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid time '{}': expected RFC 3339 or a duration such as 2h or 7d",
+                s
+            )
+        })
+}
+
+/// acs history
+pub async fn cmd_history(
+    host: &str,
+    port: u16,
+    filter: &HistoryFilter,
+    last: usize,
+    json: bool,
+) -> anyhow::Result<()> {
+    let client = Client::new();
+    let mut query = filter.query()?;
+    query.push(("limit", last.to_string()));
+
+    let response = client
+        .get(format!("{}/api/runs", base_url(host, port)))
+        .query(&query)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let runs = body["runs"].as_array().cloned().unwrap_or_default();
+    if runs.is_empty() {
+        println!("No runs found.");
+        return Ok(());
+    }
+
+    // Runs only carry their job's id; look up the names once.
+    let jobs: Value = client
+        .get(format!("{}/api/jobs", base_url(host, port)))
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+    let job_name = |id: &str| -> String {
+        jobs.as_array()
+            .and_then(|jobs| jobs.iter().find(|j| j["id"].as_str() == Some(id)))
+            .and_then(|j| j["name"].as_str())
+            .unwrap_or(id)
+            .to_string()
+    };
+
+    println!(
+        "Showing {} of {} runs:\n",
+        runs.len(),
+        body["total"].as_u64().unwrap_or(0)
+    );
+    println!(
+        "{:<38}{:<20}{:<22}{:<12}{:<6}{:<10}ERROR",
+        "RUN ID", "JOB", "STARTED", "STATUS", "EXIT", "TRIGGER"
+    );
+    for run in &runs {
+        let started = run["started_at"].as_str().unwrap_or("?");
+        let exit_code = match run["exit_code"].as_i64() {
+            Some(c) => c.to_string(),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<38}{:<20}{:<22}{:<12}{:<6}{:<10}{}",
+            run["run_id"].as_str().unwrap_or("?"),
+            job_name(run["job_id"].as_str().unwrap_or("?")),
+            started.get(..19).unwrap_or(started),
+            run["status"].as_str().unwrap_or("?"),
+            exit_code,
+            run["trigger"].as_str().unwrap_or("-"),
+            run["error"].as_str().unwrap_or("")
+        );
+    }
+
+    Ok(())
+}
+
+/// acs maintenance compress
+pub async fn cmd_compress(
+    host: &str,
+    port: u16,
+    format: Option<LogCompression>,
+    json: bool,
+) -> anyhow::Result<()> {
+    let client = Client::new();
+    let mut request = serde_json::Map::new();
+    if let Some(format) = format {
+        request.insert("format".to_string(), Value::from(format.as_str()));
+    }
+
+    let response = client
+        .post(format!("{}/api/maintenance/compress", base_url(host, port)))
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let runs = body["runs"].as_u64().unwrap_or(0);
+    if runs == 0 {
+        println!("No uncompressed logs of finished runs found.");
+        return Ok(());
+    }
+    println!(
+        "Compressed {} run log(s) with {}: {} -> {}",
+        runs,
+        body["format"].as_str().unwrap_or("?"),
+        format_bytes(body["bytes_before"].as_u64().unwrap_or(0)),
+        format_bytes(body["bytes_after"].as_u64().unwrap_or(0))
+    );
+
+    Ok(())
+}
+
 /// Format byte size into a human-readable string.
 fn format_bytes(bytes: u64) -> String {
     if bytes == 0 {
@@ -362,6 +622,49 @@ mod tests {
         assert_eq!(format_bytes(1024 * 1024), "1.0 MB");
     }
 
+    #[test]
+    fn test_group_attempts_lists_retries_together() {
+        // Newest first, as returned by the API.
+        let runs = vec![
+            serde_json::json!({"run_id": "c", "attempt": 2, "parent_run_id": "a"}),
+            serde_json::json!({"run_id": "b", "attempt": 1}),
+            serde_json::json!({"run_id": "a", "attempt": 1}),
+            serde_json::json!({"run_id": "old"}),
+        ];
+        let ordered: Vec<&str> = group_attempts(&runs)
+            .iter()
+            .map(|r| r["run_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ordered, vec!["a", "c", "b", "old"]);
+    }
+
+    #[test]
+    fn test_history_filter_query() {
+        let filter = HistoryFilter {
+            selector: Some("tag=nightly".to_string()),
+            exit_code: Some(1),
+            since: Some("2025-06-01T00:00:00Z".to_string()),
+            until: Some("1h".to_string()),
+            ..Default::default()
+        };
+        let query = filter.query().unwrap();
+        assert_eq!(query[0], ("selector", "tag=nightly".to_string()));
+        assert_eq!(query[1], ("exit_code", "1".to_string()));
+        assert_eq!(
+            query[2],
+            ("started_after", "2025-06-01T00:00:00+00:00".to_string())
+        );
+        let until = DateTime::parse_from_rfc3339(&query[3].1).unwrap();
+        let ago = Utc::now().signed_duration_since(until);
+        assert!((3590..=3610).contains(&ago.num_seconds()), "{:?}", ago);
+
+        let filter = HistoryFilter {
+            since: Some("yesterday".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.query().is_err());
+    }
+
     #[tokio::test]
     async fn test_resolve_job_id_connection_error() {
         let client = Client::new();
@@ -0,0 +1,217 @@
+// CLI manifest commands: apply, diff, export
+
+use std::io::Read;
+use std::path::Path;
+
+use reqwest::Client;
+use serde_json::Value;
+
+use super::{base_url, handle_request_error};
+use crate::models::manifest::{Change, FieldChange};
+use crate::models::{Job, Manifest, ManifestFormat, Plan};
+
+/// Read a manifest from `file`, or from stdin if it is `-`. Without an
+/// explicit format, it is taken from the file extension (YAML by default).
+fn read_manifest(file: &str, format: Option<ManifestFormat>) -> anyhow::Result<Manifest> {
+    let text = if file == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        std::fs::read_to_string(file)
+            .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", file, e))?
+    };
+    let format = format
+        .or_else(|| ManifestFormat::from_path(Path::new(file)))
+        .unwrap_or_default();
+    Manifest::parse(&text, format)
+        .map_err(|e| anyhow::anyhow!("Invalid manifest '{}': {}", file, e))
+}
+
+/// GET /api/jobs, optionally filtered by a selector.
+async fn fetch_jobs(
+    client: &Client,
+    host: &str,
+    port: u16,
+    selector: Option<&str>,
+) -> anyhow::Result<Vec<Job>> {
+    let url = format!("{}/api/jobs", base_url(host, port));
+    let query: Vec<(&str, &str)> = selector.map(|s| ("selector", s)).into_iter().collect();
+
+    let response = client
+        .get(&url)
+        .query(&query)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    Ok(serde_json::from_value(body)?)
+}
+
+fn format_field_value(value: Option<&Value>) -> String {
+    match value {
+        Some(value) => serde_json::to_string(value).unwrap_or_default(),
+        None => "(default)".to_string(),
+    }
+}
+
+fn print_plan(plan: &Plan) {
+    for change in &plan.changes {
+        match change {
+            Change::Create(job) => println!("+ {}", job.name),
+            Change::Update { job, fields, .. } => {
+                println!("~ {}", job.name);
+                for FieldChange { field, from, to } in fields {
+                    println!(
+                        "    {}: {} -> {}",
+                        field,
+                        format_field_value(from.as_ref()),
+                        format_field_value(to.as_ref())
+                    );
+                }
+            }
+            Change::Delete { name, .. } => println!("- {}", name),
+        }
+    }
+    for (name, file) in &plan.file_jobs {
+        println!("! {} (managed by jobs.d file '{}', skipped)", name, file);
+    }
+
+    let (create, update, delete) = plan.counts();
+    if !plan.is_empty() || !plan.file_jobs.is_empty() {
+        println!();
+    }
+    println!(
+        "Plan: {} to create, {} to update, {} to delete, {} unchanged.",
+        create, update, delete, plan.unchanged
+    );
+}
+
+/// Work out the plan for the manifest in `file` against the daemon's jobs.
+async fn load_plan(
+    client: &Client,
+    host: &str,
+    port: u16,
+    file: &str,
+    format: Option<ManifestFormat>,
+    prune: bool,
+) -> anyhow::Result<Plan> {
+    let manifest = read_manifest(file, format)?;
+    let current = fetch_jobs(client, host, port, None).await?;
+    Ok(Plan::new(&current, &manifest.jobs, prune))
+}
+
+/// acs diff
+pub async fn cmd_diff(
+    host: &str,
+    port: u16,
+    file: &str,
+    format: Option<ManifestFormat>,
+    prune: bool,
+) -> anyhow::Result<()> {
+    let client = Client::new();
+    let plan = load_plan(&client, host, port, file, format, prune).await?;
+    print_plan(&plan);
+    Ok(())
+}
+
+/// acs apply
+pub async fn cmd_apply(
+    host: &str,
+    port: u16,
+    file: &str,
+    format: Option<ManifestFormat>,
+    prune: bool,
+) -> anyhow::Result<()> {
+    let client = Client::new();
+    let plan = load_plan(&client, host, port, file, format, prune).await?;
+    print_plan(&plan);
+    if plan.is_empty() {
+        return Ok(());
+    }
+    println!();
+
+    let base = base_url(host, port);
+    let mut failed = 0;
+    for change in &plan.changes {
+        let (request, done) = match change {
+            Change::Create(job) => (
+                client.post(format!("{}/api/jobs", base)).json(job),
+                "created",
+            ),
+            Change::Update { id, job, .. } => (
+                client.put(format!("{}/api/jobs/{}", base, id)).json(job),
+                "updated",
+            ),
+            Change::Delete { id, .. } => (
+                client.delete(format!("{}/api/jobs/{}", base, id)),
+                "deleted",
+            ),
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| handle_request_error(e, host, port))?;
+        if response.status().is_success() {
+            println!("Job '{}' {}.", change.name(), done);
+        } else {
+            let body: Value = response
+                .json()
+                .await
+                .unwrap_or_else(|_| serde_json::json!({"message": "Unknown error"}));
+            let message = body["message"].as_str().unwrap_or("Unknown error");
+            eprintln!("Error: job '{}': {}", change.name(), message);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("{} of {} changes failed.", failed, plan.changes.len());
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// acs export
+pub async fn cmd_export(
+    host: &str,
+    port: u16,
+    output: Option<&str>,
+    format: Option<ManifestFormat>,
+    selector: Option<&str>,
+) -> anyhow::Result<()> {
+    let client = Client::new();
+    let jobs = fetch_jobs(&client, host, port, selector).await?;
+
+    let format = format
+        .or_else(|| output.and_then(|o| ManifestFormat::from_path(Path::new(o))))
+        .unwrap_or_default();
+    let text = Manifest::from_jobs(&jobs)
+        .render(format)
+        .map_err(|e| anyhow::anyhow!("Failed to write manifest: {}", e))?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, text)
+                .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path, e))?;
+            println!("Exported {} jobs to '{}'.", jobs.len(), path);
+        }
+        None => print!("{}", text),
+    }
+
+    Ok(())
+}
@@ -1,10 +1,15 @@
+pub mod calendars;
 pub mod daemon;
 pub mod jobs;
 pub mod logs;
+pub mod manifests;
+pub mod workflows;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
 use std::collections::HashMap;
 
+use crate::models::{CatchupPolicy, ConcurrencyPolicy, Interval, LogCompression, ManifestFormat};
+
 /// Agent Cron Scheduler - A cross-platform cron scheduler daemon
 #[derive(Parser, Debug)]
 #[command(
@@ -29,6 +34,8 @@ pub struct Cli {
     pub command: Option<Commands>,
 }
 
+// Parsed once per invocation, so the size of `Add` does not matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Start the daemon
@@ -68,14 +75,37 @@ pub enum Commands {
     },
 
     /// Add a new scheduled job
+    #[command(group(
+        ArgGroup::new("when")
+            .required(true)
+            .args(["schedule", "every", "at", "manual"])
+    ))]
     Add {
         /// Job name (must be unique)
         #[arg(short = 'n', long)]
         name: String,
 
-        /// Cron schedule expression (5-field)
+        /// Cron schedule expression (5 fields, or 6/7 with seconds and year)
         #[arg(short = 's', long)]
-        schedule: String,
+        schedule: Option<String>,
+
+        /// Run at a fixed interval instead of a cron schedule (e.g. 90s, 15m, 1h30m)
+        #[arg(long, value_name = "INTERVAL")]
+        every: Option<Interval>,
+
+        /// Run once at the given time, then disable the job (RFC 3339, or
+        /// YYYY-MM-DDTHH:MM[:SS] in --timezone)
+        #[arg(long, value_name = "TIME")]
+        at: Option<String>,
+
+        /// Never run on a schedule; only when triggered
+        #[arg(long)]
+        manual: bool,
+
+        /// Delay each run by a stable per-job offset of up to this many
+        /// seconds, to spread out jobs sharing a schedule
+        #[arg(long, value_name = "SECS")]
+        jitter: Option<u64>,
 
         /// Shell command to execute
         #[arg(short = 'c', long = "cmd", conflicts_with = "script")]
@@ -85,7 +115,7 @@ pub enum Commands {
         #[arg(long, conflicts_with = "cmd")]
         script: Option<String>,
 
-        /// IANA timezone (default: UTC)
+        /// IANA timezone for --schedule and --at (default: UTC)
         #[arg(long)]
         timezone: Option<String>,
 
@@ -104,6 +134,68 @@ pub enum Commands {
         /// Include full environment variables in run logs
         #[arg(long)]
         log_env: bool,
+
+        /// What to do if the job is still running when it is due again:
+        /// allow, forbid, replace, or queue:<max_depth> (default: allow)
+        #[arg(long, value_name = "POLICY")]
+        concurrency: Option<ConcurrencyPolicy>,
+
+        /// Runs to make up for after daemon downtime: none, once, or
+        /// all:<max_runs> (default: none)
+        #[arg(long, value_name = "POLICY")]
+        catchup: Option<CatchupPolicy>,
+
+        /// Only run within the periods of this calendar (repeatable)
+        #[arg(long = "include-calendar", value_name = "CALENDAR")]
+        include_calendars: Vec<String>,
+
+        /// Never run within the periods of this calendar (repeatable)
+        #[arg(long = "exclude-calendar", value_name = "CALENDAR")]
+        exclude_calendars: Vec<String>,
+
+        /// Do not run before this time (RFC 3339, or YYYY-MM-DDTHH:MM[:SS]
+        /// in --timezone)
+        #[arg(long, value_name = "TIME")]
+        start_at: Option<String>,
+
+        /// Stop running at this time and disable the job (RFC 3339, or
+        /// YYYY-MM-DDTHH:MM[:SS] in --timezone)
+        #[arg(long, value_name = "TIME")]
+        end_at: Option<String>,
+
+        /// Disable the job after this many scheduled runs
+        #[arg(long, value_name = "N")]
+        max_runs: Option<u32>,
+
+        /// Trigger this job when a run exits with code 0 (repeatable)
+        #[arg(long = "on-success", value_name = "JOB")]
+        on_success: Vec<String>,
+
+        /// Trigger this job when a run fails or exits non-zero, after its
+        /// last retry (repeatable)
+        #[arg(long = "on-failure", value_name = "JOB")]
+        on_failure: Vec<String>,
+
+        /// Pipe the last N lines of a run's output to the stdin of the jobs
+        /// it triggers
+        #[arg(long = "forward-output", value_name = "N")]
+        forward_output: Option<usize>,
+
+        /// Count runs towards this concurrency pool's limit
+        #[arg(long, value_name = "POOL")]
+        pool: Option<String>,
+
+        /// Queued runs with a higher priority start first (default: 0)
+        #[arg(long, value_name = "N", allow_negative_numbers = true)]
+        priority: Option<i32>,
+
+        /// Tag the job (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Label the job (repeatable)
+        #[arg(long = "label", value_name = "KEY=VALUE")]
+        labels: Vec<String>,
     },
 
     /// Remove a scheduled job
@@ -126,21 +218,39 @@ pub enum Commands {
         #[arg(long, conflicts_with = "enabled")]
         disabled: bool,
 
+        /// Show only jobs with this tag (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Show only jobs matching this selector, e.g. 'tag=nightly,team!=ops'
+        #[arg(short = 'l', long, value_name = "SELECTOR")]
+        selector: Option<String>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
 
-    /// Enable a scheduled job
+    /// Enable a scheduled job, or every job matching a selector
     Enable {
         /// Job name or UUID
-        job: String,
+        #[arg(required_unless_present = "selector", conflicts_with = "selector")]
+        job: Option<String>,
+
+        /// Enable every job matching this selector
+        #[arg(short = 'l', long, value_name = "SELECTOR")]
+        selector: Option<String>,
     },
 
-    /// Disable a scheduled job
+    /// Disable a scheduled job, or every job matching a selector
     Disable {
         /// Job name or UUID
-        job: String,
+        #[arg(required_unless_present = "selector", conflicts_with = "selector")]
+        job: Option<String>,
+
+        /// Disable every job matching this selector
+        #[arg(short = 'l', long, value_name = "SELECTOR")]
+        selector: Option<String>,
     },
 
     /// Manually trigger a job run
@@ -165,9 +275,132 @@ pub enum Commands {
         input: Option<String>,
     },
 
+    /// Stop a running or queued run (by run ID), or all running and queued
+    /// runs of a job
+    Kill {
+        /// Run ID, or job name or UUID
+        target: String,
+    },
+
+    /// Show runs waiting for a free slot under the concurrency limits
+    Queue {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Create, update and (with --prune) delete jobs to match a manifest
+    Apply {
+        /// Manifest file (YAML, TOML or JSON), or - for stdin
+        #[arg(short = 'f', long = "file", value_name = "FILE")]
+        file: String,
+
+        /// Manifest format (default: from the file extension, else yaml)
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<ManifestFormat>,
+
+        /// Also delete jobs the manifest does not list
+        #[arg(long)]
+        prune: bool,
+    },
+
+    /// Show what `apply` would change, without changing anything
+    Diff {
+        /// Manifest file (YAML, TOML or JSON), or - for stdin
+        #[arg(short = 'f', long = "file", value_name = "FILE")]
+        file: String,
+
+        /// Manifest format (default: from the file extension, else yaml)
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<ManifestFormat>,
+
+        /// Also show the jobs the manifest does not list as deletions
+        #[arg(long)]
+        prune: bool,
+    },
+
+    /// Write the current jobs as a manifest
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Manifest format (default: from the output extension, else yaml)
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<ManifestFormat>,
+
+        /// Only export jobs matching this selector, e.g. 'tag=nightly'
+        #[arg(short = 'l', long, value_name = "SELECTOR")]
+        selector: Option<String>,
+    },
+
     /// Restart the daemon
     Restart,
 
+    /// Manage calendars that jobs include or exclude
+    Calendar {
+        #[command(subcommand)]
+        command: CalendarCommands,
+    },
+
+    /// Manage workflows that chain jobs together
+    Workflow {
+        #[command(subcommand)]
+        command: WorkflowCommands,
+    },
+
+    /// Maintenance tasks for the daemon's data
+    Maintenance {
+        #[command(subcommand)]
+        command: MaintenanceCommands,
+    },
+
+    /// Show recent runs across all jobs
+    History {
+        /// Show the last N runs
+        #[arg(short = 'n', long, default_value_t = 20)]
+        last: usize,
+
+        /// Only runs of this job (name or UUID)
+        #[arg(long, conflicts_with = "selector")]
+        job: Option<String>,
+
+        /// Only runs of jobs matching this selector, e.g. 'tag=nightly'
+        #[arg(short = 'l', long, value_name = "SELECTOR")]
+        selector: Option<String>,
+
+        /// Only runs with this status (queued, running, completed, failed,
+        /// killed, skipped)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Only runs that exited with this code
+        #[arg(long, value_name = "CODE", allow_negative_numbers = true)]
+        exit_code: Option<i32>,
+
+        /// Only runs dispatched this way (manual, schedule, catch_up, retry,
+        /// upstream, workflow)
+        #[arg(long)]
+        trigger: Option<String>,
+
+        /// Only runs started at or after this time (RFC 3339, or a duration
+        /// ago such as 2h or 7d)
+        #[arg(long, value_name = "TIME")]
+        since: Option<String>,
+
+        /// Only runs started before this time (RFC 3339, or a duration ago)
+        #[arg(long, value_name = "TIME")]
+        until: Option<String>,
+
+        /// Only runs whose error message contains this text
+        #[arg(long, value_name = "TEXT")]
+        search: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// View job run logs
     Logs {
         /// Job name or UUID
@@ -189,6 +422,176 @@ pub enum Commands {
         #[arg(long)]
         tail: Option<usize>,
 
+        /// Only show output from one stream (stdout or stderr)
+        #[arg(long, value_parser = ["stdout", "stderr"])]
+        stream: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CalendarCommands {
+    /// List all calendars
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the periods of a calendar
+    Show {
+        /// Calendar name
+        name: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Add a calendar
+    Add {
+        /// Calendar name (must be unique)
+        name: String,
+
+        /// Free-form description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// IANA timezone for windows, dates and local times (default: UTC)
+        #[arg(long)]
+        timezone: Option<String>,
+
+        /// Date range START/END: whole days (2025-12-24/2025-12-26) or times
+        /// (RFC 3339, or YYYY-MM-DDTHH:MM in --timezone); repeatable
+        #[arg(long, value_name = "START/END")]
+        range: Vec<String>,
+
+        /// Recurring window: a cron expression followed by a duration,
+        /// e.g. "0 0 * * SAT 2d"; repeatable
+        #[arg(long, value_name = "CRON DURATION")]
+        window: Vec<String>,
+
+        /// Import the events of an iCalendar (.ics) file as date ranges;
+        /// repeatable
+        #[arg(long, value_name = "FILE")]
+        ics: Vec<String>,
+    },
+
+    /// Remove a calendar (refused while jobs reference it)
+    Remove {
+        /// Calendar name
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MaintenanceCommands {
+    /// Compress the logs of finished runs that are still stored uncompressed
+    Compress {
+        /// Compression format (gzip or zstd; defaults to the daemon's
+        /// log_compression, or gzip if that is none)
+        #[arg(long)]
+        format: Option<LogCompression>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WorkflowCommands {
+    /// List all workflows
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the nodes and edges of a workflow
+    Show {
+        /// Workflow name or UUID
+        workflow: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Add a workflow (triggered manually unless a schedule is given)
+    #[command(group(
+        ArgGroup::new("when")
+            .multiple(false)
+            .args(["schedule", "every", "at", "manual"])
+    ))]
+    Add {
+        /// Workflow name (must be unique)
+        name: String,
+
+        /// Node running a job: NAME=JOB, or JOB to name the node after its
+        /// job; repeatable
+        #[arg(long = "node", value_name = "NAME[=JOB]", required = true)]
+        nodes: Vec<String>,
+
+        /// Edge FROM:TO, optionally with :on_success (default), :on_failure
+        /// or :always; repeatable
+        #[arg(long = "edge", value_name = "FROM:TO[:CONDITION]")]
+        edges: Vec<String>,
+
+        /// Cron schedule expression (5 fields, or 6/7 with seconds and year)
+        #[arg(short = 's', long)]
+        schedule: Option<String>,
+
+        /// Run at a fixed interval (e.g. 90s, 15m, 1h30m)
+        #[arg(long, value_name = "INTERVAL")]
+        every: Option<Interval>,
+
+        /// Run once at the given time, then disable the workflow (RFC 3339,
+        /// or YYYY-MM-DDTHH:MM[:SS] in --timezone)
+        #[arg(long, value_name = "TIME")]
+        at: Option<String>,
+
+        /// Never run on a schedule; only when triggered (the default)
+        #[arg(long)]
+        manual: bool,
+
+        /// IANA timezone for --schedule and --at (default: UTC)
+        #[arg(long)]
+        timezone: Option<String>,
+
+        /// Create the workflow in disabled state
+        #[arg(long)]
+        disabled: bool,
+    },
+
+    /// Remove a workflow and its run history
+    Remove {
+        /// Workflow name or UUID
+        workflow: String,
+    },
+
+    /// Start a run of a workflow now
+    Trigger {
+        /// Workflow name or UUID
+        workflow: String,
+    },
+
+    /// Show the runs of a workflow
+    Runs {
+        /// Workflow name or UUID
+        workflow: String,
+
+        /// Show the nodes of one workflow run
+        #[arg(long)]
+        run: Option<String>,
+
+        /// Show last N runs
+        #[arg(long, default_value_t = 10)]
+        last: usize,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -219,6 +622,25 @@ pub fn parse_env_vars(env_args: &[String]) -> Result<HashMap<String, String>, St
     Ok(map)
 }
 
+/// Parse label arguments from "KEY=VALUE" format into a HashMap.
+pub fn parse_labels(label_args: &[String]) -> Result<HashMap<String, String>, String> {
+    let mut map = HashMap::new();
+    for arg in label_args {
+        match arg.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                map.insert(key.to_string(), value.to_string());
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid label format: '{}'. Expected KEY=VALUE",
+                    arg
+                ));
+            }
+        }
+    }
+    Ok(map)
+}
+
 /// Format a connection error message for when the daemon is not reachable.
 pub fn connection_error_message(host: &str, port: u16) -> String {
     format!(
@@ -227,6 +649,15 @@ pub fn connection_error_message(host: &str, port: u16) -> String {
     )
 }
 
+/// Helper to handle reqwest errors and produce a user-friendly connection error.
+pub fn handle_request_error(err: reqwest::Error, host: &str, port: u16) -> anyhow::Error {
+    if err.is_connect() || err.is_timeout() {
+        anyhow::anyhow!("{}", connection_error_message(host, port))
+    } else {
+        anyhow::anyhow!("Request failed: {}", err)
+    }
+}
+
 /// Dispatch the CLI command to the appropriate handler.
 pub async fn dispatch(cli: &Cli) -> anyhow::Result<()> {
     match &cli.command {
@@ -255,6 +686,10 @@ pub async fn dispatch(cli: &Cli) -> anyhow::Result<()> {
         Some(Commands::Add {
             name,
             schedule,
+            every,
+            at,
+            manual,
+            jitter,
             cmd,
             script,
             timezone,
@@ -262,19 +697,62 @@ pub async fn dispatch(cli: &Cli) -> anyhow::Result<()> {
             env,
             disabled,
             log_env,
+            concurrency,
+            catchup,
+            include_calendars,
+            exclude_calendars,
+            start_at,
+            end_at,
+            max_runs,
+            on_success,
+            on_failure,
+            forward_output,
+            pool,
+            priority,
+            tags,
+            labels,
         }) => {
+            let schedule = jobs::build_schedule(
+                schedule.as_deref(),
+                *every,
+                at.as_deref(),
+                *manual,
+                timezone.as_deref(),
+            )?;
+            let start_at = start_at
+                .as_deref()
+                .map(|s| jobs::parse_at(s, timezone.as_deref()))
+                .transpose()?;
+            let end_at = end_at
+                .as_deref()
+                .map(|s| jobs::parse_at(s, timezone.as_deref()))
+                .transpose()?;
             jobs::cmd_add(
                 &cli.host,
                 cli.port,
                 name,
                 schedule,
+                jitter.unwrap_or(0),
                 cmd.as_deref(),
                 script.as_deref(),
-                timezone.as_deref(),
                 working_dir.as_deref(),
                 env,
                 *disabled,
                 *log_env,
+                *concurrency,
+                *catchup,
+                include_calendars,
+                exclude_calendars,
+                start_at,
+                end_at,
+                *max_runs,
+                on_success,
+                on_failure,
+                *forward_output,
+                pool.as_deref(),
+                priority.unwrap_or(0),
+                tags,
+                labels,
             )
             .await
         }
@@ -284,10 +762,27 @@ pub async fn dispatch(cli: &Cli) -> anyhow::Result<()> {
         Some(Commands::List {
             enabled,
             disabled,
+            tags,
+            selector,
             json,
-        }) => jobs::cmd_list(&cli.host, cli.port, *enabled, *disabled, *json).await,
-        Some(Commands::Enable { job }) => jobs::cmd_enable(&cli.host, cli.port, job).await,
-        Some(Commands::Disable { job }) => jobs::cmd_disable(&cli.host, cli.port, job).await,
+        }) => {
+            let selector = jobs::build_selector(tags, selector.as_deref());
+            jobs::cmd_list(&cli.host, cli.port, *enabled, *disabled, &selector, *json).await
+        }
+        Some(Commands::Enable { job, selector }) => match selector {
+            Some(selector) => {
+                jobs::cmd_set_enabled_matching(&cli.host, cli.port, selector, true).await
+            }
+            None => jobs::cmd_enable(&cli.host, cli.port, job.as_deref().unwrap_or_default()).await,
+        },
+        Some(Commands::Disable { job, selector }) => match selector {
+            Some(selector) => {
+                jobs::cmd_set_enabled_matching(&cli.host, cli.port, selector, false).await
+            }
+            None => {
+                jobs::cmd_disable(&cli.host, cli.port, job.as_deref().unwrap_or_default()).await
+            }
+        },
         Some(Commands::Trigger {
             job,
             follow,
@@ -306,12 +801,142 @@ pub async fn dispatch(cli: &Cli) -> anyhow::Result<()> {
             )
             .await
         }
+        Some(Commands::Kill { target }) => jobs::cmd_kill(&cli.host, cli.port, target).await,
+        Some(Commands::Queue { json }) => jobs::cmd_queue(&cli.host, cli.port, *json).await,
+        Some(Commands::Apply {
+            file,
+            format,
+            prune,
+        }) => manifests::cmd_apply(&cli.host, cli.port, file, *format, *prune).await,
+        Some(Commands::Diff {
+            file,
+            format,
+            prune,
+        }) => manifests::cmd_diff(&cli.host, cli.port, file, *format, *prune).await,
+        Some(Commands::Export {
+            output,
+            format,
+            selector,
+        }) => {
+            manifests::cmd_export(
+                &cli.host,
+                cli.port,
+                output.as_deref(),
+                *format,
+                selector.as_deref(),
+            )
+            .await
+        }
+        Some(Commands::Calendar { command }) => match command {
+            CalendarCommands::List { json } => {
+                calendars::cmd_list(&cli.host, cli.port, *json).await
+            }
+            CalendarCommands::Show { name, json } => {
+                calendars::cmd_show(&cli.host, cli.port, name, *json).await
+            }
+            CalendarCommands::Add {
+                name,
+                description,
+                timezone,
+                range,
+                window,
+                ics,
+            } => {
+                let calendar = calendars::build_calendar(
+                    name,
+                    description.as_deref(),
+                    timezone.as_deref(),
+                    range,
+                    window,
+                    ics,
+                )?;
+                calendars::cmd_add(&cli.host, cli.port, &calendar).await
+            }
+            CalendarCommands::Remove { name } => {
+                calendars::cmd_remove(&cli.host, cli.port, name).await
+            }
+        },
+        Some(Commands::Workflow { command }) => match command {
+            WorkflowCommands::List { json } => {
+                workflows::cmd_list(&cli.host, cli.port, *json).await
+            }
+            WorkflowCommands::Show { workflow, json } => {
+                workflows::cmd_show(&cli.host, cli.port, workflow, *json).await
+            }
+            WorkflowCommands::Add {
+                name,
+                nodes,
+                edges,
+                schedule,
+                every,
+                at,
+                manual,
+                timezone,
+                disabled,
+            } => {
+                let manual = *manual || (schedule.is_none() && every.is_none() && at.is_none());
+                let schedule = jobs::build_schedule(
+                    schedule.as_deref(),
+                    *every,
+                    at.as_deref(),
+                    manual,
+                    timezone.as_deref(),
+                )?;
+                let workflow = workflows::build_workflow(name, nodes, edges, schedule, *disabled)?;
+                workflows::cmd_add(&cli.host, cli.port, &workflow).await
+            }
+            WorkflowCommands::Remove { workflow } => {
+                workflows::cmd_remove(&cli.host, cli.port, workflow).await
+            }
+            WorkflowCommands::Trigger { workflow } => {
+                workflows::cmd_trigger(&cli.host, cli.port, workflow).await
+            }
+            WorkflowCommands::Runs {
+                workflow,
+                run,
+                last,
+                json,
+            } => {
+                workflows::cmd_runs(&cli.host, cli.port, workflow, run.as_deref(), *last, *json)
+                    .await
+            }
+        },
+        Some(Commands::Maintenance { command }) => match command {
+            MaintenanceCommands::Compress { format, json } => {
+                logs::cmd_compress(&cli.host, cli.port, *format, *json).await
+            }
+        },
+        Some(Commands::History {
+            last,
+            job,
+            selector,
+            status,
+            exit_code,
+            trigger,
+            since,
+            until,
+            search,
+            json,
+        }) => {
+            let filter = logs::HistoryFilter {
+                job: job.clone(),
+                selector: selector.clone(),
+                status: status.clone(),
+                exit_code: *exit_code,
+                trigger: trigger.clone(),
+                since: since.clone(),
+                until: until.clone(),
+                search: search.clone(),
+            };
+            logs::cmd_history(&cli.host, cli.port, &filter, *last, *json).await
+        }
         Some(Commands::Logs {
             job,
             follow,
             run,
             last,
             tail,
+            stream,
             json,
         }) => {
             logs::cmd_logs(
@@ -322,6 +947,7 @@ pub async fn dispatch(cli: &Cli) -> anyhow::Result<()> {
                 run.as_deref(),
                 *last,
                 *tail,
+                stream.as_deref(),
                 *json,
             )
             .await
@@ -390,7 +1016,7 @@ mod tests {
                 ..
             }) => {
                 assert_eq!(name, "test");
-                assert_eq!(schedule, "* * * * *");
+                assert_eq!(schedule.as_deref(), Some("* * * * *"));
                 assert_eq!(cmd.as_deref(), Some("echo hi"));
                 assert!(script.is_none());
                 assert!(!disabled);
@@ -411,6 +1037,7 @@ mod tests {
                 json,
                 enabled,
                 disabled,
+                ..
             }) => {
                 assert!(json);
                 assert!(!enabled);
@@ -466,6 +1093,7 @@ mod tests {
                 tail,
                 run,
                 last,
+                stream,
                 json,
             }) => {
                 assert_eq!(job, "test");
@@ -473,6 +1101,7 @@ mod tests {
                 assert_eq!(*tail, Some(100));
                 assert!(run.is_none());
                 assert!(last.is_none());
+                assert!(stream.is_none());
                 assert!(!json);
             }
             other => panic!("Expected Logs command, got: {:?}", other),
@@ -583,6 +1212,16 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_labels() {
+        let args = vec!["team=data".to_string(), "tier=".to_string()];
+        let result = parse_labels(&args).unwrap();
+        assert_eq!(result.get("team"), Some(&"data".to_string()));
+        assert_eq!(result.get("tier"), Some(&String::new()));
+        assert!(parse_labels(&["team".to_string()]).is_err());
+        assert!(parse_labels(&["=data".to_string()]).is_err());
+    }
+
     #[test]
     fn test_parse_env_vars_value_with_equals() {
         let args = vec!["KEY=val=ue".to_string()];
@@ -616,6 +1255,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_add_with_concurrency_policy() {
+        let cli = Cli::try_parse_from([
+            "acs",
+            "add",
+            "-n",
+            "queued-job",
+            "-s",
+            "* * * * *",
+            "-c",
+            "echo hi",
+            "--concurrency",
+            "queue:2",
+        ])
+        .expect("Should parse add --concurrency");
+
+        match &cli.command {
+            Some(Commands::Add { concurrency, .. }) => {
+                assert_eq!(
+                    *concurrency,
+                    Some(ConcurrencyPolicy::Queue { max_depth: 2 })
+                );
+            }
+            other => panic!("Expected Add command, got: {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from([
+            "acs",
+            "add",
+            "-n",
+            "x",
+            "-s",
+            "* * * * *",
+            "-c",
+            "true",
+            "--concurrency",
+            "sometimes",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_cli_add_with_catchup_policy() {
+        let cli = Cli::try_parse_from([
+            "acs",
+            "add",
+            "-n",
+            "nightly",
+            "-s",
+            "0 2 * * *",
+            "-c",
+            "backup.sh",
+            "--catchup",
+            "all:3",
+        ])
+        .expect("Should parse add --catchup");
+
+        match &cli.command {
+            Some(Commands::Add { catchup, .. }) => {
+                assert_eq!(*catchup, Some(CatchupPolicy::All { max_runs: 3 }));
+            }
+            other => panic!("Expected Add command, got: {:?}", other),
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Additional: trigger with --follow
     // -----------------------------------------------------------------------
@@ -744,6 +1448,7 @@ mod tests {
                 enabled,
                 disabled,
                 json,
+                ..
             }) => {
                 assert!(enabled);
                 assert!(!disabled);
@@ -766,6 +1471,7 @@ mod tests {
                 enabled,
                 disabled,
                 json,
+                ..
             }) => {
                 assert!(!enabled);
                 assert!(disabled);
@@ -804,6 +1510,61 @@ mod tests {
         assert!(result.is_err(), "-c and --script should conflict");
     }
 
+    // -----------------------------------------------------------------------
+    // Additional: add takes exactly one of -s, --every, --at or --manual
+    // -----------------------------------------------------------------------
+    #[test]
+    fn test_cli_add_schedule_kinds() {
+        let parse = |args: &[&str]| {
+            let mut argv = vec!["acs", "add", "-n", "test", "-c", "echo hi"];
+            argv.extend_from_slice(args);
+            Cli::try_parse_from(argv)
+        };
+
+        match parse(&["--every", "15m"]).expect("--every").command {
+            Some(Commands::Add {
+                every, schedule, ..
+            }) => {
+                assert_eq!(every.map(|i| i.as_secs()), Some(900));
+                assert!(schedule.is_none());
+            }
+            other => panic!("Expected Add command, got: {:?}", other),
+        }
+        match parse(&["--at", "2026-11-01T09:00"]).expect("--at").command {
+            Some(Commands::Add { at, .. }) => {
+                assert_eq!(at.as_deref(), Some("2026-11-01T09:00"));
+            }
+            other => panic!("Expected Add command, got: {:?}", other),
+        }
+        match parse(&["--manual"]).expect("--manual").command {
+            Some(Commands::Add { manual, .. }) => assert!(manual),
+            other => panic!("Expected Add command, got: {:?}", other),
+        }
+        match parse(&["-s", "H * * * *", "--jitter", "300"])
+            .expect("--jitter")
+            .command
+        {
+            Some(Commands::Add {
+                schedule, jitter, ..
+            }) => {
+                assert_eq!(schedule.as_deref(), Some("H * * * *"));
+                assert_eq!(jitter, Some(300));
+            }
+            other => panic!("Expected Add command, got: {:?}", other),
+        }
+
+        assert!(parse(&[]).is_err(), "a schedule is required");
+        assert!(parse(&["--every", "15"]).is_err(), "interval needs a unit");
+        assert!(
+            parse(&["-s", "* * * * *", "--manual"]).is_err(),
+            "-s and --manual should conflict"
+        );
+        assert!(
+            parse(&["--every", "1h", "--at", "2026-11-01T09:00"]).is_err(),
+            "--every and --at should conflict"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // Additional: logs with --run
     // -----------------------------------------------------------------------
@@ -844,6 +1605,92 @@ mod tests {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Additional: logs with --stream
+    // -----------------------------------------------------------------------
+    #[test]
+    fn test_cli_logs_with_stream() {
+        let cli = Cli::try_parse_from([
+            "acs", "logs", "my-job", "--run", "abc", "--stream", "stderr",
+        ])
+        .expect("Should parse logs --stream");
+
+        match &cli.command {
+            Some(Commands::Logs { stream, .. }) => {
+                assert_eq!(stream.as_deref(), Some("stderr"));
+            }
+            other => panic!("Expected Logs command, got: {:?}", other),
+        }
+
+        assert!(
+            Cli::try_parse_from(["acs", "logs", "my-job", "--stream", "both"]).is_err(),
+            "Unknown stream names should be rejected"
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Additional: history filters
+    // -----------------------------------------------------------------------
+    #[test]
+    fn test_cli_history_parses() {
+        let cli = Cli::try_parse_from([
+            "acs",
+            "history",
+            "-n",
+            "50",
+            "-l",
+            "tag=nightly",
+            "--exit-code",
+            "-1",
+            "--since",
+            "2h",
+        ])
+        .expect("Should parse history");
+
+        match &cli.command {
+            Some(Commands::History {
+                last,
+                selector,
+                exit_code,
+                since,
+                json,
+                ..
+            }) => {
+                assert_eq!(*last, 50);
+                assert_eq!(selector.as_deref(), Some("tag=nightly"));
+                assert_eq!(*exit_code, Some(-1));
+                assert_eq!(since.as_deref(), Some("2h"));
+                assert!(!json);
+            }
+            other => panic!("Expected History command, got: {:?}", other),
+        }
+
+        assert!(
+            Cli::try_parse_from(["acs", "history", "--job", "a", "-l", "tag=x"]).is_err(),
+            "--job and --selector should conflict"
+        );
+    }
+
+    #[test]
+    fn test_cli_maintenance_compress_parses() {
+        let cli = Cli::try_parse_from(["acs", "maintenance", "compress", "--format", "zst"])
+            .expect("Should parse maintenance compress");
+        match &cli.command {
+            Some(Commands::Maintenance {
+                command: MaintenanceCommands::Compress { format, json },
+            }) => {
+                assert_eq!(*format, Some(LogCompression::Zstd));
+                assert!(!json);
+            }
+            other => panic!("Expected Maintenance command, got: {:?}", other),
+        }
+
+        assert!(
+            Cli::try_parse_from(["acs", "maintenance", "compress", "--format", "lz4"]).is_err(),
+            "unknown formats should be rejected"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // Additional: base_url helper
     // -----------------------------------------------------------------------
@@ -895,8 +1742,9 @@ mod tests {
         let cli = Cli::try_parse_from(["acs", "enable", "my-job"]).expect("Should parse enable");
 
         match &cli.command {
-            Some(Commands::Enable { job }) => {
-                assert_eq!(job, "my-job");
+            Some(Commands::Enable { job, selector }) => {
+                assert_eq!(job.as_deref(), Some("my-job"));
+                assert!(selector.is_none());
             }
             other => panic!("Expected Enable command, got: {:?}", other),
         }
@@ -907,13 +1755,49 @@ mod tests {
         let cli = Cli::try_parse_from(["acs", "disable", "my-job"]).expect("Should parse disable");
 
         match &cli.command {
-            Some(Commands::Disable { job }) => {
-                assert_eq!(job, "my-job");
+            Some(Commands::Disable { job, selector }) => {
+                assert_eq!(job.as_deref(), Some("my-job"));
+                assert!(selector.is_none());
             }
             other => panic!("Expected Disable command, got: {:?}", other),
         }
     }
 
+    #[test]
+    fn test_cli_enable_and_list_by_selector() {
+        let cli = Cli::try_parse_from(["acs", "disable", "--selector", "team=ops"])
+            .expect("Should parse disable --selector");
+        match &cli.command {
+            Some(Commands::Disable { job, selector }) => {
+                assert!(job.is_none());
+                assert_eq!(selector.as_deref(), Some("team=ops"));
+            }
+            other => panic!("Expected Disable command, got: {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["acs", "enable"]).is_err());
+        assert!(Cli::try_parse_from(["acs", "enable", "my-job", "-l", "tag=x"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "acs",
+            "list",
+            "--tag",
+            "nightly",
+            "--tag",
+            "db",
+            "-l",
+            "team!=ops",
+        ])
+        .expect("Should parse list --tag");
+        match &cli.command {
+            Some(Commands::List { tags, selector, .. }) => {
+                assert_eq!(tags, &["nightly", "db"]);
+                assert_eq!(selector.as_deref(), Some("team!=ops"));
+            }
+            other => panic!("Expected List command, got: {:?}", other),
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Additional: logs --json flag
     // -----------------------------------------------------------------------
@@ -940,6 +1824,69 @@ mod tests {
         assert!(matches!(cli.command, Some(Commands::Restart)));
     }
 
+    // -----------------------------------------------------------------------
+    // Additional: kill command parses
+    // -----------------------------------------------------------------------
+    #[test]
+    fn test_cli_kill_parses() {
+        let cli = Cli::try_parse_from(["acs", "kill", "backup"]).expect("Should parse kill");
+        match &cli.command {
+            Some(Commands::Kill { target }) => assert_eq!(target, "backup"),
+            other => panic!("Expected Kill command, got: {:?}", other),
+        }
+
+        assert!(
+            Cli::try_parse_from(["acs", "kill"]).is_err(),
+            "kill requires a target"
+        );
+    }
+
+    #[test]
+    fn test_cli_apply_diff_export_parse() {
+        let cli = Cli::try_parse_from(["acs", "apply", "-f", "jobs.toml", "--prune"])
+            .expect("Should parse apply");
+        match &cli.command {
+            Some(Commands::Apply {
+                file,
+                format,
+                prune,
+            }) => {
+                assert_eq!(file, "jobs.toml");
+                assert_eq!(*format, None);
+                assert!(*prune);
+            }
+            other => panic!("Expected Apply command, got: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["acs", "diff", "-f", "-", "--format", "json"])
+            .expect("Should parse diff");
+        match &cli.command {
+            Some(Commands::Diff { format, prune, .. }) => {
+                assert_eq!(*format, Some(ManifestFormat::Json));
+                assert!(!*prune);
+            }
+            other => panic!("Expected Diff command, got: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["acs", "export", "-o", "jobs.yaml", "-l", "tag=nightly"])
+            .expect("Should parse export");
+        match &cli.command {
+            Some(Commands::Export {
+                output, selector, ..
+            }) => {
+                assert_eq!(output.as_deref(), Some("jobs.yaml"));
+                assert_eq!(selector.as_deref(), Some("tag=nightly"));
+            }
+            other => panic!("Expected Export command, got: {:?}", other),
+        }
+
+        assert!(
+            Cli::try_parse_from(["acs", "apply"]).is_err(),
+            "apply requires a file"
+        );
+        assert!(Cli::try_parse_from(["acs", "export", "--format", "xml"]).is_err());
+    }
+
     // -----------------------------------------------------------------------
     // Additional: global options with subcommand placed after
     // -----------------------------------------------------------------------
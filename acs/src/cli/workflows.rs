@@ -0,0 +1,345 @@
+// CLI workflow commands: workflow list, show, add, remove, trigger, runs
+
+use reqwest::Client;
+use serde_json::Value;
+
+use super::{base_url, connection_error_message};
+use crate::models::workflow::{WorkflowEdge, WorkflowNode};
+use crate::models::{NewWorkflow, Schedule, Workflow, WorkflowRun};
+
+/// Helper to handle reqwest errors and produce a user-friendly connection error.
+fn handle_request_error(err: reqwest::Error, host: &str, port: u16) -> anyhow::Error {
+    if err.is_connect() || err.is_timeout() {
+        anyhow::anyhow!("{}", connection_error_message(host, port))
+    } else {
+        anyhow::anyhow!("Request failed: {}", err)
+    }
+}
+
+/// Send a request and return the parsed body, exiting with the daemon's
+/// message if it reports an error.
+async fn send(request: reqwest::RequestBuilder, host: &str, port: u16) -> anyhow::Result<Value> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
+
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+    Ok(body)
+}
+
+/// Build the workflow of `acs workflow add` from its `--node` and `--edge`
+/// options.
+pub fn build_workflow(
+    name: &str,
+    nodes: &[String],
+    edges: &[String],
+    schedule: Schedule,
+    disabled: bool,
+) -> anyhow::Result<NewWorkflow> {
+    Ok(NewWorkflow {
+        name: name.to_string(),
+        schedule,
+        enabled: !disabled,
+        nodes: nodes.iter().map(|n| parse_node(n)).collect(),
+        edges: edges
+            .iter()
+            .map(|e| parse_edge(e))
+            .collect::<anyhow::Result<_>>()?,
+    })
+}
+
+/// Parse a `--node`: `NAME=JOB`, or just `JOB` to name the node after it.
+fn parse_node(s: &str) -> WorkflowNode {
+    let (name, job) = s.split_once('=').unwrap_or((s, s));
+    WorkflowNode {
+        name: name.trim().to_string(),
+        job: job.trim().to_string(),
+    }
+}
+
+/// Parse an `--edge`: `FROM:TO`, optionally followed by `:on_success`,
+/// `:on_failure` or `:always`.
+fn parse_edge(s: &str) -> anyhow::Result<WorkflowEdge> {
+    let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+    let (from, to, condition) = match parts.as_slice() {
+        [from, to] => (from, to, Default::default()),
+        [from, to, condition] => (
+            from,
+            to,
+            condition.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+        ),
+        _ => anyhow::bail!("Invalid edge '{}': expected FROM:TO[:CONDITION]", s),
+    };
+    Ok(WorkflowEdge {
+        from: from.to_string(),
+        to: to.to_string(),
+        condition,
+    })
+}
+
+/// acs workflow list
+pub async fn cmd_list(host: &str, port: u16, json: bool) -> anyhow::Result<()> {
+    let url = format!("{}/api/workflows", base_url(host, port));
+    let body = send(Client::new().get(&url), host, port).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let workflows: Vec<Workflow> = serde_json::from_value(body)?;
+    if workflows.is_empty() {
+        println!("No workflows found.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<20}{:<24}{:<9}{:<7}NEXT RUN",
+        "NAME", "SCHEDULE", "ENABLED", "NODES"
+    );
+    for workflow in workflows {
+        println!(
+            "{:<20}{:<24}{:<9}{:<7}{}",
+            workflow.name,
+            workflow.schedule.to_string(),
+            if workflow.enabled { "yes" } else { "no" },
+            workflow.nodes.len(),
+            workflow
+                .next_run_at
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+
+    Ok(())
+}
+
+/// acs workflow show
+pub async fn cmd_show(host: &str, port: u16, workflow: &str, json: bool) -> anyhow::Result<()> {
+    let url = format!("{}/api/workflows/{}", base_url(host, port), workflow);
+    let body = send(Client::new().get(&url), host, port).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let workflow: Workflow = serde_json::from_value(body)?;
+    println!("Workflow: {}", workflow.name);
+    println!("  ID:       {}", workflow.id);
+    println!("  Schedule: {}", workflow.schedule);
+    println!("  Enabled:  {}", workflow.enabled);
+    if let Some(next) = workflow.next_run_at {
+        println!("  Next run: {}", next.to_rfc3339());
+    }
+    for node in &workflow.nodes {
+        if node.name == node.job {
+            println!("  Node:     {}", node.name);
+        } else {
+            println!("  Node:     {} (job: {})", node.name, node.job);
+        }
+    }
+    for edge in &workflow.edges {
+        println!(
+            "  Edge:     {} -> {} ({})",
+            edge.from, edge.to, edge.condition
+        );
+    }
+
+    Ok(())
+}
+
+/// acs workflow add
+pub async fn cmd_add(host: &str, port: u16, workflow: &NewWorkflow) -> anyhow::Result<()> {
+    let url = format!("{}/api/workflows", base_url(host, port));
+    send(Client::new().post(&url).json(workflow), host, port).await?;
+
+    println!("Workflow '{}' created successfully.", workflow.name);
+    println!("  Schedule: {}", workflow.schedule);
+    println!("  Nodes:    {}", workflow.nodes.len());
+    println!("  Edges:    {}", workflow.edges.len());
+
+    Ok(())
+}
+
+/// acs workflow remove
+pub async fn cmd_remove(host: &str, port: u16, workflow: &str) -> anyhow::Result<()> {
+    let url = format!("{}/api/workflows/{}", base_url(host, port), workflow);
+
+    let response = Client::new()
+        .delete(&url)
+        .send()
+        .await
+        .map_err(|e| handle_request_error(e, host, port))?;
+
+    if response.status().as_u16() == 204 {
+        println!("Workflow '{}' removed.", workflow);
+    } else {
+        let body: Value = response
+            .json()
+            .await
+            .unwrap_or_else(|_| serde_json::json!({"message": "Unknown error"}));
+        let message = body["message"].as_str().unwrap_or("Unknown error");
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// acs workflow trigger
+pub async fn cmd_trigger(host: &str, port: u16, workflow: &str) -> anyhow::Result<()> {
+    let url = format!(
+        "{}/api/workflows/{}/trigger",
+        base_url(host, port),
+        workflow
+    );
+    let body = send(Client::new().post(&url), host, port).await?;
+
+    let run: WorkflowRun = serde_json::from_value(body)?;
+    println!(
+        "Workflow '{}' triggered (workflow run: {})",
+        run.workflow_name, run.id
+    );
+    print_nodes(&run);
+
+    Ok(())
+}
+
+/// acs workflow runs
+pub async fn cmd_runs(
+    host: &str,
+    port: u16,
+    workflow: &str,
+    run: Option<&str>,
+    last: usize,
+    json: bool,
+) -> anyhow::Result<()> {
+    let base = format!("{}/api/workflows/{}/runs", base_url(host, port), workflow);
+
+    if let Some(run_id) = run {
+        let body = send(
+            Client::new().get(format!("{}/{}", base, run_id)),
+            host,
+            port,
+        )
+        .await?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&body)?);
+            return Ok(());
+        }
+        let run: WorkflowRun = serde_json::from_value(body)?;
+        println!("Workflow run {} of '{}'", run.id, run.workflow_name);
+        println!("  Status:   {:?}", run.status);
+        println!("  Started:  {}", run.started_at.to_rfc3339());
+        if let Some(finished) = run.finished_at {
+            println!("  Finished: {}", finished.to_rfc3339());
+        }
+        print_nodes(&run);
+        return Ok(());
+    }
+
+    let body = send(
+        Client::new().get(&base).query(&[("limit", last)]),
+        host,
+        port,
+    )
+    .await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let runs: Vec<WorkflowRun> = serde_json::from_value(body["runs"].clone())?;
+    if runs.is_empty() {
+        println!("No runs found.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<38}{:<11}{:<27}NODES",
+        "WORKFLOW RUN", "STATUS", "STARTED"
+    );
+    for run in runs {
+        let done = run.nodes.iter().filter(|n| n.status.is_finished()).count();
+        println!(
+            "{:<38}{:<11}{:<27}{}/{}",
+            run.id.to_string(),
+            format!("{:?}", run.status),
+            run.started_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            done,
+            run.nodes.len()
+        );
+    }
+
+    Ok(())
+}
+
+fn print_nodes(run: &WorkflowRun) {
+    for node in &run.nodes {
+        let mut line = format!("  {:<20}{:<11}", node.node, format!("{:?}", node.status));
+        if let Some(run_id) = node.run_id {
+            line.push_str(&format!("run {}", run_id));
+            if node.attempt > 1 {
+                line.push_str(&format!(" (attempt {})", node.attempt));
+            }
+        }
+        if let Some(ref error) = node.error {
+            line.push_str(&format!("  {}", error));
+        }
+        println!("{}", line.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::workflow::EdgeCondition;
+
+    #[test]
+    fn test_build_workflow_nodes_and_edges() {
+        let workflow = build_workflow(
+            "release",
+            &["build".to_string(), "ship=deploy-prod".to_string()],
+            &["build:ship".to_string(), "build:ship:always".to_string()],
+            Schedule::Manual,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(workflow.nodes[0].name, "build");
+        assert_eq!(workflow.nodes[0].job, "build");
+        assert_eq!(workflow.nodes[1].name, "ship");
+        assert_eq!(workflow.nodes[1].job, "deploy-prod");
+        assert_eq!(workflow.edges[0].condition, EdgeCondition::OnSuccess);
+        assert_eq!(workflow.edges[1].condition, EdgeCondition::Always);
+        assert!(workflow.enabled);
+    }
+
+    #[test]
+    fn test_build_workflow_rejects_malformed_edges() {
+        let build = |edge: &str| {
+            build_workflow(
+                "w",
+                &["a".to_string()],
+                &[edge.to_string()],
+                Schedule::Manual,
+                false,
+            )
+        };
+        assert!(build("a").is_err());
+        assert!(build("a:b:sometimes").is_err());
+        assert!(build("a:b:c:d").is_err());
+    }
+}
@@ -0,0 +1,1186 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::Utc;
+use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
+use tracing;
+use uuid::Uuid;
+
+use crate::daemon::events::JobEvent;
+use crate::daemon::executor::{Executor, RunHandle};
+use crate::daemon::scheduler::expire_job;
+use crate::models::{
+    ConcurrencyPolicy, DaemonConfig, DispatchRequest, JobRun, JobUpdate, KillReason, QueuedRun,
+    RunStatus, TriggerSource,
+};
+use crate::storage::{JobStore, LogStore};
+
+/// A dispatch in the run queue.
+struct QueueEntry {
+    request: DispatchRequest,
+    info: QueuedRun,
+    /// Whether the run has been recorded with status `Queued` yet.
+    recorded: bool,
+}
+
+/// Dispatches waiting for a free slot, in the order they will be started:
+/// by descending priority, then first come, first served.
+///
+/// Shared between the dispatcher, which fills and drains it, and the API,
+/// which lists and cancels queued runs.
+#[derive(Clone, Default)]
+pub struct RunQueue {
+    entries: Arc<RwLock<Vec<QueueEntry>>>,
+}
+
+impl RunQueue {
+    /// The queued runs, in the order they will be started.
+    pub async fn list(&self) -> Vec<QueuedRun> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .map(|e| e.info.clone())
+            .collect()
+    }
+
+    /// Run IDs of the job's queued runs.
+    async fn job_runs(&self, job_id: Uuid) -> Vec<Uuid> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .filter(|e| e.info.job_id == job_id)
+            .map(|e| e.info.run_id)
+            .collect()
+    }
+
+    /// Add a request behind every queued run of the same or higher priority.
+    async fn push(&self, request: DispatchRequest) {
+        let info = QueuedRun {
+            run_id: request.run_id,
+            job_id: request.job.id,
+            job_name: request.job.name.clone(),
+            priority: request.job.priority,
+            pool: request.job.pool.clone(),
+            attempt: request.attempt,
+            queued_at: Utc::now(),
+            waiting_for: String::new(),
+        };
+        let mut entries = self.entries.write().await;
+        let index = entries
+            .iter()
+            .position(|e| e.info.priority < info.priority)
+            .unwrap_or(entries.len());
+        entries.insert(
+            index,
+            QueueEntry {
+                request,
+                info,
+                recorded: false,
+            },
+        );
+    }
+
+    /// Take the job's queued requests out of the queue.
+    async fn take_job(&self, job_id: Uuid) -> Vec<DispatchRequest> {
+        self.take_job_entries(job_id)
+            .await
+            .into_iter()
+            .map(|e| e.request)
+            .collect()
+    }
+
+    async fn take_job_entries(&self, job_id: Uuid) -> Vec<QueueEntry> {
+        let mut entries = self.entries.write().await;
+        let (taken, kept) = std::mem::take(&mut *entries)
+            .into_iter()
+            .partition(|e| e.info.job_id == job_id);
+        *entries = kept;
+        taken
+    }
+
+    /// Remove a queued run and record it as `Killed` for `reason`. Returns
+    /// `None` if the run is not queued.
+    pub async fn cancel(
+        &self,
+        run_id: Uuid,
+        reason: KillReason,
+        log_store: &dyn LogStore,
+        event_tx: &broadcast::Sender<JobEvent>,
+    ) -> Option<JobRun> {
+        self.cancel_matching(|run| run.run_id == run_id, reason, log_store, event_tx)
+            .await
+            .pop()
+    }
+
+    /// Cancel every queued run of a job.
+    pub async fn cancel_job(
+        &self,
+        job_id: Uuid,
+        reason: KillReason,
+        log_store: &dyn LogStore,
+        event_tx: &broadcast::Sender<JobEvent>,
+    ) -> Vec<JobRun> {
+        self.cancel_matching(|run| run.job_id == job_id, reason, log_store, event_tx)
+            .await
+    }
+
+    /// Cancel every queued run, e.g. when the daemon shuts down.
+    pub async fn cancel_all(
+        &self,
+        reason: KillReason,
+        log_store: &dyn LogStore,
+        event_tx: &broadcast::Sender<JobEvent>,
+    ) -> Vec<JobRun> {
+        self.cancel_matching(|_| true, reason, log_store, event_tx)
+            .await
+    }
+
+    /// The write lock is held until the cancelled runs are recorded, so the
+    /// dispatcher cannot record one of them as `Queued` afterwards.
+    async fn cancel_matching(
+        &self,
+        matches: impl Fn(&QueuedRun) -> bool,
+        reason: KillReason,
+        log_store: &dyn LogStore,
+        event_tx: &broadcast::Sender<JobEvent>,
+    ) -> Vec<JobRun> {
+        let mut entries = self.entries.write().await;
+        let (cancelled, kept): (Vec<QueueEntry>, Vec<QueueEntry>) = std::mem::take(&mut *entries)
+            .into_iter()
+            .partition(|e| matches(&e.info));
+        *entries = kept;
+
+        let now = Utc::now();
+        let mut runs = Vec::with_capacity(cancelled.len());
+        for entry in cancelled {
+            let request = entry.request;
+            let run = JobRun {
+                run_id: request.run_id,
+                job_id: request.job.id,
+                started_at: entry.info.queued_at,
+                finished_at: Some(now),
+                status: RunStatus::Killed,
+                exit_code: None,
+                log_size_bytes: 0,
+                error: Some(reason.to_string()),
+                trigger_params: request.trigger_params,
+                signal: None,
+                attempt: request.attempt,
+                parent_run_id: request.parent_run_id,
+                scheduled_for: request.scheduled_for,
+                catch_up: request.catch_up,
+                trigger: Some(request.trigger),
+            };
+            tracing::info!(
+                "Queued run {} of job {} cancelled: {}",
+                run.run_id,
+                request.job.name,
+                reason
+            );
+            if let Err(e) = log_store.create_run(&run).await {
+                tracing::error!("Failed to record cancelled run {}: {}", run.run_id, e);
+            }
+            let _ = event_tx.send(JobEvent::Killed {
+                job_id: run.job_id,
+                run_id: run.run_id,
+                reason,
+                signal: None,
+                timestamp: now,
+            });
+            runs.push(run);
+        }
+        runs
+    }
+}
+
+/// Whether a request is an occurrence of the job's schedule, which counts
+/// towards the job's `max_runs` once it is accepted. Retries, triggers and
+/// occurrences excluded by a calendar do not.
+fn counts_towards_max_runs(request: &DispatchRequest) -> bool {
+    request.attempt == 1
+        && request.skip_reason.is_none()
+        && matches!(
+            request.trigger,
+            TriggerSource::Schedule | TriggerSource::CatchUp
+        )
+}
+
+/// Receives dispatch requests (from the scheduler and the trigger API),
+/// spawns them through the Executor, and keeps `active_runs` up to date.
+///
+/// `active_runs` is keyed by run ID, so any number of runs of the same job can
+/// be tracked at once. Each entry is removed as soon as its run finishes.
+///
+/// Requests that carry a `skip_reason` (e.g. an occurrence excluded by a
+/// calendar) are recorded as `Skipped` straight away.
+///
+/// Before starting a run the dispatcher applies the job's
+/// [`ConcurrencyPolicy`]: `Forbid` records the dispatch as `Skipped` while a
+/// run is in progress, `Queue` holds it until the job is idle, and `Replace`
+/// kills the running instance and starts the new one once it has exited.
+///
+/// Every accepted request then goes through the [`RunQueue`], which holds it
+/// while its job is busy or the daemon's `max_concurrent_runs` or the job's
+/// concurrency pool is full. Requests that cannot start straight away are
+/// recorded as `Queued`.
+pub struct Dispatcher {
+    executor: Executor,
+    job_store: Arc<dyn JobStore>,
+    active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>>,
+    run_queue: RunQueue,
+    /// Runs allowed at once across all jobs; 0 means no limit.
+    max_concurrent_runs: usize,
+    /// Runs allowed at once per concurrency pool; 0 means no limit.
+    pools: HashMap<String, usize>,
+    /// Pool of every run started by the dispatcher that has not finished.
+    started: HashMap<Uuid, Option<String>>,
+    /// Run IDs of finished runs, sent by the wrappers spawned in `track`.
+    finished_tx: mpsc::UnboundedSender<Uuid>,
+    finished_rx: mpsc::UnboundedReceiver<Uuid>,
+}
+
+impl Dispatcher {
+    /// Create a new Dispatcher.
+    pub fn new(
+        executor: Executor,
+        job_store: Arc<dyn JobStore>,
+        active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>>,
+    ) -> Self {
+        let (finished_tx, finished_rx) = mpsc::unbounded_channel();
+        Self {
+            executor,
+            job_store,
+            active_runs,
+            run_queue: RunQueue::default(),
+            max_concurrent_runs: 0,
+            pools: HashMap::new(),
+            started: HashMap::new(),
+            finished_tx,
+            finished_rx,
+        }
+    }
+
+    /// Use a run queue shared with the API.
+    pub fn with_queue(mut self, run_queue: RunQueue) -> Self {
+        self.run_queue = run_queue;
+        self
+    }
+
+    /// Apply the daemon's `max_concurrent_runs` and `concurrency_pools`.
+    pub fn with_limits(mut self, config: &DaemonConfig) -> Self {
+        self.max_concurrent_runs = config.max_concurrent_runs;
+        self.pools = config.concurrency_pools.clone();
+        self
+    }
+
+    /// Process dispatch requests until the channel is closed.
+    ///
+    /// Requests still waiting in the queue when the channel closes are left
+    /// there.
+    pub async fn run(mut self, mut dispatch_rx: mpsc::Receiver<DispatchRequest>) {
+        loop {
+            tokio::select! {
+                request = dispatch_rx.recv() => match request {
+                    Some(request) => self.dispatch(request).await,
+                    None => break,
+                },
+                Some(run_id) = self.finished_rx.recv() => {
+                    self.started.remove(&run_id);
+                    self.start_queued().await;
+                }
+            }
+        }
+    }
+
+    /// Apply the job's concurrency policy to a request, then queue or skip
+    /// it, and start whatever the queue allows.
+    pub async fn dispatch(&mut self, mut request: DispatchRequest) {
+        let job_id = request.job.id;
+
+        if let Some(reason) = request.skip_reason.take() {
+            self.skip(request, &format!("Skipped: {}", reason)).await;
+            return;
+        }
+
+        // A retry was scheduled from the job as it was when the failed
+        // attempt started, and an occurrence's count towards `max_runs` must
+        // be the current one; pick up any changes made since then.
+        let counted = counts_towards_max_runs(&request);
+        if request.attempt > 1 || counted {
+            match self.job_store.get_job(job_id).await {
+                Ok(Some(job)) => request.job = job,
+                Ok(None) => {
+                    tracing::info!(
+                        "Dropping run of job {} (attempt {}, trigger {:?}): the job was deleted",
+                        request.job.name,
+                        request.attempt,
+                        request.trigger
+                    );
+                    return;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to reload job {}: {}", request.job.name, e);
+                }
+            }
+        }
+
+        if counted
+            && request
+                .job
+                .max_runs
+                .is_some_and(|max| request.job.scheduled_runs >= max)
+        {
+            tracing::info!(
+                "Dropping scheduled run of job {}: it has used up its max_runs",
+                request.job.name
+            );
+            return;
+        }
+
+        let running = self.running_runs(job_id).await;
+        let queued = self.run_queue.job_runs(job_id).await;
+        let busy = !running.is_empty() || !queued.is_empty();
+
+        match request.job.concurrency_policy {
+            ConcurrencyPolicy::Allow => {
+                self.accept(request).await;
+            }
+            _ if !busy => {
+                self.accept(request).await;
+            }
+            ConcurrencyPolicy::Forbid => {
+                self.skip(request, "Skipped: a previous run is still in progress")
+                    .await;
+            }
+            ConcurrencyPolicy::Queue { max_depth } => {
+                if queued.len() < max_depth {
+                    self.accept(request).await;
+                } else {
+                    let reason = format!("Skipped: run queue is full (max depth {})", max_depth);
+                    self.skip(request, &reason).await;
+                }
+            }
+            ConcurrencyPolicy::Replace => {
+                self.kill_runs(&running, KillReason::Replaced).await;
+                for old in self.run_queue.take_job(job_id).await {
+                    self.skip(old, "Skipped: superseded by a newer run").await;
+                }
+                // Waits in the queue until the old runs have exited.
+                self.accept(request).await;
+            }
+        }
+
+        self.start_queued().await;
+    }
+
+    /// Put a request that passed the concurrency policy in the run queue.
+    /// An occurrence of the job's schedule counts towards its `max_runs`,
+    /// and the job expires with the last one.
+    async fn accept(&self, request: DispatchRequest) {
+        if counts_towards_max_runs(&request) {
+            let job = &request.job;
+            let scheduled_runs = job.scheduled_runs.saturating_add(1);
+            let update = JobUpdate {
+                scheduled_runs: Some(scheduled_runs),
+                ..Default::default()
+            };
+            if let Err(e) = self.job_store.update_job(job.id, update).await {
+                tracing::error!("Failed to count scheduled run of job {}: {}", job.name, e);
+            }
+            if let Some(max_runs) = job.max_runs.filter(|max| scheduled_runs >= *max) {
+                expire_job(
+                    self.job_store.as_ref(),
+                    Some(self.executor.event_tx()),
+                    job,
+                    &format!("reached max_runs ({})", max_runs),
+                    Utc::now(),
+                )
+                .await;
+            }
+        }
+        self.run_queue.push(request).await;
+    }
+
+    /// Start queued requests, in queue order, for as long as the limits
+    /// allow, then record the ones left waiting as `Queued`.
+    ///
+    /// Each job is reloaded first so the run uses its current definition;
+    /// requests of a job that has been deleted are dropped.
+    async fn start_queued(&mut self) {
+        while let Some(entry) = self.next_startable().await {
+            let request = match self.job_store.get_job(entry.info.job_id).await {
+                Ok(Some(job)) => DispatchRequest {
+                    job,
+                    ..entry.request
+                },
+                Ok(None) => {
+                    self.drop_deleted_job(entry).await;
+                    continue;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to reload job {}: {}", entry.info.job_name, e);
+                    entry.request
+                }
+            };
+            self.start(request).await;
+        }
+
+        let mut entries = self.run_queue.entries.write().await;
+        for entry in entries.iter_mut().filter(|e| !e.recorded) {
+            entry.recorded = true;
+            tracing::info!(
+                "Job {}: queued, waiting for {}",
+                entry.info.job_name,
+                entry.info.waiting_for
+            );
+            if let Err(e) = self
+                .executor
+                .record_queued(
+                    &entry.request,
+                    entry.info.queued_at,
+                    &entry.info.waiting_for,
+                )
+                .await
+            {
+                tracing::error!(
+                    "Failed to record queued run of job {}: {}",
+                    entry.info.job_name,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Drop the queued requests of a deleted job, `first` among them. The
+    /// job's run history went with it, so runs already recorded as `Queued`
+    /// are removed rather than left queued forever.
+    async fn drop_deleted_job(&self, first: QueueEntry) {
+        let job_id = first.info.job_id;
+        let mut dropped = vec![first];
+        dropped.extend(self.run_queue.take_job_entries(job_id).await);
+
+        let recorded: Vec<Uuid> = dropped
+            .iter()
+            .filter(|e| e.recorded)
+            .map(|e| e.info.run_id)
+            .collect();
+        if !recorded.is_empty() {
+            if let Err(e) = self
+                .executor
+                .log_store()
+                .remove_runs(job_id, &recorded)
+                .await
+            {
+                tracing::error!(
+                    "Failed to remove queued runs of deleted job {}: {}",
+                    job_id,
+                    e
+                );
+            }
+        }
+
+        let now = Utc::now();
+        for entry in dropped {
+            tracing::info!(
+                "Dropping queued run {} of job {}: the job was deleted",
+                entry.info.run_id,
+                entry.info.job_name
+            );
+            let _ = self.executor.event_tx().send(JobEvent::Killed {
+                job_id,
+                run_id: entry.info.run_id,
+                reason: KillReason::JobDeleted,
+                signal: None,
+                timestamp: now,
+            });
+        }
+    }
+
+    /// Take the first queued request that may start now out of the queue,
+    /// noting on the ones before it what they are waiting for.
+    async fn next_startable(&self) -> Option<QueueEntry> {
+        let active_jobs: HashSet<Uuid> = self
+            .active_runs
+            .read()
+            .await
+            .values()
+            .map(|h| h.job_id)
+            .collect();
+
+        let mut entries = self.run_queue.entries.write().await;
+        // Jobs with an earlier entry still waiting: runs of a job start in order.
+        let mut waiting_jobs = HashSet::new();
+        let mut startable = None;
+        for (index, entry) in entries.iter_mut().enumerate() {
+            let job = &entry.request.job;
+            let exclusive = !matches!(job.concurrency_policy, ConcurrencyPolicy::Allow);
+            let waiting_for =
+                if self.max_concurrent_runs > 0 && self.started.len() >= self.max_concurrent_runs {
+                    format!(
+                        "a free slot (max_concurrent_runs {})",
+                        self.max_concurrent_runs
+                    )
+                } else if exclusive && active_jobs.contains(&job.id) {
+                    "the job's running instance to finish".to_string()
+                } else if waiting_jobs.contains(&job.id) {
+                    "earlier runs of the job".to_string()
+                } else if let Some(limit) = self.pool_limit_reached(job.pool.as_deref()) {
+                    format!(
+                        "a free slot in pool '{}' ({} at once)",
+                        job.pool.as_deref().unwrap_or_default(),
+                        limit
+                    )
+                } else {
+                    startable = Some(index);
+                    break;
+                };
+            waiting_jobs.insert(job.id);
+            entry.info.waiting_for = waiting_for;
+        }
+        startable.map(|index| entries.remove(index))
+    }
+
+    /// The pool's limit, if the pool already has that many runs going.
+    fn pool_limit_reached(&self, pool: Option<&str>) -> Option<usize> {
+        let pool = pool?;
+        let limit = self.pools.get(pool).copied().filter(|&limit| limit > 0)?;
+        let running = self
+            .started
+            .values()
+            .filter(|p| p.as_deref() == Some(pool))
+            .count();
+        (running >= limit).then_some(limit)
+    }
+
+    /// Run IDs of the job's runs that are currently active.
+    async fn running_runs(&self, job_id: Uuid) -> Vec<Uuid> {
+        self.active_runs
+            .read()
+            .await
+            .values()
+            .filter(|h| h.job_id == job_id)
+            .map(|h| h.run_id)
+            .collect()
+    }
+
+    /// Signal runs to stop. Their handles stay registered until they exit,
+    /// so the job keeps counting as busy until then.
+    async fn kill_runs(&self, run_ids: &[Uuid], reason: KillReason) {
+        let mut runs = self.active_runs.write().await;
+        for run_id in run_ids {
+            if let Some(handle) = runs.get_mut(run_id) {
+                let kill_tx = std::mem::replace(&mut handle.kill_tx, oneshot::channel().0);
+                let _ = kill_tx.send(reason);
+            }
+        }
+    }
+
+    /// Record a request that will not run.
+    async fn skip(&self, request: DispatchRequest, reason: &str) {
+        tracing::info!("Job {}: {}", request.job.name, reason);
+        if let Err(e) = self.executor.record_skipped(&request, reason).await {
+            tracing::error!(
+                "Failed to record skipped run of job {}: {}",
+                request.job.name,
+                e
+            );
+        }
+    }
+
+    /// Spawn a single run and register it in `active_runs`.
+    async fn start(&mut self, request: DispatchRequest) {
+        match self.executor.spawn_request(&request).await {
+            Ok(handle) => {
+                self.started.insert(handle.run_id, request.job.pool);
+                self.track(handle).await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to spawn job {}: {}", request.job.name, e);
+            }
+        }
+    }
+
+    /// Insert a run into `active_runs` and remove it again once it finishes.
+    ///
+    /// The stored handle's `join_handle` is replaced by a wrapper task that
+    /// awaits the run, deregisters it and wakes the dispatch loop, so awaiting
+    /// the stored handle still waits for the run itself.
+    async fn track(&self, handle: RunHandle) {
+        let run_id = handle.run_id;
+
+        // Hold the write lock while the wrapper is spawned: a run that
+        // finishes immediately must not try to deregister before it has been
+        // inserted.
+        let mut runs = self.active_runs.write().await;
+
+        let task = handle.join_handle;
+        let active_runs = Arc::clone(&self.active_runs);
+        let finished_tx = self.finished_tx.clone();
+        let join_handle = tokio::spawn(async move {
+            if let Err(e) = task.await {
+                tracing::error!("Run {} task failed: {}", run_id, e);
+            }
+            active_runs.write().await.remove(&run_id);
+            let _ = finished_tx.send(run_id);
+        });
+
+        runs.insert(
+            run_id,
+            RunHandle {
+                join_handle,
+                ..handle
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::events::{JobChangeKind, JobEvent};
+    use crate::models::{
+        DaemonConfig, ExecutionType, Job, JobRun, NewJob, RunStatus, Schedule, TriggerSource,
+    };
+    use crate::pty::{MockPtySpawner, PtySpawner};
+    use crate::storage::jobs::JsonJobStore;
+    use crate::storage::logs::FsLogStore;
+    use crate::storage::LogStore;
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use tokio::sync::broadcast;
+
+    struct Harness {
+        dispatcher: Dispatcher,
+        active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>>,
+        log_store: Arc<dyn LogStore>,
+        job_store: Arc<dyn JobStore>,
+        job: Job,
+    }
+
+    async fn make_harness(
+        spawner: MockPtySpawner,
+        tmp: &TempDir,
+        concurrency_policy: ConcurrencyPolicy,
+    ) -> Harness {
+        let (event_tx, _) = broadcast::channel::<JobEvent>(256);
+        let log_store = Arc::new(
+            FsLogStore::new(tmp.path().to_path_buf())
+                .await
+                .expect("log store"),
+        ) as Arc<dyn LogStore>;
+        let job_store = Arc::new(
+            JsonJobStore::new(tmp.path().to_path_buf())
+                .await
+                .expect("job store"),
+        ) as Arc<dyn JobStore>;
+        let job = create_job(
+            job_store.as_ref(),
+            "dispatch-job",
+            concurrency_policy,
+            None,
+            0,
+        )
+        .await;
+        let executor = Executor::new(
+            event_tx,
+            Arc::clone(&log_store),
+            Arc::new(DaemonConfig::default()),
+            Arc::new(spawner) as Arc<dyn PtySpawner>,
+        );
+        let active_runs = Arc::new(RwLock::new(HashMap::new()));
+        Harness {
+            dispatcher: Dispatcher::new(executor, Arc::clone(&job_store), Arc::clone(&active_runs)),
+            active_runs,
+            log_store,
+            job_store,
+            job,
+        }
+    }
+
+    async fn create_job(
+        job_store: &dyn JobStore,
+        name: &str,
+        concurrency_policy: ConcurrencyPolicy,
+        pool: Option<&str>,
+        priority: i32,
+    ) -> Job {
+        job_store
+            .create_job(NewJob {
+                name: name.to_string(),
+                schedule: Schedule::cron("* * * * *"),
+                execution: ExecutionType::ShellCommand("echo hi".to_string()),
+                enabled: true,
+                jitter_secs: 0,
+                timezone: None,
+                working_dir: None,
+                env_vars: None,
+                timeout_secs: 0,
+                log_environment: false,
+                concurrency_policy,
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: pool.map(str::to_string),
+                priority,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
+            })
+            .await
+            .expect("create job")
+    }
+
+    fn request(job: &Job) -> DispatchRequest {
+        DispatchRequest {
+            job: job.clone(),
+            run_id: Uuid::now_v7(),
+            trigger_params: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: TriggerSource::Manual,
+            skip_reason: None,
+        }
+    }
+
+    async fn wait_until_empty(active_runs: &RwLock<HashMap<Uuid, RunHandle>>) {
+        for _ in 0..200 {
+            if active_runs.read().await.is_empty() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("active runs were not removed after finishing");
+    }
+
+    /// Wait until `count` runs of the job have finished, and return them.
+    async fn wait_for_finished_runs(
+        log_store: &dyn LogStore,
+        job_id: Uuid,
+        count: usize,
+    ) -> Vec<JobRun> {
+        for _ in 0..300 {
+            let (runs, _) = log_store.list_runs(job_id, 100, 0).await.expect("list");
+            let finished: Vec<JobRun> = runs
+                .into_iter()
+                .filter(|r| r.finished_at.is_some())
+                .collect();
+            if finished.len() >= count {
+                return finished;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("expected {} finished runs", count);
+    }
+
+    fn find_run(runs: &[JobRun], run_id: Uuid) -> &JobRun {
+        runs.iter()
+            .find(|r| r.run_id == run_id)
+            .expect("run should be recorded")
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_runs_of_same_job_are_all_tracked() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 300);
+        let mut h = make_harness(spawner, &tmp, ConcurrencyPolicy::Allow).await;
+
+        let first = request(&h.job);
+        let second = request(&h.job);
+        let (first_id, second_id) = (first.run_id, second.run_id);
+        h.dispatcher.dispatch(first).await;
+        h.dispatcher.dispatch(second).await;
+
+        {
+            let runs = h.active_runs.read().await;
+            assert_eq!(runs.len(), 2);
+            assert!(runs.contains_key(&first_id));
+            assert!(runs.contains_key(&second_id));
+            assert!(runs.values().all(|r| r.job_id == h.job.id));
+        }
+
+        wait_until_empty(&h.active_runs).await;
+    }
+
+    #[tokio::test]
+    async fn test_fast_run_is_removed_when_finished() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_output_and_exit(vec![b"done\n".to_vec()], 0);
+        let mut h = make_harness(spawner, &tmp, ConcurrencyPolicy::Allow).await;
+
+        let req = request(&h.job);
+        h.dispatcher.dispatch(req).await;
+
+        wait_until_empty(&h.active_runs).await;
+    }
+
+    #[tokio::test]
+    async fn test_awaiting_tracked_handle_waits_for_run() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 100);
+        let mut h = make_harness(spawner, &tmp, ConcurrencyPolicy::Allow).await;
+        let req = request(&h.job);
+        let run_id = req.run_id;
+
+        h.dispatcher.dispatch(req).await;
+        let handle = h
+            .active_runs
+            .write()
+            .await
+            .remove(&run_id)
+            .expect("run should be active");
+        handle.join_handle.await.expect("join");
+
+        assert!(h.active_runs.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forbid_records_skipped_run_while_busy() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 200);
+        let mut h = make_harness(spawner, &tmp, ConcurrencyPolicy::Forbid).await;
+
+        let first = request(&h.job);
+        let second = request(&h.job);
+        let (first_id, second_id) = (first.run_id, second.run_id);
+        h.dispatcher.dispatch(first).await;
+        h.dispatcher.dispatch(second).await;
+
+        assert_eq!(h.active_runs.read().await.len(), 1);
+        let runs = wait_for_finished_runs(h.log_store.as_ref(), h.job.id, 2).await;
+        assert_eq!(find_run(&runs, first_id).status, RunStatus::Completed);
+        let skipped = find_run(&runs, second_id);
+        assert_eq!(skipped.status, RunStatus::Skipped);
+        assert!(skipped
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("still in progress"));
+    }
+
+    #[tokio::test]
+    async fn test_only_accepted_occurrences_count_towards_max_runs() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 200);
+        let mut h = make_harness(spawner, &tmp, ConcurrencyPolicy::Forbid).await;
+        let update = JobUpdate {
+            max_runs: Some(Some(2)),
+            ..Default::default()
+        };
+        let job = h.job_store.update_job(h.job.id, update).await.unwrap();
+        let mut event_rx = h.dispatcher.executor.event_tx().subscribe();
+        let scheduled = |job: &Job| DispatchRequest {
+            scheduled_for: Some(Utc::now()),
+            trigger: TriggerSource::Schedule,
+            ..request(job)
+        };
+
+        // The second occurrence is skipped while the first runs, and does
+        // not use up a run.
+        h.dispatcher.dispatch(scheduled(&job)).await;
+        h.dispatcher.dispatch(scheduled(&job)).await;
+        let stored = h.job_store.get_job(job.id).await.unwrap().unwrap();
+        assert_eq!(stored.scheduled_runs, 1);
+        assert!(stored.enabled);
+        wait_until_empty(&h.active_runs).await;
+
+        h.dispatcher.dispatch(scheduled(&job)).await;
+        let stored = h.job_store.get_job(job.id).await.unwrap().unwrap();
+        assert_eq!(stored.scheduled_runs, 2);
+        assert!(!stored.enabled, "job should expire with its last run");
+        loop {
+            match event_rx.try_recv().expect("an Expired event") {
+                JobEvent::JobChanged { change, .. } => {
+                    assert!(matches!(change, JobChangeKind::Expired));
+                    break;
+                }
+                _ => continue,
+            }
+        }
+        wait_until_empty(&h.active_runs).await;
+
+        // Anything dispatched after the last run is dropped.
+        h.dispatcher.dispatch(scheduled(&job)).await;
+        assert!(h.active_runs.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_request_with_skip_reason_is_recorded_not_run() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_output_and_exit(vec![b"done\n".to_vec()], 0);
+        let mut h = make_harness(spawner, &tmp, ConcurrencyPolicy::Allow).await;
+
+        let req = DispatchRequest {
+            skip_reason: Some("excluded by calendar 'holidays' (Christmas)".to_string()),
+            ..request(&h.job)
+        };
+        let run_id = req.run_id;
+        h.dispatcher.dispatch(req).await;
+
+        assert!(h.active_runs.read().await.is_empty());
+        let runs = wait_for_finished_runs(h.log_store.as_ref(), h.job.id, 1).await;
+        let skipped = find_run(&runs, run_id);
+        assert_eq!(skipped.status, RunStatus::Skipped);
+        assert_eq!(
+            skipped.error.as_deref(),
+            Some("Skipped: excluded by calendar 'holidays' (Christmas)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queue_runs_one_at_a_time_and_skips_overflow() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 100);
+        let h = make_harness(spawner, &tmp, ConcurrencyPolicy::Queue { max_depth: 1 }).await;
+        let (tx, rx) = mpsc::channel(16);
+        let active_runs = Arc::clone(&h.active_runs);
+        tokio::spawn(h.dispatcher.run(rx));
+
+        let requests: Vec<DispatchRequest> = (0..3).map(|_| request(&h.job)).collect();
+        let ids: Vec<Uuid> = requests.iter().map(|r| r.run_id).collect();
+        for req in requests {
+            tx.send(req).await.expect("send");
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        {
+            let runs = active_runs.read().await;
+            assert_eq!(runs.len(), 1);
+            assert!(runs.contains_key(&ids[0]));
+        }
+
+        let runs = wait_for_finished_runs(h.log_store.as_ref(), h.job.id, 3).await;
+        assert_eq!(find_run(&runs, ids[0]).status, RunStatus::Completed);
+        assert_eq!(find_run(&runs, ids[2]).status, RunStatus::Skipped);
+        let queued = find_run(&runs, ids[1]);
+        assert_eq!(queued.status, RunStatus::Completed);
+        assert!(queued.started_at >= find_run(&runs, ids[0]).finished_at.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_replace_kills_running_instance_and_starts_new_run() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec(); 50], 0, 100);
+        let h = make_harness(spawner, &tmp, ConcurrencyPolicy::Replace).await;
+        let (tx, rx) = mpsc::channel(16);
+        let active_runs = Arc::clone(&h.active_runs);
+        tokio::spawn(h.dispatcher.run(rx));
+
+        let first = request(&h.job);
+        let second = request(&h.job);
+        let (first_id, second_id) = (first.run_id, second.run_id);
+        tx.send(first).await.expect("send");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        tx.send(second).await.expect("send");
+
+        let replaced = wait_for_finished_runs(h.log_store.as_ref(), h.job.id, 1).await;
+        let replaced = find_run(&replaced, first_id);
+        assert_eq!(replaced.status, RunStatus::Killed);
+        assert_eq!(
+            replaced.error.as_deref(),
+            Some(KillReason::Replaced.to_string().as_str())
+        );
+
+        for _ in 0..200 {
+            if active_runs.read().await.contains_key(&second_id) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(active_runs.read().await.contains_key(&second_id));
+        let handle = active_runs.write().await.remove(&second_id);
+        if let Some(handle) = handle {
+            let _ = handle.kill_tx.send(KillReason::Cancelled);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_runs_queues_by_priority() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 100);
+        let h = make_harness(spawner, &tmp, ConcurrencyPolicy::Allow).await;
+        let urgent = create_job(
+            h.job_store.as_ref(),
+            "urgent-job",
+            ConcurrencyPolicy::Allow,
+            None,
+            10,
+        )
+        .await;
+        let config = DaemonConfig {
+            max_concurrent_runs: 1,
+            ..Default::default()
+        };
+        let run_queue = RunQueue::default();
+        let dispatcher = h
+            .dispatcher
+            .with_queue(run_queue.clone())
+            .with_limits(&config);
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(dispatcher.run(rx));
+
+        let first = request(&h.job);
+        let low = request(&h.job);
+        let high = request(&urgent);
+        let (first_id, low_id, high_id) = (first.run_id, low.run_id, high.run_id);
+        for req in [first, low, high] {
+            tx.send(req).await.expect("send");
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let queued = run_queue.list().await;
+        let order: Vec<Uuid> = queued.iter().map(|r| r.run_id).collect();
+        assert_eq!(order, vec![high_id, low_id]);
+        assert!(queued[0].waiting_for.contains("max_concurrent_runs 1"));
+        let (runs, _) = h.log_store.list_runs(h.job.id, 100, 0).await.expect("list");
+        assert_eq!(find_run(&runs, low_id).status, RunStatus::Queued);
+
+        let runs = wait_for_finished_runs(h.log_store.as_ref(), h.job.id, 2).await;
+        let urgent_runs = wait_for_finished_runs(h.log_store.as_ref(), urgent.id, 1).await;
+        let first_run = find_run(&runs, first_id);
+        let high_run = find_run(&urgent_runs, high_id);
+        let low_run = find_run(&runs, low_id);
+        assert_eq!(low_run.status, RunStatus::Completed);
+        assert!(high_run.started_at >= first_run.finished_at.unwrap());
+        assert!(low_run.started_at >= high_run.finished_at.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_pool_limit_only_holds_runs_of_the_pool() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 100);
+        let h = make_harness(spawner, &tmp, ConcurrencyPolicy::Allow).await;
+        let mut pooled = Vec::new();
+        for name in ["pooled-a", "pooled-b"] {
+            let job = create_job(
+                h.job_store.as_ref(),
+                name,
+                ConcurrencyPolicy::Allow,
+                Some("claude"),
+                0,
+            )
+            .await;
+            pooled.push(job);
+        }
+        let config = DaemonConfig {
+            concurrency_pools: HashMap::from([("claude".to_string(), 1)]),
+            ..Default::default()
+        };
+        let run_queue = RunQueue::default();
+        let mut dispatcher = h
+            .dispatcher
+            .with_queue(run_queue.clone())
+            .with_limits(&config);
+
+        let a = request(&pooled[0]);
+        let b = request(&pooled[1]);
+        let other = request(&h.job);
+        let (a_id, b_id, other_id) = (a.run_id, b.run_id, other.run_id);
+        for req in [a, b, other] {
+            dispatcher.dispatch(req).await;
+        }
+
+        {
+            let runs = h.active_runs.read().await;
+            assert!(runs.contains_key(&a_id));
+            assert!(runs.contains_key(&other_id));
+            assert!(!runs.contains_key(&b_id));
+        }
+        let queued = run_queue.list().await;
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].run_id, b_id);
+        assert_eq!(queued[0].pool.as_deref(), Some("claude"));
+        assert!(queued[0].waiting_for.contains("pool 'claude'"));
+
+        wait_until_empty(&h.active_runs).await;
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_queued_run_never_starts() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 100);
+        let h = make_harness(spawner, &tmp, ConcurrencyPolicy::Queue { max_depth: 1 }).await;
+        let run_queue = RunQueue::default();
+        let dispatcher = h.dispatcher.with_queue(run_queue.clone());
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(dispatcher.run(rx));
+        let (event_tx, mut event_rx) = broadcast::channel::<JobEvent>(16);
+
+        let first = request(&h.job);
+        let second = request(&h.job);
+        let (first_id, second_id) = (first.run_id, second.run_id);
+        tx.send(first).await.expect("send");
+        tx.send(second).await.expect("send");
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        let cancelled = run_queue
+            .cancel(
+                second_id,
+                KillReason::Cancelled,
+                h.log_store.as_ref(),
+                &event_tx,
+            )
+            .await
+            .expect("run should be queued");
+        assert_eq!(cancelled.status, RunStatus::Killed);
+        assert!(run_queue.list().await.is_empty());
+        assert!(matches!(
+            event_rx.try_recv(),
+            Ok(JobEvent::Killed { run_id, .. }) if run_id == second_id
+        ));
+
+        let runs = wait_for_finished_runs(h.log_store.as_ref(), h.job.id, 2).await;
+        assert_eq!(find_run(&runs, first_id).status, RunStatus::Completed);
+        let second = find_run(&runs, second_id);
+        assert_eq!(second.status, RunStatus::Killed);
+        assert_eq!(
+            second.error.as_deref(),
+            Some(KillReason::Cancelled.to_string().as_str())
+        );
+        wait_until_empty(&h.active_runs).await;
+    }
+
+    #[tokio::test]
+    async fn test_queued_runs_of_deleted_job_are_dropped_and_removed() {
+        let tmp = TempDir::new().expect("temp dir");
+        let spawner = MockPtySpawner::with_slow_output(vec![b"tick\n".to_vec()], 0, 100);
+        let h = make_harness(spawner, &tmp, ConcurrencyPolicy::Queue { max_depth: 2 }).await;
+        let run_queue = RunQueue::default();
+        let dispatcher = h.dispatcher.with_queue(run_queue.clone());
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(dispatcher.run(rx));
+
+        let first = request(&h.job);
+        let first_id = first.run_id;
+        tx.send(first).await.expect("send");
+        for _ in 0..2 {
+            tx.send(request(&h.job)).await.expect("send");
+        }
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(run_queue.list().await.len(), 2);
+        let (runs, _) = h.log_store.list_runs(h.job.id, 100, 0).await.expect("list");
+        assert_eq!(runs.len(), 3);
+
+        // Deleted without cancelling its queued runs first, as when a
+        // dispatch races the deletion.
+        h.job_store.delete_job(h.job.id).await.expect("delete");
+        wait_until_empty(&h.active_runs).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        assert!(run_queue.list().await.is_empty());
+        let (runs, _) = h.log_store.list_runs(h.job.id, 100, 0).await.expect("list");
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].run_id, first_id);
+        assert_eq!(runs[0].status, RunStatus::Completed);
+    }
+}
@@ -4,6 +4,8 @@ use chrono::{DateTime, Utc};
 use serde::{Serialize, Serializer};
 use uuid::Uuid;
 
+use crate::models::{KillReason, KillSignal, OutputStream};
+
 /// Custom serializer for Arc<str> that serializes as a plain string.
 fn serialize_arc_str<S>(data: &Arc<str>, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -19,6 +21,11 @@ pub enum JobEvent {
         job_id: Uuid,
         run_id: Uuid,
         job_name: String,
+        /// The first attempt's run, when this run is a retry.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parent_run_id: Option<Uuid>,
+        /// The job's tags, so subscribers can filter runs by them.
+        tags: Vec<String>,
         timestamp: DateTime<Utc>,
     },
     Output {
@@ -26,6 +33,7 @@ pub enum JobEvent {
         run_id: Uuid,
         #[serde(serialize_with = "serialize_arc_str")]
         data: Arc<str>,
+        stream: OutputStream,
         timestamp: DateTime<Utc>,
     },
     Completed {
@@ -40,6 +48,30 @@ pub enum JobEvent {
         error: String,
         timestamp: DateTime<Utc>,
     },
+    /// A run was stopped on request (cancel, job deletion, daemon shutdown).
+    /// Timeouts are reported as `Failed`.
+    Killed {
+        job_id: Uuid,
+        run_id: Uuid,
+        reason: KillReason,
+        signal: Option<KillSignal>,
+        timestamp: DateTime<Utc>,
+    },
+    /// A dispatch is waiting in the run queue for a free slot.
+    Queued {
+        job_id: Uuid,
+        run_id: Uuid,
+        reason: String,
+        timestamp: DateTime<Utc>,
+    },
+    /// A dispatch was recorded as a `Skipped` run without starting a
+    /// process (concurrency policy or calendar).
+    Skipped {
+        job_id: Uuid,
+        run_id: Uuid,
+        reason: String,
+        timestamp: DateTime<Utc>,
+    },
     JobChanged {
         job_id: Uuid,
         change: JobChangeKind,
@@ -54,6 +86,9 @@ pub enum JobChangeKind {
     Removed,
     Enabled,
     Disabled,
+    /// The scheduler disabled the job because it reached its `end_at` or
+    /// `max_runs`, or its one-off time passed.
+    Expired,
 }
 
 #[cfg(test)]
@@ -69,11 +104,14 @@ mod tests {
             job_id: Uuid::nil(),
             run_id: Uuid::nil(),
             job_name: "test-job".to_string(),
+            parent_run_id: None,
+            tags: vec!["nightly".to_string()],
             timestamp: Utc::now(),
         };
         let json = serde_json::to_string(&event).expect("serialize");
         assert!(json.contains("\"event\":\"Started\""));
         assert!(json.contains("\"job_name\":\"test-job\""));
+        assert!(json.contains("\"tags\":[\"nightly\"]"));
         assert!(json.contains("\"job_id\""));
         assert!(json.contains("\"run_id\""));
         assert!(json.contains("\"timestamp\""));
@@ -86,11 +124,13 @@ mod tests {
             job_id: Uuid::nil(),
             run_id: Uuid::nil(),
             data: data.clone(),
+            stream: OutputStream::Stdout,
             timestamp: Utc::now(),
         };
         let json = serde_json::to_string(&event).expect("serialize");
         assert!(json.contains("\"event\":\"Output\""));
         assert!(json.contains("\"data\":\"hello world\\n\""));
+        assert!(json.contains("\"stream\":\"stdout\""));
 
         // Verify Arc<str> clones share the same data (cheap cloning)
         let data2 = data.clone();
@@ -135,6 +175,21 @@ mod tests {
         assert!(json.contains("\"error\":\"PTY spawn failed\""));
     }
 
+    #[test]
+    fn test_killed_event_serializes() {
+        let event = JobEvent::Killed {
+            job_id: Uuid::nil(),
+            run_id: Uuid::nil(),
+            reason: KillReason::Cancelled,
+            signal: Some(KillSignal::Term),
+            timestamp: Utc::now(),
+        };
+        let json = serde_json::to_string(&event).expect("serialize");
+        assert!(json.contains("\"event\":\"Killed\""));
+        assert!(json.contains("\"reason\":\"cancelled\""));
+        assert!(json.contains("\"signal\":\"SIGTERM\""));
+    }
+
     #[test]
     fn test_job_changed_event_serializes() {
         let event = JobEvent::JobChanged {
@@ -175,6 +230,8 @@ mod tests {
             job_id: Uuid::nil(),
             run_id: Uuid::nil(),
             job_name: "broadcast-test".to_string(),
+            parent_run_id: None,
+            tags: Vec::new(),
             timestamp: Utc::now(),
         };
 
@@ -205,6 +262,7 @@ mod tests {
                 job_id: Uuid::nil(),
                 run_id: Uuid::nil(),
                 data: Arc::from(format!("msg {}", i).as_str()),
+                stream: OutputStream::Stdout,
                 timestamp: Utc::now(),
             };
             let _ = tx.send(event);
@@ -234,6 +292,7 @@ mod tests {
             job_id: Uuid::nil(),
             run_id: Uuid::nil(),
             data,
+            stream: OutputStream::Stdout,
             timestamp: Utc::now(),
         };
         let json = serde_json::to_string(&event).expect("serialize");
@@ -250,6 +309,7 @@ mod tests {
             job_id: Uuid::nil(),
             run_id: Uuid::nil(),
             data: data.clone(),
+            stream: OutputStream::Stdout,
             timestamp: Utc::now(),
         };
         let cloned = event.clone();
@@ -1,23 +1,56 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tracing;
 use uuid::Uuid;
 
 use crate::daemon::events::JobEvent;
-use crate::models::TriggerParams;
-use crate::models::{DaemonConfig, ExecutionType, Job, JobRun, RunStatus};
-use crate::pty::PtySpawner;
+use crate::models::{
+    DaemonConfig, ExecutionType, Job, JobRun, KillReason, KillSignal, OutputStream, RunStatus,
+};
+use crate::models::{DispatchRequest, TriggerParams, TriggerSource};
+use crate::pty::{ProcessKiller, PtySpawner};
 use crate::storage::LogStore;
 
 /// Handle to a running job, allowing monitoring and cancellation.
 pub struct RunHandle {
     pub run_id: Uuid,
     pub job_id: Uuid,
+    pub job_name: String,
+    pub started_at: DateTime<Utc>,
     pub join_handle: tokio::task::JoinHandle<()>,
-    pub kill_tx: oneshot::Sender<()>,
+    /// Stops the run; the reason is recorded on the run and its `Killed` event.
+    pub kill_tx: oneshot::Sender<KillReason>,
+    /// Signals the run's process group, once its process has been spawned.
+    pub killer: Arc<OnceLock<Box<dyn ProcessKiller>>>,
+}
+
+/// How long to keep draining output after SIGKILL before giving up on a run
+/// whose output pipe is held open by a process outside its group.
+pub(crate) const KILL_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Broadcast an output chunk and hand the raw bytes to the log writer.
+async fn forward_output(
+    event_tx: &broadcast::Sender<JobEvent>,
+    log_tx: &mpsc::Sender<(OutputStream, Vec<u8>)>,
+    job_id: Uuid,
+    run_id: Uuid,
+    (stream, data): (OutputStream, Vec<u8>),
+) {
+    // Convert to lossy UTF-8 for broadcast
+    let text = String::from_utf8_lossy(&data);
+    let _ = event_tx.send(JobEvent::Output {
+        job_id,
+        run_id,
+        data: Arc::from(text.as_ref()),
+        stream,
+        timestamp: Utc::now(),
+    });
+
+    // Send raw bytes to log writer
+    let _ = log_tx.send((stream, data)).await;
 }
 
 /// The Executor spawns jobs using a PTY and manages the lifecycle.
@@ -26,6 +59,44 @@ pub struct Executor {
     log_store: Arc<dyn LogStore>,
     config: Arc<DaemonConfig>,
     pty_spawner: Arc<dyn PtySpawner>,
+    /// Dispatch channel for retry attempts. Weak, so pending retries don't
+    /// keep the dispatch loop alive.
+    retry_tx: Option<mpsc::WeakSender<DispatchRequest>>,
+}
+
+/// Dispatch the next attempt of a finished run if the job's retry policy
+/// asks for one, after the policy's backoff delay.
+fn schedule_retry(retry_tx: mpsc::WeakSender<DispatchRequest>, job: Job, run: &JobRun) {
+    if !job.retry.should_retry(run) {
+        return;
+    }
+    let delay = job.retry.delay_after(run.attempt);
+    tracing::info!(
+        "Retrying job {} (attempt {} of {}) in {:?}",
+        job.name,
+        run.attempt + 1,
+        job.retry.max_attempts,
+        delay
+    );
+    let request = DispatchRequest {
+        job,
+        run_id: Uuid::now_v7(),
+        trigger_params: run.trigger_params.clone(),
+        attempt: run.attempt + 1,
+        parent_run_id: Some(run.parent_run_id.unwrap_or(run.run_id)),
+        scheduled_for: run.scheduled_for,
+        catch_up: run.catch_up,
+        trigger: TriggerSource::Retry,
+        skip_reason: None,
+    };
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if let Some(tx) = retry_tx.upgrade() {
+            if let Err(e) = tx.send(request).await {
+                tracing::warn!("Failed to dispatch retry: {}", e);
+            }
+        }
+    });
 }
 
 impl Executor {
@@ -41,9 +112,27 @@ impl Executor {
             log_store,
             config,
             pty_spawner,
+            retry_tx: None,
         }
     }
 
+    /// Send retries of failed runs through `dispatch_tx`. Without it, the
+    /// job's retry policy is ignored.
+    pub fn with_retries(mut self, dispatch_tx: &mpsc::Sender<DispatchRequest>) -> Self {
+        self.retry_tx = Some(dispatch_tx.downgrade());
+        self
+    }
+
+    /// The channel run events are broadcast on.
+    pub fn event_tx(&self) -> &broadcast::Sender<JobEvent> {
+        &self.event_tx
+    }
+
+    /// The store runs are recorded in.
+    pub fn log_store(&self) -> &dyn LogStore {
+        self.log_store.as_ref()
+    }
+
     /// Build a CommandBuilder from the job's execution type.
     /// If trigger_args is provided, it is appended to the command string.
     /// If trigger_env is provided, those vars are applied after job env_vars (highest precedence).
@@ -142,6 +231,26 @@ impl Executor {
         run_id: Uuid,
         trigger_params: Option<&TriggerParams>,
     ) -> anyhow::Result<RunHandle> {
+        let request = DispatchRequest {
+            job: job.clone(),
+            run_id,
+            trigger_params: trigger_params.cloned(),
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: TriggerSource::Manual,
+            skip_reason: None,
+        };
+        self.spawn_request(&request).await
+    }
+
+    /// Spawn the run described by a dispatch request. The request's retry
+    /// and schedule metadata is recorded on the run.
+    pub async fn spawn_request(&self, request: &DispatchRequest) -> anyhow::Result<RunHandle> {
+        let job = &request.job;
+        let run_id = request.run_id;
+        let trigger_params = request.trigger_params.as_ref();
         let job_id = job.id;
         let job_name = job.name.clone();
         let now = Utc::now();
@@ -157,6 +266,12 @@ impl Executor {
             log_size_bytes: 0,
             error: None,
             trigger_params: trigger_params.cloned(),
+            signal: None,
+            attempt: request.attempt,
+            parent_run_id: request.parent_run_id,
+            scheduled_for: request.scheduled_for,
+            catch_up: request.catch_up,
+            trigger: Some(request.trigger),
         };
 
         // Save the initial run to the log store
@@ -167,6 +282,8 @@ impl Executor {
             job_id,
             run_id,
             job_name: job_name.clone(),
+            parent_run_id: request.parent_run_id,
+            tags: job.tags.clone(),
             timestamp: now,
         });
 
@@ -184,6 +301,7 @@ impl Executor {
         let trigger_input = trigger_params.and_then(|p| p.input.clone());
         let trigger_args = trigger_params.and_then(|p| p.args.clone());
         let trigger_params_owned = trigger_params.cloned();
+        let base_run = run;
         let event_tx = self.event_tx.clone();
         let log_store = Arc::clone(&self.log_store);
         let pty_spawner = Arc::clone(&self.pty_spawner);
@@ -196,13 +314,23 @@ impl Executor {
         } else {
             self.config.default_timeout_secs
         };
-        let max_log_files = self.config.max_log_files_per_job;
+        let retention = self.config.retention_for(&job.log_retention);
+        let max_log_files = retention.max_runs;
+        let max_log_file_size = retention.max_file_size;
+        let kill_grace = std::time::Duration::from_secs(self.config.kill_grace_period_secs);
 
         // Create kill channel
-        let (kill_tx, kill_rx) = oneshot::channel::<()>();
+        let (kill_tx, kill_rx) = oneshot::channel::<KillReason>();
+        let killer_slot: Arc<OnceLock<Box<dyn ProcessKiller>>> = Arc::new(OnceLock::new());
+        let task_killer_slot = Arc::clone(&killer_slot);
 
-        // Spawn the execution task
-        let join_handle = tokio::spawn(async move {
+        let retry = match &self.retry_tx {
+            Some(tx) if job.retry.max_attempts > 1 => Some((tx.clone(), job.clone())),
+            _ => None,
+        };
+
+        // The execution task, resolving to the run's final record
+        let execution_task = async move {
             // Try to spawn the process
             let spawn_result = {
                 let spawner = pty_spawner;
@@ -226,15 +354,10 @@ impl Executor {
 
                     // Update the run to Failed status
                     let failed_run = JobRun {
-                        run_id,
-                        job_id,
-                        started_at: now,
                         finished_at: Some(Utc::now()),
                         status: RunStatus::Failed,
-                        exit_code: None,
-                        log_size_bytes: 0,
                         error: Some(error_msg),
-                        trigger_params: trigger_params_owned.clone(),
+                        ..base_run
                     };
                     if let Err(e) = log_store.update_run(&failed_run).await {
                         tracing::error!("Failed to update run on spawn failure: {}", e);
@@ -244,7 +367,7 @@ impl Executor {
                     if let Err(e) = log_store.cleanup(job_id, max_log_files).await {
                         tracing::error!("Failed to cleanup logs for job {}: {}", job_id, e);
                     }
-                    return;
+                    return failed_run;
                 }
             };
 
@@ -258,6 +381,15 @@ impl Executor {
             }
             process.close_stdin();
 
+            // Grab a handle for signalling the process group before the read
+            // loop takes ownership of the process.
+            let killer = process.killer();
+            let _ = task_killer_slot.set(process.killer());
+
+            // The environment dump and command header count towards
+            // max_log_file_size like the output that follows them.
+            let mut preamble_bytes: u64 = 0;
+
             // If log_environment is enabled, dump full environment before command
             if log_environment {
                 let mut env_map: std::collections::BTreeMap<String, String> =
@@ -281,6 +413,7 @@ impl Executor {
                     env_dump.push_str(&format!("{}={}\n", key, value));
                 }
                 env_dump.push_str("===================\n");
+                preamble_bytes += env_dump.len() as u64;
                 let _ = log_store
                     .append_log(job_id, run_id, env_dump.as_bytes())
                     .await;
@@ -288,6 +421,7 @@ impl Executor {
                     job_id,
                     run_id,
                     data: Arc::from(env_dump.as_str()),
+                    stream: OutputStream::Stdout,
                     timestamp: Utc::now(),
                 });
             }
@@ -304,6 +438,7 @@ impl Executor {
                 },
             };
             let header = format!("$ {}\n", command_str);
+            preamble_bytes += header.len() as u64;
             let _ = log_store
                 .append_log(job_id, run_id, header.as_bytes())
                 .await;
@@ -311,38 +446,75 @@ impl Executor {
                 job_id,
                 run_id,
                 data: Arc::from(header.as_str()),
+                stream: OutputStream::Stdout,
                 timestamp: Utc::now(),
             });
 
             // Create mpsc channel for log writer (capacity 256 per SPEC)
-            let (log_tx, log_rx) = mpsc::channel::<Vec<u8>>(256);
+            let (log_tx, log_rx) = mpsc::channel::<(OutputStream, Vec<u8>)>(256);
 
             // Spawn log writer task
             let log_store_writer = Arc::clone(&log_store);
+            // Output past max_log_file_size is discarded after a marker line,
+            // while the channel is still drained so the process never blocks.
             let log_writer_handle = tokio::spawn(async move {
                 let mut rx = log_rx;
-                let mut total_bytes: u64 = 0;
-                while let Some(data) = rx.recv().await {
-                    total_bytes += data.len() as u64;
-                    if let Err(e) = log_store_writer.append_log(job_id, run_id, &data).await {
-                        tracing::error!("Failed to append log: {}", e);
+                let mut total_bytes = preamble_bytes;
+                let mut truncated = false;
+                while let Some((stream, data)) = rx.recv().await {
+                    if truncated {
+                        continue;
+                    }
+                    let mut keep = data.len();
+                    if max_log_file_size > 0 {
+                        let room = max_log_file_size.saturating_sub(total_bytes);
+                        keep = keep.min(usize::try_from(room).unwrap_or(usize::MAX));
+                    }
+                    if keep > 0 {
+                        total_bytes += keep as u64;
+                        if let Err(e) = log_store_writer
+                            .append_output(job_id, run_id, stream, &data[..keep])
+                            .await
+                        {
+                            tracing::error!("Failed to append log: {}", e);
+                        }
+                    }
+                    if keep < data.len() {
+                        truncated = true;
+                        tracing::warn!(
+                            "Run {} of job {} reached max_log_file_size ({} bytes); discarding further output",
+                            run_id,
+                            job_id,
+                            max_log_file_size
+                        );
+                        let marker = format!(
+                            "\n=== Output truncated at {} bytes (max_log_file_size) ===\n",
+                            max_log_file_size
+                        );
+                        total_bytes += marker.len() as u64;
+                        if let Err(e) = log_store_writer
+                            .append_log(job_id, run_id, marker.as_bytes())
+                            .await
+                        {
+                            tracing::error!("Failed to append log: {}", e);
+                        }
                     }
                 }
                 total_bytes
             });
 
             // Create a channel to receive output from spawn_blocking
-            let (output_tx, mut output_rx) = mpsc::channel::<Vec<u8>>(256);
+            let (output_tx, mut output_rx) = mpsc::channel::<(OutputStream, Vec<u8>)>(256);
 
-            // Spawn blocking PTY read loop
+            // Spawn blocking PTY read loop (stdout and stderr, in order)
             let read_handle = tokio::task::spawn_blocking(move || {
                 let mut buf = [0u8; 8192];
                 loop {
-                    match process.read(&mut buf) {
-                        Ok(0) => break, // EOF
-                        Ok(n) => {
+                    match process.read_stream(&mut buf) {
+                        Ok((0, _)) => break, // EOF
+                        Ok((n, stream)) => {
                             let data = buf[..n].to_vec();
-                            if output_tx.blocking_send(data).is_err() {
+                            if output_tx.blocking_send((stream, data)).is_err() {
                                 break; // Receiver dropped
                             }
                         }
@@ -357,13 +529,10 @@ impl Executor {
                 process.wait()
             });
 
-            // Forward output: broadcast events and send to log writer
-            let event_tx_output = event_tx.clone();
-            let log_tx_output = log_tx;
-
             // Process output chunks - use select to handle kill signal and timeout
             let mut kill_rx = kill_rx;
-            let mut killed = false;
+            let mut killed: Option<KillReason> = None;
+            let mut kill_handle_dropped = false;
             let mut timed_out = false;
 
             // Create timeout future if timeout is configured
@@ -380,27 +549,21 @@ impl Executor {
                     chunk = output_rx.recv() => {
                         match chunk {
                             Some(data) => {
-                                // Convert to lossy UTF-8 for broadcast
-                                let text = String::from_utf8_lossy(&data);
-                                let arc_str: Arc<str> = Arc::from(text.as_ref());
-
-                                // Broadcast Output event
-                                let _ = event_tx_output.send(JobEvent::Output {
-                                    job_id,
-                                    run_id,
-                                    data: arc_str,
-                                    timestamp: Utc::now(),
-                                });
-
-                                // Send raw bytes to log writer
-                                let _ = log_tx_output.send(data).await;
+                                forward_output(&event_tx, &log_tx, job_id, run_id, data).await;
                             }
                             None => break, // PTY read loop ended
                         }
                     }
-                    _ = &mut kill_rx => {
-                        killed = true;
-                        break;
+                    reason = &mut kill_rx, if !kill_handle_dropped => {
+                        // A dropped sender is not a kill request: the run
+                        // simply outlives its handle.
+                        match reason {
+                            Ok(reason) => {
+                                killed = Some(reason);
+                                break;
+                            }
+                            Err(_) => kill_handle_dropped = true,
+                        }
                     }
                     _ = &mut timeout_fut => {
                         timed_out = true;
@@ -409,11 +572,66 @@ impl Executor {
                 }
             }
 
+            // On kill or timeout, stop the whole process group: SIGTERM, then
+            // SIGKILL once the grace period runs out. Output produced while the
+            // process shuts down is still logged.
+            let mut signal = None;
+            let mut output_closed = !(killed.is_some() || timed_out);
+            if killed.is_some() || timed_out {
+                if let Err(e) = killer.terminate() {
+                    tracing::warn!("Failed to send SIGTERM to run {}: {}", run_id, e);
+                }
+                signal = Some(KillSignal::Term);
+
+                let deadline = tokio::time::sleep(kill_grace);
+                tokio::pin!(deadline);
+                loop {
+                    tokio::select! {
+                        chunk = output_rx.recv() => {
+                            match chunk {
+                                Some(data) => {
+                                    forward_output(&event_tx, &log_tx, job_id, run_id, data).await;
+                                }
+                                None => {
+                                    output_closed = true;
+                                    break;
+                                }
+                            }
+                        }
+                        _ = &mut deadline => {
+                            if signal == Some(KillSignal::Kill) {
+                                // Something outside the group still holds the
+                                // output pipe open; stop waiting for it.
+                                tracing::warn!("Run {} output did not close after SIGKILL", run_id);
+                                break;
+                            }
+                            tracing::warn!(
+                                "Run {} still alive {}s after SIGTERM, sending SIGKILL",
+                                run_id,
+                                kill_grace.as_secs()
+                            );
+                            if let Err(e) = killer.kill() {
+                                tracing::warn!("Failed to send SIGKILL to run {}: {}", run_id, e);
+                            }
+                            signal = Some(KillSignal::Kill);
+                            deadline
+                                .as_mut()
+                                .reset(tokio::time::Instant::now() + KILL_DRAIN_TIMEOUT);
+                        }
+                    }
+                }
+            }
+
             // Drop log_tx to signal log writer to finish
-            drop(log_tx_output);
+            drop(log_tx);
 
-            // Wait for the read handle to complete and get exit status
-            let exit_result = read_handle.await;
+            // Wait for the read handle to complete and get exit status. If the
+            // output never closed the read loop is stuck, so don't wait on it.
+            let exit_result = if output_closed {
+                Some(read_handle.await)
+            } else {
+                None
+            };
 
             // Wait for log writer to finish and get total bytes
             let total_bytes: u64 = (log_writer_handle.await).unwrap_or_default();
@@ -423,15 +641,12 @@ impl Executor {
             if timed_out {
                 // Job timed out - mark as Failed with timeout message
                 let timeout_run = JobRun {
-                    run_id,
-                    job_id,
-                    started_at: now,
                     finished_at: Some(finished_at),
                     status: RunStatus::Failed,
-                    exit_code: None,
                     log_size_bytes: total_bytes,
                     error: Some("execution timed out".to_string()),
-                    trigger_params: trigger_params_owned.clone(),
+                    signal,
+                    ..base_run
                 };
                 if let Err(e) = log_store.update_run(&timeout_run).await {
                     tracing::error!("Failed to update run on timeout: {}", e);
@@ -447,29 +662,27 @@ impl Executor {
                 if let Err(e) = log_store.cleanup(job_id, max_log_files).await {
                     tracing::error!("Failed to cleanup logs for job {}: {}", job_id, e);
                 }
-                return;
+                return timeout_run;
             }
 
-            if killed {
+            if let Some(reason) = killed {
                 // Job was killed
                 let killed_run = JobRun {
-                    run_id,
-                    job_id,
-                    started_at: now,
                     finished_at: Some(finished_at),
                     status: RunStatus::Killed,
-                    exit_code: None,
                     log_size_bytes: total_bytes,
-                    error: Some("Job was killed".to_string()),
-                    trigger_params: trigger_params_owned.clone(),
+                    error: Some(reason.to_string()),
+                    signal,
+                    ..base_run
                 };
                 if let Err(e) = log_store.update_run(&killed_run).await {
                     tracing::error!("Failed to update run on kill: {}", e);
                 }
-                let _ = event_tx.send(JobEvent::Failed {
+                let _ = event_tx.send(JobEvent::Killed {
                     job_id,
                     run_id,
-                    error: "Job was killed".to_string(),
+                    reason,
+                    signal,
                     timestamp: finished_at,
                 });
 
@@ -477,27 +690,23 @@ impl Executor {
                 if let Err(e) = log_store.cleanup(job_id, max_log_files).await {
                     tracing::error!("Failed to cleanup logs for job {}: {}", job_id, e);
                 }
-                return;
+                return killed_run;
             }
 
             // Process the exit result
-            match exit_result {
-                Ok(Ok(status)) => {
+            let finished_run = match exit_result {
+                Some(Ok(Ok(status))) => {
                     // Get exit code
                     let exit_code = status.code().unwrap_or(-1);
 
                     // Per SPEC: non-zero exit is Completed (not Failed).
                     // Failed = infrastructure error only.
                     let completed_run = JobRun {
-                        run_id,
-                        job_id,
-                        started_at: now,
                         finished_at: Some(finished_at),
                         status: RunStatus::Completed,
                         exit_code: Some(exit_code),
                         log_size_bytes: total_bytes,
-                        error: None,
-                        trigger_params: trigger_params_owned.clone(),
+                        ..base_run
                     };
                     if let Err(e) = log_store.update_run(&completed_run).await {
                         tracing::error!("Failed to update run on completion: {}", e);
@@ -509,20 +718,17 @@ impl Executor {
                         exit_code,
                         timestamp: finished_at,
                     });
+                    completed_run
                 }
-                Ok(Err(e)) => {
+                Some(Ok(Err(e))) => {
                     // Process wait failed - infrastructure error
                     let error_msg = format!("Process wait failed: {}", e);
                     let failed_run = JobRun {
-                        run_id,
-                        job_id,
-                        started_at: now,
                         finished_at: Some(finished_at),
                         status: RunStatus::Failed,
-                        exit_code: None,
                         log_size_bytes: total_bytes,
                         error: Some(error_msg.clone()),
-                        trigger_params: trigger_params_owned.clone(),
+                        ..base_run
                     };
                     if let Err(e) = log_store.update_run(&failed_run).await {
                         tracing::error!("Failed to update run on wait failure: {}", e);
@@ -534,20 +740,17 @@ impl Executor {
                         error: error_msg,
                         timestamp: finished_at,
                     });
+                    failed_run
                 }
-                Err(e) => {
+                Some(Err(e)) => {
                     // JoinError from spawn_blocking
                     let error_msg = format!("Task join error: {}", e);
                     let failed_run = JobRun {
-                        run_id,
-                        job_id,
-                        started_at: now,
                         finished_at: Some(finished_at),
                         status: RunStatus::Failed,
-                        exit_code: None,
                         log_size_bytes: total_bytes,
                         error: Some(error_msg.clone()),
-                        trigger_params: trigger_params_owned,
+                        ..base_run
                     };
                     if let Err(e) = log_store.update_run(&failed_run).await {
                         tracing::error!("Failed to update run on join error: {}", e);
@@ -559,28 +762,156 @@ impl Executor {
                         error: error_msg,
                         timestamp: finished_at,
                     });
+                    failed_run
                 }
-            }
+                None => {
+                    // Output is always drained unless the run was stopped, so
+                    // this is a bug; still finish the run rather than leave
+                    // it running forever.
+                    let error_msg = "Run ended without its output being drained".to_string();
+                    tracing::error!("Run {} of job {}: {}", run_id, job_id, error_msg);
+                    let failed_run = JobRun {
+                        finished_at: Some(finished_at),
+                        status: RunStatus::Failed,
+                        log_size_bytes: total_bytes,
+                        error: Some(error_msg.clone()),
+                        ..base_run
+                    };
+                    if let Err(e) = log_store.update_run(&failed_run).await {
+                        tracing::error!("Failed to update run with undrained output: {}", e);
+                    }
+
+                    let _ = event_tx.send(JobEvent::Failed {
+                        job_id,
+                        run_id,
+                        error: error_msg,
+                        timestamp: finished_at,
+                    });
+                    failed_run
+                }
+            };
 
             // Cleanup old log files after run completes
             if let Err(e) = log_store.cleanup(job_id, max_log_files).await {
                 tracing::error!("Failed to cleanup logs for job {}: {}", job_id, e);
             }
+            finished_run
+        };
+
+        let compress_store = Arc::clone(&self.log_store);
+        let join_handle = tokio::spawn(async move {
+            let finished_run = execution_task.await;
+            if let Some((retry_tx, job)) = retry {
+                schedule_retry(retry_tx, job, &finished_run);
+            }
+            // Compressing can take a while for a large log; the run's end has
+            // been recorded and announced, so it need not hold up anything.
+            tokio::spawn(async move { compress_store.compress_finished(&finished_run).await });
         });
 
         Ok(RunHandle {
             run_id,
             job_id,
+            job_name,
+            started_at: now,
             join_handle,
             kill_tx,
+            killer: killer_slot,
         })
     }
+
+    /// Record a dispatch that is waiting in the run queue, so it shows up in
+    /// the job's run history until it starts.
+    pub async fn record_queued(
+        &self,
+        request: &DispatchRequest,
+        queued_at: DateTime<Utc>,
+        reason: &str,
+    ) -> anyhow::Result<JobRun> {
+        let job = &request.job;
+        let run = JobRun {
+            run_id: request.run_id,
+            job_id: job.id,
+            started_at: queued_at,
+            finished_at: None,
+            status: RunStatus::Queued,
+            exit_code: None,
+            log_size_bytes: 0,
+            error: None,
+            trigger_params: request.trigger_params.clone(),
+            signal: None,
+            attempt: request.attempt,
+            parent_run_id: request.parent_run_id,
+            scheduled_for: request.scheduled_for,
+            catch_up: request.catch_up,
+            trigger: Some(request.trigger),
+        };
+        self.log_store.create_run(&run).await?;
+
+        let _ = self.event_tx.send(JobEvent::Queued {
+            job_id: job.id,
+            run_id: request.run_id,
+            reason: reason.to_string(),
+            timestamp: queued_at,
+        });
+
+        Ok(run)
+    }
+
+    /// Record a dispatch that was turned away without starting a process, so
+    /// it still shows up in the job's run history.
+    pub async fn record_skipped(
+        &self,
+        request: &DispatchRequest,
+        reason: &str,
+    ) -> anyhow::Result<JobRun> {
+        let job = &request.job;
+        let now = Utc::now();
+        let run = JobRun {
+            run_id: request.run_id,
+            job_id: job.id,
+            started_at: now,
+            finished_at: Some(now),
+            status: RunStatus::Skipped,
+            exit_code: None,
+            log_size_bytes: 0,
+            error: Some(reason.to_string()),
+            trigger_params: request.trigger_params.clone(),
+            signal: None,
+            attempt: request.attempt,
+            parent_run_id: request.parent_run_id,
+            scheduled_for: request.scheduled_for,
+            catch_up: request.catch_up,
+            trigger: Some(request.trigger),
+        };
+        self.log_store.create_run(&run).await?;
+
+        let _ = self.event_tx.send(JobEvent::Skipped {
+            job_id: job.id,
+            run_id: request.run_id,
+            reason: reason.to_string(),
+            timestamp: now,
+        });
+
+        if let Err(e) = self
+            .log_store
+            .cleanup(
+                job.id,
+                self.config.retention_for(&job.log_retention).max_runs,
+            )
+            .await
+        {
+            tracing::error!("Failed to cleanup logs for job {}: {}", job.id, e);
+        }
+
+        Ok(run)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::ExecutionType;
+    use crate::models::{ExecutionType, Schedule};
     use crate::pty::MockPtySpawner;
     use crate::storage::LogStore;
     use async_trait::async_trait;
@@ -593,6 +924,7 @@ mod tests {
         runs: RwLock<Vec<JobRun>>,
         logs: RwLock<HashMap<(Uuid, Uuid), Vec<u8>>>,
         cleanup_calls: RwLock<Vec<(Uuid, usize)>>,
+        compressed: RwLock<Vec<Uuid>>,
     }
 
     impl InMemoryLogStore {
@@ -601,6 +933,7 @@ mod tests {
                 runs: RwLock::new(Vec::new()),
                 logs: RwLock::new(HashMap::new()),
                 cleanup_calls: RwLock::new(Vec::new()),
+                compressed: RwLock::new(Vec::new()),
             }
         }
     }
@@ -658,10 +991,31 @@ mod tests {
             Ok((paginated, total))
         }
 
+        async fn find_run(&self, run_id: Uuid) -> anyhow::Result<Option<JobRun>> {
+            let runs = self.runs.read().await;
+            Ok(runs.iter().find(|r| r.run_id == run_id).cloned())
+        }
+
         async fn cleanup(&self, job_id: Uuid, max_files: usize) -> anyhow::Result<()> {
             self.cleanup_calls.write().await.push((job_id, max_files));
             Ok(())
         }
+
+        async fn remove_runs(&self, job_id: Uuid, run_ids: &[Uuid]) -> anyhow::Result<()> {
+            self.runs
+                .write()
+                .await
+                .retain(|r| r.job_id != job_id || !run_ids.contains(&r.run_id));
+            let mut logs = self.logs.write().await;
+            for run_id in run_ids {
+                logs.remove(&(job_id, *run_id));
+            }
+            Ok(())
+        }
+
+        async fn compress_finished(&self, run: &JobRun) {
+            self.compressed.write().await.push(run.run_id);
+        }
     }
 
     // --- Test helpers ---
@@ -671,19 +1025,38 @@ mod tests {
         Job {
             id: Uuid::now_v7(),
             name: "test-job".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ShellCommand("echo hello".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: now,
             updated_at: now,
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         }
     }
 
@@ -751,6 +1124,34 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_executor_compresses_log_after_completion() {
+        let spawner = MockPtySpawner::with_output_and_exit(vec![b"hello\n".to_vec()], 0);
+        let (executor, mut event_rx, log_store) = setup_executor(spawner);
+        let job = make_test_job();
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        handle.join_handle.await.expect("join");
+
+        let mut completed = false;
+        while let Ok(event) = event_rx.try_recv() {
+            completed |= matches!(event, JobEvent::Completed { .. });
+        }
+        assert!(completed, "Expected a Completed event");
+
+        // The log is compressed in the background, once the run has ended.
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while !log_store.compressed.read().await.contains(&handle.run_id) {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("log compressed");
+    }
+
     #[tokio::test]
     async fn test_executor_exit_one() {
         let spawner = MockPtySpawner::with_output_and_exit(vec![b"error\n".to_vec()], 1);
@@ -895,6 +1296,33 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_output_events_are_tagged_with_stream() {
+        let spawner = MockPtySpawner::with_stdout_and_stderr(
+            vec![b"out\n".to_vec()],
+            vec![b"err\n".to_vec()],
+            0,
+        );
+        let (executor, mut event_rx, _log_store) = setup_executor(spawner);
+        let job = make_test_job();
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        handle.join_handle.await.expect("join");
+
+        let mut chunks = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            if let JobEvent::Output { data, stream, .. } = event {
+                chunks.push((stream, data.to_string()));
+            }
+        }
+
+        assert!(chunks.contains(&(OutputStream::Stdout, "out\n".to_string())));
+        assert!(chunks.contains(&(OutputStream::Stderr, "err\n".to_string())));
+    }
+
     #[tokio::test]
     async fn test_log_writer_receives_all_output() {
         let spawner =
@@ -932,6 +1360,75 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_log_writer_truncates_at_max_file_size() {
+        let spawner = MockPtySpawner::with_output_and_exit(
+            vec![
+                b"line1\n".to_vec(),
+                b"line2\n".to_vec(),
+                b"line3\n".to_vec(),
+            ],
+            0,
+        );
+        let (executor, _event_rx, log_store) = setup_executor(spawner);
+        let mut job = make_test_job();
+        // The 13-byte "$ echo hello\n" header leaves room for 8 bytes of output.
+        job.log_retention.max_file_size = Some(21);
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        let run_id = handle.run_id;
+        handle.join_handle.await.expect("join");
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let log_content = log_store
+            .read_log(job.id, run_id, None)
+            .await
+            .expect("read_log");
+        let marker = "\n=== Output truncated at 21 bytes (max_log_file_size) ===\n";
+        assert_eq!(log_content, format!("$ echo hello\nline1\nli{}", marker));
+        assert!(!log_content.contains("line3"));
+
+        let runs = log_store.runs.read().await;
+        let run = runs.iter().find(|r| r.run_id == run_id).expect("run");
+        assert_eq!(run.status, RunStatus::Completed);
+        assert_eq!(run.log_size_bytes, 21 + marker.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_log_writer_counts_environment_dump_towards_max_file_size() {
+        let spawner = MockPtySpawner::with_output_and_exit(vec![b"output\n".to_vec()], 0);
+        let (executor, _event_rx, log_store) = setup_executor(spawner);
+        let mut job = make_test_job();
+        job.log_environment = true;
+        job.log_retention.max_file_size = Some(16);
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        let run_id = handle.run_id;
+        handle.join_handle.await.expect("join");
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let log_content = log_store
+            .read_log(job.id, run_id, None)
+            .await
+            .expect("read_log");
+        let marker = "\n=== Output truncated at 16 bytes (max_log_file_size) ===\n";
+        assert!(
+            log_content.ends_with(&format!("$ echo hello\n{}", marker)),
+            "Output past the environment dump should be discarded, got: {}",
+            log_content
+        );
+
+        let runs = log_store.runs.read().await;
+        let run = runs.iter().find(|r| r.run_id == run_id).expect("run");
+        assert_eq!(run.log_size_bytes, log_content.len() as u64);
+    }
+
     #[tokio::test]
     async fn test_executor_updates_run_on_completion() {
         let spawner = MockPtySpawner::with_output_and_exit(vec![b"output\n".to_vec()], 0);
@@ -1053,19 +1550,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "cmd-test".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ShellCommand("echo hello world".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let cmd = Executor::build_command(&job, None, None);
@@ -1087,19 +1603,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "script-test".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ScriptFile("deploy.sh".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let cmd = Executor::build_command(&job, None, None);
@@ -1191,6 +1726,7 @@ mod tests {
         assert_eq!(run.status, RunStatus::Failed);
         assert!(run.error.as_ref().unwrap().contains("timed out"));
         assert!(run.exit_code.is_none());
+        assert_eq!(run.signal, Some(KillSignal::Term));
     }
 
     #[tokio::test]
@@ -1247,6 +1783,276 @@ mod tests {
         );
     }
 
+    // --- Process group termination tests ---
+
+    fn setup_executor_with_grace(
+        spawner: MockPtySpawner,
+        kill_grace_period_secs: u64,
+    ) -> (
+        Executor,
+        broadcast::Receiver<JobEvent>,
+        Arc<InMemoryLogStore>,
+    ) {
+        let config = DaemonConfig {
+            kill_grace_period_secs,
+            ..Default::default()
+        };
+        let (event_tx, event_rx) = broadcast::channel::<JobEvent>(4096);
+        let log_store = Arc::new(InMemoryLogStore::new());
+
+        let executor = Executor::new(
+            event_tx,
+            Arc::clone(&log_store) as Arc<dyn LogStore>,
+            Arc::new(config),
+            Arc::new(spawner) as Arc<dyn PtySpawner>,
+        );
+
+        (executor, event_rx, log_store)
+    }
+
+    fn retrying_job(retry_on_nonzero_exit: bool) -> Job {
+        Job {
+            retry: crate::models::RetryPolicy {
+                max_attempts: 3,
+                initial_delay_secs: 0,
+                backoff_multiplier: 2.0,
+                max_delay_secs: 0,
+                retry_on_nonzero_exit,
+            },
+            ..make_test_job()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_run_dispatches_retry_attempt() {
+        let spawner = MockPtySpawner::with_spawn_error("boom");
+        let (executor, _event_rx, _log_store) = setup_executor(spawner);
+        let (dispatch_tx, mut dispatch_rx) = mpsc::channel(4);
+        let executor = executor.with_retries(&dispatch_tx);
+        let job = retrying_job(false);
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        let run_id = handle.run_id;
+        handle.join_handle.await.expect("join");
+
+        let retry = tokio::time::timeout(std::time::Duration::from_secs(2), dispatch_rx.recv())
+            .await
+            .expect("retry should be dispatched")
+            .expect("request");
+        assert_eq!(retry.job.id, job.id);
+        assert_eq!(retry.attempt, 2);
+        assert_eq!(retry.parent_run_id, Some(run_id));
+        assert_ne!(retry.run_id, run_id);
+
+        // The retry records its attempt number and points at the first run.
+        let handle = executor.spawn_request(&retry).await.expect("spawn_request");
+        handle.join_handle.await.expect("join");
+        let third = dispatch_rx.recv().await.expect("third attempt");
+        assert_eq!(third.attempt, 3);
+        assert_eq!(third.parent_run_id, Some(run_id));
+    }
+
+    #[tokio::test]
+    async fn test_nonzero_exit_retried_only_when_configured() {
+        for retry_on_nonzero_exit in [false, true] {
+            let spawner = MockPtySpawner::with_output_and_exit(vec![b"oops\n".to_vec()], 1);
+            let (executor, _event_rx, log_store) = setup_executor(spawner);
+            let (dispatch_tx, mut dispatch_rx) = mpsc::channel(4);
+            let executor = executor.with_retries(&dispatch_tx);
+            let job = retrying_job(retry_on_nonzero_exit);
+
+            let handle = executor
+                .spawn_job(&job, Uuid::now_v7(), None)
+                .await
+                .expect("spawn_job");
+            handle.join_handle.await.expect("join");
+            assert_eq!(log_store.runs.read().await[0].attempt, 1);
+
+            let retried =
+                tokio::time::timeout(std::time::Duration::from_millis(200), dispatch_rx.recv())
+                    .await
+                    .is_ok();
+            assert_eq!(retried, retry_on_nonzero_exit);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_last_attempt_is_not_retried() {
+        let spawner = MockPtySpawner::with_spawn_error("boom");
+        let (executor, _event_rx, _log_store) = setup_executor(spawner);
+        let (dispatch_tx, mut dispatch_rx) = mpsc::channel(4);
+        let executor = executor.with_retries(&dispatch_tx);
+        let job = retrying_job(false);
+
+        let request = DispatchRequest {
+            job: job.clone(),
+            run_id: Uuid::now_v7(),
+            trigger_params: None,
+            attempt: 3,
+            parent_run_id: Some(Uuid::now_v7()),
+            scheduled_for: None,
+            catch_up: false,
+            trigger: TriggerSource::Retry,
+            skip_reason: None,
+        };
+        let handle = executor
+            .spawn_request(&request)
+            .await
+            .expect("spawn_request");
+        handle.join_handle.await.expect("join");
+
+        let retried =
+            tokio::time::timeout(std::time::Duration::from_millis(200), dispatch_rx.recv())
+                .await
+                .is_ok();
+        assert!(!retried);
+    }
+
+    #[tokio::test]
+    async fn test_kill_sends_sigterm_and_records_signal() {
+        let spawner = MockPtySpawner::with_slow_output(vec![b"slow\n".to_vec()], 0, 5000);
+        let (executor, _event_rx, log_store) = setup_executor_with_grace(spawner, 10);
+        let job = make_test_job();
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        let run_id = handle.run_id;
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        handle
+            .kill_tx
+            .send(KillReason::Cancelled)
+            .expect("send kill");
+        tokio::time::timeout(std::time::Duration::from_secs(5), handle.join_handle)
+            .await
+            .expect("run should stop well before the grace period")
+            .expect("join");
+
+        let runs = log_store.runs.read().await;
+        let run = runs.iter().find(|r| r.run_id == run_id).expect("run");
+        assert_eq!(run.status, RunStatus::Killed);
+        assert_eq!(run.signal, Some(KillSignal::Term));
+        assert!(run.finished_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_kill_emits_killed_event_with_reason() {
+        let spawner = MockPtySpawner::with_slow_output(vec![b"slow\n".to_vec()], 0, 5000);
+        let (executor, mut event_rx, log_store) = setup_executor_with_grace(spawner, 10);
+        let job = make_test_job();
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        let run_id = handle.run_id;
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        handle
+            .kill_tx
+            .send(KillReason::JobDeleted)
+            .expect("send kill");
+        handle.join_handle.await.expect("join");
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+        assert!(
+            !events.iter().any(|e| matches!(e, JobEvent::Failed { .. })),
+            "A killed run must not be reported as Failed"
+        );
+        match events.iter().find(|e| matches!(e, JobEvent::Killed { .. })) {
+            Some(JobEvent::Killed { reason, signal, .. }) => {
+                assert_eq!(*reason, KillReason::JobDeleted);
+                assert_eq!(*signal, Some(KillSignal::Term));
+            }
+            _ => panic!("Expected a Killed event"),
+        }
+
+        let runs = log_store.runs.read().await;
+        let run = runs.iter().find(|r| r.run_id == run_id).expect("run");
+        assert_eq!(run.error.as_deref(), Some("Job was deleted"));
+    }
+
+    #[tokio::test]
+    async fn test_dropping_kill_handle_does_not_kill_run() {
+        let spawner =
+            MockPtySpawner::with_slow_output(vec![b"a\n".to_vec(), b"b\n".to_vec()], 0, 50);
+        let (executor, _event_rx, log_store) = setup_executor(spawner);
+        let job = make_test_job();
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        let run_id = handle.run_id;
+
+        drop(handle.kill_tx);
+        handle.join_handle.await.expect("join");
+
+        let runs = log_store.runs.read().await;
+        let run = runs.iter().find(|r| r.run_id == run_id).expect("run");
+        assert_eq!(run.status, RunStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_kill_escalates_to_sigkill_after_grace_period() {
+        let spawner = MockPtySpawner::new(crate::pty::MockPtyConfig {
+            output: vec![b"ignoring\n".to_vec(), b"still here\n".to_vec()],
+            chunk_delay_ms: 5000,
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let (executor, _event_rx, log_store) = setup_executor_with_grace(spawner, 1);
+        let job = make_test_job();
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        let run_id = handle.run_id;
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        handle
+            .kill_tx
+            .send(KillReason::Cancelled)
+            .expect("send kill");
+        tokio::time::timeout(std::time::Duration::from_secs(5), handle.join_handle)
+            .await
+            .expect("run should stop shortly after the grace period")
+            .expect("join");
+
+        let runs = log_store.runs.read().await;
+        let run = runs.iter().find(|r| r.run_id == run_id).expect("run");
+        assert_eq!(run.status, RunStatus::Killed);
+        assert_eq!(run.signal, Some(KillSignal::Kill));
+    }
+
+    #[tokio::test]
+    async fn test_completed_run_has_no_signal() {
+        let spawner = MockPtySpawner::with_output_and_exit(vec![b"done\n".to_vec()], 0);
+        let (executor, _event_rx, log_store) = setup_executor(spawner);
+        let job = make_test_job();
+
+        let handle = executor
+            .spawn_job(&job, Uuid::now_v7(), None)
+            .await
+            .expect("spawn_job");
+        let run_id = handle.run_id;
+        handle.join_handle.await.expect("join");
+
+        let runs = log_store.runs.read().await;
+        let run = runs.iter().find(|r| r.run_id == run_id).expect("run");
+        assert_eq!(run.status, RunStatus::Completed);
+        assert!(run.signal.is_none());
+    }
+
     // --- Phase 8: Log cleanup after run tests ---
 
     #[tokio::test]
@@ -1309,19 +2115,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "args-test".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ShellCommand("echo hello".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let cmd = Executor::build_command(&job, Some("--extra flag"), None);
@@ -1335,19 +2160,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "script-args-test".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ScriptFile("deploy.sh".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let cmd = Executor::build_command(&job, Some("--env prod"), None);
@@ -1369,19 +2213,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "unix-script-args".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ScriptFile("deploy.sh".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let cmd = Executor::build_command(&job, Some("--flag"), None);
@@ -1400,19 +2263,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "unix-script-no-args".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ScriptFile("deploy.sh".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let cmd = Executor::build_command(&job, None, None);
@@ -1430,14 +2312,29 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "win-ps1-args".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ScriptFile("deploy.ps1".to_string()),
             enabled: true,
-            timezone: None,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
@@ -1461,14 +2358,29 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "win-bat-args".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ScriptFile("deploy.bat".to_string()),
             enabled: true,
-            timezone: None,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
@@ -1494,19 +2406,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "env-test".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ShellCommand("echo".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let cmd = Executor::build_command(&job, None, Some(&trigger_env));
@@ -1535,19 +2466,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "override-test".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ShellCommand("echo".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: Some(job_env),
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let cmd = Executor::build_command(&job, None, Some(&trigger_env));
@@ -1676,19 +2626,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "script-job".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ScriptFile("deploy.sh".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: now,
             updated_at: now,
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let trigger_params = TriggerParams {
@@ -1852,6 +2821,12 @@ mod tests {
                 env: Some(env),
                 input: Some("data".to_string()),
             }),
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
         };
 
         let json = serde_json::to_string_pretty(&run).expect("serialize");
@@ -1877,19 +2852,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "log-env-test".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ShellCommand("echo hello".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: Some(job_env),
             timeout_secs: 0,
             log_environment: true,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: now,
             updated_at: now,
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
 
         let mut trigger_env = HashMap::new();
@@ -1946,6 +2940,12 @@ mod tests {
             log_size_bytes: 0,
             error: None,
             trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
         };
 
         let json = serde_json::to_string(&run).expect("serialize");
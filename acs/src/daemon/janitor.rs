@@ -0,0 +1,468 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{DaemonConfig, JobRun, RunStatus};
+use crate::storage::{JobStore, LogStore};
+
+/// Why the janitor pruned a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PruneReason {
+    /// The job has more runs than its `max_runs`.
+    MaxRuns,
+    /// The run started more than `keep_days` ago.
+    Age,
+    /// The job's logs take more than its byte budget.
+    JobBytes,
+    /// All logs together take more than `max_log_bytes_total`.
+    TotalBytes,
+}
+
+impl std::fmt::Display for PruneReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PruneReason::MaxRuns => "run limit",
+            PruneReason::Age => "age",
+            PruneReason::JobBytes => "job size limit",
+            PruneReason::TotalBytes => "total size limit",
+        })
+    }
+}
+
+/// A run removed by a sweep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunedRun {
+    pub job_id: Uuid,
+    pub run_id: Uuid,
+    /// The bytes its files took on disk.
+    pub bytes: u64,
+    pub reason: PruneReason,
+}
+
+/// Enforces log retention: every sweep prunes the runs a job no longer
+/// keeps under its limits (run count, age and byte budget, each from the
+/// job's `log_retention` or the daemon's configuration), then the oldest
+/// runs across all jobs while the logs take more than `max_log_bytes_total`.
+///
+/// Queued and running runs are never pruned, though their output counts
+/// towards the byte limits.
+pub struct LogJanitor {
+    job_store: Arc<dyn JobStore>,
+    log_store: Arc<dyn LogStore>,
+    config: Arc<DaemonConfig>,
+}
+
+impl LogJanitor {
+    pub fn new(
+        job_store: Arc<dyn JobStore>,
+        log_store: Arc<dyn LogStore>,
+        config: Arc<DaemonConfig>,
+    ) -> Self {
+        Self {
+            job_store,
+            log_store,
+            config,
+        }
+    }
+
+    /// Sweep now, then every `log_janitor_interval_secs`.
+    pub async fn run(self) {
+        let interval = Duration::from_secs(self.config.log_janitor_interval_secs.max(1));
+        loop {
+            if let Err(e) = self.sweep(Utc::now()).await {
+                tracing::warn!("Log retention sweep failed: {}", e);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Prune the runs that fall outside the retention limits at `now`, and
+    /// log what was pruned for each job.
+    pub async fn sweep(&self, now: DateTime<Utc>) -> Result<Vec<PrunedRun>> {
+        let jobs = self.job_store.list_jobs().await?;
+        let mut pruned = Vec::new();
+        // Finished runs kept so far, for the global quota.
+        let mut kept: Vec<(JobRun, u64)> = Vec::new();
+        let mut total_bytes = 0;
+
+        for job in &jobs {
+            let limits = self.config.retention_for(&job.log_retention);
+            // A limit reaching back past the earliest date keeps every run.
+            let cutoff = (limits.keep_days > 0)
+                .then(|| {
+                    now.checked_sub_signed(chrono::Duration::days(i64::from(limits.keep_days)))
+                })
+                .flatten();
+
+            let (mut runs, _) = self.log_store.list_runs(job.id, usize::MAX, 0).await?;
+            runs.sort_by_key(|r| std::cmp::Reverse((r.started_at, r.run_id)));
+
+            let mut count = 0;
+            let mut job_bytes = 0;
+            let mut over_budget = false;
+            for run in runs {
+                let bytes = self.log_store.run_disk_usage(&run).await?;
+                count += 1;
+                job_bytes += bytes;
+                if matches!(run.status, RunStatus::Queued | RunStatus::Running) {
+                    total_bytes += bytes;
+                    continue;
+                }
+
+                // Once the budget is exceeded, every older run goes too.
+                over_budget |= limits.max_bytes > 0 && job_bytes > limits.max_bytes;
+                let reason = if count > limits.max_runs {
+                    Some(PruneReason::MaxRuns)
+                } else if cutoff.is_some_and(|cutoff| run.started_at < cutoff) {
+                    Some(PruneReason::Age)
+                } else if over_budget {
+                    Some(PruneReason::JobBytes)
+                } else {
+                    None
+                };
+                match reason {
+                    Some(reason) => {
+                        job_bytes -= bytes;
+                        pruned.push(PrunedRun {
+                            job_id: job.id,
+                            run_id: run.run_id,
+                            bytes,
+                            reason,
+                        });
+                    }
+                    None => {
+                        total_bytes += bytes;
+                        kept.push((run, bytes));
+                    }
+                }
+            }
+        }
+
+        let quota = self.config.max_log_bytes_total;
+        if quota > 0 && total_bytes > quota {
+            kept.sort_by_key(|(run, _)| (run.started_at, run.run_id));
+            for (run, bytes) in kept {
+                if total_bytes <= quota {
+                    break;
+                }
+                total_bytes -= bytes;
+                pruned.push(PrunedRun {
+                    job_id: run.job_id,
+                    run_id: run.run_id,
+                    bytes,
+                    reason: PruneReason::TotalBytes,
+                });
+            }
+        }
+
+        let mut by_job: BTreeMap<Uuid, Vec<&PrunedRun>> = BTreeMap::new();
+        for run in &pruned {
+            by_job.entry(run.job_id).or_default().push(run);
+        }
+        for (job_id, runs) in by_job {
+            let run_ids: Vec<Uuid> = runs.iter().map(|r| r.run_id).collect();
+            self.log_store.remove_runs(job_id, &run_ids).await?;
+
+            let mut reasons: BTreeMap<PruneReason, usize> = BTreeMap::new();
+            for run in &runs {
+                *reasons.entry(run.reason).or_default() += 1;
+            }
+            let reasons: Vec<String> = reasons
+                .into_iter()
+                .map(|(reason, n)| format!("{} by {}", n, reason))
+                .collect();
+            let name = jobs
+                .iter()
+                .find(|j| j.id == job_id)
+                .map_or_else(|| job_id.to_string(), |j| j.name.clone());
+            tracing::info!(
+                "Pruned {} run(s) of job '{}', {} bytes ({})",
+                runs.len(),
+                name,
+                runs.iter().map(|r| r.bytes).sum::<u64>(),
+                reasons.join(", ")
+            );
+        }
+
+        Ok(pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Job, NewJob};
+    use crate::storage::jobs::JsonJobStore;
+    use crate::storage::logs::FsLogStore;
+    use tempfile::TempDir;
+
+    struct Setup {
+        _tmp: TempDir,
+        job_store: Arc<dyn JobStore>,
+        log_store: Arc<dyn LogStore>,
+    }
+
+    async fn setup() -> Setup {
+        let tmp = TempDir::new().unwrap();
+        let job_store = Arc::new(JsonJobStore::new(tmp.path().to_path_buf()).await.unwrap());
+        let log_store = Arc::new(FsLogStore::new(tmp.path().to_path_buf()).await.unwrap());
+        Setup {
+            _tmp: tmp,
+            job_store,
+            log_store,
+        }
+    }
+
+    async fn create_job(store: &dyn JobStore, json: serde_json::Value) -> Job {
+        let mut new = serde_json::json!({
+            "schedule": "* * * * *",
+            "execution": {"type": "ShellCommand", "value": "true"}
+        });
+        new.as_object_mut()
+            .unwrap()
+            .extend(json.as_object().unwrap().clone());
+        let new: NewJob = serde_json::from_value(new).unwrap();
+        store.create_job(new).await.unwrap()
+    }
+
+    /// Record a run of `job_id` that started `days_ago` days before `now`
+    /// with `size` bytes of output.
+    async fn add_run(
+        store: &dyn LogStore,
+        job_id: Uuid,
+        now: DateTime<Utc>,
+        days_ago: i64,
+        status: RunStatus,
+        size: usize,
+    ) -> JobRun {
+        let started_at = now - chrono::Duration::days(days_ago);
+        let run = JobRun {
+            run_id: Uuid::now_v7(),
+            job_id,
+            started_at,
+            finished_at: (status != RunStatus::Running).then_some(started_at),
+            status,
+            exit_code: None,
+            log_size_bytes: size as u64,
+            error: None,
+            trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
+        };
+        store.create_run(&run).await.unwrap();
+        store
+            .append_log(job_id, run.run_id, &vec![b'x'; size])
+            .await
+            .unwrap();
+        run
+    }
+
+    async fn run_ids(store: &dyn LogStore, job_id: Uuid) -> Vec<Uuid> {
+        let (runs, _) = store.list_runs(job_id, usize::MAX, 0).await.unwrap();
+        runs.into_iter().map(|r| r.run_id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_sweep_prunes_by_run_count_and_age() {
+        let s = setup().await;
+        let now = Utc::now();
+        let config = Arc::new(DaemonConfig {
+            log_keep_days: 7,
+            ..Default::default()
+        });
+        let limited = create_job(
+            s.job_store.as_ref(),
+            serde_json::json!({"name": "limited", "log_retention": {"max_runs": 2}}),
+        )
+        .await;
+        let forever = create_job(
+            s.job_store.as_ref(),
+            serde_json::json!({"name": "forever", "log_retention": {"keep_days": 0}}),
+        )
+        .await;
+
+        let mut limited_runs = Vec::new();
+        for days_ago in [1, 2, 3] {
+            let run = add_run(
+                s.log_store.as_ref(),
+                limited.id,
+                now,
+                days_ago,
+                RunStatus::Completed,
+                10,
+            )
+            .await;
+            limited_runs.push(run);
+        }
+        let old = add_run(
+            s.log_store.as_ref(),
+            forever.id,
+            now,
+            30,
+            RunStatus::Completed,
+            10,
+        )
+        .await;
+        // The default job has a 10-day-old run that is still running.
+        let default =
+            create_job(s.job_store.as_ref(), serde_json::json!({"name": "default"})).await;
+        let stale = add_run(
+            s.log_store.as_ref(),
+            default.id,
+            now,
+            10,
+            RunStatus::Failed,
+            10,
+        )
+        .await;
+        let running = add_run(
+            s.log_store.as_ref(),
+            default.id,
+            now,
+            10,
+            RunStatus::Running,
+            10,
+        )
+        .await;
+
+        let janitor = LogJanitor::new(Arc::clone(&s.job_store), Arc::clone(&s.log_store), config);
+        let pruned = janitor.sweep(now).await.unwrap();
+
+        let mut reasons: Vec<(Uuid, PruneReason)> =
+            pruned.iter().map(|p| (p.run_id, p.reason)).collect();
+        reasons.sort();
+        let mut expected = vec![
+            (limited_runs[2].run_id, PruneReason::MaxRuns),
+            (stale.run_id, PruneReason::Age),
+        ];
+        expected.sort();
+        assert_eq!(reasons, expected);
+        assert!(pruned.iter().all(|p| p.bytes > 10));
+
+        assert_eq!(
+            run_ids(s.log_store.as_ref(), limited.id).await,
+            vec![limited_runs[0].run_id, limited_runs[1].run_id]
+        );
+        assert_eq!(
+            run_ids(s.log_store.as_ref(), forever.id).await,
+            vec![old.run_id]
+        );
+        assert_eq!(
+            run_ids(s.log_store.as_ref(), default.id).await,
+            vec![running.run_id]
+        );
+        assert!(s.log_store.find_run(stale.run_id).await.unwrap().is_none());
+
+        // A second sweep has nothing left to prune.
+        assert!(janitor.sweep(now).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sweep_keeps_runs_when_keep_days_is_out_of_range() {
+        let s = setup().await;
+        let now = Utc::now();
+        let job = create_job(
+            s.job_store.as_ref(),
+            serde_json::json!({"name": "ancient", "log_retention": {"keep_days": u32::MAX}}),
+        )
+        .await;
+        let run = add_run(
+            s.log_store.as_ref(),
+            job.id,
+            now,
+            30,
+            RunStatus::Completed,
+            10,
+        )
+        .await;
+
+        let janitor = LogJanitor::new(
+            Arc::clone(&s.job_store),
+            Arc::clone(&s.log_store),
+            Arc::new(DaemonConfig::default()),
+        );
+        assert!(janitor.sweep(now).await.unwrap().is_empty());
+        assert_eq!(
+            run_ids(s.log_store.as_ref(), job.id).await,
+            vec![run.run_id]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sweep_enforces_job_budget_and_total_quota() {
+        let s = setup().await;
+        let now = Utc::now();
+        let config = Arc::new(DaemonConfig {
+            max_log_bytes_per_job: 25_000,
+            max_log_bytes_total: 25_000,
+            ..Default::default()
+        });
+        let a = create_job(s.job_store.as_ref(), serde_json::json!({"name": "a"})).await;
+        let b = create_job(s.job_store.as_ref(), serde_json::json!({"name": "b"})).await;
+
+        // Job a: three 10 KB runs, of which two fit its budget.
+        let mut a_runs = Vec::new();
+        for days_ago in [1, 3, 5] {
+            let run = add_run(
+                s.log_store.as_ref(),
+                a.id,
+                now,
+                days_ago,
+                RunStatus::Completed,
+                10_000,
+            )
+            .await;
+            a_runs.push(run);
+        }
+        // Job b: two 10 KB runs, within its budget.
+        let b_new = add_run(
+            s.log_store.as_ref(),
+            b.id,
+            now,
+            2,
+            RunStatus::Completed,
+            10_000,
+        )
+        .await;
+        let b_old = add_run(
+            s.log_store.as_ref(),
+            b.id,
+            now,
+            4,
+            RunStatus::Completed,
+            10_000,
+        )
+        .await;
+
+        let janitor = LogJanitor::new(Arc::clone(&s.job_store), Arc::clone(&s.log_store), config);
+        let pruned = janitor.sweep(now).await.unwrap();
+        let reasons: Vec<(Uuid, PruneReason)> =
+            pruned.iter().map(|p| (p.run_id, p.reason)).collect();
+        // The four runs left take over 40 KB, so the oldest go until the
+        // total fits.
+        assert_eq!(
+            reasons,
+            vec![
+                (a_runs[2].run_id, PruneReason::JobBytes),
+                (b_old.run_id, PruneReason::TotalBytes),
+                (a_runs[1].run_id, PruneReason::TotalBytes),
+            ]
+        );
+        assert_eq!(
+            run_ids(s.log_store.as_ref(), a.id).await,
+            vec![a_runs[0].run_id]
+        );
+        assert_eq!(
+            run_ids(s.log_store.as_ref(), b.id).await,
+            vec![b_new.run_id]
+        );
+    }
+}
@@ -0,0 +1,490 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::Utc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::daemon::events::{JobChangeKind, JobEvent};
+use crate::models::manifest::{changed_fields, parse_job};
+use crate::models::{ManifestFormat, NewJob};
+use crate::server::routes::{
+    check_calendars_exist, check_follow_ups, check_pool_exists, forget_deleted_job_runs,
+    stop_deleted_job_runs, ErrorResponse,
+};
+use crate::server::AppState;
+
+/// How often the jobs directory is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Problems with the files of a jobs directory, by file name.
+pub type JobsDirErrors = Arc<RwLock<BTreeMap<String, String>>>;
+
+/// Keeps the jobs defined in a directory of job files (`jobs.d/`) in line
+/// with those files: a new file creates a job, an edited one replaces the
+/// job's definition, and a removed one deletes the job. Each file holds one
+/// job, with the fields of a manifest entry, in YAML, TOML or JSON by its
+/// extension; without a `name`, the job is named after the file.
+///
+/// Files are checked like jobs created through the API: their calendars,
+/// concurrency pool and follow-up jobs must exist, and removing a file stops
+/// its job's runs. Jobs record the file that defines them in `source_file`,
+/// so jobs created through the API are never touched. A file that cannot be
+/// read, parsed or applied is reported in the daemon log and
+/// [`errors`](Self::errors), and its job is left as it was.
+pub struct JobsDirWatcher {
+    dir: PathBuf,
+    errors: JobsDirErrors,
+    /// The contents of each file when it was last applied. Files whose jobs
+    /// were created before the daemon started have none yet.
+    seen: Option<HashMap<String, Option<String>>>,
+}
+
+impl JobsDirWatcher {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            errors: Arc::new(RwLock::new(BTreeMap::new())),
+            seen: None,
+        }
+    }
+
+    /// The current problems with the directory's files, for `/health`.
+    pub fn errors(&self) -> JobsDirErrors {
+        Arc::clone(&self.errors)
+    }
+
+    /// Apply the directory, then check it for changes every few seconds.
+    pub async fn run(mut self, state: Arc<AppState>) {
+        loop {
+            self.sync(&state).await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Apply the files that were added, changed or removed since the last
+    /// call. The first call compares against the jobs in the store, which
+    /// picks up changes made while the daemon was stopped.
+    pub async fn sync(&mut self, state: &AppState) {
+        let (files, unreadable) = match read_job_files(&self.dir).await {
+            Ok(listing) => listing,
+            Err(e) => {
+                // Without a listing, no file can be told apart from a removed one.
+                tracing::warn!(
+                    "Failed to read jobs directory {}: {}",
+                    self.dir.display(),
+                    e
+                );
+                return;
+            }
+        };
+
+        let mut seen = match self.seen.take() {
+            Some(seen) => seen,
+            None => match state.job_store.list_jobs().await {
+                Ok(jobs) => jobs
+                    .into_iter()
+                    .filter_map(|j| j.source_file)
+                    .map(|file| (file, None))
+                    .collect(),
+                Err(e) => {
+                    tracing::warn!("Failed to list jobs for the jobs directory: {}", e);
+                    return;
+                }
+            },
+        };
+
+        // A file that is listed but cannot be read is still there: its job
+        // is kept, and the file is applied once it can be read.
+        for (file, error) in &unreadable {
+            self.report(file, format!("Failed to read file: {}", error))
+                .await;
+            seen.insert(file.clone(), None);
+        }
+
+        let mut changed = false;
+        let removed: Vec<String> = seen
+            .keys()
+            .filter(|file| !files.contains_key(*file) && !unreadable.contains_key(*file))
+            .cloned()
+            .collect();
+        for file in removed {
+            match self.remove_file_job(state, &file).await {
+                Ok(removed) => {
+                    changed |= removed;
+                    seen.remove(&file);
+                    self.errors.write().await.remove(&file);
+                }
+                Err(e) => self.report(&file, e).await,
+            }
+        }
+
+        for (file, contents) in files {
+            if seen
+                .get(&file)
+                .is_some_and(|c| c.as_ref() == Some(&contents))
+            {
+                continue;
+            }
+            match self.apply_file(state, &file, &contents).await {
+                Ok(applied) => {
+                    changed |= applied;
+                    self.errors.write().await.remove(&file);
+                }
+                Err(e) => self.report(&file, e).await,
+            }
+            // A file that failed is tried again once it changes.
+            seen.insert(file, Some(contents));
+        }
+
+        self.seen = Some(seen);
+        if changed {
+            state.scheduler_notify.notify_one();
+        }
+    }
+
+    async fn report(&self, file: &str, error: String) {
+        tracing::error!("Jobs directory file '{}': {}", file, error);
+        self.errors.write().await.insert(file.to_string(), error);
+    }
+
+    /// Create or update the job defined by `file`. Returns whether the
+    /// store changed.
+    async fn apply_file(
+        &self,
+        state: &AppState,
+        file: &str,
+        contents: &str,
+    ) -> Result<bool, String> {
+        let path = Path::new(file);
+        let format = ManifestFormat::from_path(path).unwrap_or_default();
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(file);
+        let new = parse_job(contents, format, stem)?;
+
+        let jobs = state
+            .job_store
+            .list_jobs()
+            .await
+            .map_err(|e| e.to_string())?;
+        let existing = jobs.iter().find(|j| j.source_file.as_deref() == Some(file));
+        if let Some(other) = jobs
+            .iter()
+            .find(|j| j.name == new.name && Some(j.id) != existing.map(|e| e.id))
+        {
+            return Err(match &other.source_file {
+                Some(other_file) => {
+                    format!("Job '{}' is already defined in '{}'", new.name, other_file)
+                }
+                None => format!("A job named '{}' was created through the API", new.name),
+            });
+        }
+
+        if existing.is_some_and(|job| changed_fields(&NewJob::from(job), &new).is_empty()) {
+            return Ok(false);
+        }
+
+        let calendars = [
+            new.include_calendars.as_slice(),
+            new.exclude_calendars.as_slice(),
+        ]
+        .concat();
+        check_calendars_exist(state, &calendars)
+            .await
+            .map_err(rejection)?;
+        check_pool_exists(state, new.pool.as_deref()).map_err(rejection)?;
+        check_follow_ups(
+            state,
+            existing.map(|job| job.id),
+            &new.name,
+            &new.on_success,
+            &new.on_failure,
+        )
+        .await
+        .map_err(rejection)?;
+
+        match existing {
+            Some(job) => {
+                let job = state
+                    .job_store
+                    .replace_job(job.id, new)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                tracing::info!("Job '{}' updated from '{}'", job.name, file);
+                send_changed(state, job.id, JobChangeKind::Updated);
+                Ok(true)
+            }
+            None => {
+                let job = state
+                    .job_store
+                    .create_file_job(new, file.to_string())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                tracing::info!("Job '{}' created from '{}'", job.name, file);
+                send_changed(state, job.id, JobChangeKind::Added);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Delete the job defined by the removed `file`. Returns whether there
+    /// was one.
+    async fn remove_file_job(&self, state: &AppState, file: &str) -> Result<bool, String> {
+        let jobs = state
+            .job_store
+            .list_jobs()
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(job) = jobs
+            .into_iter()
+            .find(|j| j.source_file.as_deref() == Some(file))
+        else {
+            return Ok(false);
+        };
+        stop_deleted_job_runs(state, job.id).await;
+        state
+            .job_store
+            .delete_job(job.id)
+            .await
+            .map_err(|e| e.to_string())?;
+        forget_deleted_job_runs(state, job.id).await;
+        tracing::info!("Job '{}' removed with '{}'", job.name, file);
+        send_changed(state, job.id, JobChangeKind::Removed);
+        Ok(true)
+    }
+}
+
+fn send_changed(state: &AppState, job_id: Uuid, change: JobChangeKind) {
+    let _ = state.event_tx.send(JobEvent::JobChanged {
+        job_id,
+        change,
+        timestamp: Utc::now(),
+    });
+}
+
+/// The message of a check that rejected a file's job.
+fn rejection((_, Json(error)): (StatusCode, Json<ErrorResponse>)) -> String {
+    error.message
+}
+
+/// The job files in `dir`, by file name: files with a `.yaml`, `.yml`,
+/// `.toml` or `.json` extension. Hidden files (such as editor swap files)
+/// are skipped. Listed files that cannot be read are returned apart, with
+/// the error.
+async fn read_job_files(
+    dir: &Path,
+) -> std::io::Result<(BTreeMap<String, String>, BTreeMap<String, std::io::Error>)> {
+    let mut files = BTreeMap::new();
+    let mut unreadable = BTreeMap::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if name.starts_with('.')
+            || ManifestFormat::from_path(Path::new(&name)).is_none()
+            || !entry.file_type().await?.is_file()
+        {
+            continue;
+        }
+        match tokio::fs::read_to_string(entry.path()).await {
+            Ok(contents) => {
+                files.insert(name, contents);
+            }
+            Err(e) => {
+                unreadable.insert(name, e);
+            }
+        }
+    }
+    Ok((files, unreadable))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::dispatcher::RunQueue;
+    use crate::daemon::executor::RunHandle;
+    use crate::models::{DaemonConfig, KillReason};
+    use crate::storage::calendars::JsonCalendarStore;
+    use crate::storage::jobs::JsonJobStore;
+    use crate::storage::logs::FsLogStore;
+    use crate::storage::workflows::JsonWorkflowStore;
+    use std::time::Instant;
+    use tempfile::TempDir;
+    use tokio::sync::{broadcast, oneshot, Notify};
+
+    const BACKUP: &str =
+        "schedule: \"0 2 * * *\"\nexecution: { type: ShellCommand, value: ./backup.sh }\n";
+
+    async fn setup() -> (TempDir, PathBuf, Arc<AppState>, JobsDirWatcher) {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("jobs.d");
+        std::fs::create_dir(&dir).unwrap();
+        let data_dir = tmp.path().to_path_buf();
+        let (event_tx, _) = broadcast::channel(16);
+        let state = Arc::new(AppState {
+            job_store: Arc::new(JsonJobStore::new(data_dir.clone()).await.unwrap()),
+            log_store: Arc::new(FsLogStore::new(data_dir.clone()).await.unwrap()),
+            calendar_store: Arc::new(JsonCalendarStore::new(data_dir.clone()).await.unwrap()),
+            workflow_store: Arc::new(JsonWorkflowStore::new(data_dir).await.unwrap()),
+            event_tx,
+            scheduler_notify: Arc::new(Notify::new()),
+            config: Arc::new(DaemonConfig::default()),
+            start_time: Instant::now(),
+            active_runs: Arc::new(RwLock::new(HashMap::new())),
+            run_queue: RunQueue::default(),
+            shutdown_tx: None,
+            dispatch_tx: None,
+            workflow_engine: None,
+            jobs_dir_errors: None,
+        });
+        let watcher = JobsDirWatcher::new(dir.clone());
+        (tmp, dir, state, watcher)
+    }
+
+    #[tokio::test]
+    async fn test_files_create_update_and_remove_jobs() {
+        let (_tmp, dir, state, mut watcher) = setup().await;
+        let store = &state.job_store;
+        let api_job: NewJob = serde_json::from_value(serde_json::json!({
+            "name": "api-job",
+            "schedule": "* * * * *",
+            "execution": {"type": "ShellCommand", "value": "true"}
+        }))
+        .unwrap();
+        store.create_job(api_job).await.unwrap();
+
+        std::fs::write(dir.join("backup.yaml"), BACKUP).unwrap();
+        std::fs::write(dir.join(".backup.yaml.swp"), "junk").unwrap();
+        watcher.sync(&state).await;
+        let job = store
+            .find_by_name("backup")
+            .await
+            .unwrap()
+            .expect("created");
+        assert_eq!(job.source_file.as_deref(), Some("backup.yaml"));
+        assert_eq!(store.list_jobs().await.unwrap().len(), 2);
+
+        std::fs::write(
+            dir.join("backup.yaml"),
+            format!("{}timeout_secs: 60\n", BACKUP),
+        )
+        .unwrap();
+        watcher.sync(&state).await;
+        let updated = store.get_job(job.id).await.unwrap().expect("kept its id");
+        assert_eq!(updated.timeout_secs, 60);
+
+        // Removing the file stops the job's runs before deleting it.
+        let run_id = Uuid::now_v7();
+        let (kill_tx, kill_rx) = oneshot::channel();
+        state.active_runs.write().await.insert(
+            run_id,
+            RunHandle {
+                run_id,
+                job_id: job.id,
+                job_name: job.name.clone(),
+                started_at: Utc::now(),
+                join_handle: tokio::spawn(async {}),
+                kill_tx,
+                killer: Default::default(),
+            },
+        );
+        std::fs::remove_file(dir.join("backup.yaml")).unwrap();
+        watcher.sync(&state).await;
+        assert!(store.get_job(job.id).await.unwrap().is_none());
+        assert!(matches!(kill_rx.await, Ok(KillReason::JobDeleted)));
+        assert!(
+            state.active_runs.read().await.contains_key(&run_id),
+            "a killed run stays active until its process exits"
+        );
+        assert!(
+            store.find_by_name("api-job").await.unwrap().is_some(),
+            "jobs created through the API are left alone"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_files_are_checked_like_api_jobs() {
+        let (_tmp, dir, state, mut watcher) = setup().await;
+        std::fs::write(
+            dir.join("backup.yaml"),
+            format!("{}include_calendars: [holidays]\n", BACKUP),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("report.yaml"),
+            format!("{}pool: reports\n", BACKUP),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("loop.yaml"),
+            format!("{}on_success: [loop]\n", BACKUP),
+        )
+        .unwrap();
+        watcher.sync(&state).await;
+
+        assert!(state.job_store.list_jobs().await.unwrap().is_empty());
+        let errors = watcher.errors();
+        let errors = errors.read().await;
+        assert_eq!(errors["backup.yaml"], "Calendar 'holidays' not found");
+        assert_eq!(
+            errors["report.yaml"],
+            "Concurrency pool 'reports' is not configured"
+        );
+        assert!(errors.contains_key("loop.yaml"));
+    }
+
+    #[tokio::test]
+    async fn test_bad_files_are_reported_without_affecting_others() {
+        let (_tmp, dir, state, mut watcher) = setup().await;
+        let store = &state.job_store;
+        std::fs::write(dir.join("backup.yaml"), BACKUP).unwrap();
+        std::fs::write(dir.join("report.toml"), "schedule = ").unwrap();
+        watcher.sync(&state).await;
+        assert!(store.find_by_name("backup").await.unwrap().is_some());
+        assert!(store.find_by_name("report").await.unwrap().is_none());
+        let errors = watcher.errors();
+        assert_eq!(
+            errors.read().await.keys().collect::<Vec<_>>(),
+            ["report.toml"]
+        );
+
+        // Breaking a file keeps its job as it was; fixing it clears the error.
+        std::fs::write(dir.join("backup.yaml"), "schedule: [").unwrap();
+        std::fs::write(
+            dir.join("report.toml"),
+            "schedule = \"0 9 * * *\"\nexecution = { type = \"ShellCommand\", value = \"r\" }\n",
+        )
+        .unwrap();
+        watcher.sync(&state).await;
+        assert!(store.find_by_name("backup").await.unwrap().is_some());
+        assert!(store.find_by_name("report").await.unwrap().is_some());
+        assert_eq!(
+            errors.read().await.keys().collect::<Vec<_>>(),
+            ["backup.yaml"]
+        );
+
+        // A file that cannot be read is reported, and its job kept.
+        std::fs::write(dir.join("report.toml"), [0xff, 0xfe, b'\n']).unwrap();
+        watcher.sync(&state).await;
+        assert!(store.find_by_name("report").await.unwrap().is_some());
+        assert!(errors.read().await["report.toml"].starts_with("Failed to read file"));
+        std::fs::write(
+            dir.join("report.toml"),
+            "schedule = \"0 9 * * *\"\nexecution = { type = \"ShellCommand\", value = \"r\" }\n",
+        )
+        .unwrap();
+        watcher.sync(&state).await;
+        assert!(!errors.read().await.contains_key("report.toml"));
+
+        // A restarted watcher removes the jobs of files deleted meanwhile.
+        std::fs::remove_file(dir.join("report.toml")).unwrap();
+        let mut restarted = JobsDirWatcher::new(dir);
+        restarted.sync(&state).await;
+        assert!(store.find_by_name("report").await.unwrap().is_none());
+        assert!(store.find_by_name("backup").await.unwrap().is_some());
+    }
+}
@@ -1,10 +1,14 @@
 // Daemon module - Phase 2+ implementation
 // Sub-modules for events, executor, scheduler, and service.
 
+pub mod dispatcher;
 pub mod events;
 pub mod executor;
+pub mod janitor;
+pub mod jobs_dir;
 pub mod scheduler;
 pub mod service;
+pub mod workflows;
 
 use std::collections::HashMap;
 use std::io::Write;
@@ -14,16 +18,21 @@ use std::time::Instant;
 
 use anyhow::{Context, Result};
 use chrono::Utc;
-use tokio::sync::{broadcast, Notify, RwLock};
+use tokio::sync::{broadcast, mpsc, Notify, RwLock};
 use tracing;
 use uuid::Uuid;
 
+use crate::daemon::dispatcher::{Dispatcher, RunQueue};
 use crate::daemon::events::JobEvent;
 use crate::daemon::executor::{Executor, RunHandle};
 use crate::daemon::scheduler::Scheduler;
-use crate::models::{DaemonConfig, RunStatus};
+use crate::daemon::workflows::WorkflowEngine;
+use crate::models::{
+    DaemonConfig, DispatchRequest, KillReason, RunStatus, StorageBackend, TriggerParams,
+    TriggerSource,
+};
 use crate::server::{self, AppState};
-use crate::storage::LogStore;
+use crate::storage::{JobStore, LogStore};
 
 // ---------------------------------------------------------------------------
 // PidFile — exclusive PID file acquisition
@@ -412,15 +421,120 @@ pub async fn create_data_dirs(data_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Follow-up triggers
+// ---------------------------------------------------------------------------
+
+/// Dispatch the `on_success` or `on_failure` jobs of the job whose run
+/// `run_id` just finished with `status`, and return how many were sent.
+///
+/// A run counts as a success if it completed with exit code 0, and as a
+/// failure if it failed or exited non-zero; killed and skipped runs trigger
+/// nothing. A failure that the job's retry policy will retry triggers
+/// nothing either; its last attempt decides. Disabled follow-up jobs are
+/// not run.
+pub async fn dispatch_follow_ups(
+    job_store: &dyn JobStore,
+    log_store: &dyn LogStore,
+    dispatch_tx: &mpsc::Sender<DispatchRequest>,
+    job_id: Uuid,
+    run_id: Uuid,
+    status: RunStatus,
+    exit_code: Option<i32>,
+) -> Result<usize> {
+    let Some(job) = job_store.get_job(job_id).await? else {
+        return Ok(0);
+    };
+    let targets = match status {
+        RunStatus::Completed if exit_code == Some(0) => &job.on_success,
+        RunStatus::Completed | RunStatus::Failed => &job.on_failure,
+        _ => return Ok(0),
+    };
+    if targets.is_empty() {
+        return Ok(0);
+    }
+
+    if job.retry.max_attempts > 1 {
+        let attempt = log_store
+            .find_run(run_id)
+            .await?
+            .map(|r| r.attempt)
+            .unwrap_or(1);
+        if job.retry.retries_after(attempt, &status, exit_code) {
+            return Ok(0);
+        }
+    }
+
+    let input = match job.forward_output_lines {
+        Some(lines) => Some(log_store.read_log(job_id, run_id, Some(lines)).await?),
+        None => None,
+    };
+    let env = HashMap::from([
+        ("ACS_UPSTREAM_RUN_ID".to_string(), run_id.to_string()),
+        ("ACS_UPSTREAM_JOB".to_string(), job.name.clone()),
+    ]);
+
+    let mut dispatched = 0;
+    for target in targets {
+        let found = match Uuid::parse_str(target) {
+            Ok(id) => job_store.get_job(id).await?,
+            Err(_) => job_store.find_by_name(target).await?,
+        };
+        let Some(follow_up) = found else {
+            tracing::warn!("Follow-up job '{}' of '{}' not found", target, job.name);
+            continue;
+        };
+        if !follow_up.enabled {
+            tracing::info!(
+                "Follow-up job '{}' of '{}' is disabled; not running it",
+                follow_up.name,
+                job.name
+            );
+            continue;
+        }
+        tracing::info!(
+            "Triggering '{}' after run {} of '{}'",
+            follow_up.name,
+            run_id,
+            job.name
+        );
+        let request = DispatchRequest {
+            job: follow_up,
+            run_id: Uuid::now_v7(),
+            trigger_params: Some(TriggerParams {
+                args: None,
+                env: Some(env.clone()),
+                input: input.clone(),
+            }),
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: TriggerSource::Upstream,
+            skip_reason: None,
+        };
+        dispatch_tx
+            .send(request)
+            .await
+            .context("Dispatch channel closed")?;
+        dispatched += 1;
+    }
+    Ok(dispatched)
+}
+
 // ---------------------------------------------------------------------------
 // Graceful shutdown
 // ---------------------------------------------------------------------------
 
+/// Extra time, on top of the kill grace period and the post-SIGKILL drain,
+/// that shutdown waits for runs to record their final status.
+const SHUTDOWN_WAIT_MARGIN: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Perform the graceful shutdown sequence per SPEC Section 8:
 ///
 /// 1. Stop accepting new HTTP connections       (handled by caller dropping server)
 /// 2. Stop scheduling new job runs              (handled by caller aborting scheduler)
-/// 3. Kill all running child processes (30s grace)
+/// 3. Kill all running child processes (`kill_grace` before SIGKILL)
 /// 4. Update all in-flight JobRun records to Killed status
 /// 5. Flush all log files                       (implicit with LogStore)
 /// 6. Remove PID file and port file
@@ -428,73 +542,82 @@ pub async fn create_data_dirs(data_dir: &Path) -> Result<()> {
 pub async fn graceful_shutdown(
     active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>>,
     log_store: Arc<dyn LogStore>,
+    kill_grace: std::time::Duration,
     pid_file: Option<&PidFile>,
     port_file: Option<&PortFile>,
 ) {
     tracing::info!("Beginning graceful shutdown sequence...");
 
-    // Step 3: Kill all running processes with 30s grace period
-    let run_entries: Vec<(Uuid, Uuid)>;
-    {
+    // Step 3: Kill all running processes. Every run is signalled first so
+    // their process groups share one SIGTERM grace period instead of being
+    // stopped one after another. The executor sends SIGKILL once the grace
+    // period runs out, so every run gets that long plus the drain timeout to
+    // finish.
+    let handles: Vec<RunHandle> = {
         let mut runs = active_runs.write().await;
-        run_entries = runs
-            .values()
-            .map(|handle| (handle.job_id, handle.run_id))
-            .collect();
-
-        // Send kill signals to all active runs
-        let keys: Vec<Uuid> = runs.keys().cloned().collect();
-        for key in keys {
-            if let Some(handle) = runs.remove(&key) {
-                let _ = handle.kill_tx.send(());
-                // Wait up to 30s for the task to finish
-                let join_handle = handle.join_handle;
-                let timeout_result =
-                    tokio::time::timeout(std::time::Duration::from_secs(30), join_handle).await;
-
-                match timeout_result {
-                    Ok(Ok(())) => {
-                        tracing::info!("Run {} shut down gracefully", key);
-                    }
-                    Ok(Err(e)) => {
-                        tracing::warn!("Run {} task failed during shutdown: {}", key, e);
-                    }
-                    Err(_) => {
-                        tracing::warn!("Run {} did not finish within 30s grace period", key);
-                    }
-                }
+        runs.drain().map(|(_, handle)| handle).collect()
+    };
+    let run_ids: Vec<Uuid> = handles.iter().map(|handle| handle.run_id).collect();
+
+    let mut join_handles = Vec::with_capacity(handles.len());
+    for handle in handles {
+        let _ = handle.kill_tx.send(KillReason::Shutdown);
+        join_handles.push((handle.run_id, handle.join_handle, handle.killer));
+    }
+
+    let wait = kill_grace + executor::KILL_DRAIN_TIMEOUT + SHUTDOWN_WAIT_MARGIN;
+    let deadline = tokio::time::Instant::now() + wait;
+    for (run_id, join_handle, killer) in join_handles {
+        let timeout_result = tokio::time::timeout_at(deadline, join_handle).await;
+
+        match timeout_result {
+            Ok(Ok(())) => {
+                tracing::info!("Run {} shut down gracefully", run_id);
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Run {} task failed during shutdown: {}", run_id, e);
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "Run {} did not finish within {}s of shutdown",
+                    run_id,
+                    wait.as_secs()
+                );
+            }
+        }
+
+        // The daemon is about to exit; make sure no part of the run's
+        // process group outlives it.
+        if let Some(killer) = killer.get() {
+            if let Err(e) = killer.kill() {
+                tracing::warn!("Failed to send SIGKILL to run {}: {}", run_id, e);
             }
         }
     }
 
     // Step 4: Update all in-flight JobRun records to Killed status
-    for (job_id, run_id) in &run_entries {
-        let (runs_list, _) = match log_store.list_runs(*job_id, 1000, 0).await {
-            Ok(r) => r,
+    for run_id in &run_ids {
+        let run = match log_store.find_run(*run_id).await {
+            Ok(Some(run)) => run,
+            Ok(None) => continue,
             Err(e) => {
-                tracing::error!("Failed to list runs for job {}: {}", job_id, e);
+                tracing::error!("Failed to look up run {}: {}", run_id, e);
                 continue;
             }
         };
 
-        for run in runs_list {
-            if run.run_id == *run_id && run.status == RunStatus::Running {
-                let killed_run = crate::models::JobRun {
-                    run_id: run.run_id,
-                    job_id: run.job_id,
-                    started_at: run.started_at,
-                    finished_at: Some(Utc::now()),
-                    status: RunStatus::Killed,
-                    exit_code: None,
-                    log_size_bytes: run.log_size_bytes,
-                    error: Some("Daemon shutting down".to_string()),
-                    trigger_params: run.trigger_params.clone(),
-                };
-                if let Err(e) = log_store.update_run(&killed_run).await {
-                    tracing::error!("Failed to mark run {} as Killed: {}", run_id, e);
-                } else {
-                    tracing::info!("Marked run {} as Killed", run_id);
-                }
+        if run.status == RunStatus::Running {
+            let killed_run = crate::models::JobRun {
+                finished_at: Some(Utc::now()),
+                status: RunStatus::Killed,
+                exit_code: None,
+                error: Some(KillReason::Shutdown.to_string()),
+                ..run
+            };
+            if let Err(e) = log_store.update_run(&killed_run).await {
+                tracing::error!("Failed to mark run {} as Killed: {}", run_id, e);
+            } else {
+                tracing::info!("Marked run {} as Killed", run_id);
             }
         }
     }
@@ -758,11 +881,49 @@ pub async fn start_daemon(
     pid_file.acquire()?;
 
     // Initialize storage
-    let job_store = Arc::new(crate::storage::jobs::JsonJobStore::new(data_dir.clone()).await?)
-        as Arc<dyn crate::storage::JobStore>;
+    let (job_store, log_store) = match config.storage {
+        StorageBackend::Json => (
+            Arc::new(crate::storage::jobs::JsonJobStore::new(data_dir.clone()).await?)
+                as Arc<dyn crate::storage::JobStore>,
+            Arc::new(
+                crate::storage::logs::FsLogStore::new(data_dir.clone())
+                    .await?
+                    .with_compression(config.log_compression),
+            ) as Arc<dyn crate::storage::LogStore>,
+        ),
+        StorageBackend::Sqlite => {
+            use crate::storage::sqlite::{import_files, Database, SqliteJobStore, SqliteLogStore};
+
+            let db = Database::open(&data_dir.join("acs.db"))?;
+            if let Some((jobs, runs)) = import_files(&db, &data_dir).await? {
+                tracing::info!("Imported {} jobs and {} runs into acs.db", jobs, runs);
+            }
+            let job_store = SqliteJobStore::new(db.clone());
+            let log_store = SqliteLogStore::new(db, data_dir.clone())
+                .await?
+                .with_compression(config.log_compression);
+
+            let job_ids = job_store.list_jobs().await?.iter().map(|j| j.id).collect();
+            match log_store.remove_orphaned_runs(job_ids).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Removed {} run records of deleted jobs", n),
+                Err(e) => tracing::warn!("Failed to remove orphaned run records: {}", e),
+            }
+
+            (
+                Arc::new(job_store) as Arc<dyn crate::storage::JobStore>,
+                Arc::new(log_store) as Arc<dyn crate::storage::LogStore>,
+            )
+        }
+    };
+
+    let calendar_store =
+        Arc::new(crate::storage::calendars::JsonCalendarStore::new(data_dir.clone()).await?)
+            as Arc<dyn crate::storage::CalendarStore>;
 
-    let log_store = Arc::new(crate::storage::logs::FsLogStore::new(data_dir.clone()).await?)
-        as Arc<dyn crate::storage::LogStore>;
+    let workflow_store =
+        Arc::new(crate::storage::workflows::JsonWorkflowStore::new(data_dir.clone()).await?)
+            as Arc<dyn crate::storage::WorkflowStore>;
 
     // Clean up orphaned log directories
     if let Err(e) = cleanup_orphaned_logs(&data_dir, job_store.as_ref()).await {
@@ -778,25 +939,57 @@ pub async fn start_daemon(
     // Shutdown channel
     let (shutdown_tx, mut shutdown_rx) = tokio::sync::watch::channel(());
 
-    // Active runs tracking
+    // Active runs tracking, keyed by run_id
     let active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>> = Arc::new(RwLock::new(HashMap::new()));
 
+    // Dispatches waiting for a free slot, listed and cancelled through the API
+    let run_queue = RunQueue::default();
+
     // Create dispatch channel (used by both scheduler and API trigger)
-    let (dispatch_tx, mut dispatch_rx) =
+    let (dispatch_tx, dispatch_rx) =
         tokio::sync::mpsc::channel::<crate::models::DispatchRequest>(64);
     let dispatch_tx_for_api = dispatch_tx.clone();
+    let follow_up_tx = dispatch_tx.clone();
+
+    // Workflow engine: starts workflows and follows their nodes' job events
+    let workflow_engine = Arc::new(WorkflowEngine::new(
+        Arc::clone(&workflow_store),
+        Arc::clone(&job_store),
+        Arc::clone(&log_store),
+        Arc::new(scheduler::SystemClock),
+        dispatch_tx.clone(),
+        config.max_log_files_per_job,
+    ));
+
+    // Jobs directory watcher: keeps the jobs defined in per-job files in sync
+    let jobs_dir_watcher = match &config.jobs_dir {
+        Some(dir) => {
+            let dir = data_dir.join(dir);
+            tokio::fs::create_dir_all(&dir)
+                .await
+                .with_context(|| format!("Failed to create jobs directory: {}", dir.display()))?;
+            tracing::info!("Watching jobs directory: {}", dir.display());
+            Some(jobs_dir::JobsDirWatcher::new(dir))
+        }
+        None => None,
+    };
 
     // Create AppState
     let state = Arc::new(AppState {
         job_store: Arc::clone(&job_store),
         log_store: Arc::clone(&log_store),
+        calendar_store: Arc::clone(&calendar_store),
+        workflow_store,
         event_tx: event_tx.clone(),
         scheduler_notify: Arc::clone(&scheduler_notify),
         config: Arc::clone(&config),
         start_time: Instant::now(),
         active_runs: Arc::clone(&active_runs),
+        run_queue: run_queue.clone(),
         shutdown_tx: Some(shutdown_tx.clone()),
         dispatch_tx: Some(dispatch_tx_for_api),
+        workflow_engine: Some(Arc::clone(&workflow_engine)),
+        jobs_dir_errors: jobs_dir_watcher.as_ref().map(|w| w.errors()),
     });
 
     // Create Executor
@@ -808,7 +1001,8 @@ pub async fn start_daemon(
         Arc::clone(&log_store),
         Arc::clone(&config),
         pty_spawner,
-    );
+    )
+    .with_retries(&dispatch_tx);
 
     // Start Scheduler
     let sched_clock: Arc<dyn scheduler::Clock> = Arc::new(scheduler::SystemClock);
@@ -817,7 +1011,9 @@ pub async fn start_daemon(
         sched_clock,
         Arc::clone(&scheduler_notify),
         dispatch_tx,
-    );
+    )
+    .with_calendars(calendar_store)
+    .with_events(event_tx.clone());
 
     let scheduler_handle = tokio::spawn(async move {
         if let Err(e) = scheduler.run().await {
@@ -826,31 +1022,32 @@ pub async fn start_daemon(
     });
 
     // Dispatch loop: receives jobs from scheduler and spawns them via executor
-    let dispatch_active_runs = Arc::clone(&active_runs);
-    let dispatch_handle = tokio::spawn(async move {
-        while let Some(request) = dispatch_rx.recv().await {
-            match executor
-                .spawn_job(
-                    &request.job,
-                    request.run_id,
-                    request.trigger_params.as_ref(),
-                )
-                .await
-            {
-                Ok(handle) => {
-                    let job_id = handle.job_id;
-                    dispatch_active_runs.write().await.insert(job_id, handle);
-                }
-                Err(e) => {
-                    tracing::error!("Failed to spawn job {}: {}", request.job.name, e);
-                }
-            }
-        }
+    let dispatcher = Dispatcher::new(executor, Arc::clone(&job_store), Arc::clone(&active_runs))
+        .with_queue(run_queue.clone())
+        .with_limits(&config);
+    let dispatch_handle = tokio::spawn(dispatcher.run(dispatch_rx));
+
+    let workflow_events = event_tx.subscribe();
+    let workflow_handle = tokio::spawn(async move { workflow_engine.run(workflow_events).await });
+
+    let jobs_dir_handle =
+        jobs_dir_watcher.map(|watcher| tokio::spawn(watcher.run(Arc::clone(&state))));
+
+    // Log janitor: prunes run logs outside the retention limits
+    let janitor_handle = (config.log_janitor_interval_secs > 0).then(|| {
+        let janitor = janitor::LogJanitor::new(
+            Arc::clone(&job_store),
+            Arc::clone(&log_store),
+            Arc::clone(&config),
+        );
+        tokio::spawn(janitor.run())
     });
 
     // Job metadata updater: listens for job events and updates job store metadata,
-    // and emits tracing log lines for job lifecycle events.
+    // emits tracing log lines for job lifecycle events, and triggers follow-up jobs.
     let updater_job_store = Arc::clone(&job_store);
+    let updater_log_store = Arc::clone(&log_store);
+    let updater_dispatch_tx = follow_up_tx;
     let mut updater_rx = event_tx.subscribe();
     let updater_handle = tokio::spawn(async move {
         loop {
@@ -875,6 +1072,19 @@ pub async fn start_daemon(
                     if let Err(e) = updater_job_store.update_job(job_id, update).await {
                         tracing::error!("Failed to update job metadata after completion: {}", e);
                     }
+                    if let Err(e) = dispatch_follow_ups(
+                        updater_job_store.as_ref(),
+                        updater_log_store.as_ref(),
+                        &updater_dispatch_tx,
+                        job_id,
+                        run_id,
+                        RunStatus::Completed,
+                        Some(exit_code),
+                    )
+                    .await
+                    {
+                        tracing::error!("Failed to trigger follow-up jobs: {}", e);
+                    }
                 }
                 Ok(JobEvent::Failed {
                     job_id,
@@ -890,6 +1100,35 @@ pub async fn start_daemon(
                     if let Err(e) = updater_job_store.update_job(job_id, update).await {
                         tracing::error!("Failed to update job metadata after failure: {}", e);
                     }
+                    if let Err(e) = dispatch_follow_ups(
+                        updater_job_store.as_ref(),
+                        updater_log_store.as_ref(),
+                        &updater_dispatch_tx,
+                        job_id,
+                        run_id,
+                        RunStatus::Failed,
+                        None,
+                    )
+                    .await
+                    {
+                        tracing::error!("Failed to trigger follow-up jobs: {}", e);
+                    }
+                }
+                Ok(JobEvent::Killed {
+                    job_id,
+                    run_id,
+                    reason,
+                    timestamp,
+                    ..
+                }) => {
+                    tracing::warn!("Job run {} killed: {}", run_id, reason);
+                    let update = crate::models::JobUpdate {
+                        last_run_at: Some(Some(timestamp)),
+                        ..Default::default()
+                    };
+                    if let Err(e) = updater_job_store.update_job(job_id, update).await {
+                        tracing::error!("Failed to update job metadata after kill: {}", e);
+                    }
                 }
                 Ok(_) => {} // Ignore other events (Output, JobChanged)
                 Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
@@ -967,15 +1206,29 @@ pub async fn start_daemon(
     // Send shutdown signal to HTTP server
     let _ = shutdown_tx.send(());
 
-    // Stop scheduler, dispatch loop, and updater
+    // Stop scheduler, dispatch loop, workflow engine, jobs directory watcher,
+    // log janitor, and updater
     scheduler_handle.abort();
     dispatch_handle.abort();
+    workflow_handle.abort();
+    if let Some(handle) = jobs_dir_handle {
+        handle.abort();
+    }
+    if let Some(handle) = janitor_handle {
+        handle.abort();
+    }
     updater_handle.abort();
 
+    // Runs still waiting in the queue will never start
+    run_queue
+        .cancel_all(KillReason::Shutdown, log_store.as_ref(), &event_tx)
+        .await;
+
     // Run graceful shutdown sequence
     graceful_shutdown(
         Arc::clone(&active_runs),
         Arc::clone(&log_store),
+        std::time::Duration::from_secs(config.kill_grace_period_secs),
         Some(&pid_file),
         Some(&port_file),
     )
@@ -995,7 +1248,7 @@ pub async fn start_daemon(
 mod tests {
     use super::*;
     use crate::daemon::executor::RunHandle;
-    use crate::models::{JobRun, RunStatus};
+    use crate::models::{JobRun, RunStatus, Schedule};
     use crate::storage::LogStore;
     use async_trait::async_trait;
     use tempfile::TempDir;
@@ -1071,9 +1324,26 @@ mod tests {
             Ok((paginated, total))
         }
 
+        async fn find_run(&self, run_id: Uuid) -> anyhow::Result<Option<JobRun>> {
+            let runs = self.runs.read().await;
+            Ok(runs.iter().find(|r| r.run_id == run_id).cloned())
+        }
+
         async fn cleanup(&self, _job_id: Uuid, _max_files: usize) -> anyhow::Result<()> {
             Ok(())
         }
+
+        async fn remove_runs(&self, job_id: Uuid, run_ids: &[Uuid]) -> anyhow::Result<()> {
+            self.runs
+                .write()
+                .await
+                .retain(|r| r.job_id != job_id || !run_ids.contains(&r.run_id));
+            let mut logs = self.logs.write().await;
+            for run_id in run_ids {
+                logs.remove(&(job_id, *run_id));
+            }
+            Ok(())
+        }
     }
 
     // =======================================================================
@@ -1208,11 +1478,17 @@ mod tests {
             log_size_bytes: 0,
             error: None,
             trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
         };
         log_store.create_run(&running_run).await.unwrap();
 
         // Create a fake active run handle
-        let (kill_tx, _kill_rx) = tokio::sync::oneshot::channel::<()>();
+        let (kill_tx, _kill_rx) = tokio::sync::oneshot::channel::<KillReason>();
         let join_handle = tokio::spawn(async {
             // Simulate a long-running task that finishes quickly on shutdown
             tokio::time::sleep(std::time::Duration::from_millis(10)).await;
@@ -1221,12 +1497,15 @@ mod tests {
         let active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>> =
             Arc::new(RwLock::new(HashMap::new()));
         active_runs.write().await.insert(
-            job_id,
+            run_id,
             RunHandle {
                 run_id,
                 job_id,
+                job_name: "test-job".to_string(),
+                started_at: Utc::now(),
                 join_handle,
                 kill_tx,
+                killer: Default::default(),
             },
         );
 
@@ -1234,6 +1513,7 @@ mod tests {
         graceful_shutdown(
             Arc::clone(&active_runs),
             Arc::clone(&log_store) as Arc<dyn LogStore>,
+            std::time::Duration::from_secs(1),
             None,
             None,
         )
@@ -1264,6 +1544,57 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_shutdown_kills_process_groups_left_behind() {
+        struct RecordingKiller(Arc<std::sync::atomic::AtomicBool>);
+        impl crate::pty::ProcessKiller for RecordingKiller {
+            fn terminate(&self) -> std::io::Result<()> {
+                Ok(())
+            }
+            fn kill(&self) -> std::io::Result<()> {
+                self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        let log_store = Arc::new(InMemoryLogStore::new());
+        let killed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let killer: Arc<std::sync::OnceLock<Box<dyn crate::pty::ProcessKiller>>> =
+            Default::default();
+        let _ = killer.set(Box::new(RecordingKiller(Arc::clone(&killed))));
+
+        let run_id = Uuid::now_v7();
+        let (kill_tx, _kill_rx) = tokio::sync::oneshot::channel::<KillReason>();
+        let active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>> =
+            Arc::new(RwLock::new(HashMap::new()));
+        active_runs.write().await.insert(
+            run_id,
+            RunHandle {
+                run_id,
+                job_id: Uuid::now_v7(),
+                job_name: "test-job".to_string(),
+                started_at: Utc::now(),
+                join_handle: tokio::spawn(async {}),
+                kill_tx,
+                killer,
+            },
+        );
+
+        graceful_shutdown(
+            active_runs,
+            log_store as Arc<dyn LogStore>,
+            std::time::Duration::from_secs(1),
+            None,
+            None,
+        )
+        .await;
+
+        assert!(
+            killed.load(std::sync::atomic::Ordering::SeqCst),
+            "The run's process group should get SIGKILL before the daemon exits"
+        );
+    }
+
     // =======================================================================
     // 6. Service detection (is_service_registered)
     // =======================================================================
@@ -1324,6 +1655,73 @@ mod tests {
     // =======================================================================
     // 8. Data directory creation
     // =======================================================================
+    #[tokio::test]
+    async fn test_shutdown_kills_every_run_of_the_same_job() {
+        let log_store = Arc::new(InMemoryLogStore::new());
+        let active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>> =
+            Arc::new(RwLock::new(HashMap::new()));
+        let job_id = Uuid::now_v7();
+
+        let mut run_ids = Vec::new();
+        for _ in 0..2 {
+            let run_id = Uuid::now_v7();
+            run_ids.push(run_id);
+            log_store
+                .create_run(&JobRun {
+                    run_id,
+                    job_id,
+                    started_at: Utc::now(),
+                    finished_at: None,
+                    status: RunStatus::Running,
+                    exit_code: None,
+                    log_size_bytes: 0,
+                    error: None,
+                    trigger_params: None,
+                    signal: None,
+                    attempt: 1,
+                    parent_run_id: None,
+                    scheduled_for: None,
+                    catch_up: false,
+                    trigger: None,
+                })
+                .await
+                .unwrap();
+
+            let (kill_tx, kill_rx) = tokio::sync::oneshot::channel::<KillReason>();
+            let join_handle = tokio::spawn(async move {
+                let _ = kill_rx.await;
+            });
+            active_runs.write().await.insert(
+                run_id,
+                RunHandle {
+                    run_id,
+                    job_id,
+                    job_name: "test-job".to_string(),
+                    started_at: Utc::now(),
+                    join_handle,
+                    kill_tx,
+                    killer: Default::default(),
+                },
+            );
+        }
+
+        graceful_shutdown(
+            Arc::clone(&active_runs),
+            Arc::clone(&log_store) as Arc<dyn LogStore>,
+            std::time::Duration::from_secs(1),
+            None,
+            None,
+        )
+        .await;
+
+        let runs = log_store.runs.read().await;
+        for run_id in run_ids {
+            let run = runs.iter().find(|r| r.run_id == run_id).expect("run");
+            assert_eq!(run.status, RunStatus::Killed);
+        }
+        assert!(active_runs.read().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_data_directory_creation() {
         let tmp_dir = TempDir::new().expect("create temp dir");
@@ -1481,7 +1879,14 @@ mod tests {
             Arc::new(RwLock::new(HashMap::new()));
         let log_store = Arc::new(InMemoryLogStore::new()) as Arc<dyn LogStore>;
 
-        graceful_shutdown(active_runs, log_store, Some(&pid_file), None).await;
+        graceful_shutdown(
+            active_runs,
+            log_store,
+            std::time::Duration::from_secs(1),
+            Some(&pid_file),
+            None,
+        )
+        .await;
 
         assert!(
             !pid_path.exists(),
@@ -1499,7 +1904,14 @@ mod tests {
         let log_store = Arc::new(InMemoryLogStore::new()) as Arc<dyn LogStore>;
 
         // Should complete without error
-        graceful_shutdown(active_runs, log_store, None, None).await;
+        graceful_shutdown(
+            active_runs,
+            log_store,
+            std::time::Duration::from_secs(1),
+            None,
+            None,
+        )
+        .await;
     }
 
     // =======================================================================
@@ -1631,7 +2043,14 @@ mod tests {
             Arc::new(RwLock::new(HashMap::new()));
         let log_store = Arc::new(InMemoryLogStore::new()) as Arc<dyn LogStore>;
 
-        graceful_shutdown(active_runs, log_store, None, Some(&port_file)).await;
+        graceful_shutdown(
+            active_runs,
+            log_store,
+            std::time::Duration::from_secs(1),
+            None,
+            Some(&port_file),
+        )
+        .await;
 
         assert!(
             !port_path.exists(),
@@ -1657,7 +2076,14 @@ mod tests {
             Arc::new(RwLock::new(HashMap::new()));
         let log_store = Arc::new(InMemoryLogStore::new()) as Arc<dyn LogStore>;
 
-        graceful_shutdown(active_runs, log_store, Some(&pid_file), Some(&port_file)).await;
+        graceful_shutdown(
+            active_runs,
+            log_store,
+            std::time::Duration::from_secs(1),
+            Some(&pid_file),
+            Some(&port_file),
+        )
+        .await;
 
         assert!(
             !pid_path.exists(),
@@ -1722,6 +2148,13 @@ mod tests {
         async fn delete_job(&self, _id: Uuid) -> anyhow::Result<()> {
             unimplemented!()
         }
+        async fn replace_job(
+            &self,
+            _id: Uuid,
+            _new: crate::models::NewJob,
+        ) -> anyhow::Result<crate::models::Job> {
+            unimplemented!()
+        }
     }
 
     fn make_test_job(id: Uuid) -> crate::models::Job {
@@ -1729,19 +2162,38 @@ mod tests {
         crate::models::Job {
             id,
             name: format!("test-{}", id),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: crate::models::ExecutionType::ShellCommand("echo hi".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: now,
             updated_at: now,
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         }
     }
 
@@ -2013,4 +2465,143 @@ mod tests {
             content
         );
     }
+
+    // =======================================================================
+    // Follow-up triggers
+    // =======================================================================
+
+    fn finished_run(job_id: Uuid, attempt: u32, exit_code: i32) -> JobRun {
+        let now = Utc::now();
+        JobRun {
+            run_id: Uuid::now_v7(),
+            job_id,
+            started_at: now,
+            finished_at: Some(now),
+            status: RunStatus::Completed,
+            exit_code: Some(exit_code),
+            log_size_bytes: 0,
+            error: None,
+            trigger_params: None,
+            signal: None,
+            attempt,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_follow_ups_dispatch_on_outcome_with_upstream_context() {
+        let job_store = InMemoryJobStore::new();
+        let log_store = InMemoryLogStore::new();
+        let (tx, mut rx) = mpsc::channel(8);
+
+        let mut upstream = make_test_job(Uuid::now_v7());
+        let report = make_test_job(Uuid::now_v7());
+        let alert = make_test_job(Uuid::now_v7());
+        let mut paused = make_test_job(Uuid::now_v7());
+        paused.enabled = false;
+        upstream.on_success = vec![report.name.clone(), paused.name.clone(), "gone".to_string()];
+        upstream.on_failure = vec![alert.id.to_string()];
+        upstream.forward_output_lines = Some(5);
+        for job in [&upstream, &report, &alert, &paused] {
+            job_store.add_job(job.clone()).await;
+        }
+
+        let run = finished_run(upstream.id, 1, 0);
+        log_store.create_run(&run).await.unwrap();
+        log_store
+            .append_log(upstream.id, run.run_id, b"42 rows\n")
+            .await
+            .unwrap();
+
+        // Success: only the enabled, existing on_success job runs.
+        let sent = dispatch_follow_ups(
+            &job_store,
+            &log_store,
+            &tx,
+            upstream.id,
+            run.run_id,
+            RunStatus::Completed,
+            Some(0),
+        )
+        .await
+        .unwrap();
+        assert_eq!(sent, 1);
+        let request = rx.try_recv().unwrap();
+        assert_eq!(request.job.id, report.id);
+        let params = request.trigger_params.unwrap();
+        assert_eq!(params.input.as_deref(), Some("42 rows\n"));
+        let env = params.env.unwrap();
+        assert_eq!(env["ACS_UPSTREAM_RUN_ID"], run.run_id.to_string());
+        assert_eq!(env["ACS_UPSTREAM_JOB"], upstream.name);
+
+        // A non-zero exit is a failure.
+        dispatch_follow_ups(
+            &job_store,
+            &log_store,
+            &tx,
+            upstream.id,
+            run.run_id,
+            RunStatus::Completed,
+            Some(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rx.try_recv().unwrap().job.id, alert.id);
+
+        // Killed runs trigger nothing.
+        let sent = dispatch_follow_ups(
+            &job_store,
+            &log_store,
+            &tx,
+            upstream.id,
+            run.run_id,
+            RunStatus::Killed,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(sent, 0);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_follow_ups_wait_for_last_retry() {
+        let job_store = InMemoryJobStore::new();
+        let log_store = InMemoryLogStore::new();
+        let (tx, mut rx) = mpsc::channel(8);
+
+        let mut upstream = make_test_job(Uuid::now_v7());
+        let alert = make_test_job(Uuid::now_v7());
+        upstream.retry.max_attempts = 2;
+        upstream.retry.retry_on_nonzero_exit = true;
+        upstream.on_failure = vec![alert.name.clone()];
+        job_store.add_job(upstream.clone()).await;
+        job_store.add_job(alert.clone()).await;
+
+        let first = finished_run(upstream.id, 1, 1);
+        let second = finished_run(upstream.id, 2, 1);
+        log_store.create_run(&first).await.unwrap();
+        log_store.create_run(&second).await.unwrap();
+
+        for (run, expected) in [(&first, 0), (&second, 1)] {
+            let sent = dispatch_follow_ups(
+                &job_store,
+                &log_store,
+                &tx,
+                upstream.id,
+                run.run_id,
+                RunStatus::Completed,
+                Some(1),
+            )
+            .await
+            .unwrap();
+            assert_eq!(sent, expected);
+        }
+        let request = rx.try_recv().unwrap();
+        assert_eq!(request.job.id, alert.id);
+        assert!(request.trigger_params.unwrap().input.is_none());
+    }
 }
@@ -1,15 +1,21 @@
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
-use chrono::{DateTime, Utc};
-use tokio::sync::{mpsc, Notify};
+use chrono::{DateTime, SubsecRound, Utc};
+use croner::Cron;
+use tokio::sync::{broadcast, mpsc, Notify};
 
 use uuid::Uuid;
 
+use crate::daemon::events::{JobChangeKind, JobEvent};
+use crate::models::calendar::{exclusion_reason, JobCalendars};
+use crate::models::cron::parse_cron;
+use crate::models::schedule::spread_seed;
 use crate::models::DispatchRequest;
-use crate::models::Job;
-use crate::storage::JobStore;
+use crate::models::{Calendar, CatchupPolicy, Job, JobUpdate, Schedule, TriggerSource};
+use crate::storage::{CalendarStore, JobStore};
 
 // ---------------------------------------------------------------------------
 // Clock trait + implementations
@@ -63,28 +69,238 @@ impl Clock for FakeClock {
 }
 
 // ---------------------------------------------------------------------------
-// compute_next_run — timezone-aware cron next-occurrence calculation
+// compute_next_run — next occurrence of a schedule
 // ---------------------------------------------------------------------------
 
-/// Compute the next run time for a cron schedule after `after` (exclusive).
+/// Compute the next run time of a job after `after` (exclusive).
+///
+/// This is the next occurrence of the job's schedule, with `H` cron fields
+/// resolved and the job's jitter offset added, both derived from the job id.
+/// Occurrences before the job's `start_at` are passed over. Returns `None`
+/// when the schedule has no further occurrences before the job's `end_at`.
+pub fn compute_next_run(job: &Job, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    RunTimes::new(job)?.next_after(after)
+}
+
+/// The run times of a job, with its schedule parsed once for computing
+/// many of them. See [`compute_next_run`].
+pub struct RunTimes<'a> {
+    job: &'a Job,
+    schedule: PreparedSchedule<'a>,
+    offset: chrono::Duration,
+}
+
+impl<'a> RunTimes<'a> {
+    pub fn new(job: &'a Job) -> Result<Self> {
+        Ok(Self {
+            job,
+            schedule: PreparedSchedule::new(&job.schedule, spread_seed(job.id))?,
+            // Cannot overflow: the offset is at most `MAX_JITTER_SECS`.
+            offset: chrono::Duration::seconds(job.jitter_offset_secs() as i64),
+        })
+    }
+
+    /// The next run time after `after` (exclusive).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        let job = self.job;
+        let out_of_range = || anyhow::anyhow!("Next run of job '{}' is out of range", job.name);
+        let after = match job.start_at {
+            Some(start_at) if after < start_at => start_at
+                .checked_sub_signed(chrono::Duration::seconds(1))
+                .ok_or_else(out_of_range)?,
+            _ => after,
+        };
+        let shifted = after
+            .trunc_subsecs(0)
+            .checked_sub_signed(self.offset)
+            .ok_or_else(out_of_range)?;
+        let next = self.schedule.next_after(shifted)?;
+        // An occurrence pushed past the largest representable time never comes.
+        Ok(next
+            .and_then(|t| t.checked_add_signed(self.offset))
+            .filter(|t| job.end_at.is_none_or(|end_at| *t < end_at)))
+    }
+
+    /// The last run time before `before` (exclusive).
+    pub fn prev_before(&self, before: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        let job = self.job;
+        let before = match job.end_at {
+            Some(end_at) if end_at < before => end_at,
+            _ => before,
+        };
+        // Before the earliest representable time there are no run times.
+        let Some(shifted) = before.checked_sub_signed(self.offset) else {
+            return Ok(None);
+        };
+        let prev = self.schedule.prev_before(shifted)?;
+        Ok(prev
+            .and_then(|t| t.checked_add_signed(self.offset))
+            .filter(|t| {
+                job.start_at
+                    .is_none_or(|start_at| *t >= start_at.trunc_subsecs(0))
+            }))
+    }
+}
+
+/// How many times `next_allowed_run` moves past a calendar period before it
+/// gives up.
+const MAX_EXCLUDED_OCCURRENCES: usize = 10_000;
+
+/// Compute the next run time of a job after `after` (exclusive) that the
+/// job's calendars allow; excluded occurrences are passed over, a whole
+/// excluded period at a time.
+///
+/// Returns `None` when the schedule has no further allowed occurrences, or
+/// when none is found within `MAX_EXCLUDED_OCCURRENCES` periods.
+pub fn next_allowed_run(
+    job: &Job,
+    calendars: &[Calendar],
+    after: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>> {
+    let run_times = RunTimes::new(job)?;
+    let calendars = JobCalendars::new(job, calendars);
+    let mut cursor = after;
+    for _ in 0..=MAX_EXCLUDED_OCCURRENCES {
+        let Some(next) = run_times.next_after(cursor)? else {
+            return Ok(None);
+        };
+        let Some(opening) = calendars.next_opening(next) else {
+            return Ok(None);
+        };
+        if opening == next {
+            return Ok(Some(next));
+        }
+        // Run times are exclusive of the cursor: step back just before the
+        // opening so an occurrence right at it counts.
+        cursor = opening - chrono::Duration::nanoseconds(1);
+    }
+    Ok(None)
+}
+
+/// A schedule with its cron expression and timezone parsed, for computing
+/// many occurrences. See [`next_occurrence`].
+pub struct PreparedSchedule<'a> {
+    schedule: &'a Schedule,
+    cron: Option<(Cron, Option<chrono_tz::Tz>)>,
+}
+
+impl<'a> PreparedSchedule<'a> {
+    pub fn new(schedule: &'a Schedule, seed: u64) -> Result<Self> {
+        let cron = match schedule {
+            Schedule::Cron { expr, tz } => {
+                Some((parse_cron(expr, seed)?, parse_timezone(tz.as_deref())?))
+            }
+            _ => None,
+        };
+        Ok(Self { schedule, cron })
+    }
+
+    /// The next occurrence after `after` (exclusive).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        // croner keeps the sub-second part of `after`; drop it so occurrences
+        // land on whole seconds.
+        let after = after.trunc_subsecs(0);
+        if let Some((cron, tz)) = &self.cron {
+            return cron_next_after(cron, *tz, after).map(Some);
+        }
+        match self.schedule {
+            Schedule::Every { interval, anchor } => {
+                let anchor = anchor.map_or(DateTime::UNIX_EPOCH, |a| a.trunc_subsecs(0));
+                if after < anchor {
+                    return Ok(Some(anchor));
+                }
+                let step = interval.as_secs() as i64;
+                let elapsed = (after - anchor).num_seconds();
+                // Past the largest representable time there are no occurrences.
+                Ok(chrono::Duration::try_seconds((elapsed / step + 1) * step)
+                    .and_then(|d| anchor.checked_add_signed(d)))
+            }
+            Schedule::At { timestamp } => Ok((*timestamp > after).then_some(*timestamp)),
+            Schedule::Cron { .. } | Schedule::Manual => Ok(None),
+        }
+    }
+
+    /// The last occurrence before `before` (exclusive).
+    pub fn prev_before(&self, before: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        // Occurrences land on whole seconds, so one in the same second as
+        // `before` but earlier must still be found.
+        let truncated = before.trunc_subsecs(0);
+        let before = if truncated < before {
+            truncated
+                .checked_add_signed(chrono::Duration::seconds(1))
+                .unwrap_or(truncated)
+        } else {
+            truncated
+        };
+        if let Some((cron, tz)) = &self.cron {
+            return cron_prev_before(cron, *tz, before).map(Some);
+        }
+        match self.schedule {
+            Schedule::Every { interval, anchor } => {
+                let anchor = anchor.map_or(DateTime::UNIX_EPOCH, |a| a.trunc_subsecs(0));
+                if before <= anchor {
+                    return Ok(None);
+                }
+                let step = interval.as_secs() as i64;
+                let elapsed = (before - anchor).num_seconds();
+                Ok(chrono::Duration::try_seconds((elapsed - 1) / step * step)
+                    .and_then(|d| anchor.checked_add_signed(d)))
+            }
+            Schedule::At { timestamp } => Ok((*timestamp < before).then_some(*timestamp)),
+            Schedule::Cron { .. } | Schedule::Manual => Ok(None),
+        }
+    }
+}
+
+/// Compute the next occurrence of a schedule after `after` (exclusive),
+/// resolving `H` cron fields with `seed`.
+///
+/// Returns `None` when the schedule has no further occurrences: `Manual`
+/// schedules never do, and `At` schedules only until their timestamp.
+/// Occurrences always fall on whole seconds.
+pub fn next_occurrence(
+    schedule: &Schedule,
+    seed: u64,
+    after: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>> {
+    PreparedSchedule::new(schedule, seed)?.next_after(after)
+}
+
+/// Compute the next occurrence of a cron expression after `after`
+/// (exclusive), resolving `H` fields with `seed`.
 ///
 /// If `timezone` is Some, the cron expression is evaluated in that IANA
 /// timezone (e.g. "America/New_York") and the result is converted back to UTC.
 /// If `timezone` is None, the cron expression is evaluated in UTC.
-pub fn compute_next_run(
-    schedule: &str,
+pub fn next_cron_occurrence(
+    expr: &str,
     timezone: Option<&str>,
+    seed: u64,
     after: DateTime<Utc>,
 ) -> Result<DateTime<Utc>> {
-    use std::str::FromStr;
-    let cron = croner::Cron::from_str(schedule)
-        .map_err(|e| anyhow::anyhow!("Invalid cron expression '{}': {}", schedule, e))?;
+    let cron = parse_cron(expr, seed)?;
+    cron_next_after(&cron, parse_timezone(timezone)?, after.trunc_subsecs(0))
+}
 
-    match timezone {
-        Some(tz_str) => {
-            let tz: chrono_tz::Tz = tz_str
+fn parse_timezone(timezone: Option<&str>) -> Result<Option<chrono_tz::Tz>> {
+    timezone
+        .map(|tz_str| {
+            tz_str
                 .parse()
-                .map_err(|e| anyhow::anyhow!("Invalid timezone '{}': {}", tz_str, e))?;
+                .map_err(|e| anyhow::anyhow!("Invalid timezone '{}': {}", tz_str, e))
+        })
+        .transpose()
+}
+
+/// The next occurrence of `cron` after `after` (exclusive), evaluated in
+/// `tz` (UTC if `None`).
+fn cron_next_after(
+    cron: &Cron,
+    tz: Option<chrono_tz::Tz>,
+    after: DateTime<Utc>,
+) -> Result<DateTime<Utc>> {
+    match tz {
+        Some(tz) => {
             // Convert to local time, find next occurrence in that zone, then back to UTC
             let local_after = after.with_timezone(&tz);
             let next_local = cron
@@ -101,17 +317,167 @@ pub fn compute_next_run(
     }
 }
 
+/// The last occurrence of `cron` before `before` (exclusive), evaluated in
+/// `tz` (UTC if `None`).
+fn cron_prev_before(
+    cron: &Cron,
+    tz: Option<chrono_tz::Tz>,
+    before: DateTime<Utc>,
+) -> Result<DateTime<Utc>> {
+    let prev = match tz {
+        Some(tz) => cron
+            .find_previous_occurrence(&before.with_timezone(&tz), false)
+            .map(|t| t.with_timezone(&Utc)),
+        None => cron.find_previous_occurrence(&before, false),
+    };
+    prev.map_err(|e| anyhow::anyhow!("Cron previous occurrence error: {}", e))
+}
+
+/// How many occurrences `occurrences_between` looks at before it gives up on
+/// finding more that pass its filter.
+const MAX_SCANNED_OCCURRENCES: usize = 100_000;
+
+/// Occurrences of the job's schedule missed while the daemon was down: those
+/// after the last dispatched occurrence (falling back to the last run) up to
+/// and including `now`, reduced according to the job's catch-up policy.
+/// Occurrences the job's calendars exclude are left out. Returned oldest
+/// first.
+pub fn missed_occurrences(
+    job: &Job,
+    calendars: &[Calendar],
+    now: DateTime<Utc>,
+) -> Result<Vec<DateTime<Utc>>> {
+    let keep = match job.catchup {
+        CatchupPolicy::None => return Ok(Vec::new()),
+        CatchupPolicy::Once => 1,
+        CatchupPolicy::All { max_runs } => max_runs,
+    };
+    // A job that never ran has nothing to catch up on.
+    let Some(cursor) = job.last_scheduled_at.or(job.last_run_at) else {
+        return Ok(Vec::new());
+    };
+    let allowed = |t| exclusion_reason(job, calendars, t).is_none();
+    let (missed, _) = occurrences_between(job, cursor, now, keep, allowed)?;
+    Ok(missed)
+}
+
+/// Occurrences of the job's schedule after `after` up to and including
+/// `until` for which `filter` holds, oldest first. Only the most recent
+/// `keep` are returned, together with whether the range holds earlier
+/// occurrences that were left out.
+///
+/// The range is walked back from `until`, so only the occurrences kept (and
+/// those `filter` rejects among them) are computed, however long the range.
+/// At most `MAX_SCANNED_OCCURRENCES` are looked at.
+fn occurrences_between(
+    job: &Job,
+    after: DateTime<Utc>,
+    until: DateTime<Utc>,
+    keep: usize,
+    filter: impl Fn(DateTime<Utc>) -> bool,
+) -> Result<(Vec<DateTime<Utc>>, bool)> {
+    let run_times = RunTimes::new(job)?;
+    // Walking back needs an occurrence to reach; without one in the range
+    // (e.g. a cron whose years have not come yet) there is none before it.
+    if run_times
+        .next_after(after)?
+        .is_none_or(|first| first > until)
+    {
+        return Ok((Vec::new(), false));
+    }
+    let mut occurrences = VecDeque::new();
+    let mut cursor = until
+        .trunc_subsecs(0)
+        .checked_add_signed(chrono::Duration::seconds(1))
+        .unwrap_or(until);
+    for _ in 0..MAX_SCANNED_OCCURRENCES {
+        let Some(prev) = run_times.prev_before(cursor)?.filter(|t| *t > after) else {
+            return Ok((occurrences.into(), false));
+        };
+        if occurrences.len() == keep {
+            return Ok((occurrences.into(), true));
+        }
+        cursor = prev;
+        if filter(prev) {
+            occurrences.push_front(prev);
+        }
+    }
+    Ok((occurrences.into(), true))
+}
+
 // ---------------------------------------------------------------------------
 // Scheduler
 // ---------------------------------------------------------------------------
 
+/// Disable a job that is over, so it does not linger as enabled, and
+/// announce it with an `Expired` change on `event_tx`.
+pub(crate) async fn expire_job(
+    job_store: &dyn JobStore,
+    event_tx: Option<&broadcast::Sender<JobEvent>>,
+    job: &Job,
+    reason: &str,
+    now: DateTime<Utc>,
+) {
+    tracing::info!("Job '{}' expired ({}); disabling it", job.name, reason);
+    let update = JobUpdate {
+        enabled: Some(false),
+        ..Default::default()
+    };
+    if let Err(e) = job_store.update_job(job.id, update).await {
+        tracing::error!("Failed to disable job '{}': {}", job.name, e);
+        return;
+    }
+    if let Some(event_tx) = event_tx {
+        let _ = event_tx.send(JobEvent::JobChanged {
+            job_id: job.id,
+            change: JobChangeKind::Expired,
+            timestamp: now,
+        });
+    }
+}
+
+/// A job's position in its schedule as seen by the running scheduler.
+///
+/// The next occurrence is always computed from `last`, never from the wall
+/// clock, so every tick is dispatched exactly once no matter how often the
+/// scheduler is woken or how late its timer fires.
+struct Cursor {
+    /// The last dispatched occurrence, or the time the scheduler started
+    /// tracking the job.
+    last: DateTime<Utc>,
+    /// The schedule `last` refers to. A schedule edit restarts the cursor.
+    schedule: Schedule,
+    jitter_secs: u64,
+}
+
+impl Cursor {
+    fn new(job: &Job, last: DateTime<Utc>) -> Self {
+        Self {
+            last,
+            schedule: job.schedule.clone(),
+            jitter_secs: job.jitter_secs,
+        }
+    }
+
+    fn tracks(&self, job: &Job) -> bool {
+        self.schedule == job.schedule && self.jitter_secs == job.jitter_secs
+    }
+}
+
 /// The cron scheduler engine.
 ///
 /// It is a long-lived task that loads enabled jobs, computes next run times,
 /// sleeps until the earliest one, and dispatches due jobs over an mpsc channel.
 /// It can be woken early via `Notify` when the job list changes.
+///
+/// Occurrences the job's calendars exclude are still dispatched, marked with
+/// the reason, so the dispatcher records them as `Skipped`. Jobs past their
+/// `end_at` or `max_runs`, and one-off jobs whose time has passed, are
+/// disabled as expired.
 pub struct Scheduler {
     job_store: Arc<dyn JobStore>,
+    calendar_store: Option<Arc<dyn CalendarStore>>,
+    event_tx: Option<broadcast::Sender<JobEvent>>,
     clock: Arc<dyn Clock>,
     notify: Arc<Notify>,
     dispatch_tx: mpsc::Sender<DispatchRequest>,
@@ -127,23 +493,56 @@ impl Scheduler {
     ) -> Self {
         Self {
             job_store,
+            calendar_store: None,
+            event_tx: None,
             clock,
             notify,
             dispatch_tx,
         }
     }
 
+    /// Evaluate the calendars jobs reference against this store. Without
+    /// one, every occurrence is allowed.
+    pub fn with_calendars(mut self, calendar_store: Arc<dyn CalendarStore>) -> Self {
+        self.calendar_store = Some(calendar_store);
+        self
+    }
+
+    /// Announce jobs the scheduler disables as expired on this channel.
+    pub fn with_events(mut self, event_tx: broadcast::Sender<JobEvent>) -> Self {
+        self.event_tx = Some(event_tx);
+        self
+    }
+
     /// Main scheduler loop.  Runs forever (or until the mpsc channel closes).
+    ///
+    /// Before entering the loop, occurrences missed while the daemon was down
+    /// are dispatched according to each job's catch-up policy. Afterwards each
+    /// job keeps a cursor on its last dispatched occurrence, so a wake-up from
+    /// `Notify` or a late timer never skips or repeats a tick.
     pub async fn run(&self) -> Result<()> {
+        let mut cursors = self.catch_up_missed().await?;
+
         loop {
-            let jobs = self.job_store.list_jobs().await?;
-            let enabled_jobs: Vec<&Job> = jobs.iter().filter(|j| j.enabled).collect();
-
-            // Compute next run for each enabled job
-            let mut next_runs: Vec<(Job, DateTime<Utc>)> = Vec::new();
-            for job in enabled_jobs {
-                match compute_next_run(&job.schedule, job.timezone.as_deref(), self.clock.now()) {
-                    Ok(next) => next_runs.push((job.clone(), next)),
+            let mut jobs = self.job_store.list_jobs().await?;
+            let calendars = self.load_calendars().await;
+            let now = self.clock.now();
+
+            // Forget jobs that were deleted or disabled; they start afresh
+            // from the moment they are enabled again.
+            cursors.retain(|id, _| jobs.iter().any(|j| j.id == *id && j.enabled));
+
+            // Dispatch whatever is due and compute the next run for each job
+            let mut next_runs: Vec<DateTime<Utc>> = Vec::new();
+            for job in jobs.iter_mut().filter(|j| j.enabled) {
+                let cursor = cursors
+                    .entry(job.id)
+                    .or_insert_with(|| Cursor::new(job, now));
+                if !cursor.tracks(job) {
+                    *cursor = Cursor::new(job, now);
+                }
+                let next = match self.dispatch_due(job, &calendars, cursor, now).await {
+                    Ok(next) => next,
                     Err(e) => {
                         tracing::error!(
                             "Invalid schedule for job '{}' ({}): {}",
@@ -152,41 +551,198 @@ impl Scheduler {
                             e
                         );
                         // Invalid jobs are skipped (not dispatched).
+                        continue;
                     }
+                };
+                if let Some(reason) = job.expiry_reason(now) {
+                    self.expire(job, &reason).await;
+                    continue;
+                }
+                match next {
+                    Some(next) => next_runs.push(next),
+                    // Nothing left to run before `end_at`; wake up then to
+                    // expire the job. Manual jobs without one stay enabled
+                    // for triggers.
+                    None => next_runs.extend(job.end_at),
                 }
             }
 
-            if next_runs.is_empty() {
+            let Some(earliest) = next_runs.into_iter().min() else {
                 // No enabled jobs — sleep indefinitely until notified
                 self.notify.notified().await;
                 continue;
+            };
+
+            // Sleep until the earliest next run, or until the job list
+            // changes; either way the cursors decide what is due.
+            let sleep_duration = (earliest - self.clock.now())
+                .to_std()
+                .unwrap_or(Duration::ZERO);
+            tokio::select! {
+                _ = tokio::time::sleep(sleep_duration) => {}
+                _ = self.notify.notified() => {}
             }
+        }
+    }
 
-            // Find earliest next run
-            let earliest = next_runs.iter().map(|(_, t)| *t).min().unwrap();
-            let now = self.clock.now();
-            let sleep_duration = (earliest - now).to_std().unwrap_or(Duration::ZERO);
+    /// Dispatch the occurrences of `job` that fell due between its cursor and
+    /// `now`, advance the cursor, and return the next occurrence after it, if
+    /// any.
+    ///
+    /// Normally at most one occurrence is due. If the scheduler fell behind
+    /// (e.g. the host was suspended), the latest occurrence runs as usual and
+    /// the earlier ones are treated as missed, following the catch-up policy.
+    /// Occurrences excluded by the job's calendars are dispatched to be
+    /// recorded as skipped.
+    async fn dispatch_due(
+        &self,
+        job: &mut Job,
+        calendars: &[Calendar],
+        cursor: &mut Cursor,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let keep = match job.catchup {
+            CatchupPolicy::None | CatchupPolicy::Once => 1,
+            CatchupPolicy::All { max_runs } => max_runs.saturating_add(1),
+        };
+        let (due, left_out) = occurrences_between(job, cursor.last, now, keep, |_| true)?;
+        if let Some((&latest, missed)) = due.split_last() {
+            if left_out {
+                tracing::warn!(
+                    "Scheduler fell behind: skipping occurrences of job '{}' before {}",
+                    job.name,
+                    due[0].to_rfc3339()
+                );
+            }
+            for &scheduled_for in missed {
+                self.dispatch_occurrence(job, calendars, scheduled_for, true)
+                    .await;
+            }
+            self.dispatch_occurrence(job, calendars, latest, false)
+                .await;
+            self.record_scheduled(job, latest).await;
+            cursor.last = latest;
+        }
+        compute_next_run(job, cursor.last)
+    }
 
-            tokio::select! {
-                _ = tokio::time::sleep(sleep_duration) => {
-                    // Dispatch all jobs that are now due
-                    let now = self.clock.now();
-                    for (job, next_time) in &next_runs {
-                        if *next_time <= now {
-                            let request = DispatchRequest {
-                                job: job.clone(),
-                                run_id: Uuid::now_v7(),
-                                trigger_params: None,
-                            };
-                            let _ = self.dispatch_tx.send(request).await;
-                        }
-                    }
-                }
-                _ = self.notify.notified() => {
-                    // Job list changed — re-evaluate from the top
+    /// Disable a job that is over, so it does not linger as enabled, and
+    /// announce it with an `Expired` change.
+    async fn expire(&self, job: &Job, reason: &str) {
+        expire_job(
+            self.job_store.as_ref(),
+            self.event_tx.as_ref(),
+            job,
+            reason,
+            self.clock.now(),
+        )
+        .await;
+    }
+
+    /// Dispatch the occurrences each enabled job missed while the daemon was
+    /// not running, and return the cursors the main loop starts from.
+    async fn catch_up_missed(&self) -> Result<HashMap<Uuid, Cursor>> {
+        let now = self.clock.now();
+        let mut jobs = self.job_store.list_jobs().await?;
+        let calendars = self.load_calendars().await;
+        let mut cursors = HashMap::new();
+        for job in jobs.iter_mut().filter(|j| j.enabled) {
+            // Everything up to `now` is settled here, dispatched or dropped.
+            cursors.insert(job.id, Cursor::new(job, now));
+            let missed = match missed_occurrences(job, &calendars, now) {
+                Ok(missed) => missed,
+                Err(e) => {
+                    tracing::error!("Cannot catch up job '{}' ({}): {}", job.name, job.id, e);
                     continue;
                 }
+            };
+            let Some(&latest) = missed.last() else {
+                continue;
+            };
+            tracing::info!(
+                "Catching up {} missed run(s) of job '{}' (policy: {})",
+                missed.len(),
+                job.name,
+                job.catchup
+            );
+            for scheduled_for in missed {
+                self.dispatch_occurrence(job, &calendars, scheduled_for, true)
+                    .await;
             }
+            self.record_scheduled(job, latest).await;
+        }
+        Ok(cursors)
+    }
+
+    /// The calendars jobs may reference. If they cannot be loaded, every
+    /// occurrence is allowed.
+    async fn load_calendars(&self) -> Vec<Calendar> {
+        let Some(ref store) = self.calendar_store else {
+            return Vec::new();
+        };
+        store.list_calendars().await.unwrap_or_else(|e| {
+            tracing::error!("Failed to load calendars: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// Dispatch one occurrence of a job's schedule, marked with the reason if
+    /// the job's calendars exclude it. Occurrences of a job that has used up
+    /// its `max_runs` are dropped; the dispatcher counts the ones it accepts.
+    async fn dispatch_occurrence(
+        &self,
+        job: &Job,
+        calendars: &[Calendar],
+        scheduled_for: DateTime<Utc>,
+        catch_up: bool,
+    ) {
+        if job.max_runs.is_some_and(|max| job.scheduled_runs >= max) {
+            return;
+        }
+        let skip_reason = exclusion_reason(job, calendars, scheduled_for);
+        self.dispatch(job, scheduled_for, catch_up, skip_reason)
+            .await;
+    }
+
+    /// Send a dispatch request for one occurrence of a job's schedule.
+    async fn dispatch(
+        &self,
+        job: &Job,
+        scheduled_for: DateTime<Utc>,
+        catch_up: bool,
+        skip_reason: Option<String>,
+    ) {
+        let request = DispatchRequest {
+            job: job.clone(),
+            run_id: Uuid::now_v7(),
+            trigger_params: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: Some(scheduled_for),
+            catch_up,
+            trigger: if catch_up {
+                TriggerSource::CatchUp
+            } else {
+                TriggerSource::Schedule
+            },
+            skip_reason,
+        };
+        let _ = self.dispatch_tx.send(request).await;
+    }
+
+    /// Persist the latest dispatched occurrence, so a restart knows where
+    /// catch-up has to start.
+    async fn record_scheduled(&self, job: &Job, scheduled_for: DateTime<Utc>) {
+        let update = JobUpdate {
+            last_scheduled_at: Some(Some(scheduled_for)),
+            ..Default::default()
+        };
+        if let Err(e) = self.job_store.update_job(job.id, update).await {
+            tracing::error!(
+                "Failed to record scheduled time of job '{}': {}",
+                job.name,
+                e
+            );
         }
     }
 }
@@ -198,7 +754,7 @@ impl Scheduler {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{ExecutionType, Job, JobUpdate, NewJob};
+    use crate::models::{CatchupPolicy, ExecutionType, Job, JobUpdate, NewJob};
     use crate::storage::JobStore;
     use async_trait::async_trait;
     use chrono::TimeZone;
@@ -250,19 +806,38 @@ mod tests {
             let job = Job {
                 id: Uuid::now_v7(),
                 name: new.name,
-                schedule: new.schedule,
+                schedule: new.schedule.with_timezone(new.timezone),
+                jitter_secs: new.jitter_secs,
                 execution: new.execution,
                 enabled: new.enabled,
-                timezone: new.timezone,
                 working_dir: new.working_dir,
                 env_vars: new.env_vars,
                 timeout_secs: new.timeout_secs,
                 log_environment: new.log_environment,
+                concurrency_policy: new.concurrency_policy,
+                retry: new.retry,
+                catchup: new.catchup,
+                include_calendars: new.include_calendars,
+                exclude_calendars: new.exclude_calendars,
+                start_at: new.start_at,
+                end_at: new.end_at,
+                max_runs: new.max_runs,
+                on_success: new.on_success,
+                on_failure: new.on_failure,
+                forward_output_lines: new.forward_output_lines,
+                pool: new.pool,
+                priority: new.priority,
+                tags: new.tags,
+                labels: new.labels,
+                log_retention: Default::default(),
+                scheduled_runs: 0,
                 created_at: now,
                 updated_at: now,
                 last_run_at: None,
                 last_exit_code: None,
+                source_file: None,
                 next_run_at: None,
+                last_scheduled_at: None,
             };
             self.jobs.write().await.push(job.clone());
             Ok(job)
@@ -283,10 +858,26 @@ mod tests {
             if let Some(enabled) = update.enabled {
                 job.enabled = enabled;
             }
+            if let Some(last_scheduled_at) = update.last_scheduled_at {
+                job.last_scheduled_at = last_scheduled_at;
+            }
+            if let Some(scheduled_runs) = update.scheduled_runs {
+                job.scheduled_runs = scheduled_runs;
+            }
             job.updated_at = Utc::now();
             Ok(job.clone())
         }
 
+        async fn replace_job(&self, id: Uuid, new: NewJob) -> Result<Job> {
+            let mut jobs = self.jobs.write().await;
+            let job = jobs
+                .iter_mut()
+                .find(|j| j.id == id)
+                .ok_or_else(|| anyhow::anyhow!("not found"))?;
+            job.redefine(new);
+            Ok(job.clone())
+        }
+
         async fn delete_job(&self, id: Uuid) -> Result<()> {
             let mut jobs = self.jobs.write().await;
             jobs.retain(|j| j.id != id);
@@ -303,26 +894,45 @@ mod tests {
         Job {
             id: Uuid::now_v7(),
             name: name.to_string(),
-            schedule: schedule.to_string(),
+            schedule: Schedule::cron(schedule),
             execution: ExecutionType::ShellCommand("echo hello".to_string()),
             enabled,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: now,
             updated_at: now,
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         }
     }
 
     #[allow(dead_code)]
     fn make_test_job_with_tz(name: &str, schedule: &str, tz: &str) -> Job {
         let mut job = make_test_job(name, schedule, true);
-        job.timezone = Some(tz.to_string());
+        job.schedule = job.schedule.with_timezone(Some(tz.to_string()));
         job
     }
 
@@ -334,7 +944,7 @@ mod tests {
     fn test_next_run_at_every_5_minutes() {
         // At 10:03, the next */5 minute boundary is 10:05
         let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 3, 0).unwrap();
-        let next = compute_next_run("*/5 * * * *", None, after).unwrap();
+        let next = next_cron_occurrence("*/5 * * * *", None, 0, after).unwrap();
         let expected = Utc.with_ymd_and_hms(2025, 6, 15, 10, 5, 0).unwrap();
         assert_eq!(next, expected);
     }
@@ -343,7 +953,7 @@ mod tests {
     fn test_next_run_at_on_boundary_is_exclusive() {
         // At exactly 10:05, the *next* */5 boundary is 10:10 (exclusive)
         let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 5, 0).unwrap();
-        let next = compute_next_run("*/5 * * * *", None, after).unwrap();
+        let next = next_cron_occurrence("*/5 * * * *", None, 0, after).unwrap();
         let expected = Utc.with_ymd_and_hms(2025, 6, 15, 10, 10, 0).unwrap();
         assert_eq!(next, expected);
     }
@@ -352,7 +962,7 @@ mod tests {
     fn test_next_run_at_every_hour() {
         // "0 * * * *" fires at the top of each hour
         let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 30, 0).unwrap();
-        let next = compute_next_run("0 * * * *", None, after).unwrap();
+        let next = next_cron_occurrence("0 * * * *", None, 0, after).unwrap();
         let expected = Utc.with_ymd_and_hms(2025, 6, 15, 11, 0, 0).unwrap();
         assert_eq!(next, expected);
     }
@@ -369,7 +979,7 @@ mod tests {
         // If it's 2025-06-15 03:00 UTC, that's 2025-06-14 23:00 EDT
         // Next midnight EDT = 2025-06-15 00:00 EDT = 2025-06-15 04:00 UTC
         let after = Utc.with_ymd_and_hms(2025, 6, 15, 3, 0, 0).unwrap();
-        let next = compute_next_run("0 0 * * *", Some("America/New_York"), after).unwrap();
+        let next = next_cron_occurrence("0 0 * * *", Some("America/New_York"), 0, after).unwrap();
         let expected = Utc.with_ymd_and_hms(2025, 6, 15, 4, 0, 0).unwrap();
         assert_eq!(next, expected);
     }
@@ -377,7 +987,7 @@ mod tests {
     #[test]
     fn test_next_run_at_with_utc_timezone_explicit() {
         let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 3, 0).unwrap();
-        let next = compute_next_run("*/5 * * * *", Some("UTC"), after).unwrap();
+        let next = next_cron_occurrence("*/5 * * * *", Some("UTC"), 0, after).unwrap();
         let expected = Utc.with_ymd_and_hms(2025, 6, 15, 10, 5, 0).unwrap();
         assert_eq!(next, expected);
     }
@@ -393,7 +1003,7 @@ mod tests {
         // Schedule: "30 2 * * *" in America/New_York
         // Before the spring-forward date:
         let after = Utc.with_ymd_and_hms(2025, 3, 9, 6, 0, 0).unwrap(); // 1:00 AM EST
-        let result = compute_next_run("30 2 * * *", Some("America/New_York"), after);
+        let result = next_cron_occurrence("30 2 * * *", Some("America/New_York"), 0, after);
         match result {
             Ok(next) => {
                 // croner may return either:
@@ -430,7 +1040,7 @@ mod tests {
         // 1:30 AM occurs twice. We should get the first (EDT) occurrence.
         // Before the overlap: 2025-11-02 04:00 UTC = midnight EDT
         let after = Utc.with_ymd_and_hms(2025, 11, 2, 4, 0, 0).unwrap();
-        let next = compute_next_run("30 1 * * *", Some("America/New_York"), after).unwrap();
+        let next = next_cron_occurrence("30 1 * * *", Some("America/New_York"), 0, after).unwrap();
         // First 1:30 AM is EDT (UTC-4): 2025-11-02 05:30 UTC
         let expected_first = Utc.with_ymd_and_hms(2025, 11, 2, 5, 30, 0).unwrap();
         // Second 1:30 AM is EST (UTC-5): 2025-11-02 06:30 UTC
@@ -676,7 +1286,7 @@ mod tests {
     #[test]
     fn test_invalid_cron_expression_returns_error() {
         let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 0).unwrap();
-        let result = compute_next_run("not a cron", None, after);
+        let result = next_cron_occurrence("not a cron", None, 0, after);
         assert!(result.is_err(), "Invalid cron should return error");
     }
 
@@ -738,7 +1348,7 @@ mod tests {
     #[test]
     fn test_invalid_timezone_returns_error() {
         let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 0).unwrap();
-        let result = compute_next_run("*/5 * * * *", Some("Not/Valid"), after);
+        let result = next_cron_occurrence("*/5 * * * *", Some("Not/Valid"), 0, after);
         assert!(result.is_err(), "Invalid timezone should return error");
     }
 
@@ -788,6 +1398,658 @@ mod tests {
         handle.abort();
     }
 
+    // =======================================================================
+    // Missed-run catch-up
+    // =======================================================================
+
+    fn make_catchup_job(catchup: CatchupPolicy) -> Job {
+        Job {
+            catchup,
+            last_scheduled_at: Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 0).unwrap()),
+            ..make_test_job("catchup-job", "*/10 * * * *", true)
+        }
+    }
+
+    #[test]
+    fn test_missed_occurrences_by_policy() {
+        // Down from 10:00 to 10:45: 10:10, 10:20, 10:30 and 10:40 were missed.
+        let now = Utc.with_ymd_and_hms(2025, 6, 15, 10, 45, 0).unwrap();
+        let at = |m| Utc.with_ymd_and_hms(2025, 6, 15, 10, m, 0).unwrap();
+
+        let none = make_catchup_job(CatchupPolicy::None);
+        assert!(missed_occurrences(&none, &[], now).unwrap().is_empty());
+
+        let once = make_catchup_job(CatchupPolicy::Once);
+        assert_eq!(missed_occurrences(&once, &[], now).unwrap(), vec![at(40)]);
+
+        let all = make_catchup_job(CatchupPolicy::All { max_runs: 3 });
+        assert_eq!(
+            missed_occurrences(&all, &[], now).unwrap(),
+            vec![at(20), at(30), at(40)]
+        );
+
+        let all = make_catchup_job(CatchupPolicy::All { max_runs: 10 });
+        assert_eq!(missed_occurrences(&all, &[], now).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_missed_occurrences_anchor() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 15, 10, 45, 0).unwrap();
+
+        // Never scheduled and never run: nothing to catch up on.
+        let mut job = make_catchup_job(CatchupPolicy::Once);
+        job.last_scheduled_at = None;
+        assert!(missed_occurrences(&job, &[], now).unwrap().is_empty());
+
+        // Falls back to last_run_at.
+        job.last_run_at = Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 35, 0).unwrap());
+        assert_eq!(
+            missed_occurrences(&job, &[], now).unwrap(),
+            vec![Utc.with_ymd_and_hms(2025, 6, 15, 10, 40, 0).unwrap()]
+        );
+
+        // An occurrence at exactly `now` counts as missed: the main loop
+        // only looks at occurrences after `now`.
+        let now = Utc.with_ymd_and_hms(2025, 6, 15, 10, 10, 0).unwrap();
+        let job = make_catchup_job(CatchupPolicy::Once);
+        assert_eq!(missed_occurrences(&job, &[], now).unwrap(), vec![now]);
+    }
+
+    #[test]
+    fn test_missed_occurrences_after_long_downtime_are_cheap() {
+        // Down for ten years: walking forward through every second would
+        // take hundreds of millions of steps.
+        let now = Utc.with_ymd_and_hms(2035, 6, 15, 10, 0, 30).unwrap();
+        let mut job = make_catchup_job(CatchupPolicy::All { max_runs: 3 });
+        job.schedule = Schedule::cron("* * * * * *");
+        let started = std::time::Instant::now();
+        assert_eq!(
+            missed_occurrences(&job, &[], now).unwrap(),
+            vec![
+                now - chrono::Duration::seconds(2),
+                now - chrono::Duration::seconds(1),
+                now
+            ]
+        );
+
+        job.schedule = Schedule::Every {
+            interval: "1s".parse().unwrap(),
+            anchor: None,
+        };
+        job.start_at = Some(now - chrono::Duration::seconds(1));
+        assert_eq!(
+            missed_occurrences(&job, &[], now).unwrap(),
+            vec![now - chrono::Duration::seconds(1), now]
+        );
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_catches_up_missed_runs_at_startup() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let now = Utc.with_ymd_and_hms(2025, 6, 15, 10, 45, 0).unwrap();
+        let clock = Arc::new(FakeClock::new(now));
+
+        let job = make_catchup_job(CatchupPolicy::All { max_runs: 2 });
+        store.add_job(job.clone()).await;
+        let mut dropped = make_catchup_job(CatchupPolicy::None);
+        dropped.name = "no-catchup".to_string();
+        dropped.id = Uuid::now_v7();
+        store.add_job(dropped).await;
+
+        let notify = Arc::new(Notify::new());
+        let (tx, mut rx) = mpsc::channel::<DispatchRequest>(16);
+        let scheduler = Scheduler::new(store.clone(), clock, notify, tx);
+        let handle = tokio::spawn(async move { scheduler.run().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let mut dispatched = Vec::new();
+        while let Ok(request) = rx.try_recv() {
+            dispatched.push(request);
+        }
+        assert_eq!(dispatched.len(), 2, "only the catch-up job's last 2 misses");
+        assert!(dispatched.iter().all(|r| r.job.id == job.id && r.catch_up));
+        assert_eq!(
+            dispatched[0].scheduled_for,
+            Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 30, 0).unwrap())
+        );
+        assert_eq!(
+            dispatched[1].scheduled_for,
+            Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 40, 0).unwrap())
+        );
+
+        let stored = store.get_job(job.id).await.unwrap().unwrap();
+        assert_eq!(stored.last_scheduled_at, dispatched[1].scheduled_for);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_records_scheduled_time_on_dispatch() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let base_time = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 30).unwrap();
+        let clock = Arc::new(FakeClock::new(base_time));
+
+        let job = make_test_job("minutely-job", "*/1 * * * *", true);
+        store.add_job(job.clone()).await;
+
+        let notify = Arc::new(Notify::new());
+        let (tx, mut rx) = mpsc::channel::<DispatchRequest>(16);
+        let scheduler = Scheduler::new(store.clone(), clock.clone(), notify, tx);
+        let handle = tokio::spawn(async move { scheduler.run().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let fire_time = Utc.with_ymd_and_hms(2025, 6, 15, 10, 1, 0).unwrap();
+        clock.set(fire_time);
+        tokio::time::pause();
+        tokio::time::advance(Duration::from_secs(31)).await;
+        tokio::time::resume();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let request = rx.try_recv().expect("job should be dispatched");
+        assert_eq!(request.scheduled_for, Some(fire_time));
+        assert!(!request.catch_up);
+        let stored = store.get_job(job.id).await.unwrap().unwrap();
+        assert_eq!(stored.last_scheduled_at, Some(fire_time));
+
+        handle.abort();
+    }
+
+    // =======================================================================
+    // Calendars
+    // =======================================================================
+
+    fn business_hours() -> Calendar {
+        Calendar {
+            name: "business-hours".to_string(),
+            description: None,
+            tz: None,
+            ranges: vec![],
+            windows: vec![crate::models::calendar::Window {
+                cron: "0 9 * * MON-FRI".to_string(),
+                duration: "8h".parse().unwrap(),
+                label: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_next_allowed_run_passes_over_excluded_occurrences() {
+        // Saturday 2025-06-14, 10:30
+        let after = Utc.with_ymd_and_hms(2025, 6, 14, 10, 30, 0).unwrap();
+        let calendars = [business_hours()];
+        let mut job = make_test_job("hourly", "0 * * * *", true);
+
+        let next = next_allowed_run(&job, &calendars, after).unwrap();
+        assert_eq!(next, compute_next_run(&job, after).unwrap());
+
+        job.include_calendars = vec!["business-hours".to_string()];
+        let next = next_allowed_run(&job, &calendars, after).unwrap();
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2025, 6, 16, 9, 0, 0).unwrap())
+        );
+
+        job.include_calendars.clear();
+        job.exclude_calendars = vec!["business-hours".to_string()];
+        let monday = Utc.with_ymd_and_hms(2025, 6, 16, 8, 30, 0).unwrap();
+        let next = next_allowed_run(&job, &calendars, monday).unwrap();
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2025, 6, 16, 17, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_next_allowed_run_skips_long_exclusions_of_frequent_schedules() {
+        // Every 10 seconds, excluded over a weekend: about 17k occurrences.
+        let weekend = Calendar {
+            name: "weekend".to_string(),
+            description: None,
+            tz: None,
+            ranges: vec![],
+            windows: vec![crate::models::calendar::Window {
+                cron: "0 0 * * SAT".to_string(),
+                duration: "2d".parse().unwrap(),
+                label: None,
+            }],
+        };
+        let mut job = make_test_job("frequent", "*/10 * * * * *", true);
+        job.exclude_calendars = vec!["weekend".to_string()];
+        let saturday = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 5).unwrap();
+        assert_eq!(
+            next_allowed_run(&job, &[weekend], saturday).unwrap(),
+            Some(Utc.with_ymd_and_hms(2025, 6, 16, 0, 0, 0).unwrap())
+        );
+
+        // Confined to one-off ranges far apart, the next run is in the next
+        // range.
+        let ranges = Calendar {
+            name: "ranges".to_string(),
+            description: None,
+            tz: None,
+            ranges: vec![crate::models::calendar::DateRange {
+                start: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 3).unwrap(),
+                end: Utc.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap(),
+                label: None,
+            }],
+            windows: vec![],
+        };
+        job.exclude_calendars.clear();
+        job.include_calendars = vec!["ranges".to_string()];
+        assert_eq!(
+            next_allowed_run(&job, &[ranges], saturday).unwrap(),
+            Some(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 10).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_missed_occurrences_leave_out_excluded() {
+        let mut job = make_catchup_job(CatchupPolicy::All { max_runs: 10 });
+        job.last_scheduled_at = Some(Utc.with_ymd_and_hms(2025, 6, 16, 8, 0, 0).unwrap());
+        job.exclude_calendars = vec!["business-hours".to_string()];
+        let now = Utc.with_ymd_and_hms(2025, 6, 16, 9, 10, 0).unwrap();
+
+        // 9:00 and 9:10 fall in business hours; 8:10 to 8:50 remain.
+        let missed = missed_occurrences(&job, &[business_hours()], now).unwrap();
+        assert_eq!(missed.len(), 5);
+        assert_eq!(
+            missed.last(),
+            Some(&Utc.with_ymd_and_hms(2025, 6, 16, 8, 50, 0).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_records_calendar_exclusions_as_skips() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let tmp = tempfile::TempDir::new().unwrap();
+        let calendar_store = Arc::new(
+            crate::storage::calendars::JsonCalendarStore::new(tmp.path().to_path_buf())
+                .await
+                .unwrap(),
+        );
+        calendar_store
+            .create_calendar(business_hours())
+            .await
+            .unwrap();
+        // Monday 2025-06-16, just before business hours end
+        let base_time = Utc.with_ymd_and_hms(2025, 6, 16, 16, 59, 30).unwrap();
+        let clock = Arc::new(FakeClock::new(base_time));
+
+        let job = Job {
+            include_calendars: vec!["business-hours".to_string()],
+            ..make_test_job("office-job", "*/1 * * * *", true)
+        };
+        store.add_job(job).await;
+
+        let notify = Arc::new(Notify::new());
+        let (tx, mut rx) = mpsc::channel::<DispatchRequest>(16);
+        let scheduler = Scheduler::new(store.clone(), clock.clone(), notify.clone(), tx)
+            .with_calendars(calendar_store);
+        let handle = tokio::spawn(async move { scheduler.run().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // 16:59 is inside business hours and runs; 17:00 is not.
+        clock.set(Utc.with_ymd_and_hms(2025, 6, 16, 17, 0, 5).unwrap());
+        notify.notify_one();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let request = rx.try_recv().expect("17:00 should be dispatched");
+        assert_eq!(
+            request.scheduled_for,
+            Some(Utc.with_ymd_and_hms(2025, 6, 16, 17, 0, 0).unwrap())
+        );
+        assert_eq!(
+            request.skip_reason.as_deref(),
+            Some("outside included calendar 'business-hours'")
+        );
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_notify_after_tick_dispatches_once() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let base_time = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 30).unwrap();
+        let clock = Arc::new(FakeClock::new(base_time));
+
+        let job = make_test_job("minutely-job", "*/1 * * * *", true);
+        store.add_job(job).await;
+
+        let notify = Arc::new(Notify::new());
+        let (tx, mut rx) = mpsc::channel::<DispatchRequest>(16);
+        let scheduler = Scheduler::new(store.clone(), clock.clone(), notify.clone(), tx);
+        let handle = tokio::spawn(async move { scheduler.run().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // A notify arriving just after the tick, before the timer fires,
+        // must not lose the 10:01 occurrence...
+        clock.set(Utc.with_ymd_and_hms(2025, 6, 15, 10, 1, 5).unwrap());
+        notify.notify_one();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let request = rx.try_recv().expect("10:01 tick should be dispatched");
+        assert_eq!(
+            request.scheduled_for,
+            Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 1, 0).unwrap())
+        );
+
+        // ...and further notifies must not dispatch it again.
+        notify.notify_one();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(rx.try_recv().is_err(), "tick must be dispatched only once");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_late_wakeup_follows_catchup_policy() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let base_time = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 30).unwrap();
+        let clock = Arc::new(FakeClock::new(base_time));
+
+        let plain = make_test_job("plain", "*/1 * * * *", true);
+        store.add_job(plain.clone()).await;
+        let all = Job {
+            id: Uuid::now_v7(),
+            catchup: CatchupPolicy::All { max_runs: 2 },
+            ..make_test_job("catch-all", "*/1 * * * *", true)
+        };
+        store.add_job(all.clone()).await;
+
+        let notify = Arc::new(Notify::new());
+        let (tx, mut rx) = mpsc::channel::<DispatchRequest>(16);
+        let scheduler = Scheduler::new(store.clone(), clock.clone(), notify.clone(), tx);
+        let handle = tokio::spawn(async move { scheduler.run().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The scheduler wakes up long after 10:01, 10:02, 10:03 and 10:04.
+        clock.set(Utc.with_ymd_and_hms(2025, 6, 15, 10, 4, 30).unwrap());
+        notify.notify_one();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let mut dispatched = Vec::new();
+        while let Ok(request) = rx.try_recv() {
+            dispatched.push((
+                request.job.id,
+                request.scheduled_for.unwrap(),
+                request.catch_up,
+            ));
+        }
+        let at = |m| Utc.with_ymd_and_hms(2025, 6, 15, 10, m, 0).unwrap();
+        assert_eq!(
+            dispatched,
+            vec![
+                (plain.id, at(4), false),
+                (all.id, at(2), true),
+                (all.id, at(3), true),
+                (all.id, at(4), false),
+            ]
+        );
+
+        handle.abort();
+    }
+
+    // =======================================================================
+    // Interval, one-off and manual schedules
+    // =======================================================================
+
+    #[test]
+    fn test_next_run_every_interval() {
+        let every = |interval: &str, anchor| Schedule::Every {
+            interval: interval.parse().unwrap(),
+            anchor,
+        };
+        let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 3, 0).unwrap();
+
+        // Without an anchor, aligned to the epoch (so on the quarter hour)
+        let next = next_occurrence(&every("15m", None), 0, after).unwrap();
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 15, 0).unwrap())
+        );
+
+        // With an anchor, aligned to it; exclusive on the boundary
+        let anchor = Utc.with_ymd_and_hms(2025, 6, 15, 9, 50, 0).unwrap();
+        let next = next_occurrence(&every("15m", Some(anchor)), 0, after).unwrap();
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 5, 0).unwrap())
+        );
+        let next = next_occurrence(&every("15m", Some(anchor)), 0, next.unwrap()).unwrap();
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 20, 0).unwrap())
+        );
+
+        // An anchor in the future is the first occurrence
+        let anchor = Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap();
+        let next = next_occurrence(&every("1d", Some(anchor)), 0, after).unwrap();
+        assert_eq!(next, Some(anchor));
+
+        // Near the end of representable time there is no next occurrence
+        let late = DateTime::<Utc>::MAX_UTC - chrono::Duration::days(1);
+        let next = next_occurrence(&every("36500d", None), 0, late).unwrap();
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn test_next_run_at_and_manual() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap();
+        let at = Schedule::At { timestamp };
+        let before = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 0).unwrap();
+        assert_eq!(next_occurrence(&at, 0, before).unwrap(), Some(timestamp));
+        assert_eq!(next_occurrence(&at, 0, timestamp).unwrap(), None);
+
+        assert_eq!(next_occurrence(&Schedule::Manual, 0, before).unwrap(), None);
+    }
+
+    #[test]
+    fn test_compute_next_run_applies_jitter() {
+        let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 30, 0).unwrap();
+        let top_of_hour = Utc.with_ymd_and_hms(2025, 6, 15, 11, 0, 0).unwrap();
+
+        let mut offsets = std::collections::HashSet::new();
+        for _ in 0..20 {
+            let job = Job {
+                id: Uuid::now_v7(),
+                jitter_secs: 600,
+                ..make_test_job("jittered", "0 * * * *", true)
+            };
+            let offset = job.jitter_offset_secs();
+            assert!(offset <= 600);
+            offsets.insert(offset);
+
+            let next = compute_next_run(&job, after).unwrap().unwrap();
+            assert_eq!(next, top_of_hour + chrono::Duration::seconds(offset as i64));
+            // Exclusive of the shifted time, and the same offset every hour
+            let following = compute_next_run(&job, next).unwrap().unwrap();
+            assert_eq!(following - next, chrono::Duration::hours(1));
+            // The shifted occurrence of 10:00 is still due after 10:00
+            let before_shift = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 0).unwrap();
+            if offset > 0 {
+                assert_eq!(
+                    compute_next_run(&job, before_shift).unwrap().unwrap(),
+                    before_shift + chrono::Duration::seconds(offset as i64)
+                );
+            }
+        }
+        assert!(
+            offsets.len() > 5,
+            "jitter should spread jobs: {:?}",
+            offsets
+        );
+
+        // Without jitter, the schedule is unchanged
+        let job = make_test_job("plain", "0 * * * *", true);
+        assert_eq!(compute_next_run(&job, after).unwrap(), Some(top_of_hour));
+    }
+
+    #[test]
+    fn test_compute_next_run_honours_start_and_end() {
+        let at = |h, m| Utc.with_ymd_and_hms(2025, 6, 15, h, m, 0).unwrap();
+        let job = Job {
+            start_at: Some(at(10, 0)),
+            end_at: Some(at(11, 0)),
+            ..make_test_job("campaign", "*/30 * * * *", true)
+        };
+
+        assert_eq!(compute_next_run(&job, at(8, 0)).unwrap(), Some(at(10, 0)));
+        assert_eq!(compute_next_run(&job, at(10, 0)).unwrap(), Some(at(10, 30)));
+        // end_at itself is excluded
+        assert_eq!(compute_next_run(&job, at(10, 30)).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_expires_job_after_max_runs() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let base_time = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 30).unwrap();
+        let clock = Arc::new(FakeClock::new(base_time));
+
+        let job = Job {
+            max_runs: Some(2),
+            scheduled_runs: 1,
+            ..make_test_job("twice", "* * * * *", true)
+        };
+        store.add_job(job.clone()).await;
+
+        let (event_tx, mut event_rx) = broadcast::channel(16);
+        let notify = Arc::new(Notify::new());
+        let (tx, mut rx) = mpsc::channel::<DispatchRequest>(16);
+        let scheduler =
+            Scheduler::new(store.clone(), clock.clone(), notify.clone(), tx).with_events(event_tx);
+        let handle = tokio::spawn(async move { scheduler.run().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        clock.set(Utc.with_ymd_and_hms(2025, 6, 15, 10, 1, 5).unwrap());
+        notify.notify_one();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The dispatcher counts the runs it accepts.
+        let request = rx.try_recv().expect("the last allowed run is dispatched");
+        assert_eq!(request.job.id, job.id);
+        let stored = store.get_job(job.id).await.unwrap().unwrap();
+        assert_eq!(stored.scheduled_runs, 1);
+        assert!(stored.enabled);
+
+        // Once the count is used up, the next tick dispatches nothing and
+        // expires the job.
+        store
+            .update_job(
+                job.id,
+                JobUpdate {
+                    scheduled_runs: Some(2),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        clock.set(Utc.with_ymd_and_hms(2025, 6, 15, 10, 2, 5).unwrap());
+        notify.notify_one();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(rx.try_recv().is_err());
+        assert!(!store.get_job(job.id).await.unwrap().unwrap().enabled);
+        match event_rx.try_recv().expect("an Expired event") {
+            JobEvent::JobChanged { job_id, change, .. } => {
+                assert_eq!(job_id, job.id);
+                assert!(matches!(change, JobChangeKind::Expired));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_expires_job_at_end_at() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let base_time = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 30).unwrap();
+        let clock = Arc::new(FakeClock::new(base_time));
+
+        let end_at = Utc.with_ymd_and_hms(2025, 6, 15, 10, 30, 0).unwrap();
+        let job = Job {
+            end_at: Some(end_at),
+            ..make_test_job("hourly-until", "0 * * * *", true)
+        };
+        store.add_job(job.clone()).await;
+
+        let notify = Arc::new(Notify::new());
+        let (tx, mut rx) = mpsc::channel::<DispatchRequest>(16);
+        let scheduler = Scheduler::new(store.clone(), clock.clone(), notify.clone(), tx);
+        let handle = tokio::spawn(async move { scheduler.run().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(store.get_job(job.id).await.unwrap().unwrap().enabled);
+
+        clock.set(end_at);
+        notify.notify_one();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert!(rx.try_recv().is_err(), "nothing was due before end_at");
+        assert!(!store.get_job(job.id).await.unwrap().unwrap().enabled);
+
+        handle.abort();
+    }
+
+    #[test]
+    fn test_compute_next_run_resolves_hash_per_job() {
+        let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 30, 0).unwrap();
+        let job = make_test_job("hashed", "H * * * *", true);
+        let next = compute_next_run(&job, after).unwrap().unwrap();
+        assert_eq!(next, compute_next_run(&job, after).unwrap().unwrap());
+        assert_eq!(
+            next,
+            next_occurrence(&job.schedule, spread_seed(job.id), after)
+                .unwrap()
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_runs_one_off_once_then_disables_it() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let base_time = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 30).unwrap();
+        let clock = Arc::new(FakeClock::new(base_time));
+
+        let fire_time = Utc.with_ymd_and_hms(2025, 6, 15, 10, 1, 0).unwrap();
+        let one_off = Job {
+            schedule: Schedule::At {
+                timestamp: fire_time,
+            },
+            ..make_test_job("one-off", "* * * * *", true)
+        };
+        store.add_job(one_off.clone()).await;
+        let manual = Job {
+            id: Uuid::now_v7(),
+            schedule: Schedule::Manual,
+            ..make_test_job("manual", "* * * * *", true)
+        };
+        store.add_job(manual.clone()).await;
+
+        let notify = Arc::new(Notify::new());
+        let (tx, mut rx) = mpsc::channel::<DispatchRequest>(16);
+        let scheduler = Scheduler::new(store.clone(), clock.clone(), notify.clone(), tx);
+        let handle = tokio::spawn(async move { scheduler.run().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        clock.set(Utc.with_ymd_and_hms(2025, 6, 15, 10, 1, 5).unwrap());
+        notify.notify_one();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let request = rx.try_recv().expect("one-off job should be dispatched");
+        assert_eq!(request.job.id, one_off.id);
+        assert_eq!(request.scheduled_for, Some(fire_time));
+        assert!(rx.try_recv().is_err(), "manual job must not be dispatched");
+
+        let stored = store.get_job(one_off.id).await.unwrap().unwrap();
+        assert!(
+            !stored.enabled,
+            "one-off job should be disabled after it ran"
+        );
+        let stored = store.get_job(manual.id).await.unwrap().unwrap();
+        assert!(stored.enabled, "manual job stays enabled for triggers");
+
+        handle.abort();
+    }
+
     // =======================================================================
     // Additional: FakeClock tests
     // =======================================================================
@@ -0,0 +1,955 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::sync::{broadcast, mpsc, Mutex, Notify};
+use uuid::Uuid;
+
+use crate::daemon::events::JobEvent;
+use crate::daemon::scheduler::{next_occurrence, Clock};
+use crate::models::schedule::spread_seed;
+use crate::models::{
+    DispatchRequest, Job, JobRun, NodeStatus, RetryPolicy, RunStatus, Schedule, TriggerSource,
+    Workflow, WorkflowRun, WorkflowRunStatus, WorkflowUpdate,
+};
+use crate::storage::{JobStore, LogStore, RunQuery, WorkflowStore};
+
+/// The node a job run, or any of its retries, was dispatched for.
+#[derive(Debug, Clone)]
+struct NodeRef {
+    workflow_run_id: Uuid,
+    node: String,
+    retry: RetryPolicy,
+}
+
+#[derive(Default)]
+struct EngineState {
+    /// Unfinished runs by id, each with the workflow as it was when the run
+    /// started, so edits do not change a run in flight.
+    active: HashMap<Uuid, (Workflow, WorkflowRun)>,
+    /// Job runs dispatched for nodes of the active runs, by run id.
+    job_runs: HashMap<Uuid, NodeRef>,
+}
+
+/// Runs workflows: starts them on their schedules or on demand, dispatches
+/// each node's job through the dispatch channel, and moves the graph along
+/// as the job events of those runs come in.
+///
+/// A node that is retried under its job's retry policy settles only once
+/// the last attempt has finished. Scheduled occurrences that fall due while
+/// the previous run of the workflow is still going are skipped.
+///
+/// If the event receiver lags, the runs of nodes still running are looked
+/// up in the log store instead, so a missed event cannot leave a workflow
+/// run going forever.
+pub struct WorkflowEngine {
+    workflow_store: Arc<dyn WorkflowStore>,
+    job_store: Arc<dyn JobStore>,
+    log_store: Arc<dyn LogStore>,
+    clock: Arc<dyn Clock>,
+    dispatch_tx: mpsc::Sender<DispatchRequest>,
+    notify: Notify,
+    /// How many runs of each workflow are kept.
+    max_runs_kept: usize,
+    state: Mutex<EngineState>,
+}
+
+impl WorkflowEngine {
+    /// Create a new WorkflowEngine.
+    pub fn new(
+        workflow_store: Arc<dyn WorkflowStore>,
+        job_store: Arc<dyn JobStore>,
+        log_store: Arc<dyn LogStore>,
+        clock: Arc<dyn Clock>,
+        dispatch_tx: mpsc::Sender<DispatchRequest>,
+        max_runs_kept: usize,
+    ) -> Self {
+        Self {
+            workflow_store,
+            job_store,
+            log_store,
+            clock,
+            dispatch_tx,
+            notify: Notify::new(),
+            max_runs_kept,
+            state: Mutex::new(EngineState::default()),
+        }
+    }
+
+    /// Wake the schedule loop after workflows were added, changed or removed.
+    pub fn workflows_changed(&self) {
+        self.notify.notify_one();
+    }
+
+    /// Main loop: follows `events` to move active runs along, and starts
+    /// workflows whose schedules fall due. Runs until the event channel
+    /// closes.
+    ///
+    /// Runs left `Running` by a previous daemon are marked `Failed` first;
+    /// the job runs they were waiting for did not survive the restart.
+    pub async fn run(&self, mut events: broadcast::Receiver<JobEvent>) {
+        self.fail_interrupted_runs().await;
+
+        let mut cursors = HashMap::new();
+        let mut next = self.start_due(&mut cursors).await;
+        loop {
+            let sleep = async {
+                match next {
+                    Some(next) => {
+                        let duration = (next - self.clock.now()).to_std().unwrap_or(Duration::ZERO);
+                        tokio::time::sleep(duration).await
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                event = events.recv() => {
+                    match event {
+                        Ok(event) => self.handle_event(&event).await,
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            tracing::warn!(
+                                "Workflow engine lagged by {} events; checking running nodes",
+                                n
+                            );
+                            self.reconcile().await;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return,
+                    }
+                    continue;
+                }
+                _ = sleep => {}
+                _ = self.notify.notified() => {}
+            }
+            next = self.start_due(&mut cursors).await;
+        }
+    }
+
+    /// Start a run of `workflow`: its root nodes are dispatched right away.
+    /// Returns the run as recorded after that.
+    pub async fn start(
+        &self,
+        workflow: &Workflow,
+        scheduled_for: Option<DateTime<Utc>>,
+    ) -> Result<WorkflowRun> {
+        let run = WorkflowRun::new(workflow, scheduled_for, self.clock.now());
+        let run_id = run.id;
+        tracing::info!(
+            "Workflow '{}' started (workflow run: {})",
+            workflow.name,
+            run_id
+        );
+
+        let mut state = self.state.lock().await;
+        state.active.insert(run_id, (workflow.clone(), run));
+        self.step(&mut state, run_id).await
+    }
+
+    /// Whether a run of the workflow is in progress.
+    pub async fn is_running(&self, workflow_id: Uuid) -> bool {
+        let state = self.state.lock().await;
+        state
+            .active
+            .values()
+            .any(|(_, run)| run.workflow_id == workflow_id)
+    }
+
+    /// Follow a job event that concerns a node of an active run.
+    pub async fn handle_event(&self, event: &JobEvent) {
+        match event {
+            JobEvent::Started {
+                run_id,
+                parent_run_id: Some(parent_run_id),
+                ..
+            } => self.follow_retry(*run_id, *parent_run_id).await,
+            JobEvent::Completed {
+                run_id, exit_code, ..
+            } => {
+                let error = (*exit_code != 0).then(|| format!("exited with code {}", exit_code));
+                self.finish_job_run(*run_id, RunStatus::Completed, Some(*exit_code), error)
+                    .await
+            }
+            JobEvent::Failed { run_id, error, .. } => {
+                self.finish_job_run(*run_id, RunStatus::Failed, None, Some(error.clone()))
+                    .await
+            }
+            JobEvent::Killed { run_id, reason, .. } => {
+                self.finish_job_run(*run_id, RunStatus::Killed, None, Some(reason.to_string()))
+                    .await
+            }
+            JobEvent::Skipped { run_id, reason, .. } => {
+                self.finish_job_run(*run_id, RunStatus::Skipped, None, Some(reason.clone()))
+                    .await
+            }
+            _ => {}
+        }
+    }
+
+    /// Point a node at the retry of its job run.
+    async fn follow_retry(&self, run_id: Uuid, parent_run_id: Uuid) {
+        let mut state = self.state.lock().await;
+        let Some(node_ref) = state.job_runs.get(&parent_run_id).cloned() else {
+            return;
+        };
+        let Some((_, run)) = state.active.get_mut(&node_ref.workflow_run_id) else {
+            return;
+        };
+        if let Some(node) = run.node_mut(&node_ref.node) {
+            node.run_id = Some(run_id);
+            node.attempt += 1;
+        }
+        let run = run.clone();
+        state.job_runs.insert(run_id, node_ref);
+        self.save(&run).await;
+    }
+
+    /// Catch up on job events that were missed: look up the latest attempt
+    /// of every running node's job run, follow retries that started unseen,
+    /// and settle the nodes whose runs have finished.
+    async fn reconcile(&self) {
+        let running: Vec<(Uuid, Uuid)> = {
+            let state = self.state.lock().await;
+            state
+                .active
+                .values()
+                .flat_map(|(_, run)| &run.nodes)
+                .filter(|node| node.status == NodeStatus::Running)
+                .filter_map(|node| Some((node.job_id?, node.run_id?)))
+                .collect()
+        };
+
+        for (job_id, run_id) in running {
+            let latest = match self.latest_attempt(job_id, run_id).await {
+                Ok(Some(latest)) => latest,
+                // Not recorded yet: its events are still to come.
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::error!("Failed to look up run {}: {}", run_id, e);
+                    continue;
+                }
+            };
+
+            if latest.run_id != run_id {
+                let mut state = self.state.lock().await;
+                let Some(node_ref) = state.job_runs.get(&run_id).cloned() else {
+                    continue;
+                };
+                let Some((_, run)) = state.active.get_mut(&node_ref.workflow_run_id) else {
+                    continue;
+                };
+                if let Some(node) = run.node_mut(&node_ref.node) {
+                    node.run_id = Some(latest.run_id);
+                    node.attempt = latest.attempt;
+                }
+                let run = run.clone();
+                state.job_runs.insert(latest.run_id, node_ref);
+                self.save(&run).await;
+            }
+
+            if matches!(latest.status, RunStatus::Queued | RunStatus::Running) {
+                continue;
+            }
+            let error = match latest.status {
+                RunStatus::Completed => latest
+                    .exit_code
+                    .filter(|&code| code != 0)
+                    .map(|code| format!("exited with code {}", code)),
+                _ => latest.error.clone(),
+            };
+            self.finish_job_run(latest.run_id, latest.status, latest.exit_code, error)
+                .await;
+        }
+    }
+
+    /// The record of the latest attempt of the job run `run_id`: the run
+    /// itself, or the retry of it with the highest attempt number. `None`
+    /// if the run has not been recorded.
+    async fn latest_attempt(&self, job_id: Uuid, run_id: Uuid) -> Result<Option<JobRun>> {
+        let Some(run) = self.log_store.find_run(run_id).await? else {
+            return Ok(None);
+        };
+        let first = run.parent_run_id.unwrap_or(run.run_id);
+        let query = RunQuery {
+            job_id: Some(job_id),
+            started_after: Some(run.started_at),
+            ..Default::default()
+        };
+        let (later, _) = self.log_store.query_runs(&query, usize::MAX, 0).await?;
+        Ok(later
+            .into_iter()
+            .filter(|r| r.parent_run_id == Some(first) && r.attempt > run.attempt)
+            .max_by_key(|r| r.attempt)
+            .or(Some(run)))
+    }
+
+    /// Settle the node a job run belongs to, unless a retry follows, and
+    /// start whatever that unblocks.
+    async fn finish_job_run(
+        &self,
+        run_id: Uuid,
+        status: RunStatus,
+        exit_code: Option<i32>,
+        error: Option<String>,
+    ) {
+        let mut state = self.state.lock().await;
+        let Some(node_ref) = state.job_runs.get(&run_id).cloned() else {
+            return;
+        };
+        let Some((_, run)) = state.active.get_mut(&node_ref.workflow_run_id) else {
+            return;
+        };
+        let Some(node) = run.node(&node_ref.node) else {
+            return;
+        };
+        // Only the node's latest attempt counts.
+        if node.run_id != Some(run_id) {
+            return;
+        }
+        if node_ref
+            .retry
+            .retries_after(node.attempt, &status, exit_code)
+        {
+            return;
+        }
+
+        let node_status = match status {
+            RunStatus::Completed if exit_code == Some(0) => NodeStatus::Succeeded,
+            RunStatus::Skipped => NodeStatus::Skipped,
+            _ => NodeStatus::Failed,
+        };
+        run.settle(&node_ref.node, node_status, error, self.clock.now());
+        if let Err(e) = self.step(&mut state, node_ref.workflow_run_id).await {
+            tracing::error!("Failed to advance workflow run: {}", e);
+        }
+    }
+
+    /// Start the nodes of an active run that are ready, persist the run, and
+    /// retire it once it has finished.
+    async fn step(&self, state: &mut EngineState, workflow_run_id: Uuid) -> Result<WorkflowRun> {
+        loop {
+            let (workflow, run) = state
+                .active
+                .get_mut(&workflow_run_id)
+                .ok_or_else(|| anyhow::anyhow!("Workflow run {} is not active", workflow_run_id))?;
+            let ready = run.advance(workflow, self.clock.now());
+            if ready.is_empty() {
+                break;
+            }
+            // A node that cannot be dispatched fails at once, which may
+            // unblock others; go round again until nothing new is ready.
+            for name in ready {
+                let (workflow, _) = &state.active[&workflow_run_id];
+                let job_ref = workflow
+                    .nodes
+                    .iter()
+                    .find(|n| n.name == name)
+                    .map(|n| n.job.clone())
+                    .unwrap_or_default();
+                let dispatched = self.dispatch_node(&job_ref).await;
+
+                let (_, run) = state.active.get_mut(&workflow_run_id).unwrap();
+                match dispatched {
+                    Ok((job, job_run_id)) => {
+                        let node = run.node_mut(&name).unwrap();
+                        node.job_id = Some(job.id);
+                        node.run_id = Some(job_run_id);
+                        node.attempt = 1;
+                        state.job_runs.insert(
+                            job_run_id,
+                            NodeRef {
+                                workflow_run_id,
+                                node: name,
+                                retry: job.retry,
+                            },
+                        );
+                    }
+                    Err(error) => {
+                        tracing::warn!("Workflow node '{}' failed to start: {}", name, error);
+                        run.settle(&name, NodeStatus::Failed, Some(error), self.clock.now());
+                    }
+                }
+            }
+        }
+
+        let run = state.active[&workflow_run_id].1.clone();
+        self.save(&run).await;
+        if run.status != WorkflowRunStatus::Running {
+            tracing::info!(
+                "Workflow '{}' finished: {:?} (workflow run: {})",
+                run.workflow_name,
+                run.status,
+                run.id
+            );
+            state.active.remove(&workflow_run_id);
+            state
+                .job_runs
+                .retain(|_, r| r.workflow_run_id != workflow_run_id);
+            if let Err(e) = self
+                .workflow_store
+                .cleanup_workflow_runs(run.workflow_id, self.max_runs_kept)
+                .await
+            {
+                tracing::error!(
+                    "Failed to clean up runs of workflow '{}': {}",
+                    run.workflow_name,
+                    e
+                );
+            }
+        }
+        Ok(run)
+    }
+
+    /// Resolve a node's job by name or id and dispatch a run of it. Returns
+    /// the job and the run id, or why the node cannot run.
+    async fn dispatch_node(&self, job_ref: &str) -> Result<(Job, Uuid), String> {
+        let job = match Uuid::parse_str(job_ref) {
+            Ok(id) => self.job_store.get_job(id).await,
+            Err(_) => self.job_store.find_by_name(job_ref).await,
+        }
+        .map_err(|e| format!("Failed to fetch job '{}': {}", job_ref, e))?
+        .ok_or_else(|| format!("Job '{}' not found", job_ref))?;
+
+        let run_id = Uuid::now_v7();
+        let request = DispatchRequest {
+            job: job.clone(),
+            run_id,
+            trigger_params: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: TriggerSource::Workflow,
+            skip_reason: None,
+        };
+        self.dispatch_tx
+            .send(request)
+            .await
+            .map_err(|e| format!("Failed to dispatch job '{}': {}", job.name, e))?;
+        Ok((job, run_id))
+    }
+
+    async fn save(&self, run: &WorkflowRun) {
+        if let Err(e) = self.workflow_store.save_workflow_run(run).await {
+            tracing::error!("Failed to save workflow run {}: {}", run.id, e);
+        }
+    }
+
+    /// Start the enabled workflows whose next occurrence has come, and
+    /// return the earliest occurrence still ahead.
+    ///
+    /// Each workflow keeps a cursor on the last time it was considered, like
+    /// the job scheduler's; only the latest due occurrence is started, so
+    /// occurrences missed while the daemon was down are not made up.
+    /// One-off workflows are disabled once started.
+    async fn start_due(
+        &self,
+        cursors: &mut HashMap<Uuid, (Schedule, DateTime<Utc>)>,
+    ) -> Option<DateTime<Utc>> {
+        let workflows = match self.workflow_store.list_workflows().await {
+            Ok(workflows) => workflows,
+            Err(e) => {
+                tracing::error!("Failed to load workflows: {}", e);
+                return None;
+            }
+        };
+        let now = self.clock.now();
+        cursors.retain(|id, _| workflows.iter().any(|w| w.id == *id && w.enabled));
+
+        let mut next_runs = Vec::new();
+        for workflow in workflows.iter().filter(|w| w.enabled) {
+            let cursor = cursors
+                .entry(workflow.id)
+                .or_insert_with(|| (workflow.schedule.clone(), now));
+            if cursor.0 != workflow.schedule {
+                *cursor = (workflow.schedule.clone(), now);
+            }
+            let next = match next_workflow_run(workflow, cursor.1) {
+                Ok(Some(next)) => next,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::error!("Invalid schedule for workflow '{}': {}", workflow.name, e);
+                    continue;
+                }
+            };
+            if next > now {
+                next_runs.push(next);
+                continue;
+            }
+
+            cursor.1 = now;
+            if self.is_running(workflow.id).await {
+                tracing::warn!(
+                    "Workflow '{}' is still running; skipping its run due at {}",
+                    workflow.name,
+                    next.to_rfc3339()
+                );
+            } else if let Err(e) = self.start(workflow, Some(next)).await {
+                tracing::error!("Failed to start workflow '{}': {}", workflow.name, e);
+            }
+
+            if let Schedule::At { .. } = workflow.schedule {
+                let update = WorkflowUpdate {
+                    enabled: Some(false),
+                    ..Default::default()
+                };
+                if let Err(e) = self
+                    .workflow_store
+                    .update_workflow(workflow.id, update)
+                    .await
+                {
+                    tracing::error!("Failed to disable workflow '{}': {}", workflow.name, e);
+                }
+                continue;
+            }
+            if let Ok(Some(next)) = next_workflow_run(workflow, now) {
+                next_runs.push(next);
+            }
+        }
+        next_runs.into_iter().min()
+    }
+
+    /// Mark runs a previous daemon left unfinished as failed.
+    async fn fail_interrupted_runs(&self) {
+        let workflows = match self.workflow_store.list_workflows().await {
+            Ok(workflows) => workflows,
+            Err(e) => {
+                tracing::error!("Failed to load workflows: {}", e);
+                return;
+            }
+        };
+        let now = self.clock.now();
+        for workflow in workflows {
+            let runs = match self
+                .workflow_store
+                .list_workflow_runs(workflow.id, usize::MAX, 0)
+                .await
+            {
+                Ok((runs, _)) => runs,
+                Err(e) => {
+                    tracing::error!("Failed to load runs of workflow '{}': {}", workflow.name, e);
+                    continue;
+                }
+            };
+            for mut run in runs
+                .into_iter()
+                .filter(|r| r.status == WorkflowRunStatus::Running)
+            {
+                tracing::warn!(
+                    "Workflow run {} of '{}' was interrupted by a daemon restart",
+                    run.id,
+                    workflow.name
+                );
+                for node in &mut run.nodes {
+                    match node.status {
+                        NodeStatus::Running => {
+                            node.status = NodeStatus::Failed;
+                            node.error = Some("interrupted by daemon restart".to_string());
+                            node.finished_at = Some(now);
+                        }
+                        NodeStatus::Pending => {
+                            node.status = NodeStatus::Skipped;
+                            node.finished_at = Some(now);
+                        }
+                        _ => {}
+                    }
+                }
+                run.status = WorkflowRunStatus::Failed;
+                run.finished_at = Some(now);
+                self.save(&run).await;
+            }
+        }
+    }
+}
+
+/// The next occurrence of a workflow's schedule after `after` (exclusive).
+pub fn next_workflow_run(
+    workflow: &Workflow,
+    after: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>> {
+    next_occurrence(&workflow.schedule, spread_seed(workflow.id), after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::scheduler::FakeClock;
+    use crate::models::workflow::{EdgeCondition, WorkflowEdge, WorkflowNode};
+    use crate::models::{ExecutionType, Interval, NewJob, NewWorkflow};
+    use crate::storage::jobs::JsonJobStore;
+    use crate::storage::logs::FsLogStore;
+    use crate::storage::workflows::JsonWorkflowStore;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    struct Harness {
+        engine: WorkflowEngine,
+        workflow_store: Arc<JsonWorkflowStore>,
+        job_store: Arc<JsonJobStore>,
+        log_store: Arc<FsLogStore>,
+        clock: Arc<FakeClock>,
+        dispatch_rx: mpsc::Receiver<DispatchRequest>,
+        _tmp: TempDir,
+    }
+
+    async fn setup() -> Harness {
+        let tmp = TempDir::new().unwrap();
+        let workflow_store = Arc::new(
+            JsonWorkflowStore::new(tmp.path().to_path_buf())
+                .await
+                .unwrap(),
+        );
+        let job_store = Arc::new(JsonJobStore::new(tmp.path().to_path_buf()).await.unwrap());
+        let log_store = Arc::new(FsLogStore::new(tmp.path().to_path_buf()).await.unwrap());
+        let clock = Arc::new(FakeClock::new(
+            Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap(),
+        ));
+        let (dispatch_tx, dispatch_rx) = mpsc::channel(16);
+        let engine = WorkflowEngine::new(
+            workflow_store.clone(),
+            job_store.clone(),
+            log_store.clone(),
+            clock.clone(),
+            dispatch_tx,
+            10,
+        );
+        Harness {
+            engine,
+            workflow_store,
+            job_store,
+            log_store,
+            clock,
+            dispatch_rx,
+            _tmp: tmp,
+        }
+    }
+
+    fn make_new_job(name: &str) -> NewJob {
+        NewJob {
+            name: name.to_string(),
+            schedule: Schedule::Manual,
+            jitter_secs: 0,
+            execution: ExecutionType::ShellCommand("true".to_string()),
+            enabled: true,
+            timezone: None,
+            working_dir: None,
+            env_vars: None,
+            timeout_secs: 0,
+            log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: vec![],
+            exclude_calendars: vec![],
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+        }
+    }
+
+    fn make_new_workflow(nodes: &[&str], edges: &[(&str, &str, EdgeCondition)]) -> NewWorkflow {
+        NewWorkflow {
+            name: "pipeline".to_string(),
+            schedule: Schedule::Manual,
+            enabled: true,
+            nodes: nodes
+                .iter()
+                .map(|n| WorkflowNode {
+                    name: n.to_string(),
+                    job: n.to_string(),
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to, condition)| WorkflowEdge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    condition: *condition,
+                })
+                .collect(),
+        }
+    }
+
+    fn completed(request: &DispatchRequest, exit_code: i32) -> JobEvent {
+        JobEvent::Completed {
+            job_id: request.job.id,
+            run_id: request.run_id,
+            exit_code,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_engine_runs_nodes_along_edges() {
+        let mut h = setup().await;
+        for name in ["build", "deploy", "rollback"] {
+            h.job_store.create_job(make_new_job(name)).await.unwrap();
+        }
+        let workflow = h
+            .workflow_store
+            .create_workflow(make_new_workflow(
+                &["build", "deploy", "rollback"],
+                &[
+                    ("build", "deploy", EdgeCondition::OnSuccess),
+                    ("deploy", "rollback", EdgeCondition::OnFailure),
+                ],
+            ))
+            .await
+            .unwrap();
+
+        let run = h.engine.start(&workflow, None).await.unwrap();
+        let build = h.dispatch_rx.try_recv().unwrap();
+        assert_eq!(build.job.name, "build");
+        assert_eq!(run.node("build").unwrap().run_id, Some(build.run_id));
+        assert!(h.dispatch_rx.try_recv().is_err());
+        assert!(h.engine.is_running(workflow.id).await);
+
+        h.engine.handle_event(&completed(&build, 0)).await;
+        let deploy = h.dispatch_rx.try_recv().unwrap();
+        assert_eq!(deploy.job.name, "deploy");
+
+        h.engine.handle_event(&completed(&deploy, 0)).await;
+        assert!(h.dispatch_rx.try_recv().is_err(), "rollback is skipped");
+
+        let run = h
+            .workflow_store
+            .get_workflow_run(workflow.id, run.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(run.status, WorkflowRunStatus::Succeeded);
+        assert_eq!(run.node("deploy").unwrap().status, NodeStatus::Succeeded);
+        assert_eq!(run.node("rollback").unwrap().status, NodeStatus::Skipped);
+        assert!(!h.engine.is_running(workflow.id).await);
+    }
+
+    #[tokio::test]
+    async fn test_engine_waits_for_retries() {
+        let mut h = setup().await;
+        let mut job = make_new_job("flaky");
+        job.retry = RetryPolicy {
+            max_attempts: 2,
+            retry_on_nonzero_exit: true,
+            ..Default::default()
+        };
+        h.job_store.create_job(job).await.unwrap();
+        let workflow = h
+            .workflow_store
+            .create_workflow(make_new_workflow(&["flaky"], &[]))
+            .await
+            .unwrap();
+
+        let run = h.engine.start(&workflow, None).await.unwrap();
+        let first = h.dispatch_rx.try_recv().unwrap();
+        h.engine.handle_event(&completed(&first, 1)).await;
+        assert!(h.engine.is_running(workflow.id).await, "a retry follows");
+
+        // The executor dispatches the retry itself.
+        let retry_run_id = Uuid::now_v7();
+        h.engine
+            .handle_event(&JobEvent::Started {
+                job_id: first.job.id,
+                run_id: retry_run_id,
+                job_name: "flaky".to_string(),
+                parent_run_id: Some(first.run_id),
+                tags: Vec::new(),
+                timestamp: Utc::now(),
+            })
+            .await;
+        h.engine
+            .handle_event(&JobEvent::Failed {
+                job_id: first.job.id,
+                run_id: retry_run_id,
+                error: "execution timed out".to_string(),
+                timestamp: Utc::now(),
+            })
+            .await;
+
+        let run = h
+            .workflow_store
+            .get_workflow_run(workflow.id, run.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let node = run.node("flaky").unwrap();
+        assert_eq!(node.attempt, 2);
+        assert_eq!(node.run_id, Some(retry_run_id));
+        assert_eq!(node.status, NodeStatus::Failed);
+        assert_eq!(node.error.as_deref(), Some("execution timed out"));
+        assert_eq!(run.status, WorkflowRunStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_engine_reconciles_missed_events() {
+        let mut h = setup().await;
+        let mut job = make_new_job("flaky");
+        job.retry = RetryPolicy {
+            max_attempts: 2,
+            retry_on_nonzero_exit: true,
+            ..Default::default()
+        };
+        h.job_store.create_job(job).await.unwrap();
+        h.job_store
+            .create_job(make_new_job("report"))
+            .await
+            .unwrap();
+        let workflow = h
+            .workflow_store
+            .create_workflow(make_new_workflow(
+                &["flaky", "report"],
+                &[("flaky", "report", EdgeCondition::OnSuccess)],
+            ))
+            .await
+            .unwrap();
+
+        let run = h.engine.start(&workflow, None).await.unwrap();
+        let first = h.dispatch_rx.try_recv().unwrap();
+
+        // Nothing recorded yet: the node keeps waiting.
+        h.engine.reconcile().await;
+        assert!(h.dispatch_rx.try_recv().is_err());
+
+        // The first attempt fails and its retry succeeds, but every event
+        // of both is lost.
+        let started_at = Utc::now();
+        let attempt = |run_id, attempt, parent_run_id, exit_code| JobRun {
+            run_id,
+            job_id: first.job.id,
+            started_at: started_at + chrono::Duration::seconds(attempt as i64),
+            finished_at: Some(started_at + chrono::Duration::seconds(attempt as i64 + 1)),
+            status: RunStatus::Completed,
+            exit_code: Some(exit_code),
+            log_size_bytes: 0,
+            error: None,
+            trigger_params: None,
+            signal: None,
+            attempt,
+            parent_run_id,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: Some(TriggerSource::Workflow),
+        };
+        let retry_run_id = Uuid::now_v7();
+        for job_run in [
+            attempt(first.run_id, 1, None, 1),
+            attempt(retry_run_id, 2, Some(first.run_id), 0),
+        ] {
+            h.log_store.create_run(&job_run).await.unwrap();
+        }
+
+        h.engine.reconcile().await;
+        let report = h.dispatch_rx.try_recv().unwrap();
+        assert_eq!(report.job.name, "report");
+        let run = h
+            .workflow_store
+            .get_workflow_run(workflow.id, run.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let node = run.node("flaky").unwrap();
+        assert_eq!(node.status, NodeStatus::Succeeded);
+        assert_eq!(node.attempt, 2);
+        assert_eq!(node.run_id, Some(retry_run_id));
+    }
+
+    #[tokio::test]
+    async fn test_engine_fails_node_with_missing_job() {
+        let mut h = setup().await;
+        h.job_store
+            .create_job(make_new_job("cleanup"))
+            .await
+            .unwrap();
+        let workflow = h
+            .workflow_store
+            .create_workflow(make_new_workflow(
+                &["gone", "cleanup"],
+                &[("gone", "cleanup", EdgeCondition::Always)],
+            ))
+            .await
+            .unwrap();
+
+        let run = h.engine.start(&workflow, None).await.unwrap();
+        let gone = run.node("gone").unwrap();
+        assert_eq!(gone.status, NodeStatus::Failed);
+        assert_eq!(gone.error.as_deref(), Some("Job 'gone' not found"));
+        // The failure unblocks the `always` edge straight away.
+        assert_eq!(h.dispatch_rx.try_recv().unwrap().job.name, "cleanup");
+    }
+
+    #[tokio::test]
+    async fn test_engine_starts_scheduled_workflows() {
+        let mut h = setup().await;
+        h.job_store
+            .create_job(make_new_job("report"))
+            .await
+            .unwrap();
+        let mut new = make_new_workflow(&["report"], &[]);
+        new.schedule = Schedule::Every {
+            interval: Interval::from_secs(3600).unwrap(),
+            anchor: None,
+        };
+        let workflow = h.workflow_store.create_workflow(new).await.unwrap();
+
+        let mut cursors = HashMap::new();
+        let next = h.engine.start_due(&mut cursors).await;
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2025, 6, 1, 13, 0, 0).unwrap())
+        );
+        assert!(h.dispatch_rx.try_recv().is_err());
+
+        h.clock.advance(chrono::Duration::minutes(61));
+        let next = h.engine.start_due(&mut cursors).await;
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2025, 6, 1, 14, 0, 0).unwrap())
+        );
+        assert_eq!(h.dispatch_rx.try_recv().unwrap().job.name, "report");
+
+        let (runs, _) = h
+            .workflow_store
+            .list_workflow_runs(workflow.id, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(
+            runs[0].scheduled_for,
+            Some(Utc.with_ymd_and_hms(2025, 6, 1, 13, 0, 0).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_runs_are_failed() {
+        let h = setup().await;
+        let workflow = h
+            .workflow_store
+            .create_workflow(make_new_workflow(
+                &["a", "b"],
+                &[("a", "b", EdgeCondition::OnSuccess)],
+            ))
+            .await
+            .unwrap();
+        let mut run = WorkflowRun::new(&workflow, None, h.clock.now());
+        run.advance(&workflow, h.clock.now());
+        h.workflow_store.save_workflow_run(&run).await.unwrap();
+
+        h.engine.fail_interrupted_runs().await;
+
+        let run = h
+            .workflow_store
+            .get_workflow_run(workflow.id, run.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(run.status, WorkflowRunStatus::Failed);
+        assert_eq!(run.node("a").unwrap().status, NodeStatus::Failed);
+        assert_eq!(run.node("b").unwrap().status, NodeStatus::Skipped);
+    }
+}
@@ -0,0 +1,782 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AcsError;
+use crate::models::cron::parse_cron;
+use crate::models::{Interval, Job};
+
+/// A named set of periods, such as holidays or maintenance windows, that jobs
+/// can be confined to (`include_calendars`) or kept out of
+/// (`exclude_calendars`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Calendar {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// IANA timezone the recurring windows are evaluated in (default: UTC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tz: Option<String>,
+    /// One-off periods, e.g. public holidays or a planned outage.
+    #[serde(default)]
+    pub ranges: Vec<DateRange>,
+    /// Recurring periods, e.g. weekends or a nightly maintenance window.
+    #[serde(default)]
+    pub windows: Vec<Window>,
+}
+
+/// A period from `start` (inclusive) to `end` (exclusive).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DateRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// A recurring period that starts at each occurrence of `cron` and lasts
+/// `duration`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Window {
+    pub cron: String,
+    pub duration: Interval,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Calendar {
+    /// The period of this calendar that `at` falls in, described for
+    /// messages, or `None` if it falls in none of them.
+    pub fn period_at(&self, at: DateTime<Utc>) -> Option<String> {
+        PreparedCalendar::new(self).period_at(at)
+    }
+}
+
+/// A calendar with its timezone and window expressions parsed, for checking
+/// many times against it. Windows with an invalid expression or timezone
+/// cover nothing; both are validated when the calendar is saved.
+struct PreparedCalendar<'a> {
+    calendar: &'a Calendar,
+    tz: Option<chrono_tz::Tz>,
+    windows: Vec<(&'a Window, Cron)>,
+}
+
+impl<'a> PreparedCalendar<'a> {
+    fn new(calendar: &'a Calendar) -> Self {
+        let tz = calendar.tz.as_deref().map(str::parse::<chrono_tz::Tz>);
+        let windows = match tz {
+            Some(Err(_)) => Vec::new(),
+            _ => calendar
+                .windows
+                .iter()
+                .filter_map(|w| Some((w, parse_cron(&w.cron, 0).ok()?)))
+                .collect(),
+        };
+        Self {
+            calendar,
+            tz: tz.and_then(Result::ok),
+            windows,
+        }
+    }
+
+    /// The period that `at` falls in, described for messages.
+    fn period_at(&self, at: DateTime<Utc>) -> Option<String> {
+        let ranges = &self.calendar.ranges;
+        if let Some(range) = ranges.iter().find(|r| r.start <= at && at < r.end) {
+            return Some(range.label.clone().unwrap_or_else(|| {
+                format!("{} to {}", range.start.to_rfc3339(), range.end.to_rfc3339())
+            }));
+        }
+        self.windows
+            .iter()
+            .find(|(w, cron)| self.window_end(w, cron, at).is_some())
+            .map(|(w, _)| {
+                w.label
+                    .clone()
+                    .unwrap_or_else(|| format!("'{}' for {}", w.cron, w.duration))
+            })
+    }
+
+    /// The end of the window that covers `at`: the latest occurrence of its
+    /// expression at or before `at`, plus its duration. A window that ends
+    /// past the largest representable time ends at that time.
+    fn window_end(&self, window: &Window, cron: &Cron, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = match self.tz {
+            Some(tz) => cron
+                .find_previous_occurrence(&at.with_timezone(&tz), true)
+                .map(|t| t.with_timezone(&Utc)),
+            None => cron.find_previous_occurrence(&at, true),
+        }
+        .ok()?;
+        let end = start
+            .checked_add_signed(window.duration.to_duration())
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        (at < end).then_some(end)
+    }
+
+    /// The latest end of the periods that cover `at`, or `None` if none do.
+    fn covered_until(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let ranges = self
+            .calendar
+            .ranges
+            .iter()
+            .filter(|r| r.start <= at && at < r.end)
+            .map(|r| r.end);
+        let windows = self
+            .windows
+            .iter()
+            .filter_map(|(w, cron)| self.window_end(w, cron, at));
+        ranges.chain(windows).max()
+    }
+
+    /// The earliest start of a period after `at`, or `None` if there is none.
+    fn next_start(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let ranges = self
+            .calendar
+            .ranges
+            .iter()
+            .map(|r| r.start)
+            .filter(|start| *start > at);
+        let windows = self.windows.iter().filter_map(|(_, cron)| match self.tz {
+            Some(tz) => cron
+                .find_next_occurrence(&at.with_timezone(&tz), false)
+                .ok()
+                .map(|t| t.with_timezone(&Utc)),
+            None => cron.find_next_occurrence(&at, false).ok(),
+        });
+        ranges.chain(windows).min()
+    }
+}
+
+/// The calendars a job references, prepared once for checking many times
+/// against them. A calendar that does not exist has no periods.
+pub struct JobCalendars<'a> {
+    include_names: &'a [String],
+    include: Vec<PreparedCalendar<'a>>,
+    exclude: Vec<(&'a str, PreparedCalendar<'a>)>,
+}
+
+impl<'a> JobCalendars<'a> {
+    pub fn new(job: &'a Job, calendars: &'a [Calendar]) -> Self {
+        let find = |name: &String| calendars.iter().find(|c| c.name == *name);
+        Self {
+            include_names: &job.include_calendars,
+            include: job
+                .include_calendars
+                .iter()
+                .filter_map(find)
+                .map(PreparedCalendar::new)
+                .collect(),
+            exclude: job
+                .exclude_calendars
+                .iter()
+                .filter_map(|name| Some((name.as_str(), PreparedCalendar::new(find(name)?))))
+                .collect(),
+        }
+    }
+
+    /// Why the calendars keep the job from running at `at`, or `None` if
+    /// it may run then.
+    pub fn exclusion_reason(&self, at: DateTime<Utc>) -> Option<String> {
+        for (name, calendar) in &self.exclude {
+            if let Some(period) = calendar.period_at(at) {
+                return Some(format!("excluded by calendar '{}' ({})", name, period));
+            }
+        }
+        if !self.include_names.is_empty() && !self.include.iter().any(|c| c.period_at(at).is_some())
+        {
+            let names: Vec<String> = self
+                .include_names
+                .iter()
+                .map(|n| format!("'{}'", n))
+                .collect();
+            return Some(format!("outside included calendar {}", names.join(", ")));
+        }
+        None
+    }
+
+    /// The earliest time at or after `at` that the calendars do not rule
+    /// out: `at` itself if the job may run then, the end of the periods
+    /// excluding it, or the start of the next included period. The job is
+    /// not necessarily allowed at the returned time, but is at no time
+    /// before it. `None` if the job is never allowed again.
+    pub fn next_opening(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if let Some(end) = self
+            .exclude
+            .iter()
+            .filter_map(|(_, c)| c.covered_until(at))
+            .max()
+        {
+            return (end < DateTime::<Utc>::MAX_UTC).then_some(end);
+        }
+        if self.include_names.is_empty()
+            || self.include.iter().any(|c| c.covered_until(at).is_some())
+        {
+            return Some(at);
+        }
+        self.include.iter().filter_map(|c| c.next_start(at)).min()
+    }
+}
+
+/// Why the calendars `job` references keep it from running at `at`, or
+/// `None` if it may run then. A calendar that does not exist has no periods.
+pub fn exclusion_reason(job: &Job, calendars: &[Calendar], at: DateTime<Utc>) -> Option<String> {
+    JobCalendars::new(job, calendars).exclusion_reason(at)
+}
+
+/// Validate a calendar before it is saved.
+pub fn validate_calendar(calendar: &Calendar) -> Result<(), AcsError> {
+    if calendar.name.trim().is_empty() {
+        return Err(AcsError::Validation(
+            "Calendar name cannot be empty".to_string(),
+        ));
+    }
+    if calendar.name.contains('/') {
+        return Err(AcsError::Validation(
+            "Calendar name cannot contain '/'".to_string(),
+        ));
+    }
+    if let Some(ref tz) = calendar.tz {
+        parse_tz(Some(tz))?;
+    }
+    for range in &calendar.ranges {
+        if range.start >= range.end {
+            return Err(AcsError::Validation(format!(
+                "Calendar range {} to {} must end after it starts",
+                range.start.to_rfc3339(),
+                range.end.to_rfc3339()
+            )));
+        }
+    }
+    for window in &calendar.windows {
+        parse_cron(&window.cron, 0)?;
+    }
+    Ok(())
+}
+
+fn parse_tz(tz: Option<&str>) -> Result<chrono_tz::Tz, AcsError> {
+    match tz {
+        Some(tz) => tz
+            .parse()
+            .map_err(|_| AcsError::Validation(format!("Invalid timezone: '{}'", tz))),
+        None => Ok(chrono_tz::UTC),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// iCalendar import
+// ---------------------------------------------------------------------------
+
+/// A `DTSTART`/`DTEND` value: a whole day, a point in time, or a local
+/// time in a zone.
+#[derive(Debug, Clone, Copy)]
+enum IcalTime {
+    Date(NaiveDate),
+    DateTime(DateTime<Utc>),
+    Local(NaiveDateTime, chrono_tz::Tz),
+}
+
+impl IcalTime {
+    /// The same time of day, `days` days later, in the same zone.
+    fn add_days(self, days: chrono::Days) -> Option<Self> {
+        Some(match self {
+            IcalTime::Date(day) => IcalTime::Date(day.checked_add_days(days)?),
+            IcalTime::DateTime(t) => IcalTime::DateTime(t.checked_add_days(days)?),
+            IcalTime::Local(t, tz) => IcalTime::Local(t.checked_add_days(days)?, tz),
+        })
+    }
+
+    fn date(self) -> NaiveDate {
+        match self {
+            IcalTime::Date(day) => day,
+            IcalTime::DateTime(t) => t.date_naive(),
+            IcalTime::Local(t, _) => t.date(),
+        }
+    }
+}
+
+/// The fields of a `VEVENT` read so far.
+#[derive(Default)]
+struct IcalEvent {
+    start: Option<IcalTime>,
+    end: Option<IcalTime>,
+    summary: Option<String>,
+    rrule: Option<String>,
+    /// A property the import cannot honour, such as `EXDATE`.
+    unsupported: Option<String>,
+}
+
+/// Recurring events without an `UNTIL` or `COUNT` are expanded up to this
+/// many years from now.
+const ICAL_RECURRENCE_YEARS: u64 = 10;
+
+/// Convert the events of an iCalendar (`.ics`) file into date ranges
+/// labelled with their summaries.
+///
+/// All-day events cover whole days, which, like times without a zone, are
+/// read in `tz` (UTC if unset); times with a `TZID` use that zone.
+/// Recurring events are expanded into one range per occurrence: `RRULE`s
+/// with `FREQ=DAILY`, `WEEKLY`, `MONTHLY` or `YEARLY`, an optional
+/// `INTERVAL`, and `UNTIL` or `COUNT` (otherwise up to
+/// `ICAL_RECURRENCE_YEARS` from now). An event using any other rule part,
+/// `RDATE` or `EXDATE` fails the import, rather than import a calendar that
+/// differs from the file.
+pub fn parse_ical(content: &str, tz: Option<&str>) -> Result<Vec<DateRange>, AcsError> {
+    let tz = parse_tz(tz)?;
+    let invalid =
+        |reason: String| AcsError::Validation(format!("Invalid iCalendar file: {}", reason));
+    let horizon = Utc::now()
+        .checked_add_days(chrono::Days::new(ICAL_RECURRENCE_YEARS * 366))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+    // Lines starting with a space or tab continue the previous one.
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut ranges = Vec::new();
+    let mut event: Option<IcalEvent> = None;
+    for line in &lines {
+        let Some((head, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut params = head.split(';');
+        let name = params.next().unwrap_or_default().to_ascii_uppercase();
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some(IcalEvent::default());
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let event = event.take().unwrap();
+                let start = event
+                    .start
+                    .ok_or_else(|| invalid("event without DTSTART".to_string()))?;
+                let described = || match &event.summary {
+                    Some(summary) => format!("event '{}'", summary),
+                    None => "an event".to_string(),
+                };
+                if let Some(property) = &event.unsupported {
+                    return Err(invalid(format!(
+                        "{} uses {}, which is not supported",
+                        described(),
+                        property
+                    )));
+                }
+                let starts = match &event.rrule {
+                    Some(rule) => expand_rrule(rule, start, tz, horizon)
+                        .map_err(|reason| invalid(format!("{}: {}", described(), reason)))?,
+                    None => vec![start],
+                };
+                // A missing end means one day for all-day events, otherwise
+                // an instant, which covers nothing.
+                let end = match (start, event.end) {
+                    (_, Some(end)) => end,
+                    (IcalTime::Date(day), None) => IcalTime::Date(day + chrono::Days::new(1)),
+                    (_, None) => continue,
+                };
+                for occurrence in starts {
+                    let offset = chrono::Days::new(
+                        (occurrence.date() - start.date()).num_days().max(0) as u64,
+                    );
+                    let Some(occurrence_end) = end.add_days(offset) else {
+                        break;
+                    };
+                    let start = ical_instant(occurrence, tz).map_err(invalid)?;
+                    let end = ical_instant(occurrence_end, tz).map_err(invalid)?;
+                    if start < end {
+                        ranges.push(DateRange {
+                            start,
+                            end,
+                            label: event.summary.clone(),
+                        });
+                    }
+                }
+            }
+            ("DTSTART", Some(event)) => {
+                event.start = Some(parse_ical_time(value, params, tz).map_err(invalid)?);
+            }
+            ("DTEND", Some(event)) => {
+                event.end = Some(parse_ical_time(value, params, tz).map_err(invalid)?);
+            }
+            ("SUMMARY", Some(event)) => {
+                event.summary = Some(value.replace("\\,", ",").replace("\\;", ";"));
+            }
+            ("RRULE", Some(event)) => {
+                event.rrule = Some(value.trim().to_string());
+            }
+            ("RDATE" | "EXDATE", Some(event)) => {
+                event.unsupported.get_or_insert(name.clone());
+            }
+            _ => {}
+        }
+    }
+    Ok(ranges)
+}
+
+/// The starts of the occurrences of an event starting at `start` that
+/// recurs by `rule`, up to `horizon`.
+fn expand_rrule(
+    rule: &str,
+    start: IcalTime,
+    tz: chrono_tz::Tz,
+    horizon: DateTime<Utc>,
+) -> Result<Vec<IcalTime>, String> {
+    let mut freq = None;
+    let mut interval = 1u32;
+    let mut count = None;
+    let mut until = None;
+    for part in rule.split(';').filter(|p| !p.is_empty()) {
+        let (key, value) = part
+            .split_once('=')
+            .ok_or_else(|| format!("invalid RRULE part '{}'", part))?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => freq = Some(value.to_ascii_uppercase()),
+            "INTERVAL" => {
+                interval = value
+                    .parse()
+                    .ok()
+                    .filter(|i| *i > 0)
+                    .ok_or_else(|| format!("invalid RRULE INTERVAL '{}'", value))?;
+            }
+            "COUNT" => {
+                count = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid RRULE COUNT '{}'", value))?,
+                );
+            }
+            "UNTIL" => {
+                let time = parse_ical_time(value, std::iter::empty(), tz)?;
+                until = Some(ical_instant(time, tz)?);
+            }
+            // The week start only matters to rule parts that are rejected.
+            "WKST" => {}
+            _ => return Err(format!("RRULE part '{}' is not supported", key)),
+        }
+    }
+
+    let freq = match freq.as_deref() {
+        Some(freq @ ("DAILY" | "WEEKLY" | "MONTHLY" | "YEARLY")) => freq,
+        Some(other) => return Err(format!("RRULE FREQ '{}' is not supported", other)),
+        None => return Err("RRULE without FREQ".to_string()),
+    };
+
+    let first = start.date();
+    let limit = until.map_or(horizon, |until| until.min(horizon));
+    let mut starts = Vec::new();
+    for n in 0u32.. {
+        if count.is_some_and(|count| starts.len() >= count) {
+            break;
+        }
+        let Some(steps) = n.checked_mul(interval) else {
+            break;
+        };
+        // The first day the step can fall on, and its date, which does not
+        // exist when the month is too short (such as February 30); those
+        // steps are skipped, as RFC 5545 asks.
+        let step = match freq {
+            "DAILY" => first
+                .checked_add_days(chrono::Days::new(steps.into()))
+                .map(|date| (date, Some(date))),
+            "WEEKLY" => first
+                .checked_add_days(chrono::Days::new(u64::from(steps) * 7))
+                .map(|date| (date, Some(date))),
+            _ => {
+                let months = if freq == "YEARLY" {
+                    steps.checked_mul(12)
+                } else {
+                    Some(steps)
+                };
+                months
+                    .and_then(|months| {
+                        first
+                            .with_day(1)?
+                            .checked_add_months(chrono::Months::new(months))
+                    })
+                    .map(|month| (month, month.with_day(first.day())))
+            }
+        };
+        let Some((earliest, date)) = step else {
+            break;
+        };
+        if earliest > limit.date_naive() {
+            break;
+        }
+        let Some(date) = date else {
+            continue;
+        };
+        let days = chrono::Days::new((date - first).num_days() as u64);
+        let Some(occurrence) = start.add_days(days) else {
+            break;
+        };
+        if ical_instant(occurrence, tz)? > limit {
+            break;
+        }
+        starts.push(occurrence);
+    }
+    Ok(starts)
+}
+
+/// Parse a `DTSTART`/`DTEND` value: `YYYYMMDD`, `YYYYMMDDTHHMMSSZ`, or a
+/// local time in the `TZID` parameter's zone (`tz` if absent).
+fn parse_ical_time<'a>(
+    value: &str,
+    params: impl Iterator<Item = &'a str>,
+    tz: chrono_tz::Tz,
+) -> Result<IcalTime, String> {
+    let value = value.trim();
+    if value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(IcalTime::Date)
+            .map_err(|_| format!("invalid date '{}'", value));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .map(|t| IcalTime::DateTime(t.and_utc()))
+            .map_err(|_| format!("invalid time '{}'", value));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map_err(|_| format!("invalid time '{}'", value))?;
+    let tz = match params
+        .filter_map(|p| p.split_once('='))
+        .find(|(k, _)| k.eq_ignore_ascii_case("TZID"))
+    {
+        Some((_, tzid)) => {
+            let tzid = tzid.trim_matches('"');
+            tzid.parse()
+                .map_err(|_| format!("unknown timezone '{}'", tzid))?
+        }
+        None => tz,
+    };
+    Ok(IcalTime::Local(naive, tz))
+}
+
+/// The instant an iCalendar time stands for; days start at midnight in `tz`.
+fn ical_instant(time: IcalTime, tz: chrono_tz::Tz) -> Result<DateTime<Utc>, String> {
+    match time {
+        IcalTime::DateTime(t) => Ok(t),
+        IcalTime::Local(t, zone) => t
+            .and_local_timezone(zone)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+            .ok_or_else(|| format!("time '{}' does not exist in {}", t, zone)),
+        IcalTime::Date(day) => day
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(tz).earliest())
+            .map(|t| t.with_timezone(&Utc))
+            .ok_or_else(|| format!("midnight of {} does not exist in {}", day, tz)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn weekends() -> Calendar {
+        Calendar {
+            name: "weekends".to_string(),
+            description: None,
+            tz: Some("America/New_York".to_string()),
+            ranges: vec![],
+            windows: vec![Window {
+                cron: "0 0 * * SAT".to_string(),
+                duration: "2d".parse().unwrap(),
+                label: Some("weekend".to_string()),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_period_at_ranges_and_windows() {
+        let mut calendar = weekends();
+        calendar.ranges.push(DateRange {
+            start: Utc.with_ymd_and_hms(2025, 12, 25, 0, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2025, 12, 26, 0, 0, 0).unwrap(),
+            label: Some("Christmas".to_string()),
+        });
+
+        // Saturday 2025-06-14 00:00 in New York is 04:00 UTC
+        let saturday = Utc.with_ymd_and_hms(2025, 6, 14, 4, 0, 0).unwrap();
+        assert_eq!(calendar.period_at(saturday).as_deref(), Some("weekend"));
+        let friday_night = saturday - chrono::Duration::seconds(1);
+        assert_eq!(calendar.period_at(friday_night), None);
+        let monday = saturday + chrono::Duration::days(2);
+        assert_eq!(calendar.period_at(monday), None);
+
+        let christmas = Utc.with_ymd_and_hms(2025, 12, 25, 12, 0, 0).unwrap();
+        assert_eq!(calendar.period_at(christmas).as_deref(), Some("Christmas"));
+        let boxing_day = Utc.with_ymd_and_hms(2025, 12, 26, 0, 0, 0).unwrap();
+        assert_eq!(calendar.period_at(boxing_day), None);
+    }
+
+    #[test]
+    fn test_exclusion_reason() {
+        let at = Utc.with_ymd_and_hms(2025, 6, 14, 12, 0, 0).unwrap();
+        let calendars = [weekends()];
+        let mut job: Job = serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::now_v7(),
+            "name": "report",
+            "schedule": "0 * * * *",
+            "execution": {"type": "ShellCommand", "value": "echo"},
+            "enabled": true,
+            "created_at": at,
+            "updated_at": at,
+        }))
+        .unwrap();
+
+        assert_eq!(exclusion_reason(&job, &calendars, at), None);
+
+        job.exclude_calendars = vec!["weekends".to_string()];
+        assert_eq!(
+            exclusion_reason(&job, &calendars, at).as_deref(),
+            Some("excluded by calendar 'weekends' (weekend)")
+        );
+        let tuesday = at + chrono::Duration::days(3);
+        assert_eq!(exclusion_reason(&job, &calendars, tuesday), None);
+
+        job.exclude_calendars.clear();
+        job.include_calendars = vec!["weekends".to_string()];
+        assert_eq!(exclusion_reason(&job, &calendars, at), None);
+        assert_eq!(
+            exclusion_reason(&job, &calendars, tuesday).as_deref(),
+            Some("outside included calendar 'weekends'")
+        );
+    }
+
+    #[test]
+    fn test_validate_calendar() {
+        assert!(validate_calendar(&weekends()).is_ok());
+
+        let mut calendar = weekends();
+        calendar.name = " ".to_string();
+        assert!(matches!(
+            validate_calendar(&calendar),
+            Err(AcsError::Validation(_))
+        ));
+
+        let mut calendar = weekends();
+        calendar.tz = Some("Not/Valid".to_string());
+        assert!(validate_calendar(&calendar).is_err());
+
+        let mut calendar = weekends();
+        calendar.windows[0].cron = "61 * * * *".to_string();
+        assert!(matches!(
+            validate_calendar(&calendar),
+            Err(AcsError::Cron(_))
+        ));
+
+        let mut calendar = weekends();
+        let start = Utc.with_ymd_and_hms(2025, 12, 25, 0, 0, 0).unwrap();
+        calendar.ranges.push(DateRange {
+            start,
+            end: start,
+            label: None,
+        });
+        assert!(validate_calendar(&calendar).is_err());
+    }
+
+    #[test]
+    fn test_parse_ical() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:Christmas\\, observed\r\n\
+                   DTSTART;VALUE=DATE:20251225\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:Database\r\n  migration\r\n\
+                   DTSTART;TZID=Europe/Berlin:20250701T220000\r\n\
+                   DTEND:20250702T020000Z\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:Reminder\r\n\
+                   DTSTART:20250801T090000\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+        let ranges = parse_ical(ics, Some("America/New_York")).unwrap();
+        assert_eq!(
+            ranges,
+            vec![
+                DateRange {
+                    start: Utc.with_ymd_and_hms(2025, 12, 25, 5, 0, 0).unwrap(),
+                    end: Utc.with_ymd_and_hms(2025, 12, 26, 5, 0, 0).unwrap(),
+                    label: Some("Christmas, observed".to_string()),
+                },
+                DateRange {
+                    start: Utc.with_ymd_and_hms(2025, 7, 1, 20, 0, 0).unwrap(),
+                    end: Utc.with_ymd_and_hms(2025, 7, 2, 2, 0, 0).unwrap(),
+                    label: Some("Database migration".to_string()),
+                },
+            ]
+        );
+
+        let err = parse_ical("BEGIN:VEVENT\nDTSTART:2025\nEND:VEVENT\n", None).unwrap_err();
+        assert!(err.to_string().contains("invalid time '2025'"));
+        let err = parse_ical("BEGIN:VEVENT\nSUMMARY:x\nEND:VEVENT\n", None).unwrap_err();
+        assert!(err.to_string().contains("without DTSTART"));
+    }
+
+    #[test]
+    fn test_parse_ical_expands_recurrences() {
+        let ics = "BEGIN:VEVENT\n\
+                   SUMMARY:Leap day\n\
+                   DTSTART;VALUE=DATE:20240229\n\
+                   RRULE:FREQ=YEARLY;COUNT=2\n\
+                   END:VEVENT\n\
+                   BEGIN:VEVENT\n\
+                   SUMMARY:Backup window\n\
+                   DTSTART;TZID=Europe/Berlin:20251019T230000\n\
+                   DTEND;TZID=Europe/Berlin:20251020T010000\n\
+                   RRULE:FREQ=WEEKLY;INTERVAL=2;UNTIL=20251102T230000Z\n\
+                   END:VEVENT\n";
+
+        let ranges = parse_ical(ics, None).unwrap();
+        let spans: Vec<_> = ranges.iter().map(|r| (r.start, r.end)).collect();
+        assert_eq!(
+            spans,
+            vec![
+                // Years without February 29 are skipped.
+                (
+                    Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+                ),
+                (
+                    Utc.with_ymd_and_hms(2028, 2, 29, 0, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2028, 3, 1, 0, 0, 0).unwrap(),
+                ),
+                // Local times keep their wall clock across the DST change.
+                (
+                    Utc.with_ymd_and_hms(2025, 10, 19, 21, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2025, 10, 19, 23, 0, 0).unwrap(),
+                ),
+                (
+                    Utc.with_ymd_and_hms(2025, 11, 2, 22, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2025, 11, 3, 0, 0, 0).unwrap(),
+                ),
+            ]
+        );
+        assert!(ranges[0].label.as_deref() == Some("Leap day"));
+
+        let err = parse_ical(
+            "BEGIN:VEVENT\nSUMMARY:Weekdays\nDTSTART:20250101T000000Z\n\
+             RRULE:FREQ=WEEKLY;BYDAY=MO,TU\nEND:VEVENT\n",
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("event 'Weekdays'"));
+        assert!(err.to_string().contains("'BYDAY' is not supported"));
+        let err = parse_ical(
+            "BEGIN:VEVENT\nDTSTART:20250101T000000Z\nRRULE:FREQ=DAILY\n\
+             EXDATE:20250102T000000Z\nEND:VEVENT\n",
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("uses EXDATE"));
+    }
+}
@@ -1,7 +1,10 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use super::job::LogRetention;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DaemonConfig {
     #[serde(default = "default_host")]
@@ -12,8 +15,29 @@ pub struct DaemonConfig {
     pub data_dir: Option<PathBuf>,
     #[serde(default = "default_max_log_files_per_job")]
     pub max_log_files_per_job: usize,
+    /// Bytes of output kept per run; a run's log is truncated with a marker
+    /// once it reaches this size. 0 means no limit.
     #[serde(default = "default_max_log_file_size")]
     pub max_log_file_size: u64,
+    /// Days a run's log is kept after it started. 0 keeps logs regardless
+    /// of age.
+    #[serde(default)]
+    pub log_keep_days: u32,
+    /// Bytes the logs of a single job may take on disk before its oldest
+    /// runs are pruned. 0 means no limit.
+    #[serde(default)]
+    pub max_log_bytes_per_job: u64,
+    /// Bytes all logs may take on disk before the oldest runs across all
+    /// jobs are pruned. 0 means no limit.
+    #[serde(default)]
+    pub max_log_bytes_total: u64,
+    /// How the logs of finished runs are compressed.
+    #[serde(default)]
+    pub log_compression: LogCompression,
+    /// Seconds between the log janitor's retention sweeps. 0 disables the
+    /// janitor.
+    #[serde(default = "default_log_janitor_interval_secs")]
+    pub log_janitor_interval_secs: u64,
     #[serde(default = "default_timeout_secs")]
     pub default_timeout_secs: u64,
     #[serde(default = "default_broadcast_capacity")]
@@ -22,6 +46,97 @@ pub struct DaemonConfig {
     pub pty_rows: u16,
     #[serde(default = "default_pty_cols")]
     pub pty_cols: u16,
+    /// Seconds to wait after SIGTERM before a killed or timed-out run's
+    /// process group is sent SIGKILL.
+    #[serde(default = "default_kill_grace_period_secs")]
+    pub kill_grace_period_secs: u64,
+    /// Runs allowed at once across all jobs; further dispatches wait in the
+    /// run queue. 0 means no limit.
+    #[serde(default)]
+    pub max_concurrent_runs: usize,
+    /// Named pools that jobs join with their `pool` field, each with the
+    /// number of its runs allowed at once. A limit of 0 means no limit.
+    #[serde(default)]
+    pub concurrency_pools: HashMap<String, usize>,
+    /// A directory of job definition files, one job per file, that the
+    /// daemon keeps its jobs in line with. Relative paths are taken from
+    /// the data directory.
+    #[serde(default)]
+    pub jobs_dir: Option<PathBuf>,
+    /// Where jobs and run records are kept.
+    #[serde(default)]
+    pub storage: StorageBackend,
+}
+
+/// The stores the daemon keeps jobs and run records in. Run output is kept
+/// in per-run log files under `logs/` either way.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// `jobs.json`, and a `.meta.json` file per run next to its log.
+    #[default]
+    Json,
+    /// An `acs.db` SQLite database. On first start, jobs and runs kept by
+    /// the `json` backend are imported into it.
+    Sqlite,
+}
+
+/// How the `.log` file of a finished run is compressed. Compressed logs
+/// are read back transparently, whatever the current setting.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogCompression {
+    /// Logs are left as plain text.
+    #[default]
+    None,
+    /// `.log.gz`
+    Gzip,
+    /// `.log.zst`
+    Zstd,
+}
+
+impl LogCompression {
+    /// The formats logs can be compressed in.
+    pub const FORMATS: [LogCompression; 2] = [LogCompression::Gzip, LogCompression::Zstd];
+
+    /// The extension added to a compressed log's `.log`, if any.
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            LogCompression::None => None,
+            LogCompression::Gzip => Some("gz"),
+            LogCompression::Zstd => Some("zst"),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogCompression::None => "none",
+            LogCompression::Gzip => "gzip",
+            LogCompression::Zstd => "zstd",
+        }
+    }
+}
+
+impl std::fmt::Display for LogCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for LogCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(LogCompression::None),
+            "gzip" | "gz" => Ok(LogCompression::Gzip),
+            "zstd" | "zst" => Ok(LogCompression::Zstd),
+            _ => Err(format!(
+                "Invalid log compression '{}': expected none, gzip or zstd",
+                s
+            )),
+        }
+    }
 }
 
 fn default_host() -> String {
@@ -40,6 +155,10 @@ fn default_max_log_file_size() -> u64 {
     10_485_760 // 10MB
 }
 
+fn default_log_janitor_interval_secs() -> u64 {
+    3600
+}
+
 fn default_timeout_secs() -> u64 {
     0
 }
@@ -56,6 +175,10 @@ fn default_pty_cols() -> u16 {
     80
 }
 
+fn default_kill_grace_period_secs() -> u64 {
+    10
+}
+
 impl Default for DaemonConfig {
     fn default() -> Self {
         Self {
@@ -64,10 +187,42 @@ impl Default for DaemonConfig {
             data_dir: None,
             max_log_files_per_job: default_max_log_files_per_job(),
             max_log_file_size: default_max_log_file_size(),
+            log_keep_days: 0,
+            max_log_bytes_per_job: 0,
+            max_log_bytes_total: 0,
+            log_compression: LogCompression::None,
+            log_janitor_interval_secs: default_log_janitor_interval_secs(),
             default_timeout_secs: default_timeout_secs(),
             broadcast_capacity: default_broadcast_capacity(),
             pty_rows: default_pty_rows(),
             pty_cols: default_pty_cols(),
+            kill_grace_period_secs: default_kill_grace_period_secs(),
+            max_concurrent_runs: 0,
+            concurrency_pools: HashMap::new(),
+            jobs_dir: None,
+            storage: StorageBackend::Json,
+        }
+    }
+}
+
+/// The log retention limits that apply to one job: its own overrides,
+/// falling back to the daemon's settings. Zero means no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionLimits {
+    pub max_runs: usize,
+    pub keep_days: u32,
+    pub max_bytes: u64,
+    pub max_file_size: u64,
+}
+
+impl DaemonConfig {
+    /// The retention limits for a job with the given overrides.
+    pub fn retention_for(&self, overrides: &LogRetention) -> RetentionLimits {
+        RetentionLimits {
+            max_runs: overrides.max_runs.unwrap_or(self.max_log_files_per_job),
+            keep_days: overrides.keep_days.unwrap_or(self.log_keep_days),
+            max_bytes: overrides.max_bytes.unwrap_or(self.max_log_bytes_per_job),
+            max_file_size: overrides.max_file_size.unwrap_or(self.max_log_file_size),
         }
     }
 }
@@ -84,10 +239,20 @@ mod tests {
         assert!(config.data_dir.is_none());
         assert_eq!(config.max_log_files_per_job, 50);
         assert_eq!(config.max_log_file_size, 10_485_760);
+        assert_eq!(config.log_keep_days, 0);
+        assert_eq!(config.max_log_bytes_per_job, 0);
+        assert_eq!(config.max_log_bytes_total, 0);
+        assert_eq!(config.log_compression, LogCompression::None);
+        assert_eq!(config.log_janitor_interval_secs, 3600);
         assert_eq!(config.default_timeout_secs, 0);
         assert_eq!(config.broadcast_capacity, 4096);
         assert_eq!(config.pty_rows, 24);
         assert_eq!(config.pty_cols, 80);
+        assert_eq!(config.kill_grace_period_secs, 10);
+        assert_eq!(config.max_concurrent_runs, 0);
+        assert!(config.concurrency_pools.is_empty());
+        assert!(config.jobs_dir.is_none());
+        assert_eq!(config.storage, StorageBackend::Json);
     }
 
     #[test]
@@ -109,6 +274,10 @@ mod tests {
         assert_eq!(deserialized.broadcast_capacity, config.broadcast_capacity);
         assert_eq!(deserialized.pty_rows, config.pty_rows);
         assert_eq!(deserialized.pty_cols, config.pty_cols);
+        assert_eq!(
+            deserialized.kill_grace_period_secs,
+            config.kill_grace_period_secs
+        );
     }
 
     #[test]
@@ -152,10 +321,20 @@ mod tests {
             "data_dir": "/data",
             "max_log_files_per_job": 100,
             "max_log_file_size": 52428800,
+            "log_keep_days": 14,
+            "max_log_bytes_per_job": 104857600,
+            "max_log_bytes_total": 1073741824,
+            "log_compression": "zstd",
+            "log_janitor_interval_secs": 600,
             "default_timeout_secs": 300,
             "broadcast_capacity": 8192,
             "pty_rows": 48,
-            "pty_cols": 120
+            "pty_cols": 120,
+            "kill_grace_period_secs": 30,
+            "max_concurrent_runs": 4,
+            "concurrency_pools": {"claude": 2},
+            "jobs_dir": "jobs.d",
+            "storage": "sqlite"
         }"#;
         let config: DaemonConfig = serde_json::from_str(json).expect("deserialize");
         assert_eq!(config.host, "0.0.0.0");
@@ -163,9 +342,64 @@ mod tests {
         assert_eq!(config.data_dir, Some(PathBuf::from("/data")));
         assert_eq!(config.max_log_files_per_job, 100);
         assert_eq!(config.max_log_file_size, 52428800);
+        assert_eq!(config.log_keep_days, 14);
+        assert_eq!(config.max_log_bytes_per_job, 104857600);
+        assert_eq!(config.max_log_bytes_total, 1073741824);
+        assert_eq!(config.log_compression, LogCompression::Zstd);
+        assert_eq!(config.log_janitor_interval_secs, 600);
         assert_eq!(config.default_timeout_secs, 300);
         assert_eq!(config.broadcast_capacity, 8192);
         assert_eq!(config.pty_rows, 48);
         assert_eq!(config.pty_cols, 120);
+        assert_eq!(config.kill_grace_period_secs, 30);
+        assert_eq!(config.max_concurrent_runs, 4);
+        assert_eq!(config.concurrency_pools.get("claude"), Some(&2));
+        assert_eq!(config.jobs_dir, Some(PathBuf::from("jobs.d")));
+        assert_eq!(config.storage, StorageBackend::Sqlite);
+    }
+
+    #[test]
+    fn test_retention_for_job_overrides() {
+        let config = DaemonConfig {
+            log_keep_days: 30,
+            max_log_bytes_per_job: 1000,
+            ..Default::default()
+        };
+        let limits = config.retention_for(&LogRetention::default());
+        assert_eq!(
+            limits,
+            RetentionLimits {
+                max_runs: 50,
+                keep_days: 30,
+                max_bytes: 1000,
+                max_file_size: 10_485_760,
+            }
+        );
+
+        let overrides = LogRetention {
+            max_runs: Some(5),
+            keep_days: Some(0),
+            max_bytes: None,
+            max_file_size: Some(4096),
+        };
+        let limits = config.retention_for(&overrides);
+        assert_eq!(limits.max_runs, 5);
+        assert_eq!(limits.keep_days, 0);
+        assert_eq!(limits.max_bytes, 1000);
+        assert_eq!(limits.max_file_size, 4096);
+    }
+
+    #[test]
+    fn test_log_compression_parse() {
+        assert_eq!("gzip".parse(), Ok(LogCompression::Gzip));
+        assert_eq!("ZST".parse(), Ok(LogCompression::Zstd));
+        assert_eq!("none".parse(), Ok(LogCompression::None));
+        assert!("lz4".parse::<LogCompression>().is_err());
+        assert_eq!(LogCompression::Zstd.extension(), Some("zst"));
+        assert_eq!(LogCompression::None.extension(), None);
+        assert_eq!(
+            serde_json::to_string(&LogCompression::Gzip).unwrap(),
+            "\"gzip\""
+        );
     }
 }
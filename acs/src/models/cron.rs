@@ -0,0 +1,329 @@
+use croner::parser::{CronParser, Seconds, Year};
+use croner::Cron;
+
+use crate::errors::AcsError;
+use crate::models::schedule::mix;
+
+/// Aliases accepted in place of a full expression.
+const ALIASES: &[&str] = &[
+    "@yearly",
+    "@annually",
+    "@monthly",
+    "@weekly",
+    "@daily",
+    "@hourly",
+];
+
+/// One field of a full 7-field expression.
+struct Field {
+    name: &'static str,
+    /// Allowed values, for error messages.
+    allowed: &'static str,
+    /// Range an `H` in this field picks from, if `H` is supported.
+    hash_range: Option<(u32, u32)>,
+}
+
+const FIELDS: [Field; 7] = [
+    Field {
+        name: "second",
+        allowed: "0-59, H",
+        hash_range: Some((0, 59)),
+    },
+    Field {
+        name: "minute",
+        allowed: "0-59, H",
+        hash_range: Some((0, 59)),
+    },
+    Field {
+        name: "hour",
+        allowed: "0-23, H",
+        hash_range: Some((0, 23)),
+    },
+    // Days past the 28th do not exist in every month.
+    Field {
+        name: "day-of-month",
+        allowed: "1-31, L, W, H",
+        hash_range: Some((1, 28)),
+    },
+    Field {
+        name: "month",
+        allowed: "1-12 or JAN-DEC, H",
+        hash_range: Some((1, 12)),
+    },
+    Field {
+        name: "day-of-week",
+        allowed: "0-7 or SUN-SAT, L, #, H",
+        hash_range: Some((0, 6)),
+    },
+    Field {
+        name: "year",
+        allowed: "1-5000",
+        hash_range: None,
+    },
+];
+
+/// The parser used for every schedule: 5 fields (minute precision), 6 fields
+/// with a leading seconds field, or 7 fields with a trailing year field.
+fn parser() -> CronParser {
+    CronParser::builder()
+        .seconds(Seconds::Optional)
+        .year(Year::Optional)
+        .build()
+}
+
+/// Parse a cron expression.
+///
+/// Accepts the standard 5-field syntax, an optional leading seconds field and
+/// trailing year field, the `@yearly`/`@monthly`/`@weekly`/`@daily`/`@hourly`
+/// aliases, and the `L`, `W` and `#` modifiers. Errors name the offending
+/// field where possible.
+///
+/// `H` fields (`H`, `H(0-29)`, `H/15`) are resolved to a value derived from
+/// `seed`, so jobs sharing an expression are spread over the field's range
+/// while each keeps the same times. Each field draws its own value.
+pub fn parse_cron(expr: &str, seed: u64) -> Result<Cron, AcsError> {
+    let invalid =
+        |reason: String| AcsError::Cron(format!("Invalid cron expression '{}': {}", expr, reason));
+    let parser = parser();
+
+    let trimmed = expr.trim();
+    let parts: Vec<&str> = trimmed.split_whitespace().collect();
+    let fields: Option<&[Field]> = match parts.len() {
+        5 => Some(&FIELDS[1..6]),
+        6 => Some(&FIELDS[..6]),
+        7 => Some(&FIELDS),
+        _ => None,
+    };
+
+    // The parser does not know `H`; substitute the values it stands for.
+    let resolved: Vec<String> = match fields {
+        Some(fields) => {
+            let first = if parts.len() == 5 { 1 } else { 0 };
+            parts
+                .iter()
+                .zip(fields)
+                .enumerate()
+                .map(|(i, (part, field))| {
+                    let hash = mix(seed.wrapping_add((first + i) as u64 + 1));
+                    resolve_hash(part, field, hash).map_err(|reason| {
+                        invalid(format!(
+                            "{} field '{}' is invalid (allowed: {}): {}",
+                            field.name, part, field.allowed, reason
+                        ))
+                    })
+                })
+                .collect::<Result<_, _>>()?
+        }
+        None => parts.iter().map(|p| p.to_string()).collect(),
+    };
+
+    let err = match parser.parse(&resolved.join(" ")) {
+        Ok(cron) => return Ok(cron),
+        Err(e) => e,
+    };
+
+    if trimmed.starts_with('@') {
+        if !ALIASES.iter().any(|a| a.eq_ignore_ascii_case(trimmed)) {
+            return Err(invalid(format!(
+                "unknown alias (expected one of {})",
+                ALIASES.join(", ")
+            )));
+        }
+        return Err(invalid(err.to_string()));
+    }
+
+    let Some(fields) = fields else {
+        return Err(invalid(format!(
+            "expected 5, 6 or 7 fields (got {})",
+            parts.len()
+        )));
+    };
+
+    // Parse each field on its own, with wildcards everywhere else, to find
+    // the one that is at fault.
+    for (i, field) in fields.iter().enumerate() {
+        let probe: Vec<&str> = (0..parts.len())
+            .map(|j| if i == j { resolved[j].as_str() } else { "*" })
+            .collect();
+        if let Err(e) = parser.parse(&probe.join(" ")) {
+            return Err(invalid(format!(
+                "{} field '{}' is invalid (allowed: {}): {}",
+                field.name, parts[i], field.allowed, e
+            )));
+        }
+    }
+    Err(invalid(err.to_string()))
+}
+
+/// Replace the `H` items of a field (`H`, `H(a-b)`, `H/n`, `H(a-b)/n`, also
+/// within a list) with the values `hash` picks for them.
+fn resolve_hash(part: &str, field: &Field, hash: u64) -> Result<String, String> {
+    if !part.contains('H') {
+        return Ok(part.to_string());
+    }
+    let Some((lo, hi)) = field.hash_range else {
+        return Err("H is not supported in this field".to_string());
+    };
+    let items: Vec<String> = part
+        .split(',')
+        .map(|item| {
+            let Some(rest) = item.strip_prefix('H') else {
+                return Ok(item.to_string());
+            };
+            let (range, step) = match rest.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (rest, None),
+            };
+            let (a, b) = if range.is_empty() {
+                (lo, hi)
+            } else {
+                let bounds = range
+                    .strip_prefix('(')
+                    .and_then(|r| r.strip_suffix(')'))
+                    .and_then(|r| r.split_once('-'))
+                    .and_then(|(a, b)| Some((a.parse::<u32>().ok()?, b.parse::<u32>().ok()?)))
+                    .ok_or_else(|| format!("malformed hash '{}'", item))?;
+                if bounds.0 > bounds.1 || bounds.0 < lo || bounds.1 > hi {
+                    return Err(format!(
+                        "hash range in '{}' must be within {}-{}",
+                        item, lo, hi
+                    ));
+                }
+                bounds
+            };
+            let span = u64::from(b - a + 1);
+            match step {
+                None => Ok((u64::from(a) + hash % span).to_string()),
+                Some(step) => {
+                    let n: u32 = step
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("malformed hash step in '{}'", item))?;
+                    let start = u64::from(a) + hash % span.min(u64::from(n));
+                    Ok(format!("{}-{}/{}", start, b, n))
+                }
+            }
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(items.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn next_after(expr: &str, after: chrono::DateTime<Utc>) -> chrono::DateTime<Utc> {
+        parse_cron(expr, 0)
+            .unwrap()
+            .find_next_occurrence(&after, false)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_extended_forms() {
+        let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 0).unwrap();
+
+        // Seconds field
+        assert_eq!(
+            next_after("*/15 * * * * *", after),
+            Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 15).unwrap()
+        );
+        // Aliases
+        assert_eq!(
+            next_after("@daily", after),
+            Utc.with_ymd_and_hms(2025, 6, 16, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            next_after("@HOURLY", after),
+            Utc.with_ymd_and_hms(2025, 6, 15, 11, 0, 0).unwrap()
+        );
+        // Last day of the month
+        assert_eq!(
+            next_after("0 0 L * *", after),
+            Utc.with_ymd_and_hms(2025, 6, 30, 0, 0, 0).unwrap()
+        );
+        // Weekday closest to the 15th (a Sunday, so Monday the 16th)
+        assert_eq!(
+            next_after("0 0 15W * *", after),
+            Utc.with_ymd_and_hms(2025, 6, 16, 0, 0, 0).unwrap()
+        );
+        // Third Friday of the month
+        assert_eq!(
+            next_after("0 0 * * FRI#3", after),
+            Utc.with_ymd_and_hms(2025, 6, 20, 0, 0, 0).unwrap()
+        );
+        // Year range
+        assert_eq!(
+            next_after("0 0 12 1 1 * 2027-2030", after),
+            Utc.with_ymd_and_hms(2027, 1, 1, 12, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_field() {
+        let message = |expr: &str| parse_cron(expr, 0).unwrap_err().to_string();
+
+        assert!(message("61 * * * *").contains("minute field '61'"));
+        assert!(message("0 25 * * *").contains("hour field '25'"));
+        assert!(message("0 0 32 * *").contains("day-of-month field '32'"));
+        assert!(message("0 0 * 13 *").contains("month field '13'"));
+        assert!(message("0 0 * * 5#9").contains("day-of-week field '5#9'"));
+        assert!(message("60 0 0 * * *").contains("second field '60'"));
+        assert!(message("0 0 0 * * * 6000").contains("year field '6000'"));
+        assert!(message("* * *").contains("expected 5, 6 or 7 fields (got 3)"));
+        assert!(message("@every").contains("unknown alias"));
+    }
+
+    #[test]
+    fn test_parse_hash_fields() {
+        let after = Utc.with_ymd_and_hms(2025, 6, 15, 10, 0, 0).unwrap();
+        let next_seeded = |expr: &str, seed: u64| {
+            parse_cron(expr, seed)
+                .unwrap()
+                .find_next_occurrence(&after, false)
+                .unwrap()
+        };
+
+        // Stable for a seed, and spread over the range across seeds
+        let minutes: std::collections::HashSet<u32> = (0..20)
+            .map(|seed| {
+                let next = next_seeded("H * * * *", seed);
+                assert_eq!(next, next_seeded("H * * * *", seed));
+                assert_eq!(chrono::Timelike::hour(&next), 10);
+                chrono::Timelike::minute(&next)
+            })
+            .collect();
+        assert!(minutes.len() > 5, "H should spread jobs: {:?}", minutes);
+
+        for seed in 0..20 {
+            // Bounded range
+            let next = next_seeded("H(0-9) * * * *", seed);
+            assert!(chrono::Timelike::minute(&next) < 10);
+            // Step: every 15 minutes from an offset below 15
+            let first = next_seeded("H/15 * * * *", seed);
+            let offset = chrono::Timelike::minute(&first) % 15;
+            let second = parse_cron("H/15 * * * *", seed)
+                .unwrap()
+                .find_next_occurrence(&first, false)
+                .unwrap();
+            assert_eq!(second - first, chrono::Duration::minutes(15));
+            assert_eq!(chrono::Timelike::minute(&second) % 15, offset);
+            // Day-of-month stays within days every month has
+            let next = next_seeded("0 0 H * *", seed);
+            assert!(chrono::Datelike::day(&next) <= 28);
+        }
+
+        // Fields draw independently of each other
+        let next = next_seeded("H H * * *", 7);
+        assert_eq!(next, next_seeded("H H * * *", 7));
+
+        let message = |expr: &str| parse_cron(expr, 0).unwrap_err().to_string();
+        assert!(message("H(30-10) * * * *").contains("minute field 'H(30-10)'"));
+        assert!(message("0 H(0-30) * * *").contains("must be within 0-23"));
+        assert!(message("H/0 * * * *").contains("malformed hash step"));
+        assert!(message("Hx * * * *").contains("malformed hash"));
+        assert!(message("0 0 0 * * * H").contains("year field 'H'"));
+    }
+}
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::models::Job;
+use crate::models::{Job, TriggerSource};
 
 /// Per-trigger parameter overrides. All fields optional.
 /// Deserialized from the trigger endpoint request body.
@@ -26,11 +27,42 @@ pub struct DispatchRequest {
     pub run_id: Uuid,
     /// Optional trigger-time parameter overrides.
     pub trigger_params: Option<TriggerParams>,
+    /// Attempt number under the job's retry policy; 1 for the initial dispatch.
+    pub attempt: u32,
+    /// Run ID of the first attempt when this request is a retry.
+    pub parent_run_id: Option<Uuid>,
+    /// The schedule occurrence this request was dispatched for, if any.
+    pub scheduled_for: Option<DateTime<Utc>>,
+    /// Whether this request makes up for an occurrence missed during downtime.
+    pub catch_up: bool,
+    /// What sent the request; recorded on the run.
+    pub trigger: TriggerSource,
+    /// Set when the occurrence must not run: it is recorded as `Skipped`
+    /// with this reason instead.
+    pub skip_reason: Option<String>,
+}
+
+/// A dispatch waiting in the run queue, as listed by `GET /api/queue`.
+///
+/// Its run is recorded with status `Queued` until it starts or is cancelled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueuedRun {
+    pub run_id: Uuid,
+    pub job_id: Uuid,
+    pub job_name: String,
+    pub priority: i32,
+    pub pool: Option<String>,
+    pub attempt: u32,
+    pub queued_at: DateTime<Utc>,
+    /// What the run is waiting for, as of the last time the dispatcher
+    /// looked at the queue.
+    pub waiting_for: String,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::Schedule;
 
     #[test]
     fn test_trigger_params_deserialize_all_fields() {
@@ -72,19 +104,38 @@ mod tests {
         let job = Job {
             id: Uuid::now_v7(),
             name: "test".to_string(),
-            schedule: "* * * * *".to_string(),
+            schedule: Schedule::cron("* * * * *"),
             execution: crate::models::ExecutionType::ShellCommand("echo hi".to_string()),
             enabled: true,
-            timezone: None,
+            jitter_secs: 0,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
         let run_id = Uuid::now_v7();
         let req = DispatchRequest {
@@ -95,6 +146,12 @@ mod tests {
                 env: None,
                 input: None,
             }),
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: TriggerSource::Manual,
+            skip_reason: None,
         };
         assert_eq!(req.run_id, run_id);
         assert_eq!(req.job.name, "test");
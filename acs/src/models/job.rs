@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
 use crate::errors::AcsError;
+use crate::models::cron::parse_cron;
+use crate::models::schedule::{self, Schedule};
+use crate::models::selector;
+use crate::models::{JobRun, RunStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", content = "value")]
@@ -13,62 +17,514 @@ pub enum ExecutionType {
     ScriptFile(String),
 }
 
+/// What to do when a job is dispatched while a previous run of it is still
+/// executing.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "type")]
+pub enum ConcurrencyPolicy {
+    /// Start the new run alongside the running one.
+    #[default]
+    Allow,
+    /// Don't start the new run; record it as `Skipped`.
+    #[serde(alias = "Skip")]
+    Forbid,
+    /// Hold the new run until the running one finishes. At most `max_depth`
+    /// runs wait at a time; further dispatches are recorded as `Skipped`.
+    Queue { max_depth: usize },
+    /// Kill the running run and start the new one once it has exited.
+    Replace,
+}
+
+impl std::fmt::Display for ConcurrencyPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConcurrencyPolicy::Allow => f.write_str("allow"),
+            ConcurrencyPolicy::Forbid => f.write_str("forbid"),
+            ConcurrencyPolicy::Queue { max_depth } => write!(f, "queue:{}", max_depth),
+            ConcurrencyPolicy::Replace => f.write_str("replace"),
+        }
+    }
+}
+
+impl std::str::FromStr for ConcurrencyPolicy {
+    type Err = String;
+
+    /// Parse the CLI form: `allow`, `forbid` (or `skip`), `replace`, or
+    /// `queue:<max_depth>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        match lower.as_str() {
+            "allow" => Ok(ConcurrencyPolicy::Allow),
+            "forbid" | "skip" => Ok(ConcurrencyPolicy::Forbid),
+            "replace" => Ok(ConcurrencyPolicy::Replace),
+            _ => match lower.strip_prefix("queue:") {
+                Some(depth) => depth
+                    .parse()
+                    .map(|max_depth| ConcurrencyPolicy::Queue { max_depth })
+                    .map_err(|_| format!("Invalid queue depth '{}'", depth)),
+                None => Err(format!(
+                    "Invalid concurrency policy '{}': expected allow, forbid, replace, or queue:<max_depth>",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Which scheduled occurrences to make up for when the daemon starts after
+/// downtime (a restart, sleep or crash).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "type")]
+pub enum CatchupPolicy {
+    /// Missed occurrences are dropped.
+    #[default]
+    None,
+    /// A single run stands in for the most recent missed occurrence.
+    Once,
+    /// One run per missed occurrence, limited to the `max_runs` most recent.
+    All { max_runs: usize },
+}
+
+impl std::fmt::Display for CatchupPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatchupPolicy::None => f.write_str("none"),
+            CatchupPolicy::Once => f.write_str("once"),
+            CatchupPolicy::All { max_runs } => write!(f, "all:{}", max_runs),
+        }
+    }
+}
+
+impl std::str::FromStr for CatchupPolicy {
+    type Err = String;
+
+    /// Parse the CLI form: `none`, `once`, or `all:<max_runs>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        match lower.as_str() {
+            "none" => Ok(CatchupPolicy::None),
+            "once" => Ok(CatchupPolicy::Once),
+            _ => match lower.strip_prefix("all:") {
+                Some(max) => max
+                    .parse()
+                    .map(|max_runs| CatchupPolicy::All { max_runs })
+                    .map_err(|_| format!("Invalid catch-up limit '{}'", max)),
+                None => Err(format!(
+                    "Invalid catch-up policy '{}': expected none, once, or all:<max_runs>",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Largest `jitter_secs` a job may have (one day).
+pub const MAX_JITTER_SECS: u64 = 86_400;
+
+/// Longest delay a retry policy may ask for between attempts (one week).
+pub const MAX_RETRY_DELAY_SECS: u64 = 7 * 86_400;
+
+/// Automatic retries of failed runs. Each retry is a new run whose
+/// `parent_run_id` points at the first attempt.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first run. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_delay_secs: u64,
+    /// Factor applied to the delay after each further attempt.
+    pub backoff_multiplier: f64,
+    /// Upper bound on the delay between attempts.
+    pub max_delay_secs: u64,
+    /// Also retry runs that exited with a non-zero code. By default only
+    /// infrastructure failures (spawn errors, timeouts) are retried.
+    pub retry_on_nonzero_exit: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_delay_secs: 10,
+            backoff_multiplier: 2.0,
+            max_delay_secs: 300,
+            retry_on_nonzero_exit: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a finished run should be followed by another attempt.
+    pub fn should_retry(&self, run: &JobRun) -> bool {
+        self.retries_after(run.attempt, &run.status, run.exit_code)
+    }
+
+    /// Whether attempt number `attempt`, finished with `status` and
+    /// `exit_code`, is followed by another attempt.
+    pub fn retries_after(&self, attempt: u32, status: &RunStatus, exit_code: Option<i32>) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        match status {
+            RunStatus::Failed => true,
+            RunStatus::Completed => self.retry_on_nonzero_exit && exit_code != Some(0),
+            _ => false,
+        }
+    }
+
+    /// How long to wait after attempt number `attempt` before starting the next one.
+    pub fn delay_after(&self, attempt: u32) -> std::time::Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.initial_delay_secs as f64 * self.backoff_multiplier.powi(exponent);
+        let max_delay = std::time::Duration::from_secs(self.max_delay_secs);
+        std::time::Duration::try_from_secs_f64(secs).map_or(max_delay, |delay| delay.min(max_delay))
+    }
+}
+
+/// Per-job overrides of the daemon's log retention settings. Unset fields
+/// fall back to the daemon's configuration.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogRetention {
+    /// Keep at most this many runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runs: Option<usize>,
+    /// Prune runs that started more than this many days ago. `0` keeps them
+    /// regardless of age.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_days: Option<u32>,
+    /// Prune the oldest runs once the job's logs take more than this many
+    /// bytes on disk. `0` means no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+    /// Truncate a run's output once it reaches this many bytes. `0` means
+    /// no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Job {
     pub id: Uuid,
     pub name: String,
-    pub schedule: String,
+    #[serde(deserialize_with = "schedule::deserialize")]
+    pub schedule: Schedule,
+    /// Upper bound of a stable per-job delay added to every occurrence.
+    #[serde(default)]
+    pub jitter_secs: u64,
     pub execution: ExecutionType,
     pub enabled: bool,
-    pub timezone: Option<String>,
     pub working_dir: Option<String>,
     pub env_vars: Option<HashMap<String, String>>,
     #[serde(default)]
     pub timeout_secs: u64,
     #[serde(default)]
     pub log_environment: bool,
+    #[serde(default)]
+    pub concurrency_policy: ConcurrencyPolicy,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    #[serde(default)]
+    pub catchup: CatchupPolicy,
+    /// Calendars the job may only run within. Empty means no restriction.
+    #[serde(default)]
+    pub include_calendars: Vec<String>,
+    /// Calendars the job must not run within.
+    #[serde(default)]
+    pub exclude_calendars: Vec<String>,
+    /// No occurrence before this time is run.
+    #[serde(default)]
+    pub start_at: Option<DateTime<Utc>>,
+    /// No occurrence at or after this time is run; the job expires then.
+    #[serde(default)]
+    pub end_at: Option<DateTime<Utc>>,
+    /// The job expires after this many scheduled runs.
+    #[serde(default)]
+    pub max_runs: Option<u32>,
+    /// Scheduled runs dispatched so far; counts towards `max_runs`.
+    #[serde(default)]
+    pub scheduled_runs: u32,
+    /// Jobs (names or ids) triggered when a run exits with code 0.
+    #[serde(default)]
+    pub on_success: Vec<String>,
+    /// Jobs (names or ids) triggered when a run fails or exits non-zero,
+    /// once it has no retries left.
+    #[serde(default)]
+    pub on_failure: Vec<String>,
+    /// Pipe this many lines from the end of a run's output to the stdin of
+    /// the jobs it triggers.
+    #[serde(default)]
+    pub forward_output_lines: Option<usize>,
+    /// Concurrency pool (from the daemon's `concurrency_pools`) whose limit
+    /// this job's runs count towards.
+    #[serde(default)]
+    pub pool: Option<String>,
+    /// Queued runs with a higher priority start first.
+    #[serde(default)]
+    pub priority: i32,
+    /// Free-form tags, matched by `tag=<tag>` in selectors.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Key/value labels, matched by `<key>=<value>` in selectors.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// Overrides of the daemon's log retention settings.
+    #[serde(default)]
+    pub log_retention: LogRetention,
+    /// The file in the daemon's `jobs_dir` that defines this job, if it
+    /// was not created through the API.
+    #[serde(default)]
+    pub source_file: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_run_at: Option<DateTime<Utc>>,
     pub last_exit_code: Option<i32>,
+    /// The scheduled occurrence the scheduler last dispatched. Missed-run
+    /// catch-up starts after it.
+    #[serde(default)]
+    pub last_scheduled_at: Option<DateTime<Utc>>,
     #[serde(skip_deserializing, default)]
     pub next_run_at: Option<DateTime<Utc>>,
 }
 
+impl Job {
+    /// Replace the job's definition with `new`, keeping its id, creation
+    /// time and run metadata.
+    pub fn redefine(&mut self, new: NewJob) {
+        self.name = new.name;
+        self.schedule = new.schedule.with_timezone(new.timezone);
+        self.jitter_secs = new.jitter_secs;
+        self.execution = new.execution;
+        self.enabled = new.enabled;
+        self.working_dir = new.working_dir;
+        self.env_vars = new.env_vars;
+        self.timeout_secs = new.timeout_secs;
+        self.log_environment = new.log_environment;
+        self.concurrency_policy = new.concurrency_policy;
+        self.retry = new.retry;
+        self.catchup = new.catchup;
+        self.include_calendars = new.include_calendars;
+        self.exclude_calendars = new.exclude_calendars;
+        self.start_at = new.start_at;
+        self.end_at = new.end_at;
+        self.max_runs = new.max_runs;
+        self.on_success = new.on_success;
+        self.on_failure = new.on_failure;
+        self.forward_output_lines = new.forward_output_lines;
+        self.pool = new.pool;
+        self.priority = new.priority;
+        self.tags = new.tags;
+        self.labels = new.labels;
+        self.log_retention = new.log_retention;
+        self.updated_at = Utc::now();
+    }
+
+    /// The delay added to each occurrence of this job's schedule: a value in
+    /// `0..=jitter_secs` derived from the job id, so it is the same for every
+    /// occurrence and across restarts. `jitter_secs` is capped at
+    /// [`MAX_JITTER_SECS`], also for jobs stored before it was validated.
+    pub fn jitter_offset_secs(&self) -> u64 {
+        match self.jitter_secs.min(MAX_JITTER_SECS) {
+            0 => 0,
+            max => schedule::spread_seed(self.id) % (max + 1),
+        }
+    }
+
+    /// Why the job is over at `now`, if it is: its `end_at` has passed, it
+    /// has used up its `max_runs`, or its one-off time has passed.
+    pub fn expiry_reason(&self, now: DateTime<Utc>) -> Option<String> {
+        if let Some(max_runs) = self.max_runs {
+            if self.scheduled_runs >= max_runs {
+                return Some(format!("reached max_runs ({})", max_runs));
+            }
+        }
+        if let Some(end_at) = self.end_at {
+            if end_at <= now {
+                return Some(format!("end_at {} has passed", end_at.to_rfc3339()));
+            }
+        }
+        if let Schedule::At { timestamp } = self.schedule {
+            if timestamp <= now {
+                return Some("one-off schedule has passed".to_string());
+            }
+        }
+        None
+    }
+}
+
 impl PartialEq for Job {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
             && self.name == other.name
             && self.schedule == other.schedule
+            && self.jitter_secs == other.jitter_secs
             && self.execution == other.execution
             && self.enabled == other.enabled
-            && self.timezone == other.timezone
             && self.working_dir == other.working_dir
             && self.env_vars == other.env_vars
             && self.timeout_secs == other.timeout_secs
             && self.log_environment == other.log_environment
+            && self.concurrency_policy == other.concurrency_policy
+            && self.retry == other.retry
+            && self.catchup == other.catchup
+            && self.include_calendars == other.include_calendars
+            && self.exclude_calendars == other.exclude_calendars
+            && self.start_at == other.start_at
+            && self.end_at == other.end_at
+            && self.max_runs == other.max_runs
+            && self.scheduled_runs == other.scheduled_runs
+            && self.on_success == other.on_success
+            && self.on_failure == other.on_failure
+            && self.forward_output_lines == other.forward_output_lines
+            && self.pool == other.pool
+            && self.priority == other.priority
+            && self.tags == other.tags
+            && self.labels == other.labels
+            && self.log_retention == other.log_retention
+            && self.source_file == other.source_file
             && self.created_at == other.created_at
             && self.updated_at == other.updated_at
             && self.last_run_at == other.last_run_at
             && self.last_exit_code == other.last_exit_code
+            && self.last_scheduled_at == other.last_scheduled_at
         // next_run_at is skipped (computed, not persisted)
     }
 }
 
+/// The definition of a job, as given when creating it or in a manifest.
+/// Fields left at their defaults are omitted when serialized, and maps are
+/// written in key order, so exported definitions are short and stable.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewJob {
     pub name: String,
-    pub schedule: String,
+    /// A typed schedule, or a plain cron expression.
+    #[serde(deserialize_with = "schedule::deserialize")]
+    pub schedule: Schedule,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub jitter_secs: u64,
     pub execution: ExecutionType,
-    #[serde(default = "default_enabled")]
+    #[serde(default = "default_enabled", skip_serializing_if = "is_true")]
     pub enabled: bool,
+    /// Shorthand for the `tz` of a cron schedule.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub working_dir: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_sorted_opt"
+    )]
     pub env_vars: Option<HashMap<String, String>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub timeout_secs: u64,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub log_environment: bool,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub concurrency_policy: ConcurrencyPolicy,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub retry: RetryPolicy,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub catchup: CatchupPolicy,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include_calendars: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_calendars: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_runs: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_success: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_output_lines: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub priority: i32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    pub labels: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub log_retention: LogRetention,
+}
+
+impl From<&Job> for NewJob {
+    /// The definition of an existing job, without its id and run metadata.
+    fn from(job: &Job) -> Self {
+        NewJob {
+            name: job.name.clone(),
+            schedule: job.schedule.clone(),
+            jitter_secs: job.jitter_secs,
+            execution: job.execution.clone(),
+            enabled: job.enabled,
+            timezone: None,
+            working_dir: job.working_dir.clone(),
+            env_vars: job.env_vars.clone(),
+            timeout_secs: job.timeout_secs,
+            log_environment: job.log_environment,
+            concurrency_policy: job.concurrency_policy,
+            retry: job.retry,
+            catchup: job.catchup,
+            include_calendars: job.include_calendars.clone(),
+            exclude_calendars: job.exclude_calendars.clone(),
+            start_at: job.start_at,
+            end_at: job.end_at,
+            max_runs: job.max_runs,
+            on_success: job.on_success.clone(),
+            on_failure: job.on_failure.clone(),
+            forward_output_lines: job.forward_output_lines,
+            pool: job.pool.clone(),
+            priority: job.priority,
+            tags: job.tags.clone(),
+            labels: job.labels.clone(),
+            log_retention: job.log_retention,
+        }
+    }
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn serialize_sorted<S: serde::Serializer>(
+    map: &HashMap<String, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter()
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
+fn serialize_sorted_opt<S: serde::Serializer>(
+    map: &Option<HashMap<String, String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.as_ref()
+        .map(|map| map.iter().collect::<std::collections::BTreeMap<_, _>>())
+        .serialize(serializer)
+}
+
+/// Deserialize a field that `null` clears: absent is `None`, `null` is
+/// `Some(None)`.
+fn deserialize_nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 fn default_enabled() -> bool {
@@ -78,14 +534,56 @@ fn default_enabled() -> bool {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct JobUpdate {
     pub name: Option<String>,
-    pub schedule: Option<String>,
+    #[serde(default, deserialize_with = "schedule::deserialize_opt")]
+    pub schedule: Option<Schedule>,
+    pub jitter_secs: Option<u64>,
     pub execution: Option<ExecutionType>,
     pub enabled: Option<bool>,
+    /// Shorthand for the `tz` of a cron schedule: applies to the new
+    /// schedule if one is given, otherwise to the job's current one.
     pub timezone: Option<String>,
     pub working_dir: Option<String>,
     pub env_vars: Option<HashMap<String, String>>,
     pub timeout_secs: Option<u64>,
     pub log_environment: Option<bool>,
+    pub concurrency_policy: Option<ConcurrencyPolicy>,
+    pub retry: Option<RetryPolicy>,
+    pub catchup: Option<CatchupPolicy>,
+    pub include_calendars: Option<Vec<String>>,
+    pub exclude_calendars: Option<Vec<String>>,
+    /// `null` clears it.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub start_at: Option<Option<DateTime<Utc>>>,
+    /// `null` clears it.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub end_at: Option<Option<DateTime<Utc>>>,
+    /// `null` clears it.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_runs: Option<Option<u32>>,
+    pub on_success: Option<Vec<String>>,
+    pub on_failure: Option<Vec<String>>,
+    pub forward_output_lines: Option<usize>,
+    /// An empty string takes the job out of its pool.
+    pub pool: Option<String>,
+    pub priority: Option<i32>,
+    /// Replaces the job's tags.
+    pub tags: Option<Vec<String>>,
+    /// Replaces the job's labels.
+    pub labels: Option<HashMap<String, String>>,
+    /// Replaces the job's log retention overrides.
+    pub log_retention: Option<LogRetention>,
     /// Internal metadata: set to Some(Some(ts)) to update, Some(None) to clear.
     /// Skipped during JSON deserialization from API clients (not user-editable).
     #[serde(skip)]
@@ -93,6 +591,16 @@ pub struct JobUpdate {
     /// Internal metadata: set to Some(Some(code)) to update, Some(None) to clear.
     #[serde(skip)]
     pub last_exit_code: Option<Option<i32>>,
+    /// Internal metadata: set by the scheduler when it dispatches an occurrence.
+    #[serde(skip)]
+    pub last_scheduled_at: Option<Option<DateTime<Utc>>>,
+    /// Internal metadata: set by the scheduler when it dispatches an occurrence.
+    #[serde(skip)]
+    pub scheduled_runs: Option<u32>,
+    /// Internal metadata: set by the jobs directory watcher for the jobs it
+    /// manages.
+    #[serde(skip)]
+    pub source_file: Option<Option<String>>,
 }
 
 /// Validate a NewJob before creation.
@@ -109,13 +617,34 @@ pub fn validate_new_job(job: &NewJob) -> Result<(), AcsError> {
         ));
     }
 
-    // Validate cron expression with croner
-    validate_cron(&job.schedule)?;
+    validate_schedule(&job.schedule, job.timezone.as_deref())?;
+    validate_jitter(job.jitter_secs)?;
 
-    // Validate timezone if provided
-    if let Some(ref tz) = job.timezone {
-        validate_timezone(tz)?;
+    validate_concurrency_policy(&job.concurrency_policy)?;
+    validate_retry_policy(&job.retry)?;
+    validate_catchup_policy(&job.catchup)?;
+
+    validate_active_period(job.start_at, job.end_at)?;
+    if let Some(end_at) = job.end_at {
+        if end_at <= Utc::now() {
+            return Err(AcsError::Validation(format!(
+                "end_at {} is in the past",
+                end_at.to_rfc3339()
+            )));
+        }
+    }
+    if let Some(max_runs) = job.max_runs {
+        validate_max_runs(max_runs)?;
+    }
+    validate_follow_ups(&job.on_success, &job.on_failure, job.forward_output_lines)?;
+    if job.pool.as_deref().is_some_and(|p| p.trim().is_empty()) {
+        return Err(AcsError::Validation(
+            "Concurrency pool name cannot be empty".to_string(),
+        ));
     }
+    validate_tags(&job.tags)?;
+    validate_labels(&job.labels)?;
+    validate_log_retention(&job.log_retention)?;
 
     Ok(())
 }
@@ -134,21 +663,244 @@ pub fn validate_job_update(update: &JobUpdate) -> Result<(), AcsError> {
     }
 
     if let Some(ref schedule) = update.schedule {
-        validate_cron(schedule)?;
+        validate_schedule(schedule, update.timezone.as_deref())?;
+    } else if let Some(ref tz) = update.timezone {
+        validate_timezone(tz)?;
+    }
+    if let Some(jitter_secs) = update.jitter_secs {
+        validate_jitter(jitter_secs)?;
     }
 
-    if let Some(ref tz) = update.timezone {
-        validate_timezone(tz)?;
+    if let Some(ref policy) = update.concurrency_policy {
+        validate_concurrency_policy(policy)?;
+    }
+
+    if let Some(ref retry) = update.retry {
+        validate_retry_policy(retry)?;
+    }
+
+    if let Some(ref catchup) = update.catchup {
+        validate_catchup_policy(catchup)?;
+    }
+
+    validate_active_period(update.start_at.flatten(), update.end_at.flatten())?;
+    if let Some(Some(max_runs)) = update.max_runs {
+        validate_max_runs(max_runs)?;
+    }
+    validate_follow_ups(
+        update.on_success.as_deref().unwrap_or_default(),
+        update.on_failure.as_deref().unwrap_or_default(),
+        update.forward_output_lines,
+    )?;
+    if let Some(ref tags) = update.tags {
+        validate_tags(tags)?;
+    }
+    if let Some(ref labels) = update.labels {
+        validate_labels(labels)?;
     }
+    if let Some(ref retention) = update.log_retention {
+        validate_log_retention(retention)?;
+    }
+
+    Ok(())
+}
 
+/// Validate a job's tags: each must be a selector key and appear once.
+fn validate_tags(tags: &[String]) -> Result<(), AcsError> {
+    for (i, tag) in tags.iter().enumerate() {
+        if !selector::is_valid_key(tag) {
+            return Err(AcsError::Validation(format!(
+                "Invalid tag '{}': use letters, digits, '.', '_', '-' or '/'",
+                tag
+            )));
+        }
+        if tags[..i].contains(tag) {
+            return Err(AcsError::Validation(format!("Duplicate tag '{}'", tag)));
+        }
+    }
+    Ok(())
+}
+
+/// Validate a job's labels so that every one of them can be selected.
+fn validate_labels(labels: &HashMap<String, String>) -> Result<(), AcsError> {
+    for (key, value) in labels {
+        if key == selector::TAG_KEY {
+            return Err(AcsError::Validation(format!(
+                "Label key '{}' is reserved for selecting tags",
+                key
+            )));
+        }
+        if !selector::is_valid_key(key) {
+            return Err(AcsError::Validation(format!(
+                "Invalid label key '{}': use letters, digits, '.', '_', '-' or '/'",
+                key
+            )));
+        }
+        if !selector::is_valid_value(value) {
+            return Err(AcsError::Validation(format!(
+                "Invalid value '{}' for label '{}': use letters, digits, '.', '_', '-' or '/'",
+                value, key
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Validate the follow-up settings of a job. Whether the jobs exist is
+/// checked by the API, which can see the other jobs.
+fn validate_follow_ups(
+    on_success: &[String],
+    on_failure: &[String],
+    forward_output_lines: Option<usize>,
+) -> Result<(), AcsError> {
+    if on_success
+        .iter()
+        .chain(on_failure)
+        .any(|name| name.trim().is_empty())
+    {
+        return Err(AcsError::Validation(
+            "Follow-up job names cannot be empty".to_string(),
+        ));
+    }
+    if forward_output_lines == Some(0) {
+        return Err(AcsError::Validation(
+            "forward_output_lines must be at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate that a job's `start_at` comes before its `end_at`.
+pub fn validate_active_period(
+    start_at: Option<DateTime<Utc>>,
+    end_at: Option<DateTime<Utc>>,
+) -> Result<(), AcsError> {
+    if let (Some(start_at), Some(end_at)) = (start_at, end_at) {
+        if start_at >= end_at {
+            return Err(AcsError::Validation(format!(
+                "start_at {} must be before end_at {}",
+                start_at.to_rfc3339(),
+                end_at.to_rfc3339()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_max_runs(max_runs: u32) -> Result<(), AcsError> {
+    if max_runs == 0 {
+        return Err(AcsError::Validation(
+            "max_runs must be at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a schedule, together with the shorthand `timezone` that is
+/// about to be applied to it.
+pub fn validate_schedule(schedule: &Schedule, timezone: Option<&str>) -> Result<(), AcsError> {
+    match schedule {
+        Schedule::Cron { expr, tz } => {
+            validate_cron(expr)?;
+            if let Some(tz) = timezone.or(tz.as_deref()) {
+                validate_timezone(tz)?;
+            }
+            return Ok(());
+        }
+        Schedule::Every { .. } | Schedule::Manual => {}
+        Schedule::At { timestamp } => {
+            if *timestamp <= Utc::now() {
+                return Err(AcsError::Validation(format!(
+                    "Schedule time {} is in the past",
+                    timestamp.to_rfc3339()
+                )));
+            }
+        }
+    }
+    if timezone.is_some() {
+        return Err(AcsError::Validation(
+            "timezone only applies to cron schedules".to_string(),
+        ));
+    }
     Ok(())
 }
 
 fn validate_cron(expr: &str) -> Result<(), AcsError> {
-    use croner::Cron;
-    use std::str::FromStr;
-    Cron::from_str(expr)
-        .map_err(|e| AcsError::Cron(format!("Invalid cron expression '{}': {}", expr, e)))?;
+    // The values `H` fields resolve to depend on the job id, which a new job
+    // does not have yet; any seed gives a valid expression if one does.
+    let cron = parse_cron(expr, 0)?;
+    // Expressions such as `0 0 30 2 *` or a year range in the past parse
+    // fine but would never fire.
+    if cron.find_next_occurrence(&Utc::now(), false).is_err() {
+        return Err(AcsError::Cron(format!(
+            "Cron expression '{}' has no upcoming occurrences",
+            expr
+        )));
+    }
+    Ok(())
+}
+
+fn validate_concurrency_policy(policy: &ConcurrencyPolicy) -> Result<(), AcsError> {
+    if let ConcurrencyPolicy::Queue { max_depth: 0 } = policy {
+        return Err(AcsError::Validation(
+            "Queue concurrency policy needs a max_depth of at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_catchup_policy(policy: &CatchupPolicy) -> Result<(), AcsError> {
+    if let CatchupPolicy::All { max_runs: 0 } = policy {
+        return Err(AcsError::Validation(
+            "Catch-up policy All needs a max_runs of at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_jitter(jitter_secs: u64) -> Result<(), AcsError> {
+    if jitter_secs > MAX_JITTER_SECS {
+        return Err(AcsError::Validation(format!(
+            "jitter_secs ({}) cannot exceed {}",
+            jitter_secs, MAX_JITTER_SECS
+        )));
+    }
+    Ok(())
+}
+
+fn validate_retry_policy(retry: &RetryPolicy) -> Result<(), AcsError> {
+    if retry.max_attempts == 0 {
+        return Err(AcsError::Validation(
+            "Retry max_attempts must be at least 1".to_string(),
+        ));
+    }
+    if !retry.backoff_multiplier.is_finite() || retry.backoff_multiplier < 1.0 {
+        return Err(AcsError::Validation(format!(
+            "Retry backoff_multiplier must be at least 1.0, got {}",
+            retry.backoff_multiplier
+        )));
+    }
+    if retry.max_delay_secs > MAX_RETRY_DELAY_SECS {
+        return Err(AcsError::Validation(format!(
+            "Retry max_delay_secs ({}) cannot exceed {}",
+            retry.max_delay_secs, MAX_RETRY_DELAY_SECS
+        )));
+    }
+    if retry.max_delay_secs < retry.initial_delay_secs {
+        return Err(AcsError::Validation(format!(
+            "Retry max_delay_secs ({}) cannot be less than initial_delay_secs ({})",
+            retry.max_delay_secs, retry.initial_delay_secs
+        )));
+    }
+    Ok(())
+}
+
+fn validate_log_retention(retention: &LogRetention) -> Result<(), AcsError> {
+    if retention.max_runs == Some(0) {
+        return Err(AcsError::Validation(
+            "Log retention max_runs must be at least 1".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -165,14 +917,31 @@ mod tests {
     fn make_new_job() -> NewJob {
         NewJob {
             name: "test-job".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ShellCommand("echo hello".to_string()),
             enabled: true,
+            jitter_secs: 0,
             timezone: None,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
         }
     }
 
@@ -181,10 +950,13 @@ mod tests {
         Job {
             id: Uuid::now_v7(),
             name: "test-job".to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::Cron {
+                expr: "*/5 * * * *".to_string(),
+                tz: Some("America/New_York".to_string()),
+            },
             execution: ExecutionType::ShellCommand("echo hello".to_string()),
             enabled: true,
-            timezone: Some("America/New_York".to_string()),
+            jitter_secs: 0,
             working_dir: Some("/tmp".to_string()),
             env_vars: Some({
                 let mut m = HashMap::new();
@@ -193,11 +965,30 @@ mod tests {
             }),
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: now,
             updated_at: now,
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         }
     }
 
@@ -253,7 +1044,7 @@ mod tests {
     #[test]
     fn test_validation_invalid_cron_rejected() {
         let mut job = make_new_job();
-        job.schedule = "not a cron".to_string();
+        job.schedule = Schedule::cron("not a cron");
         let result = validate_new_job(&job);
         assert!(result.is_err());
         match result.unwrap_err() {
@@ -262,6 +1053,138 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validation_typed_schedules() {
+        let mut job = make_new_job();
+        job.schedule = Schedule::Manual;
+        assert!(validate_new_job(&job).is_ok());
+
+        job.schedule = Schedule::At {
+            timestamp: Utc::now() + chrono::Duration::hours(1),
+        };
+        assert!(validate_new_job(&job).is_ok());
+        job.schedule = Schedule::At {
+            timestamp: Utc::now() - chrono::Duration::hours(1),
+        };
+        match validate_new_job(&job).unwrap_err() {
+            AcsError::Validation(msg) => assert!(msg.contains("in the past")),
+            other => panic!("Expected Validation, got: {:?}", other),
+        }
+
+        job.schedule = Schedule::Every {
+            interval: "15m".parse().unwrap(),
+            anchor: None,
+        };
+        job.timezone = Some("Europe/London".to_string());
+        match validate_new_job(&job).unwrap_err() {
+            AcsError::Validation(msg) => assert!(msg.contains("only applies to cron")),
+            other => panic!("Expected Validation, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_jitter_offset_is_stable_and_bounded() {
+        let mut job = make_job();
+        assert_eq!(job.jitter_offset_secs(), 0);
+        job.jitter_secs = 90;
+        let offset = job.jitter_offset_secs();
+        assert!(offset <= 90);
+        assert_eq!(job.clone().jitter_offset_secs(), offset);
+
+        // Out-of-range values are rejected, and capped if already stored.
+        job.jitter_secs = u64::MAX;
+        assert!(job.jitter_offset_secs() <= MAX_JITTER_SECS);
+        let mut new_job = make_new_job();
+        new_job.jitter_secs = MAX_JITTER_SECS + 1;
+        assert!(validate_new_job(&new_job).is_err());
+        let update = JobUpdate {
+            jitter_secs: Some(u64::MAX),
+            ..Default::default()
+        };
+        assert!(validate_job_update(&update).is_err());
+    }
+
+    #[test]
+    fn test_expiry_reason() {
+        let now = Utc::now();
+        let mut job = make_job();
+        assert_eq!(job.expiry_reason(now), None);
+
+        job.max_runs = Some(3);
+        job.scheduled_runs = 2;
+        assert_eq!(job.expiry_reason(now), None);
+        job.scheduled_runs = 3;
+        assert_eq!(
+            job.expiry_reason(now).as_deref(),
+            Some("reached max_runs (3)")
+        );
+
+        job.max_runs = None;
+        job.end_at = Some(now + chrono::Duration::hours(1));
+        assert_eq!(job.expiry_reason(now), None);
+        assert!(job
+            .expiry_reason(now + chrono::Duration::hours(1))
+            .unwrap()
+            .contains("has passed"));
+
+        job.end_at = None;
+        job.schedule = Schedule::At {
+            timestamp: now - chrono::Duration::seconds(1),
+        };
+        assert_eq!(
+            job.expiry_reason(now).as_deref(),
+            Some("one-off schedule has passed")
+        );
+    }
+
+    #[test]
+    fn test_validation_active_period_and_max_runs() {
+        let now = Utc::now();
+        let mut job = make_new_job();
+        job.start_at = Some(now + chrono::Duration::days(2));
+        job.end_at = Some(now + chrono::Duration::days(1));
+        let err = validate_new_job(&job).unwrap_err();
+        assert!(err.to_string().contains("must be before end_at"));
+
+        job.start_at = None;
+        job.end_at = Some(now - chrono::Duration::days(1));
+        let err = validate_new_job(&job).unwrap_err();
+        assert!(err.to_string().contains("is in the past"));
+
+        job.end_at = None;
+        job.max_runs = Some(0);
+        assert!(validate_new_job(&job).is_err());
+        job.max_runs = Some(1);
+        assert!(validate_new_job(&job).is_ok());
+
+        let update = JobUpdate {
+            max_runs: Some(Some(0)),
+            ..Default::default()
+        };
+        assert!(validate_job_update(&update).is_err());
+    }
+
+    #[test]
+    fn test_validation_hash_cron_accepted() {
+        let mut job = make_new_job();
+        job.schedule = Schedule::cron("H H(0-5) * * *");
+        assert!(validate_new_job(&job).is_ok());
+        job.schedule = Schedule::cron("H(0-99) * * * *");
+        assert!(matches!(validate_new_job(&job), Err(AcsError::Cron(_))));
+    }
+
+    #[test]
+    fn test_validation_schedule_that_never_fires_rejected() {
+        for schedule in ["0 0 30 2 *", "0 0 0 1 1 * 2001-2002"] {
+            let mut job = make_new_job();
+            job.schedule = Schedule::cron(schedule);
+            match validate_new_job(&job).unwrap_err() {
+                AcsError::Cron(msg) => assert!(msg.contains("no upcoming occurrences")),
+                other => panic!("Expected Cron, got: {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn test_validation_invalid_timezone_rejected() {
         let mut job = make_new_job();
@@ -287,6 +1210,266 @@ mod tests {
         assert!(validate_new_job(&job).is_ok());
     }
 
+    #[test]
+    fn test_concurrency_policy_serde() {
+        let queue = ConcurrencyPolicy::Queue { max_depth: 3 };
+        let json = serde_json::to_string(&queue).expect("serialize");
+        assert_eq!(json, r#"{"type":"Queue","max_depth":3}"#);
+        assert_eq!(
+            serde_json::from_str::<ConcurrencyPolicy>(&json).expect("deserialize"),
+            queue
+        );
+        assert_eq!(
+            serde_json::from_str::<ConcurrencyPolicy>(r#"{"type":"Skip"}"#).expect("alias"),
+            ConcurrencyPolicy::Forbid
+        );
+    }
+
+    #[test]
+    fn test_concurrency_policy_defaults_to_allow() {
+        let json = r#"{"name":"j","schedule":"* * * * *","execution":{"type":"ShellCommand","value":"true"}}"#;
+        let new: NewJob = serde_json::from_str(json).expect("deserialize");
+        assert_eq!(new.concurrency_policy, ConcurrencyPolicy::Allow);
+    }
+
+    #[test]
+    fn test_concurrency_policy_from_str() {
+        assert_eq!("skip".parse(), Ok(ConcurrencyPolicy::Forbid));
+        assert_eq!("Replace".parse(), Ok(ConcurrencyPolicy::Replace));
+        assert_eq!(
+            "queue:5".parse(),
+            Ok(ConcurrencyPolicy::Queue { max_depth: 5 })
+        );
+        assert!("queue:many".parse::<ConcurrencyPolicy>().is_err());
+        assert!("sometimes".parse::<ConcurrencyPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_catchup_policy_serde_and_from_str() {
+        let json = serde_json::to_string(&CatchupPolicy::All { max_runs: 3 }).expect("serialize");
+        assert_eq!(json, r#"{"type":"All","max_runs":3}"#);
+        let parsed: CatchupPolicy = serde_json::from_str(r#"{"type":"Once"}"#).expect("parse");
+        assert_eq!(parsed, CatchupPolicy::Once);
+
+        assert_eq!("none".parse(), Ok(CatchupPolicy::None));
+        assert_eq!("ONCE".parse(), Ok(CatchupPolicy::Once));
+        assert_eq!("all:4".parse(), Ok(CatchupPolicy::All { max_runs: 4 }));
+        assert!("all".parse::<CatchupPolicy>().is_err());
+        assert!("all:x".parse::<CatchupPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_validation_zero_catchup_limit_rejected() {
+        let mut job = make_new_job();
+        job.catchup = CatchupPolicy::All { max_runs: 0 };
+        assert!(validate_new_job(&job).is_err());
+
+        let update = JobUpdate {
+            catchup: Some(CatchupPolicy::All { max_runs: 0 }),
+            ..Default::default()
+        };
+        assert!(validate_job_update(&update).is_err());
+    }
+
+    #[test]
+    fn test_validation_zero_queue_depth_rejected() {
+        let mut job = make_new_job();
+        job.concurrency_policy = ConcurrencyPolicy::Queue { max_depth: 0 };
+        assert!(validate_new_job(&job).is_err());
+
+        let update = JobUpdate {
+            concurrency_policy: Some(ConcurrencyPolicy::Queue { max_depth: 0 }),
+            ..Default::default()
+        };
+        assert!(validate_job_update(&update).is_err());
+    }
+
+    #[test]
+    fn test_retry_policy_partial_json_uses_defaults() {
+        let retry: RetryPolicy = serde_json::from_str(r#"{"max_attempts": 4}"#).expect("parse");
+        assert_eq!(retry.max_attempts, 4);
+        assert_eq!(retry.initial_delay_secs, 10);
+        assert_eq!(retry.backoff_multiplier, 2.0);
+        assert_eq!(retry.max_delay_secs, 300);
+        assert!(!retry.retry_on_nonzero_exit);
+
+        let job: NewJob = serde_json::from_str(
+            r#"{"name":"x","schedule":"* * * * *","execution":{"type":"ShellCommand","value":"true"}}"#,
+        )
+        .expect("parse");
+        assert_eq!(job.retry.max_attempts, 1);
+    }
+
+    #[test]
+    fn test_retry_policy_backoff_is_capped() {
+        let retry = RetryPolicy {
+            max_attempts: 10,
+            initial_delay_secs: 5,
+            backoff_multiplier: 3.0,
+            max_delay_secs: 60,
+            retry_on_nonzero_exit: false,
+        };
+        assert_eq!(retry.delay_after(1).as_secs(), 5);
+        assert_eq!(retry.delay_after(2).as_secs(), 15);
+        assert_eq!(retry.delay_after(3).as_secs(), 45);
+        assert_eq!(retry.delay_after(4).as_secs(), 60);
+        assert_eq!(retry.delay_after(u32::MAX).as_secs(), 60);
+
+        // Stored policies from before the bound was validated still cap.
+        let huge = RetryPolicy {
+            initial_delay_secs: u64::MAX,
+            max_delay_secs: u64::MAX,
+            ..retry
+        };
+        assert_eq!(huge.delay_after(3).as_secs(), u64::MAX);
+    }
+
+    #[test]
+    fn test_retry_policy_should_retry() {
+        let retry = RetryPolicy {
+            max_attempts: 2,
+            ..Default::default()
+        };
+        let run = JobRun {
+            run_id: Uuid::now_v7(),
+            job_id: Uuid::now_v7(),
+            started_at: Utc::now(),
+            finished_at: Some(Utc::now()),
+            status: RunStatus::Failed,
+            exit_code: None,
+            log_size_bytes: 0,
+            error: Some("execution timed out".to_string()),
+            trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
+        };
+        assert!(retry.should_retry(&run));
+        assert!(!retry.should_retry(&JobRun {
+            attempt: 2,
+            ..run.clone()
+        }));
+        assert!(!retry.should_retry(&JobRun {
+            status: RunStatus::Killed,
+            ..run.clone()
+        }));
+
+        let nonzero = JobRun {
+            status: RunStatus::Completed,
+            exit_code: Some(1),
+            error: None,
+            ..run
+        };
+        assert!(!retry.should_retry(&nonzero));
+        let retry = RetryPolicy {
+            retry_on_nonzero_exit: true,
+            ..retry
+        };
+        assert!(retry.should_retry(&nonzero));
+        assert!(!retry.should_retry(&JobRun {
+            exit_code: Some(0),
+            ..nonzero
+        }));
+    }
+
+    #[test]
+    fn test_validation_invalid_retry_policy_rejected() {
+        let bad = [
+            RetryPolicy {
+                max_attempts: 0,
+                ..Default::default()
+            },
+            RetryPolicy {
+                backoff_multiplier: 0.5,
+                ..Default::default()
+            },
+            RetryPolicy {
+                initial_delay_secs: 60,
+                max_delay_secs: 30,
+                ..Default::default()
+            },
+            RetryPolicy {
+                initial_delay_secs: u64::MAX,
+                max_delay_secs: u64::MAX,
+                ..Default::default()
+            },
+        ];
+        for retry in bad {
+            let mut job = make_new_job();
+            job.retry = retry;
+            assert!(validate_new_job(&job).is_err(), "{:?}", retry);
+            let update = JobUpdate {
+                retry: Some(retry),
+                ..Default::default()
+            };
+            assert!(validate_job_update(&update).is_err(), "{:?}", retry);
+        }
+    }
+
+    #[test]
+    fn test_log_retention_serde_and_validation() {
+        let job = make_new_job();
+        let json = serde_json::to_value(&job).expect("serialize");
+        assert!(json.get("log_retention").is_none());
+
+        let mut job: NewJob = serde_json::from_value(serde_json::json!({
+            "name": "nightly",
+            "schedule": "0 2 * * *",
+            "execution": {"type": "ShellCommand", "value": "true"},
+            "log_retention": {"keep_days": 7, "max_bytes": 1048576}
+        }))
+        .expect("parse");
+        assert_eq!(
+            job.log_retention,
+            LogRetention {
+                max_runs: None,
+                keep_days: Some(7),
+                max_bytes: Some(1_048_576),
+                max_file_size: None,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(job.log_retention).expect("serialize"),
+            serde_json::json!({"keep_days": 7, "max_bytes": 1048576})
+        );
+        assert!(validate_new_job(&job).is_ok());
+
+        job.log_retention.max_runs = Some(0);
+        assert!(validate_new_job(&job).is_err());
+        let update = JobUpdate {
+            log_retention: Some(job.log_retention),
+            ..Default::default()
+        };
+        assert!(validate_job_update(&update).is_err());
+    }
+
+    #[test]
+    fn test_validation_tags_and_labels() {
+        let mut job = make_new_job();
+        job.tags = vec!["nightly".to_string(), "team/data".to_string()];
+        job.labels = HashMap::from([
+            ("team".to_string(), "data".to_string()),
+            ("tier".to_string(), String::new()),
+        ]);
+        assert!(validate_new_job(&job).is_ok());
+
+        for tags in [vec!["two words"], vec!["a,b"], vec![""], vec!["x", "x"]] {
+            let mut job = make_new_job();
+            job.tags = tags.iter().map(|t| t.to_string()).collect();
+            assert!(validate_new_job(&job).is_err(), "{:?}", tags);
+        }
+        for (key, value) in [("tag", "x"), ("", "x"), ("team", "a=b"), ("a!", "x")] {
+            let update = JobUpdate {
+                labels: Some(HashMap::from([(key.to_string(), value.to_string())])),
+                ..Default::default()
+            };
+            assert!(validate_job_update(&update).is_err(), "{}={}", key, value);
+        }
+    }
+
     #[test]
     fn test_execution_type_shell_command_serde() {
         let exec = ExecutionType::ShellCommand("echo hello".to_string());
@@ -366,7 +1549,7 @@ mod tests {
     #[test]
     fn test_validate_job_update_invalid_cron() {
         let update = JobUpdate {
-            schedule: Some("bad cron".to_string()),
+            schedule: Some(Schedule::cron("bad cron")),
             ..Default::default()
         };
         assert!(validate_job_update(&update).is_err());
@@ -385,7 +1568,7 @@ mod tests {
     fn test_validate_job_update_valid() {
         let update = JobUpdate {
             name: Some("new-name".to_string()),
-            schedule: Some("0 * * * *".to_string()),
+            schedule: Some(Schedule::cron("0 * * * *")),
             timezone: Some("Europe/London".to_string()),
             ..Default::default()
         };
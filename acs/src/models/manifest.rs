@@ -0,0 +1,411 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::{Job, NewJob};
+
+/// The file formats a manifest can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ManifestFormat {
+    #[default]
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ManifestFormat {
+    /// The format implied by a file's extension, if it is a known one.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl std::fmt::Display for ManifestFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestFormat::Yaml => f.write_str("yaml"),
+            ManifestFormat::Toml => f.write_str("toml"),
+            ManifestFormat::Json => f.write_str("json"),
+        }
+    }
+}
+
+impl std::str::FromStr for ManifestFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yaml" | "yml" => Ok(ManifestFormat::Yaml),
+            "toml" => Ok(ManifestFormat::Toml),
+            "json" => Ok(ManifestFormat::Json),
+            _ => Err(format!(
+                "Invalid manifest format '{}': expected yaml, toml or json",
+                s
+            )),
+        }
+    }
+}
+
+/// A set of job definitions kept in a file, e.g. in YAML:
+///
+/// ```yaml
+/// jobs:
+///   - name: backup
+///     schedule: "0 2 * * *"
+///     execution: { type: ShellCommand, value: ./backup.sh }
+/// ```
+///
+/// Each entry has the fields of `POST /api/jobs`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub jobs: Vec<NewJob>,
+}
+
+impl Manifest {
+    /// The definitions of `jobs`, in name order.
+    pub fn from_jobs(jobs: &[Job]) -> Self {
+        let mut jobs: Vec<NewJob> = jobs.iter().map(NewJob::from).collect();
+        jobs.sort_by(|a, b| a.name.cmp(&b.name));
+        Manifest { jobs }
+    }
+
+    pub fn parse(text: &str, format: ManifestFormat) -> Result<Self, String> {
+        let manifest: Manifest = match format {
+            ManifestFormat::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string())?,
+            ManifestFormat::Toml => toml::from_str(text).map_err(|e| e.to_string())?,
+            ManifestFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string())?,
+        };
+        let mut names = BTreeSet::new();
+        for job in &manifest.jobs {
+            if !names.insert(job.name.as_str()) {
+                return Err(format!("Job '{}' is defined more than once", job.name));
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn render(&self, format: ManifestFormat) -> Result<String, String> {
+        match format {
+            ManifestFormat::Yaml => serde_yaml::to_string(self).map_err(|e| e.to_string()),
+            ManifestFormat::Toml => toml::to_string(self).map_err(|e| e.to_string()),
+            ManifestFormat::Json => serde_json::to_string_pretty(self)
+                .map(|json| json + "\n")
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Parse a file that defines a single job, with the fields of a manifest
+/// entry. Without a `name`, the job is named `default_name`.
+pub fn parse_job(text: &str, format: ManifestFormat, default_name: &str) -> Result<NewJob, String> {
+    let mut value: Value = match format {
+        ManifestFormat::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string())?,
+        ManifestFormat::Toml => toml::from_str(text).map_err(|e| e.to_string())?,
+        ManifestFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string())?,
+    };
+    if let Value::Object(fields) = &mut value {
+        fields
+            .entry("name")
+            .or_insert_with(|| Value::String(default_name.to_string()));
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// A field whose value differs between a job and its new definition. A
+/// missing value means the field is at its default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Option<Value>,
+    pub to: Option<Value>,
+}
+
+/// One step of a [`Plan`].
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// Create a job that does not exist yet.
+    Create(NewJob),
+    /// Replace the definition of the existing job `id`.
+    Update {
+        id: Uuid,
+        job: NewJob,
+        fields: Vec<FieldChange>,
+    },
+    /// Delete a job the manifest does not list.
+    Delete { id: Uuid, name: String },
+}
+
+impl Change {
+    pub fn name(&self) -> &str {
+        match self {
+            Change::Create(job) | Change::Update { job, .. } => &job.name,
+            Change::Delete { name, .. } => name,
+        }
+    }
+}
+
+/// The changes that bring a set of jobs in line with a manifest. Jobs are
+/// matched by name, so a job keeps its id (and run history) across applies.
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    /// In the order they should be made: a job is created or updated after
+    /// the jobs it names as follow-ups, and deletions come last.
+    pub changes: Vec<Change>,
+    /// Jobs in the manifest that already match it.
+    pub unchanged: usize,
+    /// Jobs in the manifest that are defined in the daemon's jobs directory,
+    /// with their file. They are left to it rather than updated.
+    pub file_jobs: Vec<(String, String)>,
+}
+
+impl Plan {
+    /// Compare `current` with the definitions in `desired`. Jobs that are
+    /// not in `desired` are deleted with `prune` and left alone otherwise.
+    /// Jobs defined in the daemon's jobs directory are never changed: the
+    /// directory would not put back a definition replaced from a manifest.
+    pub fn new(current: &[Job], desired: &[NewJob], prune: bool) -> Self {
+        let mut plan = Plan::default();
+        for new in desired {
+            match current.iter().find(|j| j.name == new.name) {
+                None => plan.changes.push(Change::Create(new.clone())),
+                Some(Job {
+                    source_file: Some(file),
+                    ..
+                }) => plan.file_jobs.push((new.name.clone(), file.clone())),
+                Some(job) => {
+                    let fields = changed_fields(&NewJob::from(job), new);
+                    if fields.is_empty() {
+                        plan.unchanged += 1;
+                    } else {
+                        plan.changes.push(Change::Update {
+                            id: job.id,
+                            job: new.clone(),
+                            fields,
+                        });
+                    }
+                }
+            }
+        }
+        order_by_follow_ups(&mut plan.changes);
+        if prune {
+            for job in current {
+                // Jobs defined in the jobs directory are left to it.
+                if job.source_file.is_none() && !desired.iter().any(|new| new.name == job.name) {
+                    plan.changes.push(Change::Delete {
+                        id: job.id,
+                        name: job.name.clone(),
+                    });
+                }
+            }
+        }
+        plan
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// How many jobs are created, updated and deleted.
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.changes
+            .iter()
+            .fold((0, 0, 0), |(c, u, d), change| match change {
+                Change::Create(_) => (c + 1, u, d),
+                Change::Update { .. } => (c, u + 1, d),
+                Change::Delete { .. } => (c, u, d + 1),
+            })
+    }
+}
+
+/// The fields of `desired` that differ from `current`, in name order.
+pub fn changed_fields(current: &NewJob, desired: &NewJob) -> Vec<FieldChange> {
+    // Compare the timezone shorthand as part of the schedule it applies to.
+    let mut desired = desired.clone();
+    desired.schedule = desired.schedule.with_timezone(desired.timezone.take());
+
+    let (Ok(Value::Object(from)), Ok(Value::Object(to))) = (
+        serde_json::to_value(current),
+        serde_json::to_value(&desired),
+    ) else {
+        return Vec::new();
+    };
+    let keys: BTreeSet<&String> = from.keys().chain(to.keys()).collect();
+    keys.into_iter()
+        .filter(|key| from.get(*key) != to.get(*key))
+        .map(|key| FieldChange {
+            field: key.clone(),
+            from: from.get(key).cloned(),
+            to: to.get(key).cloned(),
+        })
+        .collect()
+}
+
+/// Reorder creations and updates so each comes after the changes to the
+/// jobs it names in `on_success`/`on_failure`, which must exist first. Jobs
+/// in a loop keep their order; the daemon rejects the loop.
+fn order_by_follow_ups(changes: &mut Vec<Change>) {
+    let mut pending = std::mem::take(changes);
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|change| {
+            let (Change::Create(job) | Change::Update { job, .. }) = change else {
+                return true;
+            };
+            job.on_success
+                .iter()
+                .chain(&job.on_failure)
+                .all(|target| target == &job.name || !pending.iter().any(|c| c.name() == target))
+        });
+        changes.push(pending.remove(ready.unwrap_or(0)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExecutionType, Schedule};
+    use chrono::Utc;
+
+    const YAML: &str = r#"
+jobs:
+  - name: report
+    schedule: "0 9 * * *"
+    timezone: Europe/Berlin
+    execution: { type: ShellCommand, value: ./report.sh }
+    on_success: [notify]
+  - name: notify
+    schedule: { type: Manual }
+    execution: { type: ShellCommand, value: ./notify.sh }
+    labels: { team: data }
+"#;
+
+    fn make_job(new: &NewJob) -> Job {
+        let now = Utc::now();
+        let mut job = Job {
+            id: Uuid::now_v7(),
+            name: String::new(),
+            schedule: Schedule::Manual,
+            jitter_secs: 0,
+            execution: ExecutionType::ShellCommand(String::new()),
+            enabled: true,
+            working_dir: None,
+            env_vars: None,
+            timeout_secs: 0,
+            log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            scheduled_runs: 0,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
+            created_at: now,
+            updated_at: now,
+            last_run_at: None,
+            last_exit_code: None,
+            source_file: None,
+            last_scheduled_at: None,
+            next_run_at: None,
+        };
+        job.redefine(new.clone());
+        job
+    }
+
+    #[test]
+    fn test_manifest_round_trips_through_every_format() {
+        let manifest = Manifest::parse(YAML, ManifestFormat::Yaml).unwrap();
+        let jobs: Vec<Job> = manifest.jobs.iter().map(make_job).collect();
+        let exported = Manifest::from_jobs(&jobs);
+        assert_eq!(exported.jobs[0].name, "notify", "exported in name order");
+
+        for format in [
+            ManifestFormat::Yaml,
+            ManifestFormat::Toml,
+            ManifestFormat::Json,
+        ] {
+            let text = exported.render(format).unwrap();
+            assert!(!text.contains("timeout_secs"), "defaults are omitted");
+            let parsed = Manifest::parse(&text, format).unwrap();
+            let plan = Plan::new(&jobs, &parsed.jobs, true);
+            assert!(plan.is_empty(), "{} round trip: {:?}", format, plan);
+            assert_eq!(plan.unchanged, 2);
+        }
+
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("jobs.yml")),
+            Some(ManifestFormat::Yaml)
+        );
+        let twice = format!(
+            "{}{}",
+            YAML,
+            &YAML[YAML.find("  - name: notify").unwrap()..]
+        );
+        let err = Manifest::parse(&twice, ManifestFormat::Yaml).unwrap_err();
+        assert!(err.contains("defined more than once"), "{}", err);
+    }
+
+    #[test]
+    fn test_plan_matches_jobs_by_name() {
+        let manifest = Manifest::parse(YAML, ManifestFormat::Yaml).unwrap();
+        let mut report = manifest.jobs[0].clone();
+        report.timeout_secs = 60;
+        report.working_dir = Some("/srv".to_string());
+        let report = make_job(&report);
+        let stale = make_job(&NewJob {
+            name: "stale".to_string(),
+            ..manifest.jobs[1].clone()
+        });
+        let current = vec![report.clone(), stale.clone()];
+
+        let plan = Plan::new(&current, &manifest.jobs, false);
+        assert_eq!(plan.counts(), (1, 1, 0));
+        // notify is created before report, which names it as a follow-up.
+        assert_eq!(plan.changes[0].name(), "notify");
+        let Change::Update { id, fields, .. } = &plan.changes[1] else {
+            panic!("expected an update: {:?}", plan.changes[1]);
+        };
+        assert_eq!(*id, report.id);
+        let fields: Vec<&str> = fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, ["timeout_secs", "working_dir"]);
+
+        let plan = Plan::new(&current, &manifest.jobs, true);
+        assert_eq!(plan.counts(), (1, 1, 1));
+        assert!(matches!(plan.changes.last(), Some(Change::Delete { id, .. }) if *id == stale.id));
+    }
+
+    #[test]
+    fn test_plan_leaves_jobs_directory_jobs_alone() {
+        let manifest = Manifest::parse(YAML, ManifestFormat::Yaml).unwrap();
+        let mut report = manifest.jobs[0].clone();
+        report.timeout_secs = 60;
+        let mut report = make_job(&report);
+        report.source_file = Some("report.yaml".to_string());
+        let mut backup = make_job(&NewJob {
+            name: "backup".to_string(),
+            ..manifest.jobs[1].clone()
+        });
+        backup.source_file = Some("backup.yaml".to_string());
+        let current = vec![report, backup];
+
+        let plan = Plan::new(&current, &manifest.jobs, true);
+        assert_eq!(plan.counts(), (1, 0, 0));
+        assert_eq!(plan.changes[0].name(), "notify");
+        assert_eq!(
+            plan.file_jobs,
+            [(manifest.jobs[0].name.clone(), "report.yaml".to_string())]
+        );
+    }
+}
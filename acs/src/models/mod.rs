@@ -1,9 +1,25 @@
+pub mod calendar;
 pub mod config;
+pub mod cron;
 pub mod dispatch;
 pub mod job;
+pub mod manifest;
 pub mod run;
+pub mod schedule;
+pub mod selector;
+pub mod workflow;
 
-pub use config::DaemonConfig;
-pub use dispatch::{DispatchRequest, TriggerParams};
-pub use job::{ExecutionType, Job, JobUpdate, NewJob};
-pub use run::{JobRun, RunStatus};
+pub use calendar::Calendar;
+pub use config::{DaemonConfig, LogCompression, RetentionLimits, StorageBackend};
+pub use dispatch::{DispatchRequest, QueuedRun, TriggerParams};
+pub use job::{
+    CatchupPolicy, ConcurrencyPolicy, ExecutionType, Job, JobUpdate, LogRetention, NewJob,
+    RetryPolicy,
+};
+pub use manifest::{Manifest, ManifestFormat, Plan};
+pub use run::{JobRun, KillReason, KillSignal, OutputStream, RunStatus, TriggerSource};
+pub use schedule::{Interval, Schedule};
+pub use selector::Selector;
+pub use workflow::{
+    NewWorkflow, NodeStatus, Workflow, WorkflowRun, WorkflowRunStatus, WorkflowUpdate,
+};
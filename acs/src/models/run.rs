@@ -4,10 +4,155 @@ use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum RunStatus {
+    /// Waiting in the run queue for a free slot under the daemon's
+    /// concurrency limits or the job's concurrency policy.
+    Queued,
     Running,
     Completed,
     Failed,
     Killed,
+    /// Never started: the job's concurrency policy or calendars turned the
+    /// dispatch away.
+    Skipped,
+}
+
+impl std::str::FromStr for RunStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "queued" => Ok(RunStatus::Queued),
+            "running" => Ok(RunStatus::Running),
+            "completed" => Ok(RunStatus::Completed),
+            "failed" => Ok(RunStatus::Failed),
+            "killed" => Ok(RunStatus::Killed),
+            "skipped" => Ok(RunStatus::Skipped),
+            other => Err(format!(
+                "Invalid status '{}': expected one of queued, running, completed, failed, killed, skipped",
+                other
+            )),
+        }
+    }
+}
+
+/// What dispatched a run.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerSource {
+    /// `POST /api/jobs/{id}/trigger` (or `acs trigger`).
+    #[default]
+    Manual,
+    /// An occurrence of the job's schedule.
+    Schedule,
+    /// An occurrence missed while the daemon was down.
+    CatchUp,
+    /// Another attempt under the job's retry policy.
+    Retry,
+    /// An upstream job's `on_success` or `on_failure`.
+    Upstream,
+    /// A node of a workflow run.
+    Workflow,
+}
+
+impl TriggerSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TriggerSource::Manual => "manual",
+            TriggerSource::Schedule => "schedule",
+            TriggerSource::CatchUp => "catch_up",
+            TriggerSource::Retry => "retry",
+            TriggerSource::Upstream => "upstream",
+            TriggerSource::Workflow => "workflow",
+        }
+    }
+}
+
+impl std::fmt::Display for TriggerSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TriggerSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "manual" => Ok(TriggerSource::Manual),
+            "schedule" => Ok(TriggerSource::Schedule),
+            "catch_up" => Ok(TriggerSource::CatchUp),
+            "retry" => Ok(TriggerSource::Retry),
+            "upstream" => Ok(TriggerSource::Upstream),
+            "workflow" => Ok(TriggerSource::Workflow),
+            other => Err(format!(
+                "Invalid trigger '{}': expected one of manual, schedule, catch_up, retry, upstream, workflow",
+                other
+            )),
+        }
+    }
+}
+
+/// Which output stream of a job process a chunk of output came from.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    #[default]
+    Stdout,
+    Stderr,
+}
+
+impl std::str::FromStr for OutputStream {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stdout" => Ok(OutputStream::Stdout),
+            "stderr" => Ok(OutputStream::Stderr),
+            other => Err(format!(
+                "Invalid stream '{}': expected 'stdout' or 'stderr'",
+                other
+            )),
+        }
+    }
+}
+
+/// Signal that ended a run which had to be stopped (kill, timeout, shutdown).
+///
+/// The whole process group is signalled: SIGTERM first, then SIGKILL if the
+/// group is still alive once the grace period expires. On Windows the
+/// equivalents are a plain and a forced `taskkill /T`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum KillSignal {
+    #[serde(rename = "SIGTERM")]
+    Term,
+    #[serde(rename = "SIGKILL")]
+    Kill,
+}
+
+/// Why a run was stopped before its process exited on its own.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KillReason {
+    /// Cancelled through `POST /api/runs/{run_id}/cancel` (or `acs kill`).
+    Cancelled,
+    /// The job was deleted while the run was in flight.
+    JobDeleted,
+    /// The daemon is shutting down.
+    Shutdown,
+    /// A newer run of the job took its place (`Replace` concurrency policy).
+    Replaced,
+}
+
+impl std::fmt::Display for KillReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            KillReason::Cancelled => "Run was cancelled",
+            KillReason::JobDeleted => "Job was deleted",
+            KillReason::Shutdown => "Daemon shutting down",
+            KillReason::Replaced => "Replaced by a newer run",
+        };
+        f.write_str(msg)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -23,6 +168,46 @@ pub struct JobRun {
     /// Trigger-time parameter overrides used for this run, if any.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trigger_params: Option<crate::models::TriggerParams>,
+    /// Signal that terminated the run's process group, if it had to be stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<KillSignal>,
+    /// Attempt number under the job's retry policy, starting at 1.
+    #[serde(default = "first_attempt")]
+    pub attempt: u32,
+    /// Run ID of the first attempt, set on retries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_run_id: Option<Uuid>,
+    /// The schedule occurrence this run was dispatched for. `None` for
+    /// manual triggers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_for: Option<DateTime<Utc>>,
+    /// Set when the run makes up for an occurrence missed while the daemon
+    /// was down; `scheduled_for` is the missed time.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub catch_up: bool,
+    /// What dispatched the run. `None` on runs recorded before triggers
+    /// were tracked; see [`JobRun::trigger_source`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<TriggerSource>,
+}
+
+impl JobRun {
+    /// What dispatched the run. For runs recorded without a trigger it is
+    /// worked out from their retry and schedule fields, which cannot tell
+    /// upstream and workflow runs from manual ones.
+    pub fn trigger_source(&self) -> TriggerSource {
+        match self.trigger {
+            Some(trigger) => trigger,
+            None if self.parent_run_id.is_some() => TriggerSource::Retry,
+            None if self.catch_up => TriggerSource::CatchUp,
+            None if self.scheduled_for.is_some() => TriggerSource::Schedule,
+            None => TriggerSource::Manual,
+        }
+    }
+}
+
+fn first_attempt() -> u32 {
+    1
 }
 
 #[cfg(test)]
@@ -40,6 +225,12 @@ mod tests {
             log_size_bytes: 1024,
             error: None,
             trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
         }
     }
 
@@ -99,6 +290,12 @@ mod tests {
             log_size_bytes: 0,
             error: Some("PTY spawn failed".to_string()),
             trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
         };
         let json = serde_json::to_string(&run).expect("serialize");
         let deserialized: JobRun = serde_json::from_str(&json).expect("deserialize");
@@ -118,10 +315,102 @@ mod tests {
             log_size_bytes: 0,
             error: None,
             trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
         };
         let json = serde_json::to_string(&run).expect("serialize");
         let deserialized: JobRun = serde_json::from_str(&json).expect("deserialize");
         assert!(deserialized.finished_at.is_none());
         assert!(deserialized.exit_code.is_none());
     }
+
+    #[test]
+    fn test_job_run_signal_serde() {
+        let mut run = make_job_run();
+        let json = serde_json::to_string(&run).expect("serialize");
+        assert!(!json.contains("signal"), "None signal should be omitted");
+
+        run.status = RunStatus::Killed;
+        run.signal = Some(KillSignal::Kill);
+        let json = serde_json::to_string(&run).expect("serialize");
+        assert!(json.contains("\"signal\":\"SIGKILL\""), "Got: {}", json);
+        let deserialized: JobRun = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(deserialized.signal, Some(KillSignal::Kill));
+    }
+
+    #[test]
+    fn test_job_run_attempt_serde() {
+        let mut run = make_job_run();
+        let mut value = serde_json::to_value(&run).expect("serialize");
+        assert_eq!(value["attempt"], 1);
+        assert!(value.get("parent_run_id").is_none());
+
+        // Records written before retries existed are first attempts.
+        value.as_object_mut().unwrap().remove("attempt");
+        let legacy: JobRun = serde_json::from_value(value).expect("deserialize");
+        assert_eq!(legacy.attempt, 1);
+
+        run.attempt = 2;
+        run.parent_run_id = Some(Uuid::now_v7());
+        let json = serde_json::to_string(&run).expect("serialize");
+        let deserialized: JobRun = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(deserialized, run);
+    }
+
+    #[test]
+    fn test_output_stream_serde_and_parse() {
+        assert_eq!(
+            serde_json::to_string(&OutputStream::Stderr).expect("serialize"),
+            "\"stderr\""
+        );
+        assert_eq!("STDOUT".parse::<OutputStream>(), Ok(OutputStream::Stdout));
+        assert_eq!("stderr".parse::<OutputStream>(), Ok(OutputStream::Stderr));
+        assert!("both".parse::<OutputStream>().is_err());
+    }
+
+    #[test]
+    fn test_trigger_source_serde_parse_and_fallback() {
+        let mut run = make_job_run();
+        let json = serde_json::to_string(&run).expect("serialize");
+        assert!(
+            !json.contains("trigger\""),
+            "None trigger should be omitted"
+        );
+        assert_eq!(run.trigger_source(), TriggerSource::Manual);
+
+        run.scheduled_for = Some(Utc::now());
+        assert_eq!(run.trigger_source(), TriggerSource::Schedule);
+        run.catch_up = true;
+        assert_eq!(run.trigger_source(), TriggerSource::CatchUp);
+        run.parent_run_id = Some(Uuid::now_v7());
+        assert_eq!(run.trigger_source(), TriggerSource::Retry);
+
+        run.trigger = Some(TriggerSource::Workflow);
+        assert_eq!(run.trigger_source(), TriggerSource::Workflow);
+        let json = serde_json::to_string(&run).expect("serialize");
+        assert!(json.contains("\"trigger\":\"workflow\""), "Got: {}", json);
+
+        assert_eq!(
+            "catch-up".parse::<TriggerSource>(),
+            Ok(TriggerSource::CatchUp)
+        );
+        assert!("cron".parse::<TriggerSource>().is_err());
+        assert_eq!("FAILED".parse::<RunStatus>(), Ok(RunStatus::Failed));
+        assert!("done".parse::<RunStatus>().is_err());
+    }
+
+    #[test]
+    fn test_kill_reason_serde_and_display() {
+        assert_eq!(
+            serde_json::to_string(&KillReason::JobDeleted).expect("serialize"),
+            "\"job_deleted\""
+        );
+        let reason: KillReason = serde_json::from_str("\"cancelled\"").expect("deserialize");
+        assert_eq!(reason, KillReason::Cancelled);
+        assert_eq!(KillReason::Shutdown.to_string(), "Daemon shutting down");
+    }
 }
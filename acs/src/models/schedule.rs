@@ -0,0 +1,288 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+/// When a job runs on its own.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum Schedule {
+    /// A cron expression, evaluated in the IANA timezone `tz` (UTC if unset).
+    Cron {
+        expr: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tz: Option<String>,
+    },
+    /// Every `interval`, at whole multiples of it from `anchor`. Without an
+    /// anchor, occurrences are aligned to the Unix epoch, so `1h` fires on
+    /// the hour (UTC).
+    Every {
+        interval: Interval,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        anchor: Option<DateTime<Utc>>,
+    },
+    /// Once, at `timestamp`. The job is disabled after it has been dispatched.
+    At { timestamp: DateTime<Utc> },
+    /// Never on its own; the job only runs when triggered.
+    Manual,
+}
+
+impl Schedule {
+    /// A cron schedule evaluated in UTC.
+    pub fn cron(expr: impl Into<String>) -> Self {
+        Schedule::Cron {
+            expr: expr.into(),
+            tz: None,
+        }
+    }
+
+    /// Apply the shorthand top-level `timezone` of `NewJob`/`JobUpdate` to a
+    /// cron schedule. Other schedules are returned unchanged; validation
+    /// rejects a timezone for them.
+    pub fn with_timezone(self, timezone: Option<String>) -> Self {
+        match (self, timezone) {
+            (Schedule::Cron { expr, .. }, Some(tz)) => Schedule::Cron { expr, tz: Some(tz) },
+            (schedule, _) => schedule,
+        }
+    }
+
+    /// Apply a `JobUpdate`'s new schedule and `timezone` to a job whose
+    /// schedule is `current`. A cron schedule given without any timezone
+    /// keeps the one of the current cron schedule.
+    pub fn replacing(self, current: &Schedule, timezone: Option<String>) -> Self {
+        match (self.with_timezone(timezone), current) {
+            (Schedule::Cron { expr, tz: None }, Schedule::Cron { tz, .. }) => Schedule::Cron {
+                expr,
+                tz: tz.clone(),
+            },
+            (schedule, _) => schedule,
+        }
+    }
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Schedule::Cron { expr, tz: None } => f.write_str(expr),
+            Schedule::Cron { expr, tz: Some(tz) } => write!(f, "{} ({})", expr, tz),
+            Schedule::Every {
+                interval,
+                anchor: None,
+            } => write!(f, "every {}", interval),
+            Schedule::Every {
+                interval,
+                anchor: Some(anchor),
+            } => write!(f, "every {} from {}", interval, anchor.to_rfc3339()),
+            Schedule::At { timestamp } => write!(f, "at {}", timestamp.to_rfc3339()),
+            Schedule::Manual => f.write_str("manual"),
+        }
+    }
+}
+
+/// Deserialize a schedule, also accepting a plain string as a cron
+/// expression evaluated in UTC (the format used before schedules were typed).
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Schedule, D::Error> {
+    from_value(serde_json::Value::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Like [`deserialize`], for optional fields.
+pub fn deserialize_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Schedule>, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Null => Ok(None),
+        value => from_value(value)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+fn from_value(value: serde_json::Value) -> Result<Schedule, serde_json::Error> {
+    match value {
+        serde_json::Value::String(expr) => Ok(Schedule::cron(expr)),
+        value => serde_json::from_value(value),
+    }
+}
+
+/// A stable pseudo-random value derived from a job id, used to spread jobs
+/// that share a schedule: it picks the values of `H` cron fields and the
+/// jitter offset. The same id always gives the same value.
+pub fn spread_seed(id: Uuid) -> u64 {
+    let v = id.as_u128();
+    mix(v as u64 ^ (v >> 64) as u64)
+}
+
+/// The splitmix64 finalizer: scrambles `x` so that nearby inputs give
+/// unrelated outputs.
+pub(crate) fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// A fixed interval with whole-second precision, written as a sequence of
+/// numbers with units: `90s`, `15m`, `1h30m`, `7d`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Interval {
+    secs: u64,
+}
+
+impl Interval {
+    /// The longest interval allowed: 36500 days, about a century. Longer
+    /// intervals would overflow date arithmetic.
+    pub const MAX_SECS: u64 = 36_500 * 86_400;
+
+    /// Build an interval from a number of seconds. Returns `None` for zero
+    /// or anything longer than [`Interval::MAX_SECS`].
+    pub fn from_secs(secs: u64) -> Option<Self> {
+        (secs > 0 && secs <= Self::MAX_SECS).then_some(Self { secs })
+    }
+
+    /// The interval as a `chrono` duration.
+    pub fn to_duration(&self) -> chrono::Duration {
+        // Cannot overflow: `secs` is at most `MAX_SECS`.
+        chrono::Duration::seconds(self.secs as i64)
+    }
+
+    pub fn as_secs(&self) -> u64 {
+        self.secs
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rest = self.secs;
+        for (unit, secs) in [("d", 86_400), ("h", 3_600), ("m", 60), ("s", 1)] {
+            if rest >= secs {
+                write!(f, "{}{}", rest / secs, unit)?;
+                rest %= secs;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid interval '{}': expected a duration such as 90s, 15m, 1h30m or 7d",
+                s
+            )
+        };
+        let mut secs: u64 = 0;
+        let mut digits = String::new();
+        for c in s.trim().chars() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+            let unit: u64 = match c.to_ascii_lowercase() {
+                's' => 1,
+                'm' => 60,
+                'h' => 3_600,
+                'd' => 86_400,
+                _ => return Err(invalid()),
+            };
+            let n: u64 = digits.parse().map_err(|_| invalid())?;
+            secs = n
+                .checked_mul(unit)
+                .and_then(|v| secs.checked_add(v))
+                .ok_or_else(invalid)?;
+            digits.clear();
+        }
+        if !digits.is_empty() {
+            return Err(invalid());
+        }
+        if secs > Self::MAX_SECS {
+            return Err(format!(
+                "Invalid interval '{}': cannot be longer than {}",
+                s,
+                Interval {
+                    secs: Self::MAX_SECS
+                }
+            ));
+        }
+        Interval::from_secs(secs).ok_or_else(invalid)
+    }
+}
+
+impl TryFrom<String> for Interval {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Interval> for String {
+    fn from(interval: Interval) -> Self {
+        interval.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_interval_parse_and_display() {
+        let parse = |s: &str| s.parse::<Interval>().map(|i| i.as_secs());
+        assert_eq!(parse("90s"), Ok(90));
+        assert_eq!(parse("15m"), Ok(900));
+        assert_eq!(parse("1h30m"), Ok(5_400));
+        assert_eq!(parse("7D"), Ok(604_800));
+        assert!(parse("15").is_err(), "a unit is required");
+        assert!(parse("0s").is_err());
+        assert!(parse("5x").is_err());
+        assert!(parse("").is_err());
+        assert_eq!(parse("36500d"), Ok(Interval::MAX_SECS));
+        assert!(parse("36501d").is_err());
+        assert!(parse("999999999999999s").is_err());
+        assert!(Interval::from_secs(u64::MAX).is_none());
+
+        assert_eq!(Interval::from_secs(5_400).unwrap().to_string(), "1h30m");
+        assert_eq!(Interval::from_secs(90_061).unwrap().to_string(), "1d1h1m1s");
+    }
+
+    #[test]
+    fn test_schedule_serde_roundtrip() {
+        let schedules = [
+            Schedule::Cron {
+                expr: "0 9 * * *".to_string(),
+                tz: Some("Europe/London".to_string()),
+            },
+            Schedule::Every {
+                interval: "15m".parse().unwrap(),
+                anchor: Some(Utc.with_ymd_and_hms(2025, 6, 15, 10, 5, 0).unwrap()),
+            },
+            Schedule::At {
+                timestamp: Utc.with_ymd_and_hms(2026, 11, 1, 9, 0, 0).unwrap(),
+            },
+            Schedule::Manual,
+        ];
+        for schedule in schedules {
+            let json = serde_json::to_value(&schedule).unwrap();
+            assert_eq!(deserialize(json).unwrap(), schedule);
+        }
+
+        let json = serde_json::to_value(Schedule::Every {
+            interval: "1h".parse().unwrap(),
+            anchor: None,
+        })
+        .unwrap();
+        assert_eq!(json, serde_json::json!({"type": "Every", "interval": "1h"}));
+    }
+
+    #[test]
+    fn test_schedule_deserialize_plain_string_as_cron() {
+        let schedule = deserialize(serde_json::json!("*/5 * * * *")).unwrap();
+        assert_eq!(schedule, Schedule::cron("*/5 * * * *"));
+
+        let err = deserialize(serde_json::json!({"type": "Every", "interval": "5x"}));
+        assert!(err.unwrap_err().to_string().contains("Invalid interval"));
+    }
+}
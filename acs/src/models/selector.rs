@@ -0,0 +1,207 @@
+use crate::models::Job;
+
+/// The selector key that matches a job's tags rather than its labels.
+pub const TAG_KEY: &str = "tag";
+
+/// A filter over jobs' tags and labels: comma-separated requirements that
+/// must all hold, e.g. `tag=nightly,team!=ops`.
+///
+/// - `tag=<tag>` / `tag!=<tag>`: the job has / lacks the tag
+/// - `<key>=<value>`: the job has the label with this value (`==` also works)
+/// - `<key>!=<value>`: the job lacks the label or has another value
+/// - `<key>` / `!<key>`: the job has / lacks the label
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Selector {
+    requirements: Vec<Requirement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Requirement {
+    HasTag(String),
+    LacksTag(String),
+    Equals(String, String),
+    NotEquals(String, String),
+    Exists(String),
+    NotExists(String),
+}
+
+impl Selector {
+    /// Whether the selector matches every job.
+    pub fn is_empty(&self) -> bool {
+        self.requirements.is_empty()
+    }
+
+    /// Whether the job satisfies every requirement.
+    pub fn matches(&self, job: &Job) -> bool {
+        self.requirements.iter().all(|req| match req {
+            Requirement::HasTag(tag) => job.tags.contains(tag),
+            Requirement::LacksTag(tag) => !job.tags.contains(tag),
+            Requirement::Equals(key, value) => job.labels.get(key) == Some(value),
+            Requirement::NotEquals(key, value) => job.labels.get(key) != Some(value),
+            Requirement::Exists(key) => job.labels.contains_key(key),
+            Requirement::NotExists(key) => !job.labels.contains_key(key),
+        })
+    }
+}
+
+impl std::str::FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut requirements = Vec::new();
+        for term in s.split(',').map(str::trim) {
+            if term.is_empty() {
+                continue;
+            }
+            requirements.push(parse_requirement(term)?);
+        }
+        Ok(Selector { requirements })
+    }
+}
+
+fn parse_requirement(term: &str) -> Result<Requirement, String> {
+    let invalid = |what: &str| format!("Invalid selector term '{}': {}", term, what);
+
+    let (key, value, negated) = if let Some((key, value)) = term.split_once("!=") {
+        (key.trim(), Some(value.trim()), true)
+    } else if let Some((key, value)) = term.split_once("==") {
+        (key.trim(), Some(value.trim()), false)
+    } else if let Some((key, value)) = term.split_once('=') {
+        (key.trim(), Some(value.trim()), false)
+    } else if let Some(key) = term.strip_prefix('!') {
+        (key.trim(), None, true)
+    } else {
+        (term, None, false)
+    };
+
+    if !is_valid_key(key) {
+        return Err(invalid("expected a tag or label key"));
+    }
+    match value {
+        Some(value) if !is_valid_value(value) => Err(invalid("invalid value")),
+        Some(value) if key == TAG_KEY => {
+            if value.is_empty() {
+                return Err(invalid("expected a tag"));
+            }
+            Ok(if negated {
+                Requirement::LacksTag(value.to_string())
+            } else {
+                Requirement::HasTag(value.to_string())
+            })
+        }
+        Some(value) => Ok(if negated {
+            Requirement::NotEquals(key.to_string(), value.to_string())
+        } else {
+            Requirement::Equals(key.to_string(), value.to_string())
+        }),
+        None if key == TAG_KEY => Err(invalid("expected tag=<tag> or tag!=<tag>")),
+        None => Ok(if negated {
+            Requirement::NotExists(key.to_string())
+        } else {
+            Requirement::Exists(key.to_string())
+        }),
+    }
+}
+
+/// Whether `s` can be a tag or label key: non-empty, made of letters,
+/// digits, `.`, `_`, `-` and `/`.
+pub fn is_valid_key(s: &str) -> bool {
+    !s.is_empty() && is_valid_value(s)
+}
+
+/// Whether `s` can be a label value: like a key, but may be empty.
+pub fn is_valid_value(s: &str) -> bool {
+    s.chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExecutionType, Schedule};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn make_job(tags: &[&str], labels: &[(&str, &str)]) -> Job {
+        let now = Utc::now();
+        Job {
+            id: Uuid::now_v7(),
+            name: "job".to_string(),
+            schedule: Schedule::cron("* * * * *"),
+            jitter_secs: 0,
+            execution: ExecutionType::ShellCommand("true".to_string()),
+            enabled: true,
+            working_dir: None,
+            env_vars: None,
+            timeout_secs: 0,
+            log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            scheduled_runs: 0,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            log_retention: Default::default(),
+            created_at: now,
+            updated_at: now,
+            last_run_at: None,
+            last_exit_code: None,
+            source_file: None,
+            last_scheduled_at: None,
+            next_run_at: None,
+        }
+    }
+
+    fn matches(selector: &str, job: &Job) -> bool {
+        selector.parse::<Selector>().expect("parse").matches(job)
+    }
+
+    #[test]
+    fn test_selector_tags_and_labels() {
+        let job = make_job(&["nightly", "db"], &[("team", "data"), ("tier", "")]);
+
+        assert!(matches("", &job));
+        assert!(matches("tag=nightly", &job));
+        assert!(matches("tag = nightly , tag==db", &job));
+        assert!(!matches("tag=hourly", &job));
+        assert!(matches("tag!=hourly", &job));
+        assert!(!matches("tag!=db", &job));
+
+        assert!(matches("team=data", &job));
+        assert!(matches("team!=ops", &job));
+        assert!(!matches("team=ops", &job));
+        assert!(matches("owner!=ops", &job), "missing label is not equal");
+        assert!(matches("tier=", &job));
+        assert!(matches("team,!owner", &job));
+        assert!(!matches("owner", &job));
+        assert!(
+            !matches("tag=nightly,team=ops", &job),
+            "all terms must hold"
+        );
+    }
+
+    #[test]
+    fn test_selector_rejects_malformed_terms() {
+        for bad in ["=x", "tag", "tag=", "team=a b", "te am=x", "!", "a=b=c"] {
+            assert!(
+                bad.parse::<Selector>().is_err(),
+                "{:?} should not parse",
+                bad
+            );
+        }
+        assert!("".parse::<Selector>().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,628 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::AcsError;
+use crate::models::job::validate_schedule;
+use crate::models::schedule::{self, Schedule};
+
+/// A graph of jobs run as a unit: each node runs a job once the nodes it
+/// depends on have finished and the edges from them allow it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(deserialize_with = "schedule::deserialize")]
+    pub schedule: Schedule,
+    pub enabled: bool,
+    pub nodes: Vec<WorkflowNode>,
+    #[serde(default)]
+    pub edges: Vec<WorkflowEdge>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_deserializing, default)]
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+impl PartialEq for Workflow {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.schedule == other.schedule
+            && self.enabled == other.enabled
+            && self.nodes == other.nodes
+            && self.edges == other.edges
+            && self.created_at == other.created_at
+            && self.updated_at == other.updated_at
+        // next_run_at is skipped (computed, not persisted)
+    }
+}
+
+/// A step of a workflow. The same job may appear in several nodes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowNode {
+    /// Unique within the workflow; edges refer to nodes by this name.
+    pub name: String,
+    /// Name or id of the job the node runs, resolved when the node starts.
+    pub job: String,
+}
+
+/// A dependency: `to` waits for `from` to finish, and runs only if
+/// `from`'s outcome meets `condition`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub condition: EdgeCondition,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeCondition {
+    /// Run if the upstream node succeeded (its job exited with code 0).
+    #[default]
+    OnSuccess,
+    /// Run if the upstream node failed.
+    OnFailure,
+    /// Run once the upstream node has finished either way.
+    Always,
+}
+
+impl EdgeCondition {
+    /// Whether an upstream node that finished with `status` lets the
+    /// downstream node run. A skipped node meets no condition.
+    pub fn is_met_by(self, status: NodeStatus) -> bool {
+        match self {
+            EdgeCondition::OnSuccess => status == NodeStatus::Succeeded,
+            EdgeCondition::OnFailure => status == NodeStatus::Failed,
+            EdgeCondition::Always => {
+                matches!(status, NodeStatus::Succeeded | NodeStatus::Failed)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for EdgeCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EdgeCondition::OnSuccess => f.write_str("on_success"),
+            EdgeCondition::OnFailure => f.write_str("on_failure"),
+            EdgeCondition::Always => f.write_str("always"),
+        }
+    }
+}
+
+impl std::str::FromStr for EdgeCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "on_success" | "success" => Ok(EdgeCondition::OnSuccess),
+            "on_failure" | "failure" => Ok(EdgeCondition::OnFailure),
+            "always" => Ok(EdgeCondition::Always),
+            _ => Err(format!(
+                "Invalid edge condition '{}': expected on_success, on_failure or always",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewWorkflow {
+    pub name: String,
+    /// A typed schedule, or a plain cron expression. Defaults to manual.
+    #[serde(
+        default = "manual_schedule",
+        deserialize_with = "schedule::deserialize"
+    )]
+    pub schedule: Schedule,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub nodes: Vec<WorkflowNode>,
+    #[serde(default)]
+    pub edges: Vec<WorkflowEdge>,
+}
+
+fn manual_schedule() -> Schedule {
+    Schedule::Manual
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Changes to a workflow. `nodes` and `edges` replace the whole graph.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WorkflowUpdate {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "schedule::deserialize_opt")]
+    pub schedule: Option<Schedule>,
+    pub enabled: Option<bool>,
+    pub nodes: Option<Vec<WorkflowNode>>,
+    pub edges: Option<Vec<WorkflowEdge>>,
+}
+
+// ---------------------------------------------------------------------------
+// Workflow runs
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WorkflowRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NodeStatus {
+    /// Waiting for upstream nodes.
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    /// Not run: an incoming edge's condition was not met, or the job run
+    /// was itself skipped.
+    Skipped,
+}
+
+impl NodeStatus {
+    pub fn is_finished(self) -> bool {
+        !matches!(self, NodeStatus::Pending | NodeStatus::Running)
+    }
+}
+
+/// The state of one node within a workflow run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeRun {
+    pub node: String,
+    pub status: NodeStatus,
+    /// The job the node resolved to when it started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<Uuid>,
+    /// The node's latest job run; retries replace it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<Uuid>,
+    #[serde(default)]
+    pub attempt: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// One execution of a workflow, tracking the job run of each node.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowRun {
+    pub id: Uuid,
+    pub workflow_id: Uuid,
+    pub workflow_name: String,
+    pub status: WorkflowRunStatus,
+    /// The schedule occurrence this run was started for; `None` if triggered.
+    #[serde(default)]
+    pub scheduled_for: Option<DateTime<Utc>>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub nodes: Vec<NodeRun>,
+}
+
+impl WorkflowRun {
+    /// A run of `workflow` with every node pending.
+    pub fn new(
+        workflow: &Workflow,
+        scheduled_for: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            workflow_id: workflow.id,
+            workflow_name: workflow.name.clone(),
+            status: WorkflowRunStatus::Running,
+            scheduled_for,
+            started_at: now,
+            finished_at: None,
+            nodes: workflow
+                .nodes
+                .iter()
+                .map(|n| NodeRun {
+                    node: n.name.clone(),
+                    status: NodeStatus::Pending,
+                    job_id: None,
+                    run_id: None,
+                    attempt: 0,
+                    error: None,
+                    started_at: None,
+                    finished_at: None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn node(&self, name: &str) -> Option<&NodeRun> {
+        self.nodes.iter().find(|n| n.node == name)
+    }
+
+    pub fn node_mut(&mut self, name: &str) -> Option<&mut NodeRun> {
+        self.nodes.iter_mut().find(|n| n.node == name)
+    }
+
+    /// Record that a running node finished.
+    pub fn settle(
+        &mut self,
+        node: &str,
+        status: NodeStatus,
+        error: Option<String>,
+        now: DateTime<Utc>,
+    ) {
+        if let Some(n) = self.node_mut(node) {
+            n.status = status;
+            n.error = error;
+            n.finished_at = Some(now);
+        }
+    }
+
+    /// Move pending nodes along once everything upstream of them has
+    /// finished, and return the names of the nodes that should start now;
+    /// they are marked `Running`.
+    ///
+    /// A node with several incoming edges (fan-in) runs only if every edge's
+    /// condition is met; otherwise it is skipped, which in turn fails the
+    /// conditions of the edges leaving it. When every node has finished the
+    /// run finishes too: `Failed` if any node failed without an
+    /// `on_failure` or `always` edge to handle it, `Succeeded` otherwise.
+    pub fn advance(&mut self, workflow: &Workflow, now: DateTime<Utc>) -> Vec<String> {
+        let mut ready = Vec::new();
+        loop {
+            let statuses: HashMap<String, NodeStatus> = self
+                .nodes
+                .iter()
+                .map(|n| (n.node.clone(), n.status))
+                .collect();
+            let mut skipped_any = false;
+            for node in self
+                .nodes
+                .iter_mut()
+                .filter(|n| n.status == NodeStatus::Pending)
+            {
+                let mut waiting = false;
+                let mut runnable = true;
+                for edge in workflow.edges.iter().filter(|e| e.to == node.node) {
+                    match statuses.get(&edge.from) {
+                        Some(status) if !status.is_finished() => waiting = true,
+                        status => runnable &= status.is_some_and(|s| edge.condition.is_met_by(*s)),
+                    }
+                }
+                if waiting {
+                    continue;
+                }
+                if runnable {
+                    node.status = NodeStatus::Running;
+                    node.started_at = Some(now);
+                    ready.push(node.node.clone());
+                } else {
+                    node.status = NodeStatus::Skipped;
+                    node.finished_at = Some(now);
+                    skipped_any = true;
+                }
+            }
+            if !skipped_any {
+                break;
+            }
+        }
+
+        if self.status == WorkflowRunStatus::Running
+            && self.nodes.iter().all(|n| n.status.is_finished())
+        {
+            let handled = |node: &str| {
+                workflow.edges.iter().any(|e| {
+                    e.from == node
+                        && matches!(
+                            e.condition,
+                            EdgeCondition::OnFailure | EdgeCondition::Always
+                        )
+                })
+            };
+            let failed = self
+                .nodes
+                .iter()
+                .any(|n| n.status == NodeStatus::Failed && !handled(&n.node));
+            self.status = if failed {
+                WorkflowRunStatus::Failed
+            } else {
+                WorkflowRunStatus::Succeeded
+            };
+            self.finished_at = Some(now);
+        }
+        ready
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Validate a NewWorkflow before creation.
+pub fn validate_new_workflow(new: &NewWorkflow) -> Result<(), AcsError> {
+    validate_workflow_name(&new.name)?;
+    validate_schedule(&new.schedule, None)?;
+    validate_graph(&new.nodes, &new.edges)
+}
+
+/// Validate a WorkflowUpdate before applying. The graph it results in is
+/// checked with `validate_graph` once merged with the current one.
+pub fn validate_workflow_update(update: &WorkflowUpdate) -> Result<(), AcsError> {
+    if let Some(ref name) = update.name {
+        validate_workflow_name(name)?;
+    }
+    if let Some(ref schedule) = update.schedule {
+        validate_schedule(schedule, None)?;
+    }
+    Ok(())
+}
+
+fn validate_workflow_name(name: &str) -> Result<(), AcsError> {
+    if name.trim().is_empty() {
+        return Err(AcsError::Validation(
+            "Workflow name cannot be empty".to_string(),
+        ));
+    }
+    if Uuid::parse_str(name).is_ok() {
+        return Err(AcsError::Validation(
+            "Workflow name cannot be a valid UUID".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that the nodes have unique names, that every edge joins two of
+/// them, and that the edges form no cycle.
+pub fn validate_graph(nodes: &[WorkflowNode], edges: &[WorkflowEdge]) -> Result<(), AcsError> {
+    if nodes.is_empty() {
+        return Err(AcsError::Validation(
+            "A workflow needs at least one node".to_string(),
+        ));
+    }
+    let mut names = HashSet::new();
+    for node in nodes {
+        if node.name.trim().is_empty() {
+            return Err(AcsError::Validation(
+                "Workflow node name cannot be empty".to_string(),
+            ));
+        }
+        if node.job.trim().is_empty() {
+            return Err(AcsError::Validation(format!(
+                "Workflow node '{}' has no job",
+                node.name
+            )));
+        }
+        if !names.insert(node.name.as_str()) {
+            return Err(AcsError::Validation(format!(
+                "Duplicate workflow node '{}'",
+                node.name
+            )));
+        }
+    }
+    for edge in edges {
+        for end in [&edge.from, &edge.to] {
+            if !names.contains(end.as_str()) {
+                return Err(AcsError::Validation(format!(
+                    "Edge {} -> {} refers to unknown node '{}'",
+                    edge.from, edge.to, end
+                )));
+            }
+        }
+        if edge.from == edge.to {
+            return Err(AcsError::Validation(format!(
+                "Node '{}' cannot depend on itself",
+                edge.from
+            )));
+        }
+    }
+
+    // Kahn's algorithm: whatever cannot be ordered lies on a cycle.
+    let mut in_degree: HashMap<&str, usize> = names.iter().map(|n| (*n, 0)).collect();
+    for edge in edges {
+        *in_degree.get_mut(edge.to.as_str()).unwrap() += 1;
+    }
+    let mut queue: Vec<&str> = in_degree
+        .iter()
+        .filter(|(_, d)| **d == 0)
+        .map(|(n, _)| *n)
+        .collect();
+    let mut ordered = 0;
+    while let Some(name) = queue.pop() {
+        ordered += 1;
+        for edge in edges.iter().filter(|e| e.from == name) {
+            let degree = in_degree.get_mut(edge.to.as_str()).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push(edge.to.as_str());
+            }
+        }
+    }
+    if ordered < nodes.len() {
+        let mut cyclic: Vec<&str> = in_degree
+            .into_iter()
+            .filter(|(_, d)| *d > 0)
+            .map(|(n, _)| n)
+            .collect();
+        cyclic.sort_unstable();
+        return Err(AcsError::Validation(format!(
+            "Workflow edges form a cycle through {}",
+            cyclic.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str) -> WorkflowNode {
+        WorkflowNode {
+            name: name.to_string(),
+            job: name.to_string(),
+        }
+    }
+
+    fn edge(from: &str, to: &str, condition: EdgeCondition) -> WorkflowEdge {
+        WorkflowEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            condition,
+        }
+    }
+
+    fn workflow(nodes: &[&str], edges: Vec<WorkflowEdge>) -> Workflow {
+        let now = Utc::now();
+        Workflow {
+            id: Uuid::now_v7(),
+            name: "pipeline".to_string(),
+            schedule: Schedule::Manual,
+            enabled: true,
+            nodes: nodes.iter().map(|n| node(n)).collect(),
+            edges,
+            created_at: now,
+            updated_at: now,
+            next_run_at: None,
+        }
+    }
+
+    fn status(run: &WorkflowRun, node: &str) -> NodeStatus {
+        run.node(node).unwrap().status
+    }
+
+    #[test]
+    fn test_validate_graph() {
+        let nodes = vec![node("a"), node("b"), node("c")];
+        let ok = vec![
+            edge("a", "b", EdgeCondition::OnSuccess),
+            edge("a", "c", EdgeCondition::OnFailure),
+            edge("b", "c", EdgeCondition::Always),
+        ];
+        assert!(validate_graph(&nodes, &ok).is_ok());
+
+        let err = validate_graph(&[], &[]).unwrap_err();
+        assert!(err.to_string().contains("at least one node"));
+
+        let err = validate_graph(&[node("a"), node("a")], &[]).unwrap_err();
+        assert!(err.to_string().contains("Duplicate workflow node 'a'"));
+
+        let err = validate_graph(&nodes, &[edge("a", "x", EdgeCondition::OnSuccess)]).unwrap_err();
+        assert!(err.to_string().contains("unknown node 'x'"));
+
+        let err = validate_graph(&nodes, &[edge("a", "a", EdgeCondition::Always)]).unwrap_err();
+        assert!(err.to_string().contains("cannot depend on itself"));
+
+        let cycle = vec![
+            edge("a", "b", EdgeCondition::OnSuccess),
+            edge("b", "c", EdgeCondition::OnSuccess),
+            edge("c", "b", EdgeCondition::OnFailure),
+        ];
+        let err = validate_graph(&nodes, &cycle).unwrap_err();
+        assert!(err.to_string().contains("cycle through b, c"));
+    }
+
+    #[test]
+    fn test_edge_condition_parse_and_serde() {
+        assert_eq!(
+            "on-failure".parse::<EdgeCondition>().unwrap(),
+            EdgeCondition::OnFailure
+        );
+        assert!("sometimes".parse::<EdgeCondition>().is_err());
+        let e: WorkflowEdge = serde_json::from_str(r#"{"from":"a","to":"b"}"#).unwrap();
+        assert_eq!(e.condition, EdgeCondition::OnSuccess);
+        assert_eq!(
+            serde_json::to_string(&EdgeCondition::Always).unwrap(),
+            "\"always\""
+        );
+    }
+
+    #[test]
+    fn test_advance_fans_out_and_in() {
+        // a -> (b, c) -> d
+        let wf = workflow(
+            &["a", "b", "c", "d"],
+            vec![
+                edge("a", "b", EdgeCondition::OnSuccess),
+                edge("a", "c", EdgeCondition::OnSuccess),
+                edge("b", "d", EdgeCondition::OnSuccess),
+                edge("c", "d", EdgeCondition::OnSuccess),
+            ],
+        );
+        let now = Utc::now();
+        let mut run = WorkflowRun::new(&wf, None, now);
+
+        assert_eq!(run.advance(&wf, now), vec!["a"]);
+        assert!(run.advance(&wf, now).is_empty());
+
+        run.settle("a", NodeStatus::Succeeded, None, now);
+        assert_eq!(run.advance(&wf, now), vec!["b", "c"]);
+
+        run.settle("b", NodeStatus::Succeeded, None, now);
+        assert!(run.advance(&wf, now).is_empty(), "d waits for c");
+
+        run.settle("c", NodeStatus::Succeeded, None, now);
+        assert_eq!(run.advance(&wf, now), vec!["d"]);
+        assert_eq!(run.status, WorkflowRunStatus::Running);
+
+        run.settle("d", NodeStatus::Succeeded, None, now);
+        assert!(run.advance(&wf, now).is_empty());
+        assert_eq!(run.status, WorkflowRunStatus::Succeeded);
+        assert_eq!(run.finished_at, Some(now));
+    }
+
+    #[test]
+    fn test_advance_follows_failure_edges_and_skips() {
+        // build -> deploy (on success), build -> notify (on failure),
+        // deploy -> report (always)
+        let wf = workflow(
+            &["build", "deploy", "notify", "report"],
+            vec![
+                edge("build", "deploy", EdgeCondition::OnSuccess),
+                edge("build", "notify", EdgeCondition::OnFailure),
+                edge("deploy", "report", EdgeCondition::Always),
+            ],
+        );
+        let now = Utc::now();
+        let mut run = WorkflowRun::new(&wf, None, now);
+        run.advance(&wf, now);
+        run.settle("build", NodeStatus::Failed, None, now);
+
+        assert_eq!(run.advance(&wf, now), vec!["notify"]);
+        assert_eq!(status(&run, "deploy"), NodeStatus::Skipped);
+        // A skipped node meets no condition, not even `always`.
+        assert_eq!(status(&run, "report"), NodeStatus::Skipped);
+
+        run.settle("notify", NodeStatus::Succeeded, None, now);
+        run.advance(&wf, now);
+        // The failure was handled by an on_failure edge.
+        assert_eq!(run.status, WorkflowRunStatus::Succeeded);
+    }
+
+    #[test]
+    fn test_unhandled_failure_fails_the_run() {
+        let wf = workflow(
+            &["a", "b", "c"],
+            vec![
+                edge("a", "b", EdgeCondition::OnSuccess),
+                edge("a", "c", EdgeCondition::OnSuccess),
+            ],
+        );
+        let now = Utc::now();
+        let mut run = WorkflowRun::new(&wf, None, now);
+        run.advance(&wf, now);
+        run.settle("a", NodeStatus::Succeeded, None, now);
+        run.advance(&wf, now);
+        run.settle("b", NodeStatus::Failed, Some("exit 1".to_string()), now);
+        run.settle("c", NodeStatus::Succeeded, None, now);
+        run.advance(&wf, now);
+        assert_eq!(run.status, WorkflowRunStatus::Failed);
+    }
+}
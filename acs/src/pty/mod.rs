@@ -4,6 +4,8 @@
 use std::io;
 use std::process::ExitStatus;
 
+use crate::models::OutputStream;
+
 /// Trait for spawning PTY processes.
 pub trait PtySpawner: Send + Sync {
     fn spawn(
@@ -17,8 +19,18 @@ pub trait PtySpawner: Send + Sync {
 /// Trait for interacting with a spawned PTY process.
 pub trait PtyProcess: Send {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+    /// Read the next chunk of output along with the stream it came from.
+    /// Chunks are returned in the order the process produced them. The
+    /// default treats everything `read` returns as stdout.
+    fn read_stream(&mut self, buf: &mut [u8]) -> io::Result<(usize, OutputStream)> {
+        self.read(buf).map(|n| (n, OutputStream::Stdout))
+    }
     fn kill(&mut self) -> io::Result<()>;
     fn wait(&mut self) -> io::Result<ExitStatus>;
+    /// Return a handle that can signal the process tree from another task.
+    /// The read loop owns the process itself, so kill/timeout handling goes
+    /// through this handle instead.
+    fn killer(&self) -> Box<dyn ProcessKiller>;
     /// Write data to the process's stdin. Default is no-op.
     fn write_stdin(&mut self, _data: &[u8]) -> io::Result<()> {
         Ok(())
@@ -27,6 +39,17 @@ pub trait PtyProcess: Send {
     fn close_stdin(&mut self) {}
 }
 
+/// Signals the process group of a spawned process.
+///
+/// Spawners start each job in its own process group so that shells and
+/// anything they fork can be stopped together.
+pub trait ProcessKiller: Send + Sync {
+    /// Politely ask the process group to exit (SIGTERM on Unix).
+    fn terminate(&self) -> io::Result<()>;
+    /// Forcefully stop the process group (SIGKILL on Unix).
+    fn kill(&self) -> io::Result<()>;
+}
+
 // This module provides NoPtySpawner as the production process spawner.
 // It uses piped I/O via std::process::Command, which reliably handles EOF
 // on all platforms. PTY emulation is intentionally not used.
@@ -84,6 +107,20 @@ impl PtySpawner for NoPtySpawner {
             .stderr(Stdio::piped())
             .stdin(Stdio::piped());
 
+        // Put the child in its own process group so a kill reaches everything
+        // the shell spawned, not just the shell itself.
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+            command.creation_flags(CREATE_NEW_PROCESS_GROUP);
+        }
+
         // Forward working directory and environment variables from CommandBuilder.
         // Previously these were silently dropped, causing jobs with working_dir
         // or env_vars to ignore those settings.
@@ -94,24 +131,81 @@ impl PtySpawner for NoPtySpawner {
             command.env(key, val);
         }
 
-        let child = command.spawn()?;
+        let mut child = command.spawn()?;
+
+        // Drain stdout and stderr on their own threads so neither pipe can
+        // fill up and block the child while we wait on the other. Chunks are
+        // funnelled into one channel, which keeps their relative order.
+        let (output_tx, output_rx) = std::sync::mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            spawn_pipe_reader(stdout, OutputStream::Stdout, output_tx.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            spawn_pipe_reader(stderr, OutputStream::Stderr, output_tx);
+        }
 
-        Ok(Box::new(NoPtyProcess { child }))
+        Ok(Box::new(NoPtyProcess {
+            child,
+            output_rx,
+            pending: None,
+        }))
     }
 }
 
+type OutputChunk = (OutputStream, Vec<u8>);
+
+/// Forward everything read from `pipe` to `tx` until EOF.
+fn spawn_pipe_reader<R: io::Read + Send + 'static>(
+    mut pipe: R,
+    stream: OutputStream,
+    tx: std::sync::mpsc::Sender<OutputChunk>,
+) {
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if tx.send((stream, buf[..n].to_vec())).is_err() {
+                        break;
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+    });
+}
+
 struct NoPtyProcess {
     child: std::process::Child,
+    /// Output from the pipe reader threads. Closed once both pipes hit EOF.
+    output_rx: std::sync::mpsc::Receiver<OutputChunk>,
+    /// Remainder of a chunk that did not fit into the caller's buffer.
+    pending: Option<(OutputStream, Vec<u8>, usize)>,
 }
 
 impl PtyProcess for NoPtyProcess {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        if let Some(ref mut stdout) = self.child.stdout {
-            use std::io::Read;
-            stdout.read(buf)
-        } else {
-            Ok(0)
+        self.read_stream(buf).map(|(n, _)| n)
+    }
+
+    fn read_stream(&mut self, buf: &mut [u8]) -> io::Result<(usize, OutputStream)> {
+        let (stream, data, offset) = match self.pending.take() {
+            Some(pending) => pending,
+            None => match self.output_rx.recv() {
+                Ok((stream, data)) => (stream, data, 0),
+                // Both reader threads finished: EOF
+                Err(_) => return Ok((0, OutputStream::Stdout)),
+            },
+        };
+
+        let len = std::cmp::min(buf.len(), data.len() - offset);
+        buf[..len].copy_from_slice(&data[offset..offset + len]);
+        if offset + len < data.len() {
+            self.pending = Some((stream, data, offset + len));
         }
+        Ok((len, stream))
     }
 
     fn kill(&mut self) -> io::Result<()> {
@@ -122,6 +216,12 @@ impl PtyProcess for NoPtyProcess {
         self.child.wait()
     }
 
+    fn killer(&self) -> Box<dyn ProcessKiller> {
+        Box::new(ProcessGroupKiller {
+            pid: self.child.id(),
+        })
+    }
+
     fn write_stdin(&mut self, data: &[u8]) -> io::Result<()> {
         if let Some(ref mut stdin) = self.child.stdin {
             use std::io::Write;
@@ -136,8 +236,72 @@ impl PtyProcess for NoPtyProcess {
     }
 }
 
+/// Kills the process group led by `pid`.
+struct ProcessGroupKiller {
+    pid: u32,
+}
+
+#[cfg(unix)]
+impl ProcessGroupKiller {
+    fn signal(&self, signal: libc::c_int) -> io::Result<()> {
+        // A negative pid addresses the whole process group.
+        let ret = unsafe { libc::kill(-(self.pid as libc::pid_t), signal) };
+        if ret == 0 {
+            return Ok(());
+        }
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::ESRCH) {
+            // The group is already gone.
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+}
+
+#[cfg(unix)]
+impl ProcessKiller for ProcessGroupKiller {
+    fn terminate(&self) -> io::Result<()> {
+        self.signal(libc::SIGTERM)
+    }
+
+    fn kill(&self) -> io::Result<()> {
+        self.signal(libc::SIGKILL)
+    }
+}
+
+#[cfg(not(unix))]
+impl ProcessGroupKiller {
+    fn taskkill(&self, force: bool) -> io::Result<()> {
+        let mut command = std::process::Command::new("taskkill");
+        command.arg("/T");
+        if force {
+            command.arg("/F");
+        }
+        command
+            .arg("/PID")
+            .arg(self.pid.to_string())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        // taskkill fails when the process already exited, which is fine here.
+        command.status().map(|_| ())
+    }
+}
+
+#[cfg(not(unix))]
+impl ProcessKiller for ProcessGroupKiller {
+    fn terminate(&self) -> io::Result<()> {
+        self.taskkill(false)
+    }
+
+    fn kill(&self) -> io::Result<()> {
+        self.taskkill(true)
+    }
+}
+
 // --- Mock implementations for testing ---
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// Configuration for creating a MockPtyProcess.
@@ -151,6 +315,11 @@ pub struct MockPtyConfig {
     pub spawn_error: Option<String>,
     /// Delay between output chunks in milliseconds (for timeout testing)
     pub chunk_delay_ms: u64,
+    /// Whether the process ignores SIGTERM and only stops on SIGKILL
+    pub ignore_terminate: bool,
+    /// Stderr chunks. Each one is emitted right after the stdout chunk with
+    /// the same index (or after all stdout once stdout runs out).
+    pub stderr_output: Vec<Vec<u8>>,
 }
 
 /// Mock PTY spawner for testing.
@@ -172,6 +341,22 @@ impl MockPtySpawner {
             exit_code,
             spawn_error: None,
             chunk_delay_ms: 0,
+            ignore_terminate: false,
+            stderr_output: Vec::new(),
+        })
+    }
+
+    /// Create a MockPtySpawner that writes to both stdout and stderr.
+    pub fn with_stdout_and_stderr(
+        output: Vec<Vec<u8>>,
+        stderr_output: Vec<Vec<u8>>,
+        exit_code: i32,
+    ) -> Self {
+        Self::new(MockPtyConfig {
+            output,
+            stderr_output,
+            exit_code,
+            ..Default::default()
         })
     }
 
@@ -182,6 +367,8 @@ impl MockPtySpawner {
             exit_code,
             spawn_error: None,
             chunk_delay_ms,
+            ignore_terminate: false,
+            stderr_output: Vec::new(),
         })
     }
 
@@ -207,39 +394,98 @@ impl PtySpawner for MockPtySpawner {
             return Err(anyhow::anyhow!(error));
         }
 
+        // Interleave stdout and stderr chunks: out[0], err[0], out[1], ...
+        let mut output_chunks = Vec::new();
+        let mut stdout = config.output.into_iter();
+        let mut stderr = config.stderr_output.into_iter();
+        loop {
+            let out = stdout.next();
+            let err = stderr.next();
+            if out.is_none() && err.is_none() {
+                break;
+            }
+            if let Some(chunk) = out {
+                output_chunks.push((OutputStream::Stdout, chunk));
+            }
+            if let Some(chunk) = err {
+                output_chunks.push((OutputStream::Stderr, chunk));
+            }
+        }
+
         Ok(Box::new(MockPtyProcess {
-            output_chunks: config.output,
+            output_chunks,
             chunk_index: 0,
             exit_code: config.exit_code,
             chunk_delay_ms: config.chunk_delay_ms,
+            killer: Arc::new(MockProcessKiller {
+                ignore_terminate: config.ignore_terminate,
+                ..Default::default()
+            }),
         }))
     }
 }
 
 /// Mock PTY process for testing.
 pub struct MockPtyProcess {
-    output_chunks: Vec<Vec<u8>>,
+    output_chunks: Vec<(OutputStream, Vec<u8>)>,
     chunk_index: usize,
     exit_code: i32,
     chunk_delay_ms: u64,
+    killer: Arc<MockProcessKiller>,
+}
+
+/// Records the signals sent to a MockPtyProcess. Once a signal the process
+/// honors arrives, its output ends as if the process had exited.
+#[derive(Default)]
+pub struct MockProcessKiller {
+    ignore_terminate: bool,
+    terminated: AtomicBool,
+    killed: AtomicBool,
+}
+
+impl MockProcessKiller {
+    fn stopped(&self) -> bool {
+        self.killed.load(Ordering::SeqCst)
+            || (!self.ignore_terminate && self.terminated.load(Ordering::SeqCst))
+    }
+}
+
+impl ProcessKiller for Arc<MockProcessKiller> {
+    fn terminate(&self) -> io::Result<()> {
+        self.terminated.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn kill(&self) -> io::Result<()> {
+        self.killed.store(true, Ordering::SeqCst);
+        Ok(())
+    }
 }
 
 impl PtyProcess for MockPtyProcess {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        if self.chunk_delay_ms > 0 {
-            std::thread::sleep(std::time::Duration::from_millis(self.chunk_delay_ms));
+        self.read_stream(buf).map(|(n, _)| n)
+    }
+
+    fn read_stream(&mut self, buf: &mut [u8]) -> io::Result<(usize, OutputStream)> {
+        // Sleep in small steps so a signal interrupts a slow chunk promptly.
+        let mut waited = 0;
+        while waited < self.chunk_delay_ms && !self.killer.stopped() {
+            let step = std::cmp::min(10, self.chunk_delay_ms - waited);
+            std::thread::sleep(std::time::Duration::from_millis(step));
+            waited += step;
         }
 
-        if self.chunk_index >= self.output_chunks.len() {
+        if self.killer.stopped() || self.chunk_index >= self.output_chunks.len() {
             // Simulate EOF
-            return Ok(0);
+            return Ok((0, OutputStream::Stdout));
         }
 
-        let chunk = &self.output_chunks[self.chunk_index];
+        let (stream, chunk) = &self.output_chunks[self.chunk_index];
         let len = std::cmp::min(buf.len(), chunk.len());
         buf[..len].copy_from_slice(&chunk[..len]);
         self.chunk_index += 1;
-        Ok(len)
+        Ok((len, *stream))
     }
 
     fn kill(&mut self) -> io::Result<()> {
@@ -249,6 +495,10 @@ impl PtyProcess for MockPtyProcess {
     fn wait(&mut self) -> io::Result<ExitStatus> {
         Ok(exit_status_from_code(self.exit_code))
     }
+
+    fn killer(&self) -> Box<dyn ProcessKiller> {
+        Box::new(Arc::clone(&self.killer))
+    }
 }
 
 /// Helper to create an ExitStatus from a raw exit code.
@@ -374,4 +624,114 @@ mod tests {
         let status = exit_status_from_code(1);
         assert!(!status.success());
     }
+
+    #[test]
+    fn test_mock_killer_terminate_ends_output() {
+        let spawner = MockPtySpawner::with_slow_output(vec![b"slow\n".to_vec()], 0, 5000);
+        let cmd = portable_pty::CommandBuilder::new("sleep");
+        let mut process = spawner.spawn(cmd, 24, 80).expect("spawn");
+
+        process.killer().terminate().expect("terminate");
+
+        let mut buf = [0u8; 1024];
+        let n = process.read(&mut buf).expect("read");
+        assert_eq!(n, 0, "terminated process should report EOF");
+    }
+
+    #[test]
+    fn test_mock_killer_ignored_terminate_needs_kill() {
+        let spawner = MockPtySpawner::new(MockPtyConfig {
+            output: vec![b"one\n".to_vec(), b"two\n".to_vec()],
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let cmd = portable_pty::CommandBuilder::new("stubborn");
+        let mut process = spawner.spawn(cmd, 24, 80).expect("spawn");
+        let killer = process.killer();
+
+        killer.terminate().expect("terminate");
+        let mut buf = [0u8; 1024];
+        let n = process.read(&mut buf).expect("read");
+        assert_eq!(&buf[..n], b"one\n", "SIGTERM should be ignored");
+
+        killer.kill().expect("kill");
+        let n = process.read(&mut buf).expect("read");
+        assert_eq!(n, 0, "SIGKILL should end output");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_no_pty_killer_terminates_whole_process_group() {
+        // The shell forks a background sleep that inherits stdout. Killing only
+        // the shell would leave the pipe open and the read below would block.
+        let mut cmd = portable_pty::CommandBuilder::new("/bin/sh");
+        cmd.arg("-c");
+        cmd.arg("sleep 30 & sleep 30");
+        let mut process = NoPtySpawner.spawn(cmd, 24, 80).expect("spawn");
+
+        process.killer().terminate().expect("terminate");
+
+        let mut buf = [0u8; 1024];
+        let n = process.read(&mut buf).expect("read");
+        assert_eq!(n, 0);
+        let status = process.wait().expect("wait");
+        assert!(!status.success());
+    }
+
+    #[test]
+    fn test_mock_pty_process_interleaves_stderr() {
+        let spawner = MockPtySpawner::with_stdout_and_stderr(
+            vec![b"out1\n".to_vec(), b"out2\n".to_vec()],
+            vec![b"err1\n".to_vec()],
+            0,
+        );
+        let cmd = portable_pty::CommandBuilder::new("echo");
+        let mut process = spawner.spawn(cmd, 24, 80).expect("spawn");
+
+        let mut buf = [0u8; 1024];
+        let mut chunks = Vec::new();
+        loop {
+            let (n, stream) = process.read_stream(&mut buf).expect("read");
+            if n == 0 {
+                break;
+            }
+            chunks.push((stream, buf[..n].to_vec()));
+        }
+
+        assert_eq!(
+            chunks,
+            vec![
+                (OutputStream::Stdout, b"out1\n".to_vec()),
+                (OutputStream::Stderr, b"err1\n".to_vec()),
+                (OutputStream::Stdout, b"out2\n".to_vec()),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_no_pty_process_captures_stderr() {
+        let mut cmd = portable_pty::CommandBuilder::new("/bin/sh");
+        cmd.arg("-c");
+        cmd.arg("echo out; sleep 0.1; echo err >&2");
+        let mut process = NoPtySpawner.spawn(cmd, 24, 80).expect("spawn");
+
+        let mut buf = [0u8; 1024];
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        loop {
+            let (n, stream) = process.read_stream(&mut buf).expect("read");
+            if n == 0 {
+                break;
+            }
+            match stream {
+                OutputStream::Stdout => stdout.extend_from_slice(&buf[..n]),
+                OutputStream::Stderr => stderr.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        assert_eq!(stdout, b"out\n");
+        assert_eq!(stderr, b"err\n");
+        assert!(process.wait().expect("wait").success());
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use axum::extract::State;
@@ -16,6 +17,9 @@ pub struct HealthResponse {
     pub total_jobs: usize,
     pub version: String,
     pub data_dir: String,
+    /// Files in the jobs directory that could not be applied, with the reason.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs_dir_errors: Option<BTreeMap<String, String>>,
 }
 
 pub async fn health_check(State(state): State<Arc<AppState>>) -> impl IntoResponse {
@@ -38,6 +42,11 @@ pub async fn health_check(State(state): State<Arc<AppState>>) -> impl IntoRespon
         .map(|p| p.display().to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
+    let jobs_dir_errors = match &state.jobs_dir_errors {
+        Some(errors) => Some(errors.read().await.clone()),
+        None => None,
+    };
+
     let response = HealthResponse {
         status: "ok".to_string(),
         uptime_seconds: uptime,
@@ -45,6 +54,7 @@ pub async fn health_check(State(state): State<Arc<AppState>>) -> impl IntoRespon
         total_jobs: total_jobs.1,
         version: "0.1.0".to_string(),
         data_dir,
+        jobs_dir_errors,
     };
 
     (StatusCode::OK, Json(response))
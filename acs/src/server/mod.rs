@@ -13,22 +13,33 @@ use tokio::sync::{broadcast, Notify, RwLock};
 use tower_http::cors::{Any, CorsLayer};
 use uuid::Uuid;
 
+use crate::daemon::dispatcher::RunQueue;
 use crate::daemon::events::JobEvent;
 use crate::daemon::executor::RunHandle;
+use crate::daemon::jobs_dir::JobsDirErrors;
+use crate::daemon::workflows::WorkflowEngine;
 use crate::models::DaemonConfig;
-use crate::storage::{JobStore, LogStore};
+use crate::storage::{CalendarStore, JobStore, LogStore, WorkflowStore};
 
 /// Shared application state for the Axum server.
 pub struct AppState {
     pub job_store: Arc<dyn JobStore>,
     pub log_store: Arc<dyn LogStore>,
+    pub calendar_store: Arc<dyn CalendarStore>,
+    pub workflow_store: Arc<dyn WorkflowStore>,
     pub event_tx: broadcast::Sender<JobEvent>,
     pub scheduler_notify: Arc<Notify>,
     pub config: Arc<DaemonConfig>,
     pub start_time: Instant,
     pub active_runs: Arc<RwLock<HashMap<Uuid, RunHandle>>>,
+    /// Dispatches waiting for a free slot; shared with the dispatcher.
+    pub run_queue: RunQueue,
     pub shutdown_tx: Option<tokio::sync::watch::Sender<()>>,
     pub dispatch_tx: Option<tokio::sync::mpsc::Sender<crate::models::DispatchRequest>>,
+    /// Runs workflows; triggering one needs it.
+    pub workflow_engine: Option<Arc<WorkflowEngine>>,
+    /// Problems with the files of the jobs directory, when one is configured.
+    pub jobs_dir_errors: Option<JobsDirErrors>,
 }
 
 /// Create the Axum router with all routes.
@@ -36,20 +47,57 @@ pub fn create_router(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/health", get(health::health_check))
         .route("/api/jobs", get(routes::list_jobs).post(routes::create_job))
+        .route("/api/jobs/bulk", post(routes::bulk_jobs))
         .route(
             "/api/jobs/{id}",
             get(routes::get_job)
                 .patch(routes::update_job)
+                .put(routes::replace_job)
                 .delete(routes::delete_job),
         )
         .route("/api/jobs/{id}/enable", post(routes::enable_job))
         .route("/api/jobs/{id}/disable", post(routes::disable_job))
         .route("/api/jobs/{id}/trigger", post(routes::trigger_job))
         .route("/api/jobs/{id}/runs", get(routes::list_runs))
+        .route("/api/runs", get(routes::search_runs))
+        .route("/api/runs/active", get(routes::list_active_runs))
+        .route("/api/runs/{run_id}", get(routes::get_run))
         .route("/api/runs/{run_id}/log", get(routes::get_log))
+        .route("/api/runs/{run_id}/cancel", post(routes::cancel_run))
+        .route("/api/queue", get(routes::list_queue))
+        .route(
+            "/api/calendars",
+            get(routes::list_calendars).post(routes::create_calendar),
+        )
+        .route(
+            "/api/calendars/{name}",
+            get(routes::get_calendar)
+                .put(routes::update_calendar)
+                .delete(routes::delete_calendar),
+        )
+        .route(
+            "/api/workflows",
+            get(routes::list_workflows).post(routes::create_workflow),
+        )
+        .route(
+            "/api/workflows/{id}",
+            get(routes::get_workflow)
+                .patch(routes::update_workflow)
+                .delete(routes::delete_workflow),
+        )
+        .route(
+            "/api/workflows/{id}/trigger",
+            post(routes::trigger_workflow),
+        )
+        .route("/api/workflows/{id}/runs", get(routes::list_workflow_runs))
+        .route(
+            "/api/workflows/{id}/runs/{run_id}",
+            get(routes::get_workflow_run),
+        )
         .route("/api/events", get(sse::sse_handler))
         .route("/api/shutdown", post(routes::shutdown))
         .route("/api/restart", post(routes::restart))
+        .route("/api/maintenance/compress", post(routes::compress_logs))
         .route("/api/logs", get(routes::get_daemon_logs))
         .route("/api/service/status", get(routes::service_status))
         .with_state(state)
@@ -68,10 +116,13 @@ pub fn create_router(state: Arc<AppState>) -> Router {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::daemon::events::JobEvent;
+    use crate::daemon::events::{JobChangeKind, JobEvent};
     use crate::models::job::{ExecutionType, NewJob};
-    use crate::models::{Job, JobRun, JobUpdate, RunStatus};
-    use crate::storage::{JobStore, LogStore};
+    use crate::models::{
+        Calendar, Job, JobRun, JobUpdate, KillReason, NewWorkflow, RunStatus, Schedule,
+        TriggerSource, Workflow, WorkflowRun, WorkflowUpdate,
+    };
+    use crate::storage::{CalendarStore, JobStore, LogStore, RunQuery, WorkflowStore};
     use async_trait::async_trait;
     use axum::body::Body;
     use axum::http::{Request, StatusCode};
@@ -128,19 +179,38 @@ mod tests {
             let job = Job {
                 id: Uuid::now_v7(),
                 name: new.name,
-                schedule: new.schedule,
+                schedule: new.schedule.with_timezone(new.timezone),
+                jitter_secs: new.jitter_secs,
                 execution: new.execution,
                 enabled: new.enabled,
-                timezone: new.timezone,
                 working_dir: new.working_dir,
                 env_vars: new.env_vars,
                 timeout_secs: new.timeout_secs,
                 log_environment: new.log_environment,
+                concurrency_policy: new.concurrency_policy,
+                retry: new.retry,
+                catchup: new.catchup,
+                include_calendars: new.include_calendars,
+                exclude_calendars: new.exclude_calendars,
+                start_at: new.start_at,
+                end_at: new.end_at,
+                max_runs: new.max_runs,
+                on_success: new.on_success,
+                on_failure: new.on_failure,
+                forward_output_lines: new.forward_output_lines,
+                pool: new.pool,
+                priority: new.priority,
+                tags: new.tags,
+                labels: new.labels,
+                log_retention: Default::default(),
+                scheduled_runs: 0,
                 created_at: now,
                 updated_at: now,
                 last_run_at: None,
                 last_exit_code: None,
+                source_file: None,
                 next_run_at: None,
+                last_scheduled_at: None,
             };
             jobs.push(job.clone());
             Ok(job)
@@ -156,7 +226,7 @@ mod tests {
                 job.name = name;
             }
             if let Some(schedule) = update.schedule {
-                job.schedule = schedule;
+                job.schedule = schedule.replacing(&job.schedule, update.timezone);
             }
             if let Some(execution) = update.execution {
                 job.execution = execution;
@@ -164,9 +234,6 @@ mod tests {
             if let Some(enabled) = update.enabled {
                 job.enabled = enabled;
             }
-            if let Some(tz) = update.timezone {
-                job.timezone = Some(tz);
-            }
             if let Some(wd) = update.working_dir {
                 job.working_dir = Some(wd);
             }
@@ -180,6 +247,16 @@ mod tests {
             Ok(job.clone())
         }
 
+        async fn replace_job(&self, id: Uuid, new: NewJob) -> anyhow::Result<Job> {
+            let mut jobs = self.jobs.write().await;
+            let job = jobs
+                .iter_mut()
+                .find(|j| j.id == id)
+                .ok_or_else(|| anyhow::anyhow!("not found"))?;
+            job.redefine(new);
+            Ok(job.clone())
+        }
+
         async fn delete_job(&self, id: Uuid) -> anyhow::Result<()> {
             let mut jobs = self.jobs.write().await;
             jobs.retain(|j| j.id != id);
@@ -267,9 +344,197 @@ mod tests {
             Ok((paginated, total))
         }
 
+        async fn find_run(&self, run_id: Uuid) -> anyhow::Result<Option<JobRun>> {
+            let runs = self.runs.read().await;
+            Ok(runs.iter().find(|r| r.run_id == run_id).cloned())
+        }
+
+        async fn query_runs(
+            &self,
+            query: &RunQuery,
+            limit: usize,
+            offset: usize,
+        ) -> anyhow::Result<(Vec<JobRun>, usize)> {
+            let runs = self.runs.read().await.clone();
+            Ok(query.page(runs, limit, offset))
+        }
+
         async fn cleanup(&self, _job_id: Uuid, _max_files: usize) -> anyhow::Result<()> {
             Ok(())
         }
+
+        async fn remove_runs(&self, job_id: Uuid, run_ids: &[Uuid]) -> anyhow::Result<()> {
+            self.runs
+                .write()
+                .await
+                .retain(|r| r.job_id != job_id || !run_ids.contains(&r.run_id));
+            let mut logs = self.logs.write().await;
+            for run_id in run_ids {
+                logs.remove(&(job_id, *run_id));
+            }
+            Ok(())
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // InMemoryCalendarStore - test double
+    // -----------------------------------------------------------------------
+
+    struct InMemoryCalendarStore {
+        calendars: RwLock<Vec<Calendar>>,
+    }
+
+    impl InMemoryCalendarStore {
+        fn new() -> Self {
+            Self {
+                calendars: RwLock::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CalendarStore for InMemoryCalendarStore {
+        async fn list_calendars(&self) -> anyhow::Result<Vec<Calendar>> {
+            Ok(self.calendars.read().await.clone())
+        }
+
+        async fn get_calendar(&self, name: &str) -> anyhow::Result<Option<Calendar>> {
+            Ok(self
+                .calendars
+                .read()
+                .await
+                .iter()
+                .find(|c| c.name == name)
+                .cloned())
+        }
+
+        async fn create_calendar(&self, calendar: Calendar) -> anyhow::Result<Calendar> {
+            let mut calendars = self.calendars.write().await;
+            if calendars.iter().any(|c| c.name == calendar.name) {
+                return Err(anyhow::anyhow!(
+                    "Conflict: A calendar with name '{}' already exists",
+                    calendar.name
+                ));
+            }
+            calendars.push(calendar.clone());
+            Ok(calendar)
+        }
+
+        async fn update_calendar(&self, calendar: Calendar) -> anyhow::Result<Calendar> {
+            let mut calendars = self.calendars.write().await;
+            let existing = calendars
+                .iter_mut()
+                .find(|c| c.name == calendar.name)
+                .ok_or_else(|| anyhow::anyhow!("Calendar '{}' not found", calendar.name))?;
+            *existing = calendar.clone();
+            Ok(calendar)
+        }
+
+        async fn delete_calendar(&self, name: &str) -> anyhow::Result<()> {
+            let mut calendars = self.calendars.write().await;
+            let idx = calendars
+                .iter()
+                .position(|c| c.name == name)
+                .ok_or_else(|| anyhow::anyhow!("Calendar '{}' not found", name))?;
+            calendars.remove(idx);
+            Ok(())
+        }
+    }
+
+    struct InMemoryWorkflowStore {
+        workflows: RwLock<Vec<Workflow>>,
+    }
+
+    impl InMemoryWorkflowStore {
+        fn new() -> Self {
+            Self {
+                workflows: RwLock::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl WorkflowStore for InMemoryWorkflowStore {
+        async fn list_workflows(&self) -> anyhow::Result<Vec<Workflow>> {
+            Ok(self.workflows.read().await.clone())
+        }
+
+        async fn get_workflow(&self, id: Uuid) -> anyhow::Result<Option<Workflow>> {
+            let workflows = self.workflows.read().await;
+            Ok(workflows.iter().find(|w| w.id == id).cloned())
+        }
+
+        async fn find_workflow_by_name(&self, name: &str) -> anyhow::Result<Option<Workflow>> {
+            let workflows = self.workflows.read().await;
+            Ok(workflows.iter().find(|w| w.name == name).cloned())
+        }
+
+        async fn create_workflow(&self, new: NewWorkflow) -> anyhow::Result<Workflow> {
+            let now = Utc::now();
+            let workflow = Workflow {
+                id: Uuid::now_v7(),
+                name: new.name,
+                schedule: new.schedule,
+                enabled: new.enabled,
+                nodes: new.nodes,
+                edges: new.edges,
+                created_at: now,
+                updated_at: now,
+                next_run_at: None,
+            };
+            self.workflows.write().await.push(workflow.clone());
+            Ok(workflow)
+        }
+
+        async fn update_workflow(
+            &self,
+            id: Uuid,
+            update: WorkflowUpdate,
+        ) -> anyhow::Result<Workflow> {
+            let mut workflows = self.workflows.write().await;
+            let workflow = workflows
+                .iter_mut()
+                .find(|w| w.id == id)
+                .ok_or_else(|| anyhow::anyhow!("Workflow with id '{}' not found", id))?;
+            if let Some(enabled) = update.enabled {
+                workflow.enabled = enabled;
+            }
+            Ok(workflow.clone())
+        }
+
+        async fn delete_workflow(&self, id: Uuid) -> anyhow::Result<()> {
+            self.workflows.write().await.retain(|w| w.id != id);
+            Ok(())
+        }
+
+        async fn save_workflow_run(&self, _run: &WorkflowRun) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn get_workflow_run(
+            &self,
+            _workflow_id: Uuid,
+            _run_id: Uuid,
+        ) -> anyhow::Result<Option<WorkflowRun>> {
+            Ok(None)
+        }
+
+        async fn list_workflow_runs(
+            &self,
+            _workflow_id: Uuid,
+            _limit: usize,
+            _offset: usize,
+        ) -> anyhow::Result<(Vec<WorkflowRun>, usize)> {
+            Ok((Vec::new(), 0))
+        }
+
+        async fn cleanup_workflow_runs(
+            &self,
+            _workflow_id: Uuid,
+            _max_runs: usize,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
     }
 
     // -----------------------------------------------------------------------
@@ -281,6 +546,8 @@ mod tests {
         Arc::new(AppState {
             job_store: Arc::new(InMemoryJobStore::new()),
             log_store: Arc::new(InMemoryLogStore::new()),
+            calendar_store: Arc::new(InMemoryCalendarStore::new()),
+            workflow_store: Arc::new(InMemoryWorkflowStore::new()),
             event_tx,
             scheduler_notify: Arc::new(Notify::new()),
             config: Arc::new(DaemonConfig::default()),
@@ -288,6 +555,9 @@ mod tests {
             active_runs: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: None,
             dispatch_tx: None,
+            workflow_engine: None,
+            jobs_dir_errors: None,
+            run_queue: RunQueue::default(),
         })
     }
 
@@ -299,6 +569,8 @@ mod tests {
         Arc::new(AppState {
             job_store,
             log_store,
+            calendar_store: Arc::new(InMemoryCalendarStore::new()),
+            workflow_store: Arc::new(InMemoryWorkflowStore::new()),
             event_tx,
             scheduler_notify: Arc::new(Notify::new()),
             config: Arc::new(DaemonConfig::default()),
@@ -306,6 +578,9 @@ mod tests {
             active_runs: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: None,
             dispatch_tx: None,
+            workflow_engine: None,
+            jobs_dir_errors: None,
+            run_queue: RunQueue::default(),
         })
     }
 
@@ -388,7 +663,7 @@ mod tests {
         let json: serde_json::Value = serde_json::from_str(&body).unwrap();
 
         assert_eq!(json["name"], "test-job");
-        assert_eq!(json["schedule"], "*/5 * * * *");
+        assert_eq!(json["schedule"]["expr"], "*/5 * * * *");
         assert!(json["id"].is_string());
         assert_eq!(json["enabled"], true);
     }
@@ -443,14 +718,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "dup-job".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo hello".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -530,14 +822,31 @@ mod tests {
                 .job_store
                 .create_job(NewJob {
                     name: name.to_string(),
-                    schedule: "*/5 * * * *".to_string(),
+                    schedule: Schedule::cron("*/5 * * * *"),
                     execution: ExecutionType::ShellCommand("echo".to_string()),
                     enabled: true,
+                    jitter_secs: 0,
                     timezone: None,
                     working_dir: None,
                     env_vars: None,
                     timeout_secs: 0,
                     log_environment: false,
+                    concurrency_policy: Default::default(),
+                    retry: Default::default(),
+                    catchup: Default::default(),
+                    include_calendars: Vec::new(),
+                    exclude_calendars: Vec::new(),
+                    start_at: None,
+                    end_at: None,
+                    max_runs: None,
+                    on_success: Vec::new(),
+                    on_failure: Vec::new(),
+                    forward_output_lines: None,
+                    pool: None,
+                    priority: 0,
+                    tags: Vec::new(),
+                    labels: Default::default(),
+                    log_retention: Default::default(),
                 })
                 .await
                 .unwrap();
@@ -574,14 +883,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "enabled-job".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -590,14 +916,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "disabled-job".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: false,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -633,14 +976,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "fetch-me".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -672,14 +1032,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "my-named-job".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -738,14 +1115,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "update-me".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -776,7 +1170,43 @@ mod tests {
         let body = body_string(response.into_body()).await;
         let json: serde_json::Value = serde_json::from_str(&body).unwrap();
         assert_eq!(json["name"], "updated-name");
-        assert_eq!(json["schedule"], "0 * * * *");
+        assert_eq!(json["schedule"]["expr"], "0 * * * *");
+    }
+
+    #[tokio::test]
+    async fn test_replace_job_clears_omitted_fields() {
+        let state = make_test_state();
+        let mut new: NewJob = serde_json::from_str(&new_job_json("replace-me")).unwrap();
+        new.working_dir = Some("/srv".to_string());
+        new.timeout_secs = 60;
+        let job = state.job_store.create_job(new).await.unwrap();
+
+        let app = make_test_app(state);
+        let body = serde_json::json!({
+            "name": "replace-me",
+            "schedule": "0 * * * *",
+            "execution": {"type": "ShellCommand", "value": "echo replaced"}
+        })
+        .to_string();
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri(format!("/api/jobs/{}", job.id))
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let json: serde_json::Value =
+            serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+        assert_eq!(json["id"], job.id.to_string(), "the id is kept");
+        assert_eq!(json["execution"]["value"], "echo replaced");
+        assert!(json["working_dir"].is_null());
+        assert_eq!(json["timeout_secs"], 0);
     }
 
     // =======================================================================
@@ -791,14 +1221,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "job-a".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -807,14 +1254,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "job-b".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -859,14 +1323,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "delete-me".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -899,14 +1380,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "disabled-job".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: false,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -945,14 +1443,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "enabled-job".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -991,14 +1506,31 @@ mod tests {
             .job_store
             .create_job(NewJob {
                 name: "trigger-me".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -1038,14 +1570,31 @@ mod tests {
         let job = job_store
             .create_job(NewJob {
                 name: "runs-job".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -1062,6 +1611,12 @@ mod tests {
                 log_size_bytes: 100,
                 error: None,
                 trigger_params: None,
+                signal: None,
+                attempt: 1,
+                parent_run_id: None,
+                scheduled_for: None,
+                catch_up: false,
+                trigger: None,
             };
             log_store.create_run(&run).await.unwrap();
         }
@@ -1100,14 +1655,31 @@ mod tests {
         let job = job_store
             .create_job(NewJob {
                 name: "log-job".to_string(),
-                schedule: "*/5 * * * *".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
                 execution: ExecutionType::ShellCommand("echo".to_string()),
                 enabled: true,
+                jitter_secs: 0,
                 timezone: None,
                 working_dir: None,
                 env_vars: None,
                 timeout_secs: 0,
                 log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
             })
             .await
             .unwrap();
@@ -1123,6 +1695,12 @@ mod tests {
             log_size_bytes: 0,
             error: None,
             trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
         };
         log_store.create_run(&run).await.unwrap();
 
@@ -1151,99 +1729,557 @@ mod tests {
         assert!(body.contains("Line 2"));
     }
 
-    // =======================================================================
-    // 19. All error responses match { "error": ..., "message": ... } format
-    // =======================================================================
     #[tokio::test]
-    async fn test_error_responses_match_format() {
-        let state = make_test_state();
-        let app = make_test_app(state);
+    async fn test_get_log_stream_filter() {
+        let job_store = Arc::new(InMemoryJobStore::new());
+        let log_store = Arc::new(InMemoryLogStore::new());
 
-        // 404 for unknown job
-        let response = app
-            .oneshot(
-                Request::builder()
-                    .uri("/api/jobs/nonexistent-name")
-                    .body(Body::empty())
-                    .unwrap(),
-            )
+        let job = job_store
+            .create_job(NewJob {
+                name: "stream-job".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
+                execution: ExecutionType::ShellCommand("echo".to_string()),
+                enabled: true,
+                jitter_secs: 0,
+                timezone: None,
+                working_dir: None,
+                env_vars: None,
+                timeout_secs: 0,
+                log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
+            })
             .await
             .unwrap();
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
-
-        let body = body_string(response.into_body()).await;
-        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let run = make_run(job.id);
+        let run_id = run.run_id;
+        log_store.create_run(&run).await.unwrap();
+        log_store
+            .append_log(job.id, run_id, b"stdout only\n")
+            .await
+            .unwrap();
 
-        // Must have both "error" and "message" fields
-        assert!(
-            json.get("error").is_some(),
-            "Error response must have 'error' field"
-        );
-        assert!(
-            json.get("message").is_some(),
-            "Error response must have 'message' field"
+        let state = make_test_state_with_stores(
+            job_store as Arc<dyn JobStore>,
+            log_store as Arc<dyn LogStore>,
         );
-        assert!(json["error"].is_string());
-        assert!(json["message"].is_string());
-    }
-
-    // =======================================================================
-    // 20. POST /api/shutdown returns 200
-    // =======================================================================
-    #[tokio::test]
-    async fn test_shutdown_returns_200() {
-        let state = make_test_state();
         let app = make_test_app(state);
 
+        // A run without stderr output yields an empty body, not a 404
+        let uri = format!("/api/runs/{}/log?stream=stderr", run_id);
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_string(response.into_body()).await.is_empty());
+
+        let uri = format!("/api/runs/{}/log?stream=stdout", run_id);
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_string(response.into_body())
+            .await
+            .contains("stdout only"));
+
+        // The SSE endpoint rejects the same bad value instead of ignoring it.
         let response = app
+            .clone()
             .oneshot(
                 Request::builder()
-                    .method("POST")
-                    .uri("/api/shutdown")
+                    .uri("/api/events?stream=both")
                     .body(Body::empty())
                     .unwrap(),
             )
             .await
             .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
-        assert_eq!(response.status(), StatusCode::OK);
+        let uri = format!("/api/runs/{}/log?stream=both", run_id);
+        let response = app
+            .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
 
-        let body = body_string(response.into_body()).await;
-        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
-        assert_eq!(json["message"], "Shutdown initiated");
+    fn make_run(job_id: Uuid) -> JobRun {
+        JobRun {
+            run_id: Uuid::now_v7(),
+            job_id,
+            started_at: Utc::now(),
+            finished_at: Some(Utc::now()),
+            status: RunStatus::Completed,
+            exit_code: Some(0),
+            log_size_bytes: 0,
+            error: None,
+            trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
+        }
     }
 
-    // =======================================================================
-    // Additional: GET /api/service/status returns 200
-    // =======================================================================
     #[tokio::test]
-    async fn test_service_status_returns_200() {
+    async fn test_search_runs_filters_and_cursor() {
         let state = make_test_state();
-        let app = make_test_app(state);
+        let mut backup: NewJob = serde_json::from_str(&new_job_json("backup")).unwrap();
+        backup.tags = vec!["nightly".to_string()];
+        let backup = state.job_store.create_job(backup).await.unwrap();
+        let sync: NewJob = serde_json::from_str(&new_job_json("sync")).unwrap();
+        let sync = state.job_store.create_job(sync).await.unwrap();
+
+        let mut scheduled = Vec::new();
+        for (exit_code, minutes_ago) in [(1, 30), (0, 20), (1, 10)] {
+            let mut run = make_run(backup.id);
+            run.started_at = Utc::now() - chrono::Duration::minutes(minutes_ago);
+            run.exit_code = Some(exit_code);
+            run.trigger = Some(TriggerSource::Schedule);
+            state.log_store.create_run(&run).await.unwrap();
+            scheduled.push(run);
+        }
+        let mut killed = make_run(backup.id);
+        killed.status = RunStatus::Killed;
+        killed.exit_code = None;
+        state.log_store.create_run(&killed).await.unwrap();
+        let mut failed = make_run(sync.id);
+        failed.status = RunStatus::Failed;
+        failed.exit_code = Some(1);
+        failed.error = Some("Disk full".to_string());
+        state.log_store.create_run(&failed).await.unwrap();
+
+        let get = |uri: String| {
+            let app = make_test_app(Arc::clone(&state));
+            async move {
+                let response = app
+                    .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let json: serde_json::Value =
+                    serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+                (status, json)
+            }
+        };
 
-        let response = app
-            .oneshot(
-                Request::builder()
-                    .uri("/api/service/status")
-                    .body(Body::empty())
-                    .unwrap(),
-            )
-            .await
-            .unwrap();
+        let (status, json) =
+            get("/api/runs?selector=tag%3Dnightly&exit_code=1&limit=1".into()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["runs"][0]["run_id"], scheduled[2].run_id.to_string());
+        let cursor = json["next_cursor"].as_str().unwrap().to_string();
 
-        assert_eq!(response.status(), StatusCode::OK);
+        let uri = format!(
+            "/api/runs?selector=tag%3Dnightly&exit_code=1&limit=1&cursor={}",
+            cursor
+        );
+        let (_, json) = get(uri).await;
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["runs"][0]["run_id"], scheduled[0].run_id.to_string());
+        assert!(json["next_cursor"].is_null());
+
+        let (_, json) = get("/api/runs?q=disk".into()).await;
+        assert_eq!(json["runs"][0]["run_id"], failed.run_id.to_string());
+        let (_, json) = get("/api/runs?trigger=schedule".into()).await;
+        assert_eq!(json["total"], 3);
+        let (_, json) = get(format!(
+            "/api/runs?job=sync&started_after={}",
+            "2000-01-01T00:00:00Z"
+        ))
+        .await;
+        assert_eq!(json["total"], 1);
+
+        for uri in [
+            "/api/runs?status=done",
+            "/api/runs?cursor=abc",
+            "/api/runs?started_after=today",
+        ] {
+            let (status, json) = get(uri.into()).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+            assert_eq!(json["error"], "validation_error");
+        }
 
-        let body = body_string(response.into_body()).await;
-        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
-        assert!(json["platform"].is_string());
+        // The status filter applies before the page is cut, so the total
+        // counts every matching run.
+        let (_, json) = get(format!(
+            "/api/jobs/{}/runs?status=completed&limit=1",
+            backup.id
+        ))
+        .await;
+        assert_eq!(json["total"], 3);
+        assert_eq!(json["runs"].as_array().unwrap().len(), 1);
     }
 
-    // =======================================================================
-    // Additional: DELETE /api/jobs/{id} with nonexistent returns 404
-    // =======================================================================
     #[tokio::test]
-    async fn test_delete_nonexistent_job_returns_404() {
+    async fn test_get_run_and_log_of_run_without_output() {
+        let state = make_test_state();
+        let run = make_run(Uuid::now_v7());
+        state.log_store.create_run(&run).await.unwrap();
+        let app = make_test_app(state);
+
+        let uri = format!("/api/runs/{}", run.run_id);
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json: serde_json::Value =
+            serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+        assert_eq!(json["run_id"], run.run_id.to_string());
+        assert_eq!(json["status"], "Completed");
+
+        // A run that wrote nothing has an empty log rather than a missing one
+        let uri = format!("/api/runs/{}/log", run.run_id);
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_string(response.into_body()).await.is_empty());
+
+        for uri in [
+            format!("/api/runs/{}", Uuid::now_v7()),
+            format!("/api/runs/{}/log", Uuid::now_v7()),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_run_kills_active_run_and_returns_final_status() {
+        let job_store = Arc::new(InMemoryJobStore::new());
+        let log_store = Arc::new(InMemoryLogStore::new());
+
+        let job = job_store
+            .create_job(NewJob {
+                name: "cancel-job".to_string(),
+                schedule: Schedule::cron("*/5 * * * *"),
+                execution: ExecutionType::ShellCommand("sleep 60".to_string()),
+                enabled: true,
+                jitter_secs: 0,
+                timezone: None,
+                working_dir: None,
+                env_vars: None,
+                timeout_secs: 0,
+                log_environment: false,
+                concurrency_policy: Default::default(),
+                retry: Default::default(),
+                catchup: Default::default(),
+                include_calendars: Vec::new(),
+                exclude_calendars: Vec::new(),
+                start_at: None,
+                end_at: None,
+                max_runs: None,
+                on_success: Vec::new(),
+                on_failure: Vec::new(),
+                forward_output_lines: None,
+                pool: None,
+                priority: 0,
+                tags: Vec::new(),
+                labels: Default::default(),
+                log_retention: Default::default(),
+            })
+            .await
+            .unwrap();
+
+        let run_id = Uuid::now_v7();
+        let run = JobRun {
+            run_id,
+            job_id: job.id,
+            started_at: Utc::now(),
+            finished_at: None,
+            status: RunStatus::Running,
+            exit_code: None,
+            log_size_bytes: 0,
+            error: None,
+            trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
+        };
+        log_store.create_run(&run).await.unwrap();
+
+        let state = make_test_state_with_stores(
+            job_store as Arc<dyn JobStore>,
+            Arc::clone(&log_store) as Arc<dyn LogStore>,
+        );
+
+        // Stand-in for the executor task and the dispatcher's wrapper: record
+        // the kill once signalled, then deregister the run.
+        let (kill_tx, kill_rx) = tokio::sync::oneshot::channel::<KillReason>();
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<()>();
+        let task_store = Arc::clone(&log_store);
+        let task_runs = Arc::clone(&state.active_runs);
+        let join_handle = tokio::spawn(async move {
+            let reason = kill_rx.await.expect("kill reason");
+            // The process takes a while to exit after the signal.
+            let _ = exit_rx.await;
+            let killed = JobRun {
+                finished_at: Some(Utc::now()),
+                status: RunStatus::Killed,
+                error: Some(reason.to_string()),
+                ..run
+            };
+            task_store.update_run(&killed).await.unwrap();
+            task_runs.write().await.remove(&run_id);
+        });
+        state.active_runs.write().await.insert(
+            run_id,
+            RunHandle {
+                run_id,
+                job_id: job.id,
+                job_name: job.name.clone(),
+                started_at: Utc::now(),
+                join_handle,
+                kill_tx,
+                killer: Default::default(),
+            },
+        );
+        let app = make_test_app(Arc::clone(&state));
+
+        let uri = format!("/api/runs/{}/cancel", run_id);
+        let cancel = tokio::spawn(
+            app.clone().oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(&uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            ),
+        );
+
+        // Until the process exits, the run stays registered so the job
+        // still counts as busy.
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(state.active_runs.read().await.contains_key(&run_id));
+        exit_tx.send(()).unwrap();
+
+        let response = cancel.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: serde_json::Value =
+            serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+        assert_eq!(body["status"], "Killed");
+        assert_eq!(body["error"], "Run was cancelled");
+        assert!(state.active_runs.read().await.is_empty());
+
+        // The run is no longer active, so a second cancel conflicts
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(&uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_list_active_runs_includes_concurrent_runs_of_same_job() {
+        let state = make_test_state();
+        let job_id = Uuid::now_v7();
+
+        let mut run_ids = Vec::new();
+        for _ in 0..2 {
+            let run_id = Uuid::now_v7();
+            run_ids.push(run_id);
+            let (kill_tx, _kill_rx) = tokio::sync::oneshot::channel::<KillReason>();
+            state.active_runs.write().await.insert(
+                run_id,
+                RunHandle {
+                    run_id,
+                    job_id,
+                    job_name: "busy-job".to_string(),
+                    started_at: Utc::now(),
+                    join_handle: tokio::spawn(async {}),
+                    kill_tx,
+                    killer: Default::default(),
+                },
+            );
+        }
+
+        let app = make_test_app(state);
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/runs/active")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: serde_json::Value =
+            serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+        assert_eq!(body["total"], 2);
+        let runs = body["runs"].as_array().unwrap();
+        for run_id in run_ids {
+            assert!(runs
+                .iter()
+                .any(|r| r["run_id"] == run_id.to_string() && r["job_name"] == "busy-job"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_unknown_run_returns_404() {
+        let app = make_test_app(make_test_state());
+
+        let uri = format!("/api/runs/{}/cancel", Uuid::now_v7());
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(&uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/runs/not-a-uuid/cancel")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    // =======================================================================
+    // 19. All error responses match { "error": ..., "message": ... } format
+    // =======================================================================
+    #[tokio::test]
+    async fn test_error_responses_match_format() {
+        let state = make_test_state();
+        let app = make_test_app(state);
+
+        // 404 for unknown job
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/jobs/nonexistent-name")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = body_string(response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        // Must have both "error" and "message" fields
+        assert!(
+            json.get("error").is_some(),
+            "Error response must have 'error' field"
+        );
+        assert!(
+            json.get("message").is_some(),
+            "Error response must have 'message' field"
+        );
+        assert!(json["error"].is_string());
+        assert!(json["message"].is_string());
+    }
+
+    // =======================================================================
+    // 20. POST /api/shutdown returns 200
+    // =======================================================================
+    #[tokio::test]
+    async fn test_shutdown_returns_200() {
+        let state = make_test_state();
+        let app = make_test_app(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/shutdown")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_string(response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["message"], "Shutdown initiated");
+    }
+
+    // =======================================================================
+    // Additional: GET /api/service/status returns 200
+    // =======================================================================
+    #[tokio::test]
+    async fn test_service_status_returns_200() {
+        let state = make_test_state();
+        let app = make_test_app(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/service/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_string(response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(json["platform"].is_string());
+    }
+
+    // =======================================================================
+    // Additional: DELETE /api/jobs/{id} with nonexistent returns 404
+    // =======================================================================
+    #[tokio::test]
+    async fn test_delete_nonexistent_job_returns_404() {
         let state = make_test_state();
         let app = make_test_app(state);
 
@@ -1317,6 +2353,8 @@ mod tests {
         let state = Arc::new(AppState {
             job_store: Arc::new(InMemoryJobStore::new()),
             log_store: Arc::new(InMemoryLogStore::new()),
+            calendar_store: Arc::new(InMemoryCalendarStore::new()),
+            workflow_store: Arc::new(InMemoryWorkflowStore::new()),
             event_tx,
             scheduler_notify: Arc::new(Notify::new()),
             config: Arc::new(DaemonConfig::default()),
@@ -1324,6 +2362,9 @@ mod tests {
             active_runs: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: None,
             dispatch_tx: None,
+            workflow_engine: None,
+            jobs_dir_errors: None,
+            run_queue: RunQueue::default(),
         });
 
         let app = make_test_app(state);
@@ -1371,6 +2412,8 @@ mod tests {
         let state = Arc::new(AppState {
             job_store: Arc::new(InMemoryJobStore::new()),
             log_store: Arc::new(InMemoryLogStore::new()),
+            calendar_store: Arc::new(InMemoryCalendarStore::new()),
+            workflow_store: Arc::new(InMemoryWorkflowStore::new()),
             event_tx,
             scheduler_notify: Arc::new(Notify::new()),
             config: Arc::new(config),
@@ -1378,6 +2421,9 @@ mod tests {
             active_runs: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: None,
             dispatch_tx: None,
+            workflow_engine: None,
+            jobs_dir_errors: None,
+            run_queue: RunQueue::default(),
         });
         let app = make_test_app(state);
 
@@ -1431,6 +2477,78 @@ mod tests {
         );
     }
 
+    // =======================================================================
+    // Additional: POST /api/maintenance/compress
+    // =======================================================================
+    #[tokio::test]
+    async fn test_compress_logs_route() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let log_store = crate::storage::logs::FsLogStore::new(tmp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        let job_id = Uuid::now_v7();
+        let run = JobRun {
+            run_id: Uuid::now_v7(),
+            job_id,
+            started_at: Utc::now(),
+            finished_at: Some(Utc::now()),
+            status: RunStatus::Completed,
+            exit_code: Some(0),
+            log_size_bytes: 6,
+            error: None,
+            trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
+        };
+        log_store.create_run(&run).await.unwrap();
+        log_store
+            .append_log(job_id, run.run_id, b"hello\n")
+            .await
+            .unwrap();
+        let app = make_test_app(make_test_state_with_stores(
+            Arc::new(InMemoryJobStore::new()),
+            Arc::new(log_store),
+        ));
+
+        let compress = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/maintenance/compress")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(compress(r#"{"format":"none"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .clone()
+            .oneshot(compress(r#"{"format":"zstd"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_string(response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["format"], "zstd");
+        assert_eq!(json["runs"], 1);
+        assert_eq!(json["bytes_before"], 6);
+
+        let uri = format!("/api/runs/{}/log", run.run_id);
+        let response = app
+            .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(body_string(response.into_body()).await, "hello\n");
+    }
+
     // =======================================================================
     // Additional: Health shows correct active/total count
     // =======================================================================
@@ -1444,14 +2562,31 @@ mod tests {
                 .job_store
                 .create_job(NewJob {
                     name: name.to_string(),
-                    schedule: "*/5 * * * *".to_string(),
+                    schedule: Schedule::cron("*/5 * * * *"),
                     execution: ExecutionType::ShellCommand("echo".to_string()),
                     enabled: *enabled,
+                    jitter_secs: 0,
                     timezone: None,
                     working_dir: None,
                     env_vars: None,
                     timeout_secs: 0,
                     log_environment: false,
+                    concurrency_policy: Default::default(),
+                    retry: Default::default(),
+                    catchup: Default::default(),
+                    include_calendars: Vec::new(),
+                    exclude_calendars: Vec::new(),
+                    start_at: None,
+                    end_at: None,
+                    max_runs: None,
+                    on_success: Vec::new(),
+                    on_failure: Vec::new(),
+                    forward_output_lines: None,
+                    pool: None,
+                    priority: 0,
+                    tags: Vec::new(),
+                    labels: Default::default(),
+                    log_retention: Default::default(),
                 })
                 .await
                 .unwrap();
@@ -1475,4 +2610,467 @@ mod tests {
         assert_eq!(json["total_jobs"], 3);
         assert_eq!(json["active_jobs"], 2);
     }
+
+    // =======================================================================
+    // Workflows: graph and job checks, and trigger without an engine
+    // =======================================================================
+    #[tokio::test]
+    async fn test_create_workflow_checks_graph_and_jobs() {
+        let state = make_test_state();
+        state
+            .job_store
+            .create_job(serde_json::from_str(&new_job_json("build")).unwrap())
+            .await
+            .unwrap();
+
+        let post = |body: serde_json::Value| {
+            let app = make_test_app(Arc::clone(&state));
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::builder()
+                            .method("POST")
+                            .uri("/api/workflows")
+                            .header("content-type", "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let json: serde_json::Value =
+                    serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+                (status, json)
+            }
+        };
+
+        let (status, json) = post(serde_json::json!({
+            "name": "cyclic",
+            "nodes": [{"name": "a", "job": "build"}, {"name": "b", "job": "build"}],
+            "edges": [{"from": "a", "to": "b"}, {"from": "b", "to": "a"}],
+        }))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["message"].as_str().unwrap().contains("cycle"));
+
+        let (status, json) = post(serde_json::json!({
+            "name": "missing-job",
+            "nodes": [{"name": "a", "job": "nope"}],
+        }))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "Job 'nope' of node 'a' not found");
+
+        let (status, json) = post(serde_json::json!({
+            "name": "release",
+            "nodes": [{"name": "a", "job": "build"}],
+        }))
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["schedule"]["type"], "Manual");
+
+        let response = make_test_app(Arc::clone(&state))
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/workflows/release/trigger")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_follow_ups_must_exist_and_not_loop() {
+        let state = make_test_state();
+        state
+            .job_store
+            .create_job(serde_json::from_str(&new_job_json("a")).unwrap())
+            .await
+            .unwrap();
+
+        let send = |method: &str, uri: &str, body: serde_json::Value| {
+            let app = make_test_app(Arc::clone(&state));
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let json: serde_json::Value =
+                    serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+                (status, json)
+            }
+        };
+        let job_with = |name: &str, on_success: &[&str], on_failure: &[&str]| {
+            let mut job: serde_json::Value = serde_json::from_str(&new_job_json(name)).unwrap();
+            job["on_success"] = serde_json::json!(on_success);
+            job["on_failure"] = serde_json::json!(on_failure);
+            job
+        };
+
+        let (status, json) = send("POST", "/api/jobs", job_with("b", &["a"], &[])).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["on_success"], serde_json::json!(["a"]));
+
+        let (status, json) = send("POST", "/api/jobs", job_with("c", &[], &["c"])).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "Job 'c' cannot trigger itself");
+
+        let (status, json) = send("POST", "/api/jobs", job_with("d", &["nope"], &[])).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "Follow-up job 'nope' not found");
+
+        let (status, json) = send(
+            "PATCH",
+            "/api/jobs/a",
+            serde_json::json!({"on_failure": ["b"]}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "Follow-up jobs form a loop: a -> b -> a");
+    }
+
+    #[tokio::test]
+    async fn test_job_pool_must_be_configured_and_queue_is_listed() {
+        let (event_tx, _) = broadcast::channel::<JobEvent>(16);
+        let state = Arc::new(AppState {
+            config: Arc::new(DaemonConfig {
+                max_concurrent_runs: 4,
+                concurrency_pools: HashMap::from([("claude".to_string(), 2)]),
+                ..Default::default()
+            }),
+            event_tx,
+            ..Arc::try_unwrap(make_test_state()).ok().unwrap()
+        });
+
+        let post_job = |pool: &str| {
+            let app = make_test_app(Arc::clone(&state));
+            let mut job: serde_json::Value = serde_json::from_str(&new_job_json("pooled")).unwrap();
+            job["pool"] = serde_json::json!(pool);
+            job["priority"] = serde_json::json!(5);
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/jobs")
+                .header("content-type", "application/json")
+                .body(Body::from(job.to_string()))
+                .unwrap();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let json: serde_json::Value =
+                    serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+                (status, json)
+            }
+        };
+
+        let (status, json) = post_job("gpu").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "Concurrency pool 'gpu' is not configured");
+
+        let (status, json) = post_job("claude").await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["pool"], "claude");
+        assert_eq!(json["priority"], 5);
+
+        let app = make_test_app(Arc::clone(&state));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/queue")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json: serde_json::Value =
+            serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+        assert_eq!(json["total"], 0);
+        assert_eq!(json["running"], 0);
+        assert_eq!(json["max_concurrent_runs"], 4);
+        assert_eq!(json["runs"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_list_jobs_by_selector() {
+        let state = make_test_state();
+
+        for (name, tags, labels) in [
+            (
+                "backup",
+                vec!["nightly"],
+                serde_json::json!({"team": "ops"}),
+            ),
+            (
+                "report",
+                vec!["nightly"],
+                serde_json::json!({"team": "data"}),
+            ),
+            ("sync", vec![], serde_json::json!({})),
+        ] {
+            let app = make_test_app(Arc::clone(&state));
+            let mut job: serde_json::Value = serde_json::from_str(&new_job_json(name)).unwrap();
+            job["tags"] = serde_json::json!(tags);
+            job["labels"] = labels;
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/api/jobs")
+                        .header("content-type", "application/json")
+                        .body(Body::from(job.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+
+        let list = |selector: &str| {
+            let app = make_test_app(Arc::clone(&state));
+            let uri = format!("/api/jobs?selector={}", selector);
+            async move {
+                let response = app
+                    .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let json: serde_json::Value =
+                    serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+                (status, json)
+            }
+        };
+        let names = |json: &serde_json::Value| -> Vec<String> {
+            let mut names: Vec<String> = json
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|j| j["name"].as_str().unwrap().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let (status, json) = list("tag%3Dnightly").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(names(&json), ["backup", "report"]);
+
+        let (_, json) = list("tag%3Dnightly,team!%3Dops").await;
+        assert_eq!(names(&json), ["report"]);
+
+        let (_, json) = list("!team").await;
+        assert_eq!(names(&json), ["sync"]);
+
+        let (status, json) = list("tag").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["error"], "validation_error");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_jobs() {
+        let state = make_test_state();
+        let mut events = state.event_tx.subscribe();
+
+        for name in ["a", "b", "c"] {
+            let app = make_test_app(Arc::clone(&state));
+            let mut job: serde_json::Value = serde_json::from_str(&new_job_json(name)).unwrap();
+            job["tags"] = serde_json::json!(if name == "c" { vec![] } else { vec!["nightly"] });
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/api/jobs")
+                        .header("content-type", "application/json")
+                        .body(Body::from(job.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        while events.try_recv().is_ok() {}
+
+        let bulk = |body: serde_json::Value| {
+            let app = make_test_app(Arc::clone(&state));
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::builder()
+                            .method("POST")
+                            .uri("/api/jobs/bulk")
+                            .header("content-type", "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let json: serde_json::Value =
+                    serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+                (status, json)
+            }
+        };
+
+        // Results follow the request order; unknown and repeated jobs are
+        // reported or skipped without failing the others.
+        let (status, json) = bulk(serde_json::json!({
+            "action": "disable",
+            "ids": ["a", "missing", "c", "a"],
+        }))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["total"], 3);
+        assert_eq!(json["succeeded"], 2);
+        assert_eq!(json["failed"], 1);
+        let results = json["results"].as_array().unwrap();
+        assert_eq!(results[0]["job_name"], "a");
+        assert_eq!(results[1]["target"], "missing");
+        assert_eq!(results[1]["ok"], false);
+        assert_eq!(results[1]["error"]["error"], "not_found");
+        assert_eq!(results[2]["job_name"], "c");
+
+        let mut changed = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let JobEvent::JobChanged { job_id, change, .. } = event {
+                assert!(matches!(change, JobChangeKind::Disabled));
+                changed.push(job_id.to_string());
+            }
+        }
+        assert_eq!(
+            changed,
+            [
+                results[0]["job_id"].as_str().unwrap(),
+                results[2]["job_id"].as_str().unwrap()
+            ]
+        );
+
+        let (status, json) = bulk(serde_json::json!({
+            "action": "update",
+            "selector": "tag=nightly",
+            "update": {"timeout_secs": 60},
+        }))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["succeeded"], 2);
+        let jobs = state.job_store.list_jobs().await.unwrap();
+        for job in &jobs {
+            assert_eq!(
+                job.timeout_secs,
+                if job.name == "c" { 0 } else { 60 },
+                "{}",
+                job.name
+            );
+            assert_eq!(job.enabled, job.name == "b", "{}", job.name);
+        }
+
+        let (status, json) = bulk(serde_json::json!({
+            "action": "update",
+            "ids": ["a"],
+            "update": {"name": "z"},
+        }))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["error"], "validation_error");
+
+        let (status, _) = bulk(serde_json::json!({
+            "action": "enable",
+            "ids": ["a"],
+            "selector": "tag=nightly",
+        }))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = bulk(serde_json::json!({
+            "action": "delete",
+            "selector": "tag!=nightly",
+        }))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["succeeded"], 1);
+        assert_eq!(json["results"][0]["job_name"], "c");
+        assert_eq!(state.job_store.list_jobs().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deleted_job_runs_are_gone_with_sqlite_store() {
+        use crate::storage::sqlite::{Database, SqliteJobStore, SqliteLogStore};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = Database::open(&tmp.path().join("acs.db")).unwrap();
+        let job_store = Arc::new(SqliteJobStore::new(db.clone()));
+        let log_store = Arc::new(
+            SqliteLogStore::new(db, tmp.path().to_path_buf())
+                .await
+                .unwrap(),
+        );
+        let job = job_store
+            .create_job(serde_json::from_str(&new_job_json("sqlite-job")).unwrap())
+            .await
+            .unwrap();
+        let run = JobRun {
+            run_id: Uuid::now_v7(),
+            job_id: job.id,
+            started_at: Utc::now(),
+            finished_at: Some(Utc::now()),
+            status: RunStatus::Completed,
+            exit_code: Some(0),
+            log_size_bytes: 0,
+            error: None,
+            trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
+        };
+        log_store.create_run(&run).await.unwrap();
+
+        let state = make_test_state_with_stores(
+            job_store as Arc<dyn JobStore>,
+            log_store as Arc<dyn LogStore>,
+        );
+        let request = |method: &str, uri: String| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let app = make_test_app(state);
+        let response = app
+            .clone()
+            .oneshot(request("GET", format!("/api/runs/{}", run.run_id)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(request("DELETE", format!("/api/jobs/{}", job.id)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        for uri in [
+            format!("/api/runs/{}", run.run_id),
+            format!("/api/runs/{}/log", run.run_id),
+        ] {
+            let response = app.clone().oneshot(request("GET", uri)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+        let response = app
+            .oneshot(request("GET", "/api/runs".to_string()))
+            .await
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&body_string(response.into_body()).await).unwrap();
+        assert_eq!(json["total"], 0);
+    }
 }
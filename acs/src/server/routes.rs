@@ -4,14 +4,23 @@ use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
 use uuid::Uuid;
 
 use super::AppState;
 use crate::daemon::events::{JobChangeKind, JobEvent};
+use crate::daemon::workflows::next_workflow_run;
+use crate::errors::AcsError;
+use crate::models::calendar::validate_calendar;
 use crate::models::job::{validate_job_update, validate_new_job};
-use crate::models::{DispatchRequest, Job, JobUpdate, NewJob, TriggerParams};
+use crate::models::workflow::{validate_new_workflow, validate_workflow_update, WorkflowNode};
+use crate::models::{
+    Calendar, DispatchRequest, Job, JobUpdate, KillReason, LogCompression, NewJob, NewWorkflow,
+    OutputStream, RunStatus, Selector, TriggerParams, TriggerSource, Workflow, WorkflowUpdate,
+};
+use crate::storage::{RunCursor, RunQuery};
 
 // ---------------------------------------------------------------------------
 // Error response
@@ -23,7 +32,7 @@ pub struct ErrorResponse {
     pub message: String,
 }
 
-fn error_response(status: StatusCode, error: &str, message: &str) -> impl IntoResponse {
+pub(crate) fn error_response(status: StatusCode, error: &str, message: &str) -> impl IntoResponse {
     (
         status,
         Json(ErrorResponse {
@@ -94,6 +103,174 @@ async fn resolve_job(
     }
 }
 
+/// Check that every calendar a job refers to exists.
+pub(crate) async fn check_calendars_exist(
+    state: &AppState,
+    names: &[String],
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    for name in names {
+        match state.calendar_store.get_calendar(name).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: "validation_error".to_string(),
+                        message: format!("Calendar '{}' not found", name),
+                    }),
+                ));
+            }
+            Err(e) => {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: "internal_error".to_string(),
+                        message: format!("Failed to fetch calendar: {}", e),
+                    }),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check that a job's concurrency pool is one of the daemon's
+/// `concurrency_pools`. An empty name (leaving the pool) is always fine.
+pub(crate) fn check_pool_exists(
+    state: &AppState,
+    pool: Option<&str>,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    match pool {
+        Some(pool) if !pool.is_empty() && !state.config.concurrency_pools.contains_key(pool) => {
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "validation_error".to_string(),
+                    message: format!("Concurrency pool '{}' is not configured", pool),
+                }),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check the follow-up jobs of job `name` (`id` if it exists already):
+/// each must be another existing job, and following them must never lead
+/// back to this one.
+pub(crate) async fn check_follow_ups(
+    state: &AppState,
+    id: Option<Uuid>,
+    name: &str,
+    on_success: &[String],
+    on_failure: &[String],
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if on_success.is_empty() && on_failure.is_empty() {
+        return Ok(());
+    }
+    let invalid = |message: String| {
+        Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "validation_error".to_string(),
+                message,
+            }),
+        ))
+    };
+    let jobs = match state.job_store.list_jobs().await {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "internal_error".to_string(),
+                    message: format!("Failed to list jobs: {}", e),
+                }),
+            ))
+        }
+    };
+
+    // The job being checked goes by the nil id until it is created, and
+    // by its new name if it is being renamed.
+    let self_id = id.unwrap_or(Uuid::nil());
+    let resolve = |target: &str| {
+        if target == name {
+            return Some(self_id);
+        }
+        jobs.iter()
+            .find(|j| j.name == target || j.id.to_string() == target)
+            .map(|j| j.id)
+    };
+    let name_of = |id: Uuid| {
+        if id == self_id {
+            name.to_string()
+        } else {
+            jobs.iter()
+                .find(|j| j.id == id)
+                .map(|j| j.name.clone())
+                .unwrap_or_default()
+        }
+    };
+
+    let mut targets = Vec::new();
+    for target in on_success.iter().chain(on_failure) {
+        match resolve(target) {
+            Some(target_id) if target_id == self_id => {
+                return invalid(format!("Job '{}' cannot trigger itself", name));
+            }
+            Some(target_id) => targets.push(target_id),
+            None => return invalid(format!("Follow-up job '{}' not found", target)),
+        }
+    }
+
+    // Follow the other jobs' follow-ups, depth first, looking for this job.
+    let follow_ups = |id: Uuid| -> Vec<Uuid> {
+        jobs.iter()
+            .find(|j| j.id == id)
+            .map(|j| {
+                j.on_success
+                    .iter()
+                    .chain(&j.on_failure)
+                    .filter_map(|t| resolve(t))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut stack: Vec<Vec<Uuid>> = targets.iter().map(|t| vec![self_id, *t]).collect();
+    let mut seen = std::collections::HashSet::new();
+    while let Some(path) = stack.pop() {
+        let last = *path.last().unwrap();
+        if !seen.insert(last) {
+            continue;
+        }
+        for next in follow_ups(last) {
+            let mut longer = path.clone();
+            longer.push(next);
+            if next == self_id {
+                let names: Vec<String> = longer.into_iter().map(name_of).collect();
+                return invalid(format!(
+                    "Follow-up jobs form a loop: {}",
+                    names.join(" -> ")
+                ));
+            }
+            stack.push(longer);
+        }
+    }
+    Ok(())
+}
+
+/// The calendars used to work out `next_run_at`. If they cannot be loaded,
+/// every occurrence counts as allowed.
+async fn load_calendars(state: &AppState) -> Vec<Calendar> {
+    state
+        .calendar_store
+        .list_calendars()
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load calendars: {}", e);
+            Vec::new()
+        })
+}
+
 // ---------------------------------------------------------------------------
 // Query params
 // ---------------------------------------------------------------------------
@@ -101,6 +278,9 @@ async fn resolve_job(
 #[derive(Debug, Deserialize, Default)]
 pub struct ListJobsParams {
     pub enabled: Option<bool>,
+    /// Only list jobs matching this tag/label selector, e.g.
+    /// `tag=nightly,team!=ops`.
+    pub selector: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -112,14 +292,127 @@ pub struct ListRunsParams {
     pub status: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SearchRunsParams {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    /// Only runs of this job (name or UUID).
+    pub job: Option<String>,
+    /// Only runs of jobs matching this tag/label selector.
+    pub selector: Option<String>,
+    pub status: Option<String>,
+    pub exit_code: Option<i32>,
+    /// What dispatched the run, e.g. `schedule` or `manual`.
+    pub trigger: Option<String>,
+    pub started_after: Option<String>,
+    pub started_before: Option<String>,
+    pub finished_after: Option<String>,
+    pub finished_before: Option<String>,
+    /// Only runs whose error message contains this text.
+    pub q: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListWorkflowRunsParams {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub offset: usize,
+}
+
 fn default_limit() -> usize {
     20
 }
 
+// ---------------------------------------------------------------------------
+// Bulk operations
+// ---------------------------------------------------------------------------
+
+/// Body of `POST /api/jobs/bulk`: an action applied to the jobs named in
+/// `ids` (names or UUIDs), or to every job matching `selector`.
+#[derive(Debug, Deserialize)]
+pub struct BulkJobsRequest {
+    #[serde(flatten)]
+    pub action: BulkAction,
+    pub ids: Option<Vec<String>>,
+    pub selector: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkAction {
+    Enable,
+    Disable,
+    Delete,
+    Trigger {
+        #[serde(default)]
+        params: Option<TriggerParams>,
+    },
+    /// Apply the same update to every job. Renaming is not allowed.
+    Update {
+        update: Box<JobUpdate>,
+    },
+}
+
+impl BulkAction {
+    fn name(&self) -> &'static str {
+        match self {
+            BulkAction::Enable => "enable",
+            BulkAction::Disable => "disable",
+            BulkAction::Delete => "delete",
+            BulkAction::Trigger { .. } => "trigger",
+            BulkAction::Update { .. } => "update",
+        }
+    }
+}
+
+/// The outcome of a bulk action for one job.
+#[derive(Debug, Serialize)]
+pub struct BulkItemResult {
+    /// The id or name as given, or the job's UUID when matched by selector.
+    pub target: String,
+    pub job_id: Option<Uuid>,
+    pub job_name: Option<String>,
+    pub ok: bool,
+    /// The run started by a `trigger` action.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorResponse>,
+}
+
+impl BulkItemResult {
+    fn succeeded(target: String, job: &Job, run_id: Option<Uuid>) -> Self {
+        BulkItemResult {
+            target,
+            job_id: Some(job.id),
+            job_name: Some(job.name.clone()),
+            ok: true,
+            run_id,
+            error: None,
+        }
+    }
+
+    fn failed(target: String, job: Option<&Job>, error: ErrorResponse) -> Self {
+        BulkItemResult {
+            target,
+            job_id: job.map(|j| j.id),
+            job_name: job.map(|j| j.name.clone()),
+            ok: false,
+            run_id: None,
+            error: Some(error),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub struct GetLogParams {
     pub tail: Option<usize>,
     pub format: Option<String>,
+    /// Only return output from this stream (`stdout` or `stderr`).
+    pub stream: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -131,23 +424,31 @@ pub async fn list_jobs(
     State(state): State<Arc<AppState>>,
     Query(params): Query<ListJobsParams>,
 ) -> impl IntoResponse {
+    let selector = match params.selector.as_deref().map(str::parse::<Selector>) {
+        Some(Ok(selector)) => selector,
+        Some(Err(msg)) => {
+            return error_response(StatusCode::BAD_REQUEST, "validation_error", &msg)
+                .into_response();
+        }
+        None => Selector::default(),
+    };
+
     match state.job_store.list_jobs().await {
         Ok(jobs) => {
-            let mut filtered: Vec<Job> = match params.enabled {
-                Some(enabled) => jobs.into_iter().filter(|j| j.enabled == enabled).collect(),
-                None => jobs,
-            };
+            let mut filtered: Vec<Job> = jobs
+                .into_iter()
+                .filter(|j| params.enabled.is_none_or(|enabled| j.enabled == enabled))
+                .filter(|j| selector.matches(j))
+                .collect();
             // Compute next_run_at for each job (it is #[serde(skip)] so not persisted)
             let now = Utc::now();
+            let calendars = load_calendars(&state).await;
             for job in &mut filtered {
                 if job.enabled {
-                    if let Ok(next) = crate::daemon::scheduler::compute_next_run(
-                        &job.schedule,
-                        job.timezone.as_deref(),
-                        now,
-                    ) {
-                        job.next_run_at = Some(next);
-                    }
+                    job.next_run_at =
+                        crate::daemon::scheduler::next_allowed_run(job, &calendars, now)
+                            .ok()
+                            .flatten();
                 }
             }
             (
@@ -177,6 +478,29 @@ pub async fn create_job(
         return error_response(status, error_code, &e.to_string()).into_response();
     }
 
+    let calendars = [
+        new_job.include_calendars.as_slice(),
+        new_job.exclude_calendars.as_slice(),
+    ]
+    .concat();
+    if let Err(resp) = check_calendars_exist(&state, &calendars).await {
+        return resp.into_response();
+    }
+    if let Err(resp) = check_pool_exists(&state, new_job.pool.as_deref()) {
+        return resp.into_response();
+    }
+    if let Err(resp) = check_follow_ups(
+        &state,
+        None,
+        &new_job.name,
+        &new_job.on_success,
+        &new_job.on_failure,
+    )
+    .await
+    {
+        return resp.into_response();
+    }
+
     // Check duplicate name
     match state.job_store.find_by_name(&new_job.name).await {
         Ok(Some(_)) => {
@@ -248,13 +572,11 @@ pub async fn get_job(
         Ok(mut job) => {
             // Compute next_run_at (it is not persisted)
             if job.enabled {
-                if let Ok(next) = crate::daemon::scheduler::compute_next_run(
-                    &job.schedule,
-                    job.timezone.as_deref(),
-                    Utc::now(),
-                ) {
-                    job.next_run_at = Some(next);
-                }
+                let calendars = load_calendars(&state).await;
+                job.next_run_at =
+                    crate::daemon::scheduler::next_allowed_run(&job, &calendars, Utc::now())
+                        .ok()
+                        .flatten();
             }
             (StatusCode::OK, Json(serde_json::to_value(&job).unwrap())).into_response()
         }
@@ -281,6 +603,31 @@ pub async fn update_job(
             .into_response();
     }
 
+    let calendars = [
+        update.include_calendars.as_deref().unwrap_or_default(),
+        update.exclude_calendars.as_deref().unwrap_or_default(),
+    ]
+    .concat();
+    if let Err(resp) = check_calendars_exist(&state, &calendars).await {
+        return resp.into_response();
+    }
+    if let Err(resp) = check_pool_exists(&state, update.pool.as_deref()) {
+        return resp.into_response();
+    }
+    if update.on_success.is_some() || update.on_failure.is_some() {
+        if let Err(resp) = check_follow_ups(
+            &state,
+            Some(job.id),
+            update.name.as_deref().unwrap_or(&job.name),
+            update.on_success.as_deref().unwrap_or(&job.on_success),
+            update.on_failure.as_deref().unwrap_or(&job.on_failure),
+        )
+        .await
+        {
+            return resp.into_response();
+        }
+    }
+
     // Check name uniqueness (excluding self)
     if let Some(ref new_name) = update.name {
         match state.job_store.find_by_name(new_name).await {
@@ -341,27 +688,96 @@ pub async fn update_job(
     }
 }
 
-/// DELETE /api/jobs/{id}
-pub async fn delete_job(
+/// PUT /api/jobs/{id}
+///
+/// Replace a job's definition, keeping its id and run history. Fields the
+/// body leaves out are reset to their defaults.
+pub async fn replace_job(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
+    Json(new_job): Json<NewJob>,
 ) -> impl IntoResponse {
     let job = match resolve_job(&state, &id).await {
         Ok(j) => j,
         Err(resp) => return resp.into_response(),
     };
 
-    // Kill active run if any
+    if let Err(e) = validate_new_job(&new_job) {
+        tracing::warn!("Job replacement failed for '{}': {}", id, e);
+        return error_response(StatusCode::BAD_REQUEST, "validation_error", &e.to_string())
+            .into_response();
+    }
+
+    let calendars = [
+        new_job.include_calendars.as_slice(),
+        new_job.exclude_calendars.as_slice(),
+    ]
+    .concat();
+    if let Err(resp) = check_calendars_exist(&state, &calendars).await {
+        return resp.into_response();
+    }
+    if let Err(resp) = check_pool_exists(&state, new_job.pool.as_deref()) {
+        return resp.into_response();
+    }
+    if let Err(resp) = check_follow_ups(
+        &state,
+        Some(job.id),
+        &new_job.name,
+        &new_job.on_success,
+        &new_job.on_failure,
+    )
+    .await
     {
-        let mut runs = state.active_runs.write().await;
-        if let Some(handle) = runs.remove(&job.id) {
-            let _ = handle.kill_tx.send(());
+        return resp.into_response();
+    }
+
+    match state.job_store.replace_job(job.id, new_job).await {
+        Ok(replaced) => {
+            tracing::info!("Job '{}' replaced (id: {})", replaced.name, replaced.id);
+
+            let _ = state.event_tx.send(JobEvent::JobChanged {
+                job_id: replaced.id,
+                change: JobChangeKind::Updated,
+                timestamp: Utc::now(),
+            });
+            state.scheduler_notify.notify_one();
+
+            (
+                StatusCode::OK,
+                Json(serde_json::to_value(&replaced).unwrap()),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Job replacement failed: {}", e);
+            let error = store_error(&e);
+            let status = match error.error.as_str() {
+                "conflict" => StatusCode::CONFLICT,
+                "not_found" => StatusCode::NOT_FOUND,
+                "validation_error" => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(error)).into_response()
         }
     }
+}
+
+/// DELETE /api/jobs/{id}
+pub async fn delete_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let job = match resolve_job(&state, &id).await {
+        Ok(j) => j,
+        Err(resp) => return resp.into_response(),
+    };
+
+    stop_deleted_job_runs(&state, job.id).await;
 
     match state.job_store.delete_job(job.id).await {
         Ok(()) => {
             tracing::info!("Job '{}' deleted (id: {})", job.name, job.id);
+            forget_deleted_job_runs(&state, job.id).await;
 
             // Broadcast JobChanged::Removed
             let _ = state.event_tx.send(JobEvent::JobChanged {
@@ -385,6 +801,35 @@ pub async fn delete_job(
     }
 }
 
+/// Kill the active runs of a job that is being deleted, and cancel its
+/// queued runs. Killed runs stay active until their processes exit, when
+/// the dispatcher deregisters them.
+pub(crate) async fn stop_deleted_job_runs(state: &AppState, job_id: Uuid) {
+    {
+        let mut runs = state.active_runs.write().await;
+        for handle in runs.values_mut().filter(|handle| handle.job_id == job_id) {
+            let kill_tx = std::mem::replace(&mut handle.kill_tx, oneshot::channel().0);
+            let _ = kill_tx.send(KillReason::JobDeleted);
+        }
+    }
+    state
+        .run_queue
+        .cancel_job(
+            job_id,
+            KillReason::JobDeleted,
+            state.log_store.as_ref(),
+            &state.event_tx,
+        )
+        .await;
+}
+
+/// Stop finding the runs of a deleted job by run id.
+pub(crate) async fn forget_deleted_job_runs(state: &AppState, job_id: Uuid) {
+    if let Err(e) = state.log_store.forget_job(job_id).await {
+        tracing::warn!("Failed to forget the runs of deleted job {}: {}", job_id, e);
+    }
+}
+
 /// POST /api/jobs/{id}/enable
 pub async fn enable_job(
     State(state): State<Arc<AppState>>,
@@ -501,6 +946,37 @@ pub async fn trigger_job(
         }
     };
 
+    let run_id = match dispatch_trigger(&state, &job, trigger_params).await {
+        Ok(run_id) => run_id,
+        Err(message) => {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                &message,
+            )
+            .into_response();
+        }
+    };
+
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "message": "Job triggered",
+            "job_id": job.id,
+            "job_name": job.name,
+            "run_id": run_id,
+        })),
+    )
+        .into_response()
+}
+
+/// Send a manual run of `job` to the dispatcher, returning its pre-generated
+/// run id.
+async fn dispatch_trigger(
+    state: &AppState,
+    job: &Job,
+    trigger_params: Option<TriggerParams>,
+) -> Result<Uuid, String> {
     // Pre-generate run_id so we can return it in the response
     let run_id = Uuid::now_v7();
 
@@ -510,141 +986,1252 @@ pub async fn trigger_job(
             job: job.clone(),
             run_id,
             trigger_params,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: TriggerSource::Manual,
+            skip_reason: None,
         };
         if let Err(e) = tx.send(request).await {
             tracing::warn!("Failed to trigger job '{}': {}", job.name, e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": "internal_error",
-                    "message": format!("Failed to dispatch job: {}", e),
-                })),
-            )
-                .into_response();
+            return Err(format!("Failed to dispatch job: {}", e));
         }
     }
 
     tracing::info!("Job '{}' triggered (run_id: {})", job.name, run_id);
-
-    (
-        StatusCode::ACCEPTED,
-        Json(serde_json::json!({
-            "message": "Job triggered",
-            "job_id": job.id,
-            "job_name": job.name,
-            "run_id": run_id,
-        })),
-    )
-        .into_response()
+    Ok(run_id)
 }
 
-/// GET /api/jobs/{id}/runs
-pub async fn list_runs(
+/// POST /api/jobs/bulk
+pub async fn bulk_jobs(
     State(state): State<Arc<AppState>>,
-    Path(id): Path<String>,
-    Query(params): Query<ListRunsParams>,
+    Json(request): Json<BulkJobsRequest>,
 ) -> impl IntoResponse {
-    let job = match resolve_job(&state, &id).await {
-        Ok(j) => j,
-        Err(resp) => return resp.into_response(),
+    let invalid = |message: &str| {
+        error_response(StatusCode::BAD_REQUEST, "validation_error", message).into_response()
     };
 
-    match state
-        .log_store
-        .list_runs(job.id, params.limit, params.offset)
-        .await
-    {
-        Ok((runs, total)) => {
-            // Apply status filter if provided
-            let filtered = match params.status {
-                Some(ref status_filter) => {
-                    let s = status_filter.to_lowercase();
-                    runs.into_iter()
-                        .filter(|r| {
-                            let rs = format!("{:?}", r.status).to_lowercase();
-                            rs == s
-                        })
-                        .collect::<Vec<_>>()
+    if let BulkAction::Update { ref update } = request.action {
+        if update.name.is_some() {
+            return invalid("Jobs cannot be renamed in bulk");
+        }
+        if let Err(e) = validate_job_update(update) {
+            return invalid(&e.to_string());
+        }
+        let calendars = [
+            update.include_calendars.as_deref().unwrap_or_default(),
+            update.exclude_calendars.as_deref().unwrap_or_default(),
+        ]
+        .concat();
+        if let Err(resp) = check_calendars_exist(&state, &calendars).await {
+            return resp.into_response();
+        }
+        if let Err(resp) = check_pool_exists(&state, update.pool.as_deref()) {
+            return resp.into_response();
+        }
+    }
+
+    // Resolve the targets. Jobs that cannot be resolved fail on their own;
+    // a job named twice is only acted on once.
+    let mut results: Vec<(usize, BulkItemResult)> = Vec::new();
+    let mut targets: Vec<BulkTarget> = Vec::new();
+    match (request.ids, request.selector) {
+        (Some(ids), None) => {
+            for (index, target) in ids.into_iter().enumerate() {
+                match resolve_job(&state, &target).await {
+                    Ok(job) if targets.iter().any(|t| t.job.id == job.id) => {}
+                    Ok(job) => targets.push(BulkTarget { index, target, job }),
+                    Err((_, Json(error))) => {
+                        results.push((index, BulkItemResult::failed(target, None, error)));
+                    }
                 }
-                None => runs,
+            }
+        }
+        (None, Some(selector)) => {
+            let selector = match selector.parse::<Selector>() {
+                Ok(selector) => selector,
+                Err(msg) => return invalid(&msg),
             };
+            match state.job_store.list_jobs().await {
+                Ok(jobs) => {
+                    targets = jobs
+                        .into_iter()
+                        .filter(|j| selector.matches(j))
+                        .enumerate()
+                        .map(|(index, job)| BulkTarget {
+                            index,
+                            target: job.id.to_string(),
+                            job,
+                        })
+                        .collect();
+                }
+                Err(e) => {
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "internal_error",
+                        &format!("Failed to list jobs: {}", e),
+                    )
+                    .into_response();
+                }
+            }
+        }
+        _ => return invalid("Exactly one of ids and selector must be given"),
+    }
 
-            (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "runs": filtered,
-                    "total": total,
-                    "limit": params.limit,
-                    "offset": params.offset,
-                })),
-            )
-                .into_response()
+    let action = request.action.name();
+    let (change, outcome) = match request.action {
+        BulkAction::Enable => {
+            let update = JobUpdate {
+                enabled: Some(true),
+                ..Default::default()
+            };
+            let outcome = bulk_update(&state, targets, update, &mut results).await;
+            (Some(JobChangeKind::Enabled), outcome)
         }
-        Err(e) => error_response(
+        BulkAction::Disable => {
+            let update = JobUpdate {
+                enabled: Some(false),
+                ..Default::default()
+            };
+            let outcome = bulk_update(&state, targets, update, &mut results).await;
+            (Some(JobChangeKind::Disabled), outcome)
+        }
+        BulkAction::Update { update } => {
+            let outcome = bulk_update(&state, targets, *update, &mut results).await;
+            (Some(JobChangeKind::Updated), outcome)
+        }
+        BulkAction::Delete => {
+            let outcome = bulk_delete(&state, targets, &mut results).await;
+            (Some(JobChangeKind::Removed), outcome)
+        }
+        BulkAction::Trigger { params } => {
+            for t in targets {
+                let result = match dispatch_trigger(&state, &t.job, params.clone()).await {
+                    Ok(run_id) => BulkItemResult::succeeded(t.target, &t.job, Some(run_id)),
+                    Err(message) => BulkItemResult::failed(
+                        t.target,
+                        Some(&t.job),
+                        ErrorResponse {
+                            error: "internal_error".to_string(),
+                            message,
+                        },
+                    ),
+                };
+                results.push((t.index, result));
+            }
+            (None, Ok(()))
+        }
+    };
+    if let Err(e) = outcome {
+        tracing::warn!("Bulk {} failed: {}", action, e);
+        return error_response(
             StatusCode::INTERNAL_SERVER_ERROR,
             "internal_error",
-            &format!("Failed to list runs: {}", e),
+            &format!("Failed to save jobs: {}", e),
         )
-        .into_response(),
+        .into_response();
+    }
+    results.sort_by_key(|(index, _)| *index);
+    let results: Vec<BulkItemResult> = results.into_iter().map(|(_, r)| r).collect();
+
+    // One JobChanged per job that changed, and one scheduler wake-up.
+    if let Some(change) = change {
+        let timestamp = Utc::now();
+        for job_id in results.iter().filter(|r| r.ok).filter_map(|r| r.job_id) {
+            let _ = state.event_tx.send(JobEvent::JobChanged {
+                job_id,
+                change: change.clone(),
+                timestamp,
+            });
+        }
+        if results.iter().any(|r| r.ok) {
+            state.scheduler_notify.notify_one();
+        }
+    }
+
+    let succeeded = results.iter().filter(|r| r.ok).count();
+    tracing::info!(
+        "Bulk {}: {} of {} jobs succeeded",
+        action,
+        succeeded,
+        results.len()
+    );
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "action": action,
+            "total": results.len(),
+            "succeeded": succeeded,
+            "failed": results.len() - succeeded,
+            "results": results,
+        })),
+    )
+        .into_response()
+}
+
+/// The error of a job store call, classified like the single-job endpoints
+/// classify theirs.
+fn store_error(e: &anyhow::Error) -> ErrorResponse {
+    let error = match e.downcast_ref::<AcsError>() {
+        Some(AcsError::NotFound(_)) => "not_found",
+        Some(AcsError::Conflict(_)) => "conflict",
+        Some(AcsError::Validation(_)) => "validation_error",
+        _ => "internal_error",
+    };
+    ErrorResponse {
+        error: error.to_string(),
+        message: e.to_string(),
+    }
+}
+
+/// A job a bulk action applies to, with its position in the request.
+struct BulkTarget {
+    index: usize,
+    target: String,
+    job: Job,
+}
+
+/// Apply `update` to every target with a single store write.
+async fn bulk_update(
+    state: &AppState,
+    targets: Vec<BulkTarget>,
+    update: JobUpdate,
+    results: &mut Vec<(usize, BulkItemResult)>,
+) -> anyhow::Result<()> {
+    let mut valid = Vec::new();
+    for t in targets {
+        if update.on_success.is_some() || update.on_failure.is_some() {
+            if let Err((_, Json(error))) = check_follow_ups(
+                state,
+                Some(t.job.id),
+                &t.job.name,
+                update.on_success.as_deref().unwrap_or(&t.job.on_success),
+                update.on_failure.as_deref().unwrap_or(&t.job.on_failure),
+            )
+            .await
+            {
+                results.push((
+                    t.index,
+                    BulkItemResult::failed(t.target, Some(&t.job), error),
+                ));
+                continue;
+            }
+        }
+        valid.push(t);
+    }
+
+    let updates = valid.iter().map(|t| (t.job.id, update.clone())).collect();
+    let outcomes = state.job_store.update_jobs(updates).await?;
+    for (t, outcome) in valid.into_iter().zip(outcomes) {
+        let result = match outcome {
+            Ok(updated) => BulkItemResult::succeeded(t.target, &updated, None),
+            Err(e) => BulkItemResult::failed(t.target, Some(&t.job), store_error(&e)),
+        };
+        results.push((t.index, result));
+    }
+    Ok(())
+}
+
+/// Delete every target with a single store write, stopping their runs first.
+async fn bulk_delete(
+    state: &AppState,
+    targets: Vec<BulkTarget>,
+    results: &mut Vec<(usize, BulkItemResult)>,
+) -> anyhow::Result<()> {
+    for t in &targets {
+        stop_deleted_job_runs(state, t.job.id).await;
+    }
+    let ids: Vec<Uuid> = targets.iter().map(|t| t.job.id).collect();
+    let outcomes = state.job_store.delete_jobs(&ids).await?;
+    for (t, outcome) in targets.into_iter().zip(outcomes) {
+        let result = match outcome {
+            Ok(()) => {
+                forget_deleted_job_runs(state, t.job.id).await;
+                BulkItemResult::succeeded(t.target, &t.job, None)
+            }
+            Err(e) => BulkItemResult::failed(t.target, Some(&t.job), store_error(&e)),
+        };
+        results.push((t.index, result));
+    }
+    Ok(())
+}
+
+/// GET /api/jobs/{id}/runs
+pub async fn list_runs(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<ListRunsParams>,
+) -> impl IntoResponse {
+    let job = match resolve_job(&state, &id).await {
+        Ok(j) => j,
+        Err(resp) => return resp.into_response(),
+    };
+    let status = match params.status.as_deref().map(str::parse::<RunStatus>) {
+        Some(Ok(status)) => Some(status),
+        Some(Err(msg)) => {
+            return error_response(StatusCode::BAD_REQUEST, "validation_error", &msg)
+                .into_response();
+        }
+        None => None,
+    };
+
+    let query = RunQuery {
+        job_id: Some(job.id),
+        status,
+        ..Default::default()
+    };
+    match state
+        .log_store
+        .query_runs(&query, params.limit, params.offset)
+        .await
+    {
+        Ok((runs, total)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "runs": runs,
+                "total": total,
+                "limit": params.limit,
+                "offset": params.offset,
+            })),
+        )
+            .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to list runs: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// GET /api/runs
+///
+/// Runs of every job, newest first, filtered by the query parameters. Pass
+/// a page's `next_cursor` as `cursor` to get the next page.
+pub async fn search_runs(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchRunsParams>,
+) -> impl IntoResponse {
+    let mut query = match run_query(&params) {
+        Ok(query) => query,
+        Err(msg) => {
+            return error_response(StatusCode::BAD_REQUEST, "validation_error", &msg)
+                .into_response();
+        }
+    };
+
+    if let Some(job) = &params.job {
+        match resolve_job(&state, job).await {
+            Ok(job) => query.job_id = Some(job.id),
+            Err(resp) => return resp.into_response(),
+        }
+    }
+    if let Some(selector) = &params.selector {
+        let selector = match selector.parse::<Selector>() {
+            Ok(selector) => selector,
+            Err(msg) => {
+                return error_response(StatusCode::BAD_REQUEST, "validation_error", &msg)
+                    .into_response();
+            }
+        };
+        match state.job_store.list_jobs().await {
+            Ok(jobs) => {
+                query.job_ids = Some(
+                    jobs.iter()
+                        .filter(|j| selector.matches(j))
+                        .map(|j| j.id)
+                        .collect(),
+                );
+            }
+            Err(e) => {
+                return error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    &format!("Failed to list jobs: {}", e),
+                )
+                .into_response();
+            }
+        }
+    }
+
+    // One run past the page tells whether there is a next page.
+    let limit = params.limit.max(1);
+    match state
+        .log_store
+        .query_runs(&query, limit.saturating_add(1), 0)
+        .await
+    {
+        Ok((mut runs, total)) => {
+            let next_cursor = if runs.len() > limit {
+                runs.truncate(limit);
+                runs.last().map(|run| RunCursor::after(run).to_string())
+            } else {
+                None
+            };
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "runs": runs,
+                    "total": total,
+                    "limit": limit,
+                    "next_cursor": next_cursor,
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to list runs: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// The filters of a `GET /api/runs` request, apart from the job ones that
+/// need the job store.
+fn run_query(params: &SearchRunsParams) -> Result<RunQuery, String> {
+    fn parse<T: std::str::FromStr<Err = String>>(
+        value: &Option<String>,
+    ) -> Result<Option<T>, String> {
+        value.as_deref().map(str::parse).transpose()
+    }
+    fn time(name: &str, value: &Option<String>) -> Result<Option<DateTime<Utc>>, String> {
+        value
+            .as_deref()
+            .map(|v| {
+                DateTime::parse_from_rfc3339(v)
+                    .map(|t| t.with_timezone(&Utc))
+                    .map_err(|_| {
+                        format!("Invalid {} '{}': expected an RFC 3339 timestamp", name, v)
+                    })
+            })
+            .transpose()
+    }
+
+    Ok(RunQuery {
+        status: parse(&params.status)?,
+        exit_code: params.exit_code,
+        trigger: parse(&params.trigger)?,
+        started_after: time("started_after", &params.started_after)?,
+        started_before: time("started_before", &params.started_before)?,
+        finished_after: time("finished_after", &params.finished_after)?,
+        finished_before: time("finished_before", &params.finished_before)?,
+        error_contains: params.q.clone().filter(|q| !q.is_empty()),
+        before: parse(&params.cursor)?,
+        ..Default::default()
+    })
+}
+
+/// GET /api/runs/{run_id}/log
+pub async fn get_log(
+    State(state): State<Arc<AppState>>,
+    Path(run_id_str): Path<String>,
+    Query(params): Query<GetLogParams>,
+) -> impl IntoResponse {
+    let run_id = match Uuid::parse_str(&run_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "validation_error",
+                "Invalid run_id format",
+            )
+            .into_response();
+        }
+    };
+
+    let stream = match params.stream.as_deref().map(str::parse::<OutputStream>) {
+        Some(Ok(stream)) => Some(stream),
+        Some(Err(msg)) => {
+            return error_response(StatusCode::BAD_REQUEST, "validation_error", &msg)
+                .into_response();
+        }
+        None => None,
+    };
+
+    let run = match state.log_store.find_run(run_id).await {
+        Ok(Some(run)) => run,
+        Ok(None) => {
+            return error_response(
+                StatusCode::NOT_FOUND,
+                "not_found",
+                &format!("Run '{}' not found", run_id),
+            )
+            .into_response();
+        }
+        Err(e) => {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                &format!("Failed to look up run: {}", e),
+            )
+            .into_response();
+        }
+    };
+
+    // A run with no output (or none on the requested stream) has an empty log.
+    let content = match stream {
+        Some(stream) => {
+            state
+                .log_store
+                .read_output(run.job_id, run_id, stream, params.tail)
+                .await
+        }
+        None => {
+            state
+                .log_store
+                .read_log(run.job_id, run_id, params.tail)
+                .await
+        }
+    };
+    match content {
+        Ok(content) => (
+            StatusCode::OK,
+            [(axum::http::header::CONTENT_TYPE, "text/plain")],
+            content,
+        )
+            .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to read log: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// GET /api/runs/{run_id}
+pub async fn get_run(
+    State(state): State<Arc<AppState>>,
+    Path(run_id_str): Path<String>,
+) -> impl IntoResponse {
+    let run_id = match Uuid::parse_str(&run_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "validation_error",
+                "Invalid run_id format",
+            )
+            .into_response();
+        }
+    };
+
+    match state.log_store.find_run(run_id).await {
+        Ok(Some(run)) => (StatusCode::OK, Json(run)).into_response(),
+        Ok(None) => error_response(
+            StatusCode::NOT_FOUND,
+            "not_found",
+            &format!("Run '{}' not found", run_id),
+        )
+        .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to look up run: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// A run currently executing, as reported by `GET /api/runs/active`.
+#[derive(Debug, Serialize)]
+pub struct ActiveRun {
+    pub run_id: Uuid,
+    pub job_id: Uuid,
+    pub job_name: String,
+    pub started_at: chrono::DateTime<Utc>,
+}
+
+/// GET /api/runs/active
+pub async fn list_active_runs(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut runs: Vec<ActiveRun> = state
+        .active_runs
+        .read()
+        .await
+        .values()
+        .map(|handle| ActiveRun {
+            run_id: handle.run_id,
+            job_id: handle.job_id,
+            job_name: handle.job_name.clone(),
+            started_at: handle.started_at,
+        })
+        .collect();
+    runs.sort_by_key(|run| run.started_at);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "total": runs.len(),
+            "runs": runs,
+        })),
+    )
+}
+
+/// GET /api/queue
+///
+/// Runs waiting for a free slot, in the order they will start.
+pub async fn list_queue(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let runs = state.run_queue.list().await;
+    let running = state.active_runs.read().await.len();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "total": runs.len(),
+            "running": running,
+            "max_concurrent_runs": state.config.max_concurrent_runs,
+            "runs": runs,
+        })),
+    )
+}
+
+/// Extra time, on top of the kill grace period, that a cancel request waits
+/// for the run to record its final status.
+const CANCEL_WAIT_MARGIN: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How often a cancel request checks whether the run has exited.
+const CANCEL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// POST /api/runs/{run_id}/cancel
+///
+/// Stops an in-flight run (SIGTERM, then SIGKILL after the grace period) and
+/// returns its final `JobRun`. If the run has not finished recording its
+/// status in time, the current record is returned with 202 Accepted. A run
+/// still waiting in the run queue is taken out of it and recorded as
+/// `Killed` without ever starting.
+pub async fn cancel_run(
+    State(state): State<Arc<AppState>>,
+    Path(run_id_str): Path<String>,
+) -> impl IntoResponse {
+    let run_id = match Uuid::parse_str(&run_id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "validation_error",
+                "Invalid run_id format",
+            )
+            .into_response();
+        }
+    };
+
+    // Signal the run but leave its handle registered: the dispatcher keeps
+    // treating the job as busy until the process has exited and the
+    // executor wrapper deregisters it.
+    let job_id = state
+        .active_runs
+        .write()
+        .await
+        .get_mut(&run_id)
+        .map(|handle| {
+            let kill_tx = std::mem::replace(&mut handle.kill_tx, oneshot::channel().0);
+            let _ = kill_tx.send(KillReason::Cancelled);
+            handle.job_id
+        });
+
+    let Some(job_id) = job_id else {
+        if let Some(run) = state
+            .run_queue
+            .cancel(
+                run_id,
+                KillReason::Cancelled,
+                state.log_store.as_ref(),
+                &state.event_tx,
+            )
+            .await
+        {
+            return (StatusCode::OK, Json(run)).into_response();
+        }
+        return match state.log_store.find_run(run_id).await {
+            Ok(Some(run)) if run.status != RunStatus::Running => error_response(
+                StatusCode::CONFLICT,
+                "conflict",
+                &format!("Run '{}' is not running (status: {:?})", run_id, run.status),
+            )
+            .into_response(),
+            Ok(_) => error_response(
+                StatusCode::NOT_FOUND,
+                "not_found",
+                &format!("No active run '{}'", run_id),
+            )
+            .into_response(),
+            Err(e) => error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                &format!("Failed to look up run: {}", e),
+            )
+            .into_response(),
+        };
+    };
+
+    tracing::info!("Cancelling run {} of job {}", run_id, job_id);
+
+    // Wait for the executor to stop the process and record the final status.
+    let wait =
+        std::time::Duration::from_secs(state.config.kill_grace_period_secs) + CANCEL_WAIT_MARGIN;
+    let finished = tokio::time::timeout(wait, async {
+        while state.active_runs.read().await.contains_key(&run_id) {
+            tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .is_ok();
+
+    let run = match state.log_store.find_run(run_id).await {
+        Ok(run) => run,
+        Err(e) => {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                &format!("Failed to read run: {}", e),
+            )
+            .into_response();
+        }
+    };
+
+    match run {
+        Some(run) => {
+            let status = if finished {
+                StatusCode::OK
+            } else {
+                StatusCode::ACCEPTED
+            };
+            (status, Json(run)).into_response()
+        }
+        None => error_response(
+            StatusCode::NOT_FOUND,
+            "not_found",
+            &format!("Run '{}' not found", run_id),
+        )
+        .into_response(),
+    }
+}
+
+/// GET /api/calendars
+pub async fn list_calendars(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match state.calendar_store.list_calendars().await {
+        Ok(calendars) => (
+            StatusCode::OK,
+            Json(serde_json::to_value(&calendars).unwrap()),
+        )
+            .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to list calendars: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// POST /api/calendars
+pub async fn create_calendar(
+    State(state): State<Arc<AppState>>,
+    Json(calendar): Json<Calendar>,
+) -> impl IntoResponse {
+    if let Err(e) = validate_calendar(&calendar) {
+        tracing::warn!("Calendar creation failed: {}", e);
+        return error_response(StatusCode::BAD_REQUEST, "validation_error", &e.to_string())
+            .into_response();
+    }
+
+    match state.calendar_store.create_calendar(calendar).await {
+        Ok(calendar) => {
+            tracing::info!("Calendar '{}' created", calendar.name);
+            (
+                StatusCode::CREATED,
+                Json(serde_json::to_value(&calendar).unwrap()),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            let err_str = e.to_string();
+            tracing::warn!("Calendar creation failed: {}", err_str);
+            if err_str.contains("already exists") {
+                error_response(StatusCode::CONFLICT, "conflict", &err_str).into_response()
+            } else {
+                error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    &err_str,
+                )
+                .into_response()
+            }
+        }
+    }
+}
+
+/// GET /api/calendars/{name}
+pub async fn get_calendar(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match state.calendar_store.get_calendar(&name).await {
+        Ok(Some(calendar)) => (
+            StatusCode::OK,
+            Json(serde_json::to_value(&calendar).unwrap()),
+        )
+            .into_response(),
+        Ok(None) => error_response(
+            StatusCode::NOT_FOUND,
+            "not_found",
+            &format!("Calendar '{}' not found", name),
+        )
+        .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to fetch calendar: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// PUT /api/calendars/{name}
+///
+/// Replaces the calendar's periods. The name in the path wins over any name
+/// in the body; calendars cannot be renamed.
+pub async fn update_calendar(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(mut calendar): Json<Calendar>,
+) -> impl IntoResponse {
+    calendar.name = name;
+    if let Err(e) = validate_calendar(&calendar) {
+        tracing::warn!("Calendar update failed for '{}': {}", calendar.name, e);
+        return error_response(StatusCode::BAD_REQUEST, "validation_error", &e.to_string())
+            .into_response();
+    }
+
+    match state.calendar_store.update_calendar(calendar).await {
+        Ok(calendar) => {
+            tracing::info!("Calendar '{}' updated", calendar.name);
+            // Jobs referencing it may now be due at other times.
+            state.scheduler_notify.notify_one();
+            (
+                StatusCode::OK,
+                Json(serde_json::to_value(&calendar).unwrap()),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            let err_str = e.to_string();
+            tracing::warn!("Calendar update failed: {}", err_str);
+            if err_str.contains("not found") {
+                error_response(StatusCode::NOT_FOUND, "not_found", &err_str).into_response()
+            } else {
+                error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    &err_str,
+                )
+                .into_response()
+            }
+        }
+    }
+}
+
+/// DELETE /api/calendars/{name}
+///
+/// Refused with 409 while any job still references the calendar.
+pub async fn delete_calendar(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let jobs = match state.job_store.list_jobs().await {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                &format!("Failed to list jobs: {}", e),
+            )
+            .into_response();
+        }
+    };
+    let users: Vec<&str> = jobs
+        .iter()
+        .filter(|j| j.include_calendars.contains(&name) || j.exclude_calendars.contains(&name))
+        .map(|j| j.name.as_str())
+        .collect();
+    if !users.is_empty() {
+        tracing::warn!("Calendar deletion failed: '{}' is in use", name);
+        return error_response(
+            StatusCode::CONFLICT,
+            "conflict",
+            &format!(
+                "Calendar '{}' is used by job(s): {}",
+                name,
+                users.join(", ")
+            ),
+        )
+        .into_response();
+    }
+
+    match state.calendar_store.delete_calendar(&name).await {
+        Ok(()) => {
+            tracing::info!("Calendar '{}' deleted", name);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => {
+            let err_str = e.to_string();
+            tracing::warn!("Calendar deletion failed for '{}': {}", name, err_str);
+            if err_str.contains("not found") {
+                error_response(StatusCode::NOT_FOUND, "not_found", &err_str).into_response()
+            } else {
+                error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    &err_str,
+                )
+                .into_response()
+            }
+        }
     }
 }
 
-/// GET /api/runs/{run_id}/log
-pub async fn get_log(
+// ---------------------------------------------------------------------------
+// Workflows
+// ---------------------------------------------------------------------------
+
+async fn resolve_workflow(
+    state: &AppState,
+    id_or_name: &str,
+) -> Result<Workflow, (StatusCode, Json<ErrorResponse>)> {
+    let found = match Uuid::parse_str(id_or_name) {
+        Ok(uuid) => state.workflow_store.get_workflow(uuid).await,
+        Err(_) => state.workflow_store.find_workflow_by_name(id_or_name).await,
+    };
+    match found {
+        Ok(Some(workflow)) => Ok(workflow),
+        Ok(None) => {
+            tracing::warn!("Workflow not found: '{}'", id_or_name);
+            Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "not_found".to_string(),
+                    message: format!("Workflow '{}' not found", id_or_name),
+                }),
+            ))
+        }
+        Err(e) => {
+            tracing::warn!("Failed to fetch workflow '{}': {}", id_or_name, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "internal_error".to_string(),
+                    message: format!("Failed to fetch workflow: {}", e),
+                }),
+            ))
+        }
+    }
+}
+
+/// Check that the job of every workflow node exists.
+async fn check_node_jobs_exist(
+    state: &AppState,
+    nodes: &[WorkflowNode],
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    for node in nodes {
+        match resolve_job(state, &node.job).await {
+            Ok(_) => {}
+            Err((status, _)) if status == StatusCode::NOT_FOUND => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: "validation_error".to_string(),
+                        message: format!("Job '{}' of node '{}' not found", node.job, node.name),
+                    }),
+                ));
+            }
+            Err(resp) => return Err(resp),
+        }
+    }
+    Ok(())
+}
+
+/// Fill in `next_run_at` (it is not persisted).
+fn with_next_run(mut workflow: Workflow) -> Workflow {
+    if workflow.enabled {
+        workflow.next_run_at = next_workflow_run(&workflow, Utc::now()).ok().flatten();
+    }
+    workflow
+}
+
+/// GET /api/workflows
+pub async fn list_workflows(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match state.workflow_store.list_workflows().await {
+        Ok(workflows) => {
+            let workflows: Vec<Workflow> = workflows.into_iter().map(with_next_run).collect();
+            (
+                StatusCode::OK,
+                Json(serde_json::to_value(&workflows).unwrap()),
+            )
+                .into_response()
+        }
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to list workflows: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// POST /api/workflows
+pub async fn create_workflow(
     State(state): State<Arc<AppState>>,
-    Path(run_id_str): Path<String>,
-    Query(params): Query<GetLogParams>,
+    Json(new_workflow): Json<NewWorkflow>,
 ) -> impl IntoResponse {
-    let run_id = match Uuid::parse_str(&run_id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                "validation_error",
-                "Invalid run_id format",
-            )
+    if let Err(e) = validate_new_workflow(&new_workflow) {
+        tracing::warn!("Workflow creation failed: {}", e);
+        return error_response(StatusCode::BAD_REQUEST, "validation_error", &e.to_string())
             .into_response();
+    }
+    if let Err(resp) = check_node_jobs_exist(&state, &new_workflow.nodes).await {
+        return resp.into_response();
+    }
+
+    match state.workflow_store.create_workflow(new_workflow).await {
+        Ok(workflow) => {
+            tracing::info!("Workflow '{}' created (id: {})", workflow.name, workflow.id);
+            if let Some(ref engine) = state.workflow_engine {
+                engine.workflows_changed();
+            }
+            (
+                StatusCode::CREATED,
+                Json(serde_json::to_value(with_next_run(workflow)).unwrap()),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            let err_str = e.to_string();
+            tracing::warn!("Workflow creation failed: {}", err_str);
+            if err_str.contains("already exists") {
+                error_response(StatusCode::CONFLICT, "conflict", &err_str).into_response()
+            } else {
+                error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    &err_str,
+                )
+                .into_response()
+            }
         }
+    }
+}
+
+/// GET /api/workflows/{id}
+pub async fn get_workflow(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match resolve_workflow(&state, &id).await {
+        Ok(workflow) => (
+            StatusCode::OK,
+            Json(serde_json::to_value(with_next_run(workflow)).unwrap()),
+        )
+            .into_response(),
+        Err(resp) => resp.into_response(),
+    }
+}
+
+/// PATCH /api/workflows/{id}
+pub async fn update_workflow(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(update): Json<WorkflowUpdate>,
+) -> impl IntoResponse {
+    let workflow = match resolve_workflow(&state, &id).await {
+        Ok(w) => w,
+        Err(resp) => return resp.into_response(),
     };
 
-    // We need to find the job_id for this run_id. Search across all jobs.
-    let jobs = match state.job_store.list_jobs().await {
-        Ok(j) => j,
-        Err(e) => {
-            return error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "internal_error",
-                &format!("Failed to list jobs: {}", e),
-            )
+    if let Err(e) = validate_workflow_update(&update) {
+        tracing::warn!("Workflow update failed for '{}': {}", id, e);
+        return error_response(StatusCode::BAD_REQUEST, "validation_error", &e.to_string())
             .into_response();
+    }
+    if let Some(ref nodes) = update.nodes {
+        if let Err(resp) = check_node_jobs_exist(&state, nodes).await {
+            return resp.into_response();
         }
-    };
+    }
 
-    // Try each job to find the run
-    for job in &jobs {
-        match state.log_store.read_log(job.id, run_id, params.tail).await {
-            Ok(content) if !content.is_empty() => {
-                return (
-                    StatusCode::OK,
-                    [(axum::http::header::CONTENT_TYPE, "text/plain")],
-                    content,
+    match state
+        .workflow_store
+        .update_workflow(workflow.id, update)
+        .await
+    {
+        Ok(updated) => {
+            tracing::info!("Workflow '{}' updated (id: {})", updated.name, updated.id);
+            if let Some(ref engine) = state.workflow_engine {
+                engine.workflows_changed();
+            }
+            (
+                StatusCode::OK,
+                Json(serde_json::to_value(with_next_run(updated)).unwrap()),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            let err_str = e.to_string();
+            tracing::warn!("Workflow update failed: {}", err_str);
+            if err_str.contains("already exists") {
+                error_response(StatusCode::CONFLICT, "conflict", &err_str).into_response()
+            } else if err_str.contains("Validation") {
+                error_response(StatusCode::BAD_REQUEST, "validation_error", &err_str)
+                    .into_response()
+            } else {
+                error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    &err_str,
                 )
-                    .into_response();
+                .into_response()
             }
-            _ => continue,
         }
     }
+}
 
-    // Also try with a direct read using a nil job_id approach
-    // If no log found in any job, check if there is log data with any job_id
-    // In the InMemoryLogStore test case, job_id must match, so we try all.
-    // If still not found, return 404
-    error_response(
-        StatusCode::NOT_FOUND,
-        "not_found",
-        &format!("Log for run '{}' not found", run_id),
-    )
-    .into_response()
+/// DELETE /api/workflows/{id}
+///
+/// Also deletes the workflow's runs. Job runs already dispatched for a run
+/// in progress are left to finish.
+pub async fn delete_workflow(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workflow = match resolve_workflow(&state, &id).await {
+        Ok(w) => w,
+        Err(resp) => return resp.into_response(),
+    };
+
+    match state.workflow_store.delete_workflow(workflow.id).await {
+        Ok(()) => {
+            tracing::info!("Workflow '{}' deleted (id: {})", workflow.name, workflow.id);
+            if let Some(ref engine) = state.workflow_engine {
+                engine.workflows_changed();
+            }
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to delete workflow: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// POST /api/workflows/{id}/trigger
+///
+/// Starts a run now, whatever the schedule, and returns it with its root
+/// nodes dispatched.
+pub async fn trigger_workflow(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let workflow = match resolve_workflow(&state, &id).await {
+        Ok(w) => w,
+        Err(resp) => return resp.into_response(),
+    };
+    let Some(ref engine) = state.workflow_engine else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "unavailable",
+            "The workflow engine is not running",
+        )
+        .into_response();
+    };
+
+    match engine.start(&workflow, None).await {
+        Ok(run) => (StatusCode::ACCEPTED, Json(run)).into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to start workflow: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// GET /api/workflows/{id}/runs
+pub async fn list_workflow_runs(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<ListWorkflowRunsParams>,
+) -> impl IntoResponse {
+    let workflow = match resolve_workflow(&state, &id).await {
+        Ok(w) => w,
+        Err(resp) => return resp.into_response(),
+    };
+
+    match state
+        .workflow_store
+        .list_workflow_runs(workflow.id, params.limit, params.offset)
+        .await
+    {
+        Ok((runs, total)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "runs": runs,
+                "total": total,
+                "limit": params.limit,
+                "offset": params.offset,
+            })),
+        )
+            .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to list workflow runs: {}", e),
+        )
+        .into_response(),
+    }
+}
+
+/// GET /api/workflows/{id}/runs/{run_id}
+pub async fn get_workflow_run(
+    State(state): State<Arc<AppState>>,
+    Path((id, run_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let workflow = match resolve_workflow(&state, &id).await {
+        Ok(w) => w,
+        Err(resp) => return resp.into_response(),
+    };
+    let Ok(run_id) = Uuid::parse_str(&run_id) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "validation_error",
+            "Invalid run_id format",
+        )
+        .into_response();
+    };
+
+    match state
+        .workflow_store
+        .get_workflow_run(workflow.id, run_id)
+        .await
+    {
+        Ok(Some(run)) => (StatusCode::OK, Json(run)).into_response(),
+        Ok(None) => error_response(
+            StatusCode::NOT_FOUND,
+            "not_found",
+            &format!("Workflow run '{}' not found", run_id),
+        )
+        .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to fetch workflow run: {}", e),
+        )
+        .into_response(),
+    }
 }
 
 /// POST /api/shutdown
@@ -714,6 +2301,79 @@ pub async fn get_daemon_logs(
     }
 }
 
+/// Request body for `POST /api/maintenance/compress`.
+#[derive(Debug, Default, Deserialize)]
+pub struct CompressLogsRequest {
+    /// Defaults to the daemon's `log_compression`, or gzip if that is `none`.
+    #[serde(default)]
+    pub format: Option<LogCompression>,
+}
+
+/// POST /api/maintenance/compress — compress the logs of finished runs
+pub async fn compress_logs(
+    State(state): State<Arc<AppState>>,
+    body: axum::body::Bytes,
+) -> impl IntoResponse {
+    let request: CompressLogsRequest = if body.is_empty() {
+        CompressLogsRequest::default()
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(request) => request,
+            Err(e) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "validation_error",
+                    &format!("Invalid compress body: {}", e),
+                )
+                .into_response();
+            }
+        }
+    };
+    let format = match request.format {
+        Some(LogCompression::None) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "validation_error",
+                "format must be gzip or zstd",
+            )
+            .into_response();
+        }
+        Some(format) => format,
+        None => match state.config.log_compression {
+            LogCompression::None => LogCompression::Gzip,
+            format => format,
+        },
+    };
+
+    match state.log_store.compress_logs(format).await {
+        Ok(compressed) => {
+            tracing::info!(
+                "Compressed {} run logs with {}: {} -> {} bytes",
+                compressed.runs,
+                format,
+                compressed.bytes_before,
+                compressed.bytes_after
+            );
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "format": format,
+                    "runs": compressed.runs,
+                    "bytes_before": compressed.bytes_before,
+                    "bytes_after": compressed.bytes_after,
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            &format!("Failed to compress logs: {}", e),
+        )
+        .into_response(),
+    }
+}
+
 /// POST /api/restart — restart the daemon
 pub async fn restart(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     tracing::info!("Restart requested");
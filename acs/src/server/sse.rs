@@ -1,22 +1,33 @@
+use std::collections::HashSet;
 use std::convert::Infallible;
 use std::sync::Arc;
 use std::time::Duration;
 
 use axum::extract::{Query, State};
+use axum::http::StatusCode;
 use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use futures_util::stream::Stream;
 use serde::Deserialize;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 use uuid::Uuid;
 
+use super::routes::error_response;
 use super::AppState;
 use crate::daemon::events::JobEvent;
+use crate::models::OutputStream;
 
 #[derive(Debug, Deserialize, Default)]
 pub struct SseParams {
     pub job_id: Option<String>,
     pub run_id: Option<String>,
+    /// Only forward `Output` events from this stream (`stdout` or `stderr`).
+    /// Other event types are unaffected.
+    pub stream: Option<String>,
+    /// Only forward events of runs whose job had this tag when they started.
+    /// `Queued`, `Skipped` and `JobChanged` events are dropped.
+    pub tag: Option<String>,
 }
 
 /// Guard that logs at debug level when the SSE stream is dropped (client disconnects).
@@ -31,6 +42,23 @@ impl Drop for SseDropGuard {
 pub async fn sse_handler(
     State(state): State<Arc<AppState>>,
     Query(params): Query<SseParams>,
+) -> Response {
+    // Reject a bad stream filter up front, as GET /api/runs/{run_id}/log does.
+    let filter_stream = match params.stream.as_deref().map(str::parse::<OutputStream>) {
+        Some(Ok(stream)) => Some(stream),
+        Some(Err(msg)) => {
+            return error_response(StatusCode::BAD_REQUEST, "validation_error", &msg)
+                .into_response();
+        }
+        None => None,
+    };
+    event_stream(state, params, filter_stream).into_response()
+}
+
+fn event_stream(
+    state: Arc<AppState>,
+    params: SseParams,
+    filter_stream: Option<OutputStream>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     tracing::info!("SSE client connected");
 
@@ -39,6 +67,9 @@ pub async fn sse_handler(
     // Parse filter UUIDs
     let filter_job_id = params.job_id.and_then(|s| Uuid::parse_str(&s).ok());
     let filter_run_id = params.run_id.and_then(|s| Uuid::parse_str(&s).ok());
+    let filter_tag = params.tag;
+    // Runs that started with `filter_tag`, until they finish.
+    let mut tagged_runs: HashSet<Uuid> = HashSet::new();
 
     // The drop guard is moved into the closure so it lives as long as the stream.
     // When the client disconnects and the stream is dropped, the guard logs the disconnect.
@@ -55,6 +86,9 @@ pub async fn sse_handler(
                         JobEvent::Output { job_id, .. } => Some(*job_id),
                         JobEvent::Completed { job_id, .. } => Some(*job_id),
                         JobEvent::Failed { job_id, .. } => Some(*job_id),
+                        JobEvent::Killed { job_id, .. } => Some(*job_id),
+                        JobEvent::Queued { job_id, .. } => Some(*job_id),
+                        JobEvent::Skipped { job_id, .. } => Some(*job_id),
                         JobEvent::JobChanged { job_id, .. } => Some(*job_id),
                     };
                     if event_job_id != Some(fj) {
@@ -68,6 +102,9 @@ pub async fn sse_handler(
                         JobEvent::Output { run_id, .. } => Some(*run_id),
                         JobEvent::Completed { run_id, .. } => Some(*run_id),
                         JobEvent::Failed { run_id, .. } => Some(*run_id),
+                        JobEvent::Killed { run_id, .. } => Some(*run_id),
+                        JobEvent::Queued { run_id, .. } => Some(*run_id),
+                        JobEvent::Skipped { run_id, .. } => Some(*run_id),
                         JobEvent::JobChanged { .. } => None,
                     };
                     if event_run_id != Some(fr) {
@@ -75,12 +112,39 @@ pub async fn sse_handler(
                     }
                 }
 
+                if let Some(ref tag) = filter_tag {
+                    let forward = match &event {
+                        JobEvent::Started { run_id, tags, .. } => {
+                            tags.contains(tag) && tagged_runs.insert(*run_id)
+                        }
+                        JobEvent::Output { run_id, .. } => tagged_runs.contains(run_id),
+                        JobEvent::Completed { run_id, .. }
+                        | JobEvent::Failed { run_id, .. }
+                        | JobEvent::Killed { run_id, .. } => tagged_runs.remove(run_id),
+                        JobEvent::Queued { .. }
+                        | JobEvent::Skipped { .. }
+                        | JobEvent::JobChanged { .. } => false,
+                    };
+                    if !forward {
+                        return None;
+                    }
+                }
+
+                if let (Some(fs), JobEvent::Output { stream, .. }) = (filter_stream, &event) {
+                    if *stream != fs {
+                        return None;
+                    }
+                }
+
                 // Determine event type name
                 let event_type = match &event {
                     JobEvent::Started { .. } => "started",
                     JobEvent::Output { .. } => "output",
                     JobEvent::Completed { .. } => "completed",
                     JobEvent::Failed { .. } => "failed",
+                    JobEvent::Killed { .. } => "killed",
+                    JobEvent::Queued { .. } => "queued",
+                    JobEvent::Skipped { .. } => "skipped",
                     JobEvent::JobChanged { .. } => "job_changed",
                 };
 
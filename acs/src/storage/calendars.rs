@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::errors::AcsError;
+use crate::models::calendar::validate_calendar;
+use crate::models::Calendar;
+use crate::storage::CalendarStore;
+
+/// Calendars kept in `calendars.json`, next to `jobs.json`.
+pub struct JsonCalendarStore {
+    file_path: PathBuf,
+    cache: RwLock<Vec<Calendar>>,
+}
+
+impl JsonCalendarStore {
+    /// Create a new JsonCalendarStore, loading existing data from disk if
+    /// present.
+    ///
+    /// If `calendars.json` is corrupted, creates a backup at
+    /// `calendars.json.bak`, logs a warning, and starts with no calendars.
+    pub async fn new(data_dir: PathBuf) -> Result<Self> {
+        tokio::fs::create_dir_all(&data_dir)
+            .await
+            .context("Failed to create data directory")?;
+
+        let file_path = data_dir.join("calendars.json");
+
+        let calendars = if file_path.exists() {
+            let content = tokio::fs::read_to_string(&file_path)
+                .await
+                .context("Failed to read calendars.json")?;
+            match serde_json::from_str::<Vec<Calendar>>(&content) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    tracing::warn!(
+                        "calendars.json is corrupted ({}), creating backup and starting empty",
+                        e
+                    );
+                    let backup_path = data_dir.join("calendars.json.bak");
+                    if let Err(backup_err) = tokio::fs::copy(&file_path, &backup_path).await {
+                        tracing::error!(
+                            "Failed to create backup of corrupted calendars.json: {}",
+                            backup_err
+                        );
+                    }
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            file_path,
+            cache: RwLock::new(calendars),
+        })
+    }
+
+    /// Atomically write the calendars cache to disk.
+    async fn persist(&self, calendars: &[Calendar]) -> Result<()> {
+        let tmp_path = self.file_path.with_extension("json.tmp");
+
+        let json =
+            serde_json::to_string_pretty(calendars).context("Failed to serialize calendars")?;
+
+        tokio::fs::write(&tmp_path, json.as_bytes())
+            .await
+            .context("Failed to write temporary calendars file")?;
+
+        tokio::fs::rename(&tmp_path, &self.file_path)
+            .await
+            .context("Failed to rename temporary calendars file")?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CalendarStore for JsonCalendarStore {
+    async fn list_calendars(&self) -> Result<Vec<Calendar>> {
+        Ok(self.cache.read().await.clone())
+    }
+
+    async fn get_calendar(&self, name: &str) -> Result<Option<Calendar>> {
+        let cache = self.cache.read().await;
+        Ok(cache.iter().find(|c| c.name == name).cloned())
+    }
+
+    async fn create_calendar(&self, calendar: Calendar) -> Result<Calendar> {
+        validate_calendar(&calendar)?;
+
+        let mut cache = self.cache.write().await;
+        if cache.iter().any(|c| c.name == calendar.name) {
+            return Err(AcsError::Conflict(format!(
+                "A calendar with name '{}' already exists",
+                calendar.name
+            ))
+            .into());
+        }
+
+        cache.push(calendar.clone());
+        self.persist(&cache).await?;
+
+        Ok(calendar)
+    }
+
+    async fn update_calendar(&self, calendar: Calendar) -> Result<Calendar> {
+        validate_calendar(&calendar)?;
+
+        let mut cache = self.cache.write().await;
+        let existing = cache
+            .iter_mut()
+            .find(|c| c.name == calendar.name)
+            .ok_or_else(|| AcsError::NotFound(format!("Calendar '{}' not found", calendar.name)))?;
+        *existing = calendar.clone();
+        self.persist(&cache).await?;
+
+        Ok(calendar)
+    }
+
+    async fn delete_calendar(&self, name: &str) -> Result<()> {
+        let mut cache = self.cache.write().await;
+        let idx = cache
+            .iter()
+            .position(|c| c.name == name)
+            .ok_or_else(|| AcsError::NotFound(format!("Calendar '{}' not found", name)))?;
+
+        cache.remove(idx);
+        self.persist(&cache).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::calendar::DateRange;
+    use chrono::{TimeZone, Utc};
+    use tempfile::TempDir;
+
+    fn make_calendar(name: &str) -> Calendar {
+        Calendar {
+            name: name.to_string(),
+            description: Some("Company holidays".to_string()),
+            tz: None,
+            ranges: vec![DateRange {
+                start: Utc.with_ymd_and_hms(2025, 12, 25, 0, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2025, 12, 27, 0, 0, 0).unwrap(),
+                label: Some("Christmas".to_string()),
+            }],
+            windows: vec![],
+        }
+    }
+
+    async fn setup_store() -> (JsonCalendarStore, TempDir) {
+        let tmp_dir = TempDir::new().expect("create temp dir");
+        let store = JsonCalendarStore::new(tmp_dir.path().to_path_buf())
+            .await
+            .expect("create store");
+        (store, tmp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_calendar_crud_persists() {
+        let (store, tmp) = setup_store().await;
+        store
+            .create_calendar(make_calendar("holidays"))
+            .await
+            .unwrap();
+
+        let mut updated = make_calendar("holidays");
+        updated.ranges.clear();
+        store.update_calendar(updated.clone()).await.unwrap();
+
+        let reloaded = JsonCalendarStore::new(tmp.path().to_path_buf())
+            .await
+            .unwrap();
+        assert_eq!(reloaded.list_calendars().await.unwrap(), vec![updated]);
+
+        reloaded.delete_calendar("holidays").await.unwrap();
+        assert!(reloaded.get_calendar("holidays").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_calendar_errors() {
+        let (store, _tmp) = setup_store().await;
+        store
+            .create_calendar(make_calendar("holidays"))
+            .await
+            .unwrap();
+
+        let err = store
+            .create_calendar(make_calendar("holidays"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        let err = store
+            .update_calendar(make_calendar("missing"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        assert!(store.delete_calendar("missing").await.is_err());
+
+        let err = store.create_calendar(make_calendar("")).await.unwrap_err();
+        assert!(err.to_string().contains("cannot be empty"));
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_calendars_json_recovers_empty() {
+        let tmp_dir = TempDir::new().unwrap();
+        std::fs::write(tmp_dir.path().join("calendars.json"), "{ not json").unwrap();
+
+        let store = JsonCalendarStore::new(tmp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        assert!(store.list_calendars().await.unwrap().is_empty());
+        assert!(tmp_dir.path().join("calendars.json.bak").exists());
+    }
+}
@@ -7,8 +7,8 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::errors::AcsError;
-use crate::models::job::{validate_job_update, validate_new_job};
-use crate::models::{Job, JobUpdate, NewJob};
+use crate::models::job::{validate_active_period, validate_job_update, validate_new_job};
+use crate::models::{Job, JobUpdate, NewJob, Schedule};
 use crate::storage::JobStore;
 
 pub struct JsonJobStore {
@@ -32,7 +32,11 @@ impl JsonJobStore {
             let content = tokio::fs::read_to_string(&file_path)
                 .await
                 .context("Failed to read jobs.json")?;
-            match serde_json::from_str::<Vec<Job>>(&content) {
+            let parsed = serde_json::from_str::<serde_json::Value>(&content).and_then(|mut v| {
+                migrate_legacy_timezone(&mut v);
+                serde_json::from_value::<Vec<Job>>(v)
+            });
+            match parsed {
                 Ok(parsed) => parsed,
                 Err(e) => {
                     // Corrupted JSON: create backup and start with empty list
@@ -77,6 +81,51 @@ impl JsonJobStore {
 
         Ok(())
     }
+
+    /// Validate and add a new job, defined by `source_file` if given.
+    async fn insert_job(&self, new: NewJob, source_file: Option<String>) -> Result<Job> {
+        validate_new_job(&new)?;
+
+        let mut cache = self.cache.write().await;
+
+        // Check for duplicate name
+        if cache.iter().any(|j| j.name == new.name) {
+            return Err(AcsError::Conflict(format!(
+                "A job with name '{}' already exists",
+                new.name
+            ))
+            .into());
+        }
+
+        let job = Job {
+            source_file,
+            ..new_job(new)
+        };
+
+        cache.push(job.clone());
+        self.persist(&cache).await?;
+
+        Ok(job)
+    }
+}
+
+/// Move the top-level `timezone` of jobs written before schedules were typed
+/// into their cron schedule, so `{"schedule": "0 9 * * *", "timezone": "X"}`
+/// loads as a cron schedule evaluated in `X`.
+fn migrate_legacy_timezone(jobs: &mut serde_json::Value) {
+    let Some(jobs) = jobs.as_array_mut() else {
+        return;
+    };
+    for job in jobs.iter_mut().filter_map(|j| j.as_object_mut()) {
+        let Some(tz) = job.remove("timezone") else {
+            continue;
+        };
+        if let (Some(serde_json::Value::String(expr)), false) = (job.get("schedule"), tz.is_null())
+        {
+            let schedule = serde_json::json!({"type": "Cron", "expr": expr, "tz": tz});
+            job.insert("schedule".to_string(), schedule);
+        }
+    }
 }
 
 #[async_trait]
@@ -97,47 +146,58 @@ impl JobStore for JsonJobStore {
     }
 
     async fn create_job(&self, new: NewJob) -> Result<Job> {
+        self.insert_job(new, None).await
+    }
+
+    async fn create_file_job(&self, new: NewJob, source_file: String) -> Result<Job> {
+        self.insert_job(new, Some(source_file)).await
+    }
+
+    async fn update_job(&self, id: Uuid, update: JobUpdate) -> Result<Job> {
+        let mut cache = self.cache.write().await;
+        let updated_job = apply_update(&mut cache, id, update)?;
+        self.persist(&cache).await?;
+
+        Ok(updated_job)
+    }
+
+    async fn replace_job(&self, id: Uuid, new: NewJob) -> Result<Job> {
         validate_new_job(&new)?;
 
         let mut cache = self.cache.write().await;
 
-        // Check for duplicate name
-        if cache.iter().any(|j| j.name == new.name) {
+        if cache.iter().any(|j| j.name == new.name && j.id != id) {
             return Err(AcsError::Conflict(format!(
                 "A job with name '{}' already exists",
                 new.name
             ))
             .into());
         }
-
-        let now = Utc::now();
-        let job = Job {
-            id: Uuid::now_v7(),
-            name: new.name,
-            schedule: new.schedule,
-            execution: new.execution,
-            enabled: new.enabled,
-            timezone: new.timezone,
-            working_dir: new.working_dir,
-            env_vars: new.env_vars,
-            timeout_secs: new.timeout_secs,
-            log_environment: new.log_environment,
-            created_at: now,
-            updated_at: now,
-            last_run_at: None,
-            last_exit_code: None,
-            next_run_at: None,
-        };
-
-        cache.push(job.clone());
+        let job = cache
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| AcsError::NotFound(format!("Job with id '{}' not found", id)))?;
+        job.redefine(new);
+        let job = job.clone();
         self.persist(&cache).await?;
 
         Ok(job)
     }
 
-    async fn update_job(&self, id: Uuid, update: JobUpdate) -> Result<Job> {
-        validate_job_update(&update)?;
+    async fn update_jobs(&self, updates: Vec<(Uuid, JobUpdate)>) -> Result<Vec<Result<Job>>> {
+        let mut cache = self.cache.write().await;
+        let results: Vec<Result<Job>> = updates
+            .into_iter()
+            .map(|(id, update)| apply_update(&mut cache, id, update))
+            .collect();
+        if results.iter().any(|r| r.is_ok()) {
+            self.persist(&cache).await?;
+        }
+
+        Ok(results)
+    }
 
+    async fn delete_job(&self, id: Uuid) -> Result<()> {
         let mut cache = self.cache.write().await;
 
         let idx = cache
@@ -145,74 +205,211 @@ impl JobStore for JsonJobStore {
             .position(|j| j.id == id)
             .ok_or_else(|| AcsError::NotFound(format!("Job with id '{}' not found", id)))?;
 
-        // Check for duplicate name (excluding self)
-        if let Some(ref new_name) = update.name {
-            if cache.iter().any(|j| j.name == *new_name && j.id != id) {
-                return Err(AcsError::Conflict(format!(
-                    "A job with name '{}' already exists",
-                    new_name
-                ))
-                .into());
-            }
-        }
+        cache.remove(idx);
+        self.persist(&cache).await?;
 
-        let job = &mut cache[idx];
+        Ok(())
+    }
 
-        if let Some(name) = update.name {
-            job.name = name;
-        }
-        if let Some(schedule) = update.schedule {
-            job.schedule = schedule;
-        }
-        if let Some(execution) = update.execution {
-            job.execution = execution;
-        }
-        if let Some(enabled) = update.enabled {
-            job.enabled = enabled;
-        }
-        if let Some(timezone) = update.timezone {
-            job.timezone = Some(timezone);
-        }
-        if let Some(working_dir) = update.working_dir {
-            job.working_dir = Some(working_dir);
-        }
-        if let Some(env_vars) = update.env_vars {
-            job.env_vars = Some(env_vars);
-        }
-        if let Some(timeout_secs) = update.timeout_secs {
-            job.timeout_secs = timeout_secs;
-        }
-        if let Some(log_environment) = update.log_environment {
-            job.log_environment = log_environment;
-        }
-        // Internal metadata fields (not user-editable, set by the daemon)
-        if let Some(last_run_at) = update.last_run_at {
-            job.last_run_at = last_run_at;
-        }
-        if let Some(last_exit_code) = update.last_exit_code {
-            job.last_exit_code = last_exit_code;
+    async fn delete_jobs(&self, ids: &[Uuid]) -> Result<Vec<Result<()>>> {
+        let mut cache = self.cache.write().await;
+        let results: Vec<Result<()>> = ids
+            .iter()
+            .map(|id| match cache.iter().position(|j| j.id == *id) {
+                Some(idx) => {
+                    cache.remove(idx);
+                    Ok(())
+                }
+                None => Err(AcsError::NotFound(format!("Job with id '{}' not found", id)).into()),
+            })
+            .collect();
+        if results.iter().any(|r| r.is_ok()) {
+            self.persist(&cache).await?;
         }
-        job.updated_at = Utc::now();
 
-        let updated_job = job.clone();
-        self.persist(&cache).await?;
+        Ok(results)
+    }
+}
 
-        Ok(updated_job)
+/// Build the record of a newly created job from its definition.
+pub(crate) fn new_job(new: NewJob) -> Job {
+    let now = Utc::now();
+    Job {
+        id: Uuid::now_v7(),
+        name: new.name,
+        schedule: new.schedule.with_timezone(new.timezone),
+        jitter_secs: new.jitter_secs,
+        execution: new.execution,
+        enabled: new.enabled,
+        working_dir: new.working_dir,
+        env_vars: new.env_vars,
+        timeout_secs: new.timeout_secs,
+        log_environment: new.log_environment,
+        concurrency_policy: new.concurrency_policy,
+        retry: new.retry,
+        catchup: new.catchup,
+        include_calendars: new.include_calendars,
+        exclude_calendars: new.exclude_calendars,
+        start_at: new.start_at,
+        end_at: new.end_at,
+        max_runs: new.max_runs,
+        scheduled_runs: 0,
+        on_success: new.on_success,
+        on_failure: new.on_failure,
+        forward_output_lines: new.forward_output_lines,
+        pool: new.pool,
+        priority: new.priority,
+        tags: new.tags,
+        labels: new.labels,
+        log_retention: new.log_retention,
+        created_at: now,
+        updated_at: now,
+        last_run_at: None,
+        last_exit_code: None,
+        source_file: None,
+        next_run_at: None,
+        last_scheduled_at: None,
     }
+}
 
-    async fn delete_job(&self, id: Uuid) -> Result<()> {
-        let mut cache = self.cache.write().await;
+/// Validate `update` and apply it to the cached job `id`, without
+/// persisting. Nothing is changed if it fails.
+pub(crate) fn apply_update(cache: &mut [Job], id: Uuid, update: JobUpdate) -> Result<Job> {
+    validate_job_update(&update)?;
 
-        let idx = cache
-            .iter()
-            .position(|j| j.id == id)
-            .ok_or_else(|| AcsError::NotFound(format!("Job with id '{}' not found", id)))?;
+    let idx = cache
+        .iter()
+        .position(|j| j.id == id)
+        .ok_or_else(|| AcsError::NotFound(format!("Job with id '{}' not found", id)))?;
 
-        cache.remove(idx);
-        self.persist(&cache).await?;
+    // Check for duplicate name (excluding self)
+    if let Some(ref new_name) = update.name {
+        if cache.iter().any(|j| j.name == *new_name && j.id != id) {
+            return Err(AcsError::Conflict(format!(
+                "A job with name '{}' already exists",
+                new_name
+            ))
+            .into());
+        }
+    }
 
-        Ok(())
+    let job = &mut cache[idx];
+
+    validate_active_period(
+        update.start_at.unwrap_or(job.start_at),
+        update.end_at.unwrap_or(job.end_at),
+    )?;
+
+    if let Some(schedule) = update.schedule {
+        job.schedule = schedule.replacing(&job.schedule, update.timezone);
+    } else if let Some(timezone) = update.timezone {
+        // A timezone on its own applies to the current schedule.
+        match &mut job.schedule {
+            Schedule::Cron { tz, .. } => *tz = Some(timezone),
+            _ => {
+                return Err(AcsError::Validation(
+                    "timezone only applies to cron schedules".to_string(),
+                )
+                .into())
+            }
+        }
+    }
+    if let Some(jitter_secs) = update.jitter_secs {
+        job.jitter_secs = jitter_secs;
+    }
+    if let Some(name) = update.name {
+        job.name = name;
+    }
+    if let Some(execution) = update.execution {
+        job.execution = execution;
+    }
+    if let Some(enabled) = update.enabled {
+        // Enabling a job that used up its max_runs starts a new count, so it
+        // does not expire again straight away.
+        if enabled && !job.enabled && job.max_runs.is_some_and(|max| job.scheduled_runs >= max) {
+            job.scheduled_runs = 0;
+        }
+        job.enabled = enabled;
+    }
+    if let Some(working_dir) = update.working_dir {
+        job.working_dir = Some(working_dir);
+    }
+    if let Some(env_vars) = update.env_vars {
+        job.env_vars = Some(env_vars);
+    }
+    if let Some(timeout_secs) = update.timeout_secs {
+        job.timeout_secs = timeout_secs;
+    }
+    if let Some(log_environment) = update.log_environment {
+        job.log_environment = log_environment;
+    }
+    if let Some(concurrency_policy) = update.concurrency_policy {
+        job.concurrency_policy = concurrency_policy;
+    }
+    if let Some(retry) = update.retry {
+        job.retry = retry;
+    }
+    if let Some(catchup) = update.catchup {
+        job.catchup = catchup;
+    }
+    if let Some(include_calendars) = update.include_calendars {
+        job.include_calendars = include_calendars;
+    }
+    if let Some(exclude_calendars) = update.exclude_calendars {
+        job.exclude_calendars = exclude_calendars;
+    }
+    if let Some(start_at) = update.start_at {
+        job.start_at = start_at;
+    }
+    if let Some(end_at) = update.end_at {
+        job.end_at = end_at;
+    }
+    if let Some(max_runs) = update.max_runs {
+        job.max_runs = max_runs;
+    }
+    if let Some(on_success) = update.on_success {
+        job.on_success = on_success;
+    }
+    if let Some(on_failure) = update.on_failure {
+        job.on_failure = on_failure;
+    }
+    if let Some(lines) = update.forward_output_lines {
+        job.forward_output_lines = Some(lines);
+    }
+    if let Some(pool) = update.pool {
+        job.pool = Some(pool).filter(|p| !p.is_empty());
     }
+    if let Some(priority) = update.priority {
+        job.priority = priority;
+    }
+    if let Some(tags) = update.tags {
+        job.tags = tags;
+    }
+    if let Some(labels) = update.labels {
+        job.labels = labels;
+    }
+    if let Some(log_retention) = update.log_retention {
+        job.log_retention = log_retention;
+    }
+    // Internal metadata fields (not user-editable, set by the daemon)
+    if let Some(last_run_at) = update.last_run_at {
+        job.last_run_at = last_run_at;
+    }
+    if let Some(last_exit_code) = update.last_exit_code {
+        job.last_exit_code = last_exit_code;
+    }
+    if let Some(last_scheduled_at) = update.last_scheduled_at {
+        job.last_scheduled_at = last_scheduled_at;
+    }
+    if let Some(scheduled_runs) = update.scheduled_runs {
+        job.scheduled_runs = scheduled_runs;
+    }
+    if let Some(source_file) = update.source_file {
+        job.source_file = source_file;
+    }
+    job.updated_at = Utc::now();
+
+    Ok(job.clone())
 }
 
 #[cfg(test)]
@@ -224,14 +421,31 @@ mod tests {
     fn make_new_job(name: &str) -> NewJob {
         NewJob {
             name: name.to_string(),
-            schedule: "*/5 * * * *".to_string(),
+            schedule: Schedule::cron("*/5 * * * *"),
             execution: ExecutionType::ShellCommand("echo hello".to_string()),
             enabled: true,
+            jitter_secs: 0,
             timezone: None,
             working_dir: None,
             env_vars: None,
             timeout_secs: 0,
             log_environment: false,
+            concurrency_policy: Default::default(),
+            retry: Default::default(),
+            catchup: Default::default(),
+            include_calendars: Vec::new(),
+            exclude_calendars: Vec::new(),
+            start_at: None,
+            end_at: None,
+            max_runs: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            forward_output_lines: None,
+            pool: None,
+            priority: 0,
+            tags: Vec::new(),
+            labels: Default::default(),
+            log_retention: Default::default(),
         }
     }
 
@@ -251,7 +465,7 @@ mod tests {
             .await
             .expect("create");
         assert_eq!(job.name, "test-job");
-        assert_eq!(job.schedule, "*/5 * * * *");
+        assert_eq!(job.schedule, Schedule::cron("*/5 * * * *"));
         assert!(job.enabled);
         assert!(job.last_run_at.is_none());
         assert!(job.last_exit_code.is_none());
@@ -316,13 +530,13 @@ mod tests {
 
         let update = JobUpdate {
             name: Some("updated-job".to_string()),
-            schedule: Some("0 * * * *".to_string()),
+            schedule: Some(Schedule::cron("0 * * * *")),
             ..Default::default()
         };
 
         let updated = store.update_job(created.id, update).await.expect("update");
         assert_eq!(updated.name, "updated-job");
-        assert_eq!(updated.schedule, "0 * * * *");
+        assert_eq!(updated.schedule, Schedule::cron("0 * * * *"));
         assert!(updated.updated_at >= created.updated_at);
     }
 
@@ -553,4 +767,227 @@ mod tests {
         let jobs = store.list_jobs().await.expect("list");
         assert_eq!(jobs.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_legacy_timezone_migrated_into_schedule() {
+        let (store, tmp) = setup_store().await;
+        store
+            .create_job(make_new_job("legacy"))
+            .await
+            .expect("create");
+        drop(store);
+
+        // Rewrite the file in the format used before schedules were typed
+        let jobs_file = tmp.path().join("jobs.json");
+        let content = tokio::fs::read_to_string(&jobs_file).await.expect("read");
+        let mut jobs: serde_json::Value = serde_json::from_str(&content).unwrap();
+        jobs[0]["schedule"] = serde_json::json!("0 9 * * *");
+        jobs[0]["timezone"] = serde_json::json!("Europe/London");
+        tokio::fs::write(&jobs_file, jobs.to_string())
+            .await
+            .expect("write");
+
+        let store = JsonJobStore::new(tmp.path().to_path_buf())
+            .await
+            .expect("reload");
+        let jobs = store.list_jobs().await.expect("list");
+        assert_eq!(
+            jobs.len(),
+            1,
+            "legacy file must not be treated as corrupted"
+        );
+        assert_eq!(
+            jobs[0].schedule,
+            Schedule::Cron {
+                expr: "0 9 * * *".to_string(),
+                tz: Some("Europe/London".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_timezone_only() {
+        let (store, _tmp) = setup_store().await;
+        let created = store
+            .create_job(make_new_job("tz-job"))
+            .await
+            .expect("create");
+
+        let update = JobUpdate {
+            timezone: Some("Asia/Tokyo".to_string()),
+            ..Default::default()
+        };
+        let updated = store.update_job(created.id, update).await.expect("update");
+        assert_eq!(
+            updated.schedule,
+            Schedule::Cron {
+                expr: "*/5 * * * *".to_string(),
+                tz: Some("Asia/Tokyo".to_string()),
+            }
+        );
+
+        // A timezone cannot be applied to a non-cron schedule
+        let update = JobUpdate {
+            schedule: Some(Schedule::Manual),
+            ..Default::default()
+        };
+        store.update_job(created.id, update).await.expect("update");
+        let update = JobUpdate {
+            timezone: Some("Asia/Tokyo".to_string()),
+            ..Default::default()
+        };
+        assert!(store.update_job(created.id, update).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_schedule_expression_keeps_timezone() {
+        let (store, _tmp) = setup_store().await;
+        let created = store
+            .create_job(NewJob {
+                timezone: Some("Europe/Berlin".to_string()),
+                ..make_new_job("berlin-job")
+            })
+            .await
+            .expect("create");
+
+        let update = JobUpdate {
+            schedule: Some(Schedule::cron("0 9 * * *")),
+            ..Default::default()
+        };
+        let updated = store.update_job(created.id, update).await.expect("update");
+        assert_eq!(
+            updated.schedule,
+            Schedule::Cron {
+                expr: "0 9 * * *".to_string(),
+                tz: Some("Europe/Berlin".to_string()),
+            }
+        );
+
+        // An explicit timezone still replaces it
+        let update = JobUpdate {
+            schedule: Some(Schedule::cron("0 10 * * *")),
+            timezone: Some("Asia/Tokyo".to_string()),
+            ..Default::default()
+        };
+        let updated = store.update_job(created.id, update).await.expect("update");
+        assert_eq!(
+            updated.schedule,
+            Schedule::Cron {
+                expr: "0 10 * * *".to_string(),
+                tz: Some("Asia/Tokyo".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_start_at_checked_against_existing_end_at() {
+        let (store, _tmp) = setup_store().await;
+        let end_at = Utc::now() + chrono::Duration::days(1);
+        let created = store
+            .create_job(NewJob {
+                end_at: Some(end_at),
+                max_runs: Some(5),
+                ..make_new_job("campaign")
+            })
+            .await
+            .expect("create");
+        assert_eq!(created.end_at, Some(end_at));
+        assert_eq!(created.scheduled_runs, 0);
+
+        let update = JobUpdate {
+            start_at: Some(Some(end_at + chrono::Duration::hours(1))),
+            ..Default::default()
+        };
+        let err = store.update_job(created.id, update).await.unwrap_err();
+        assert!(err.to_string().contains("must be before end_at"));
+
+        let update = JobUpdate {
+            start_at: Some(Some(end_at - chrono::Duration::hours(1))),
+            max_runs: Some(Some(10)),
+            ..Default::default()
+        };
+        let updated = store.update_job(created.id, update).await.expect("update");
+        assert_eq!(updated.start_at, Some(end_at - chrono::Duration::hours(1)));
+        assert_eq!(updated.max_runs, Some(10));
+
+        // `null` clears a limit; leaving a field out keeps it
+        let update: JobUpdate =
+            serde_json::from_str(r#"{"end_at": null, "max_runs": null}"#).expect("parse");
+        let updated = store.update_job(created.id, update).await.expect("update");
+        assert_eq!(updated.start_at, Some(end_at - chrono::Duration::hours(1)));
+        assert_eq!(updated.end_at, None);
+        assert_eq!(updated.max_runs, None);
+    }
+
+    #[tokio::test]
+    async fn test_enabling_job_that_used_up_max_runs_restarts_count() {
+        let (store, _tmp) = setup_store().await;
+        let created = store
+            .create_job(NewJob {
+                max_runs: Some(2),
+                ..make_new_job("limited")
+            })
+            .await
+            .expect("create");
+        let expired = JobUpdate {
+            enabled: Some(false),
+            scheduled_runs: Some(2),
+            ..Default::default()
+        };
+        store.update_job(created.id, expired).await.expect("update");
+
+        let update = JobUpdate {
+            enabled: Some(true),
+            ..Default::default()
+        };
+        let updated = store.update_job(created.id, update).await.expect("update");
+        assert!(updated.enabled);
+        assert_eq!(updated.scheduled_runs, 0);
+        assert_eq!(updated.expiry_reason(Utc::now()), None);
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete_jobs_in_one_write() {
+        let (store, tmp) = setup_store().await;
+        let a = store.create_job(make_new_job("a")).await.expect("create");
+        let b = store.create_job(make_new_job("b")).await.expect("create");
+        let disable = || JobUpdate {
+            enabled: Some(false),
+            ..Default::default()
+        };
+
+        let results = store
+            .update_jobs(vec![
+                (a.id, disable()),
+                (Uuid::now_v7(), disable()),
+                (
+                    b.id,
+                    JobUpdate {
+                        name: Some("a".to_string()),
+                        ..Default::default()
+                    },
+                ),
+            ])
+            .await
+            .expect("update_jobs");
+        assert!(!results[0].as_ref().expect("a updated").enabled);
+        assert!(results[1].is_err(), "unknown job");
+        assert!(results[2].is_err(), "duplicate name");
+
+        // The successful update was persisted; the failed one changed nothing.
+        let reloaded = JsonJobStore::new(tmp.path().to_path_buf())
+            .await
+            .expect("reload");
+        assert!(!reloaded.get_job(a.id).await.unwrap().unwrap().enabled);
+        assert_eq!(reloaded.get_job(b.id).await.unwrap().unwrap().name, "b");
+
+        let results = store
+            .delete_jobs(&[a.id, a.id, b.id])
+            .await
+            .expect("delete_jobs");
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err(), "already deleted");
+        assert!(results[2].is_ok());
+        assert!(store.list_jobs().await.unwrap().is_empty());
+    }
 }
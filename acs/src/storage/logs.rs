@@ -1,24 +1,220 @@
-use std::path::PathBuf;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 use uuid::Uuid;
 
-use crate::models::JobRun;
-use crate::storage::LogStore;
+use crate::models::{JobRun, LogCompression, OutputStream, RunStatus};
+use crate::storage::{CompressedLogs, LogStore, RunQuery};
 
 pub struct FsLogStore {
     logs_dir: PathBuf,
+    /// The job of every run, kept in `logs/runs.idx` so a run can be found
+    /// by its id alone. `None` for stores that only hold run output.
+    run_index: Option<Mutex<RunIndex>>,
+    /// How the log of a run is compressed once it finishes.
+    compression: LogCompression,
+    /// A lock for each run whose log files are being changed or opened, so
+    /// that readers never see a log halfway through being compressed or
+    /// reopened. Only runs whose lock is held are kept.
+    run_locks: std::sync::Mutex<HashMap<Uuid, Arc<Mutex<()>>>>,
+}
+
+/// The run index, as loaded from `logs/runs.idx` and changed since.
+///
+/// New runs are appended as `<run_id> <job_id>` lines and removed ones as
+/// `-<run_id>` tombstones, so neither costs a rewrite of the whole file. It
+/// is compacted once stale lines outnumber the runs it holds.
+#[derive(Default)]
+struct RunIndex {
+    runs: HashMap<Uuid, Uuid>,
+    /// Lines in the file, stale ones included.
+    lines: usize,
+}
+
+/// Below this many lines the run index is never compacted.
+const RUN_INDEX_COMPACT_MIN_LINES: usize = 1024;
+
+/// The files holding a run's output: a compressed log once the run has
+/// finished, or a plain one that is written while it goes on. Both exist only
+/// if compressing or reopening a log was interrupted after the file replacing
+/// the other was complete, so the plain log then holds the whole output.
+struct LogFiles {
+    archive: Option<(PathBuf, LogCompression)>,
+    plain: PathBuf,
 }
 
 impl FsLogStore {
     /// Create a new FsLogStore rooted at data_dir/logs/.
     pub async fn new(data_dir: PathBuf) -> Result<Self> {
+        let mut store = Self::for_output(data_dir).await?;
+        let index = store.load_run_index().await?;
+        store.run_index = Some(Mutex::new(index));
+        Ok(store)
+    }
+
+    /// Create a store used only for the log files of runs whose records are
+    /// kept elsewhere. It keeps no run index, so `find_run` finds nothing.
+    pub(crate) async fn for_output(data_dir: PathBuf) -> Result<Self> {
         let logs_dir = data_dir.join("logs");
         tokio::fs::create_dir_all(&logs_dir)
             .await
             .context("Failed to create logs directory")?;
-        Ok(Self { logs_dir })
+        Ok(Self {
+            logs_dir,
+            run_index: None,
+            compression: LogCompression::None,
+            run_locks: std::sync::Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Compress the log of each run once it finishes.
+    pub fn with_compression(mut self, compression: LogCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Wait until no other task is changing or opening the log files of run
+    /// `run_id`, and keep them from doing so until the guard is dropped.
+    async fn lock_run(&self, run_id: Uuid) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.run_locks.lock().unwrap();
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            Arc::clone(locks.entry(run_id).or_default())
+        };
+        lock.lock_owned().await
+    }
+
+    /// Get the path to the run index. Each line is `<run_id> <job_id>`.
+    fn run_index_path(&self) -> PathBuf {
+        self.logs_dir.join("runs.idx")
+    }
+
+    /// Load the run index, rebuilding it from the `.meta.json` files if it
+    /// does not exist yet (such as in data directories from before it did).
+    async fn load_run_index(&self) -> Result<RunIndex> {
+        let path = self.run_index_path();
+        let mut index = RunIndex::default();
+
+        if path.exists() {
+            let content = tokio::fs::read_to_string(&path)
+                .await
+                .context("Failed to read run index")?;
+            for line in content.lines() {
+                index.lines += 1;
+                if let Some(removed) = line.strip_prefix('-') {
+                    match Uuid::parse_str(removed.trim()) {
+                        Ok(run_id) => {
+                            index.runs.remove(&run_id);
+                        }
+                        Err(_) => tracing::warn!("Skipping malformed run index line {:?}", line),
+                    }
+                    continue;
+                }
+                let mut parts = line.split_whitespace();
+                match (
+                    parts.next().and_then(|p| Uuid::parse_str(p).ok()),
+                    parts.next().and_then(|p| Uuid::parse_str(p).ok()),
+                ) {
+                    (Some(run_id), Some(job_id)) => {
+                        index.runs.insert(run_id, job_id);
+                    }
+                    _ => tracing::warn!("Skipping malformed run index line {:?}", line),
+                }
+            }
+            return Ok(index);
+        }
+
+        for job_id in self.job_ids().await? {
+            let mut entries = tokio::fs::read_dir(self.job_dir(job_id))
+                .await
+                .context("Failed to read job log directory")?;
+            while let Some(entry) = entries.next_entry().await? {
+                if let Some(run_id) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.strip_suffix(".meta.json"))
+                    .and_then(|id| Uuid::parse_str(id).ok())
+                {
+                    index.runs.insert(run_id, job_id);
+                }
+            }
+        }
+        self.write_run_index(&mut index).await?;
+        Ok(index)
+    }
+
+    /// Atomically rewrite the run index with only the runs it holds.
+    async fn write_run_index(&self, index: &mut RunIndex) -> Result<()> {
+        let content: String = index
+            .runs
+            .iter()
+            .map(|(run_id, job_id)| format!("{} {}\n", run_id, job_id))
+            .collect();
+        let tmp_path = self.run_index_path().with_extension("idx.tmp");
+        tokio::fs::write(&tmp_path, content.as_bytes())
+            .await
+            .context("Failed to write temporary run index")?;
+        tokio::fs::rename(&tmp_path, self.run_index_path())
+            .await
+            .context("Failed to rename temporary run index")?;
+        index.lines = index.runs.len();
+        Ok(())
+    }
+
+    /// Append lines to the run index, compacting it instead once it has
+    /// grown to more than twice the runs it holds.
+    async fn append_run_index(&self, index: &mut RunIndex, lines: &[String]) -> Result<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let total = index.lines + lines.len();
+        if total >= RUN_INDEX_COMPACT_MIN_LINES && total > 2 * index.runs.len() {
+            return self.write_run_index(index).await;
+        }
+
+        use tokio::io::AsyncWriteExt;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.run_index_path())
+            .await
+            .context("Failed to open run index")?;
+        file.write_all(lines.concat().as_bytes())
+            .await
+            .context("Failed to append to run index")?;
+        file.flush().await.context("Failed to flush run index")?;
+        index.lines = total;
+        Ok(())
+    }
+
+    /// Add a run to the run index.
+    async fn index_run(&self, run: &JobRun) -> Result<()> {
+        let Some(index) = &self.run_index else {
+            return Ok(());
+        };
+        let mut index = index.lock().await;
+        if index.runs.insert(run.run_id, run.job_id) == Some(run.job_id) {
+            return Ok(());
+        }
+        let line = format!("{} {}\n", run.run_id, run.job_id);
+        self.append_run_index(&mut index, &[line]).await
+    }
+
+    /// Remove runs from the run index, appending a tombstone for each one it
+    /// held.
+    async fn unindex_runs(&self, index: &mut RunIndex, run_ids: &[Uuid]) -> Result<()> {
+        let tombstones: Vec<String> = run_ids
+            .iter()
+            .filter(|run_id| index.runs.remove(run_id).is_some())
+            .map(|run_id| format!("-{}\n", run_id))
+            .collect();
+        self.append_run_index(index, &tombstones).await
     }
 
     /// Get the directory for a specific job's logs.
@@ -35,39 +231,42 @@ impl FsLogStore {
     fn log_path(&self, job_id: Uuid, run_id: Uuid) -> PathBuf {
         self.job_dir(job_id).join(format!("{}.log", run_id))
     }
-}
-
-#[async_trait]
-impl LogStore for FsLogStore {
-    async fn create_run(&self, run: &JobRun) -> Result<()> {
-        let job_dir = self.job_dir(run.job_id);
-        tokio::fs::create_dir_all(&job_dir)
-            .await
-            .context("Failed to create job log directory")?;
 
-        let meta_path = self.meta_path(run.job_id, run.run_id);
-        let json = serde_json::to_string_pretty(run).context("Failed to serialize run metadata")?;
-        tokio::fs::write(&meta_path, json.as_bytes())
-            .await
-            .context("Failed to write run metadata")?;
+    /// Get the path to a run's log file once compressed into the format with
+    /// the given extension.
+    fn archive_path(&self, job_id: Uuid, run_id: Uuid, extension: &str) -> PathBuf {
+        self.job_dir(job_id)
+            .join(format!("{}.log.{}", run_id, extension))
+    }
 
-        Ok(())
+    /// The files holding a run's output.
+    fn log_files(&self, job_id: Uuid, run_id: Uuid) -> LogFiles {
+        let archive = LogCompression::FORMATS.into_iter().find_map(|compression| {
+            let path = self.archive_path(job_id, run_id, compression.extension()?);
+            path.exists().then_some((path, compression))
+        });
+        LogFiles {
+            archive,
+            plain: self.log_path(job_id, run_id),
+        }
     }
 
-    async fn update_run(&self, run: &JobRun) -> Result<()> {
-        let meta_path = self.meta_path(run.job_id, run.run_id);
-        let json = serde_json::to_string_pretty(run).context("Failed to serialize run metadata")?;
-        tokio::fs::write(&meta_path, json.as_bytes())
-            .await
-            .context("Failed to write run metadata")?;
-        Ok(())
+    /// Get the path to a run's stderr index. Each line is `<offset> <len>`,
+    /// a byte range of the log file that was written to stderr. Runs that
+    /// never wrote to stderr have no index.
+    fn stderr_index_path(&self, job_id: Uuid, run_id: Uuid) -> PathBuf {
+        self.job_dir(job_id).join(format!("{}.stderr.idx", run_id))
     }
 
-    async fn append_log(&self, job_id: Uuid, run_id: Uuid, data: &[u8]) -> Result<()> {
+    /// Append bytes to a run's log file, returning the offset they start at.
+    /// A compressed log is reopened first.
+    async fn append_bytes(&self, job_id: Uuid, run_id: Uuid, data: &[u8]) -> Result<u64> {
         let job_dir = self.job_dir(job_id);
         tokio::fs::create_dir_all(&job_dir)
             .await
             .context("Failed to create job log directory")?;
+        let _guard = self.lock_run(run_id).await;
+        self.reopen_archive(job_id, run_id).await?;
 
         let log_path = self.log_path(job_id, run_id);
 
@@ -79,47 +278,45 @@ impl LogStore for FsLogStore {
             .await
             .context("Failed to open log file for appending")?;
 
+        let offset = file
+            .metadata()
+            .await
+            .context("Failed to stat log file")?
+            .len();
+
         file.write_all(data)
             .await
             .context("Failed to append to log file")?;
 
         file.flush().await.context("Failed to flush log file")?;
 
-        Ok(())
+        Ok(offset)
     }
 
-    async fn read_log(&self, job_id: Uuid, run_id: Uuid, tail: Option<usize>) -> Result<String> {
-        let log_path = self.log_path(job_id, run_id);
-
-        if !log_path.exists() {
-            return Ok(String::new());
-        }
-
-        let content = tokio::fs::read_to_string(&log_path)
+    /// The ids of the jobs that have a log directory.
+    pub(crate) async fn job_ids(&self) -> Result<Vec<Uuid>> {
+        let mut job_ids = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.logs_dir)
             .await
-            .context("Failed to read log file")?;
-
-        match tail {
-            Some(n) => {
-                let lines: Vec<&str> = content.lines().collect();
-                let start = if lines.len() > n { lines.len() - n } else { 0 };
-                let tail_lines = &lines[start..];
-                Ok(tail_lines.join("\n"))
+            .context("Failed to read logs directory")?;
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(job_id) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| Uuid::parse_str(name).ok())
+            {
+                job_ids.push(job_id);
             }
-            None => Ok(content),
         }
+        Ok(job_ids)
     }
 
-    async fn list_runs(
-        &self,
-        job_id: Uuid,
-        limit: usize,
-        offset: usize,
-    ) -> Result<(Vec<JobRun>, usize)> {
+    /// Load the metadata of every run of a job, in no particular order.
+    pub(crate) async fn read_runs(&self, job_id: Uuid) -> Result<Vec<JobRun>> {
         let job_dir = self.job_dir(job_id);
 
         if !job_dir.exists() {
-            return Ok((Vec::new(), 0));
+            return Ok(Vec::new());
         }
 
         let mut runs = Vec::new();
@@ -144,80 +341,573 @@ impl LogStore for FsLogStore {
             }
         }
 
-        // Sort by started_at descending
-        runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        Ok(runs)
+    }
 
-        let total = runs.len();
+    /// Remove the metadata, log and stderr index files of a run.
+    pub(crate) async fn remove_run_files(&self, job_id: Uuid, run_id: Uuid) -> Result<()> {
+        // A compression in progress would otherwise leave its archive behind.
+        let _guard = self.lock_run(run_id).await;
+        let meta_path = self.meta_path(job_id, run_id);
+        let log_path = self.log_path(job_id, run_id);
+        let index_path = self.stderr_index_path(job_id, run_id);
 
-        // Apply offset and limit
-        let paginated: Vec<JobRun> = runs.into_iter().skip(offset).take(limit).collect();
+        if meta_path.exists() {
+            tokio::fs::remove_file(&meta_path)
+                .await
+                .context("Failed to remove old meta file")?;
+        }
+        if log_path.exists() {
+            tokio::fs::remove_file(&log_path)
+                .await
+                .context("Failed to remove old log file")?;
+        }
+        if index_path.exists() {
+            tokio::fs::remove_file(&index_path)
+                .await
+                .context("Failed to remove old stderr index")?;
+        }
+        if let Some((archive_path, _)) = self.log_files(job_id, run_id).archive {
+            tokio::fs::remove_file(&archive_path)
+                .await
+                .context("Failed to remove old compressed log")?;
+        }
 
-        Ok((paginated, total))
+        Ok(())
     }
 
-    async fn cleanup(&self, job_id: Uuid, max_files: usize) -> Result<()> {
-        let job_dir = self.job_dir(job_id);
+    /// Compress a run's plain log with `compression`, unless it has none.
+    /// A compressed log left over from an interrupted compression or
+    /// reopening is replaced. Returns the log's size before and after.
+    pub(crate) async fn compress_log(
+        &self,
+        job_id: Uuid,
+        run_id: Uuid,
+        compression: LogCompression,
+    ) -> Result<Option<(u64, u64)>> {
+        let Some(extension) = compression.extension() else {
+            return Ok(None);
+        };
+        let _guard = self.lock_run(run_id).await;
+        let files = self.log_files(job_id, run_id);
+        if !files.plain.exists() {
+            return Ok(None);
+        }
+        let archive = self.archive_path(job_id, run_id, extension);
+        tokio::task::spawn_blocking(move || {
+            let before = std::fs::metadata(&files.plain)
+                .context("Failed to stat log file")?
+                .len();
+            let after = compress_file(&files.plain, &archive, compression)?;
+            if let Some((stale, _)) = files.archive.filter(|(path, _)| *path != archive) {
+                std::fs::remove_file(&stale).context("Failed to remove compressed log")?;
+            }
+            Ok(Some((before, after)))
+        })
+        .await
+        .context("Log compression task failed")?
+    }
 
-        if !job_dir.exists() {
+    /// Compress the logs of the finished runs among `runs`.
+    pub(crate) async fn compress_run_logs(
+        &self,
+        runs: &[JobRun],
+        compression: LogCompression,
+    ) -> Result<CompressedLogs> {
+        let mut compressed = CompressedLogs::default();
+        for run in runs.iter().filter(|r| is_finished(r)) {
+            if let Some((before, after)) = self
+                .compress_log(run.job_id, run.run_id, compression)
+                .await?
+            {
+                compressed.runs += 1;
+                compressed.bytes_before += before;
+                compressed.bytes_after += after;
+            }
+        }
+        Ok(compressed)
+    }
+
+    /// Turn a run's compressed log back into a plain one, so that output
+    /// appended to a finished run starts at its offset in the whole log,
+    /// which is what the stderr index records. The caller holds the run's
+    /// lock.
+    async fn reopen_archive(&self, job_id: Uuid, run_id: Uuid) -> Result<()> {
+        let files = self.log_files(job_id, run_id);
+        let Some((archive, _)) = files.archive.clone() else {
             return Ok(());
+        };
+        tokio::task::spawn_blocking(move || {
+            let plain = files.plain.clone();
+            // A plain log left next to the archive already holds the whole
+            // output.
+            if plain.exists() {
+                return std::fs::remove_file(&archive).context("Failed to remove compressed log");
+            }
+            let mut tmp = plain.as_os_str().to_owned();
+            tmp.push(".tmp");
+            let tmp = PathBuf::from(tmp);
+
+            let write = || -> Result<()> {
+                let Some(mut reader) = open_log(files)? else {
+                    return Ok(());
+                };
+                let mut output = File::create(&tmp).context("Failed to create log file")?;
+                std::io::copy(&mut reader, &mut output).context("Failed to decompress log")?;
+                output.sync_all().context("Failed to sync log file")
+            };
+            if let Err(e) = write() {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(e);
+            }
+
+            std::fs::rename(&tmp, &plain).context("Failed to rename decompressed log")?;
+            std::fs::remove_file(&archive).context("Failed to remove compressed log")
+        })
+        .await
+        .context("Log decompression task failed")?
+    }
+
+    /// Open a run's output for reading, once no other task is changing its
+    /// files. `None` if it has no log.
+    async fn open_run_log(
+        &self,
+        job_id: Uuid,
+        run_id: Uuid,
+    ) -> Result<Option<Box<dyn Read + Send>>> {
+        let _guard = self.lock_run(run_id).await;
+        let files = self.log_files(job_id, run_id);
+        tokio::task::spawn_blocking(move || open_log(files))
+            .await
+            .context("Log open task failed")?
+    }
+
+    /// The whole output of a run, or `None` if it has no log.
+    async fn read_log_bytes(&self, job_id: Uuid, run_id: Uuid) -> Result<Option<Vec<u8>>> {
+        let Some(mut reader) = self.open_run_log(job_id, run_id).await? else {
+            return Ok(None);
+        };
+        tokio::task::spawn_blocking(move || {
+            let mut content = Vec::new();
+            reader
+                .read_to_end(&mut content)
+                .context("Failed to read log file")?;
+            Ok(Some(content))
+        })
+        .await
+        .context("Log read task failed")?
+    }
+
+    /// The combined size of a run's metadata, log and stderr index files.
+    pub(crate) async fn run_files_size(&self, job_id: Uuid, run_id: Uuid) -> Result<u64> {
+        let mut size = 0;
+        let archives = LogCompression::FORMATS
+            .into_iter()
+            .filter_map(|c| Some(self.archive_path(job_id, run_id, c.extension()?)));
+        for path in [
+            self.meta_path(job_id, run_id),
+            self.log_path(job_id, run_id),
+            self.stderr_index_path(job_id, run_id),
+        ]
+        .into_iter()
+        .chain(archives)
+        {
+            match tokio::fs::metadata(&path).await {
+                Ok(metadata) => size += metadata.len(),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to stat {}", path.display()))
+                }
+            }
         }
+        Ok(size)
+    }
 
-        // Read all meta files to get runs sorted by started_at
-        let mut runs = Vec::new();
-        let mut entries = tokio::fs::read_dir(&job_dir)
+    /// Load the stderr byte ranges recorded for a run.
+    async fn stderr_ranges(&self, job_id: Uuid, run_id: Uuid) -> Result<Vec<(usize, usize)>> {
+        let index_path = self.stderr_index_path(job_id, run_id);
+        if !index_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = tokio::fs::read_to_string(&index_path)
             .await
-            .context("Failed to read job log directory")?;
+            .context("Failed to read stderr index")?;
+
+        let mut ranges = Vec::new();
+        for line in content.lines() {
+            let mut parts = line.split_whitespace();
+            match (
+                parts.next().and_then(|p| p.parse::<usize>().ok()),
+                parts.next().and_then(|p| p.parse::<usize>().ok()),
+            ) {
+                (Some(offset), Some(len)) => ranges.push((offset, len)),
+                _ => tracing::warn!("Skipping malformed stderr index line {:?}", line),
+            }
+        }
+        Ok(ranges)
+    }
+}
 
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if name.ends_with(".meta.json") {
-                    let content = tokio::fs::read_to_string(&path)
-                        .await
-                        .context("Failed to read run metadata")?;
-                    match serde_json::from_str::<JobRun>(&content) {
-                        Ok(run) => runs.push(run),
-                        Err(e) => {
-                            tracing::warn!("Skipping malformed meta file {:?}: {}", path, e);
-                        }
-                    }
+/// Keep only the last `n` lines of `content`.
+fn tail_lines(content: &str, n: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = if lines.len() > n { lines.len() - n } else { 0 };
+    lines[start..].join("\n")
+}
+
+/// Keep only the last `n` lines read from `reader`, like [`tail_lines`] but
+/// holding no more than those lines in memory.
+fn tail_reader(reader: impl Read, n: usize) -> Result<String> {
+    let mut reader = BufReader::new(reader);
+    let mut lines = VecDeque::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader
+            .read_until(b'\n', &mut line)
+            .context("Failed to read log file")?
+            == 0
+        {
+            break;
+        }
+        if line.ends_with(b"\n") {
+            line.pop();
+            if line.ends_with(b"\r") {
+                line.pop();
+            }
+        }
+        if n == 0 {
+            continue;
+        }
+        if lines.len() == n {
+            lines.pop_front();
+        }
+        lines.push_back(String::from_utf8_lossy(&line).into_owned());
+    }
+    Ok(Vec::from(lines).join("\n"))
+}
+
+fn is_finished(run: &JobRun) -> bool {
+    !matches!(run.status, RunStatus::Queued | RunStatus::Running)
+}
+
+/// Open a run's output for reading: its plain log if it has one, otherwise
+/// its compressed log, decompressed as it is read. `None` if it has neither.
+fn open_log(files: LogFiles) -> Result<Option<Box<dyn Read + Send>>> {
+    match File::open(&files.plain) {
+        Ok(file) => return Ok(Some(Box::new(file))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).context("Failed to open log file"),
+    }
+    Ok(match files.archive {
+        Some((path, compression)) => {
+            let file = File::open(&path).context("Failed to open compressed log")?;
+            Some(match compression {
+                LogCompression::Gzip => {
+                    Box::new(flate2::read::MultiGzDecoder::new(BufReader::new(file)))
                 }
+                LogCompression::Zstd => Box::new(
+                    zstd::stream::read::Decoder::new(file)
+                        .context("Failed to open compressed log")?,
+                ),
+                LogCompression::None => Box::new(file),
+            })
+        }
+        None => None,
+    })
+}
+
+/// Compress `src` into `dst`, through a temporary file next to it, then
+/// remove `src`. Returns the size of `dst`.
+fn compress_file(src: &Path, dst: &Path, compression: LogCompression) -> Result<u64> {
+    let mut tmp = dst.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let write = || -> Result<u64> {
+        let mut input = File::open(src).context("Failed to open log file")?;
+        let output = File::create(&tmp).context("Failed to create compressed log")?;
+        let output = match compression {
+            LogCompression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(output, flate2::Compression::default());
+                std::io::copy(&mut input, &mut encoder).context("Failed to compress log")?;
+                encoder.finish().context("Failed to compress log")?
+            }
+            LogCompression::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(output, 0)
+                    .context("Failed to compress log")?;
+                std::io::copy(&mut input, &mut encoder).context("Failed to compress log")?;
+                encoder.finish().context("Failed to compress log")?
+            }
+            LogCompression::None => anyhow::bail!("No compression format given"),
+        };
+        output.sync_all().context("Failed to sync compressed log")?;
+        Ok(output
+            .metadata()
+            .context("Failed to stat compressed log")?
+            .len())
+    };
+    let size = match write() {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+    };
+
+    std::fs::rename(&tmp, dst).context("Failed to rename compressed log")?;
+    std::fs::remove_file(src).context("Failed to remove uncompressed log")?;
+    Ok(size)
+}
+
+#[async_trait]
+impl LogStore for FsLogStore {
+    async fn create_run(&self, run: &JobRun) -> Result<()> {
+        let job_dir = self.job_dir(run.job_id);
+        tokio::fs::create_dir_all(&job_dir)
+            .await
+            .context("Failed to create job log directory")?;
+
+        // Indexed first, so a crash in between leaves an index entry without
+        // metadata (which `find_run` skips) rather than an unfindable run.
+        self.index_run(run).await?;
+
+        let meta_path = self.meta_path(run.job_id, run.run_id);
+        let json = serde_json::to_string_pretty(run).context("Failed to serialize run metadata")?;
+        tokio::fs::write(&meta_path, json.as_bytes())
+            .await
+            .context("Failed to write run metadata")
+    }
+
+    async fn update_run(&self, run: &JobRun) -> Result<()> {
+        let meta_path = self.meta_path(run.job_id, run.run_id);
+        let json = serde_json::to_string_pretty(run).context("Failed to serialize run metadata")?;
+        tokio::fs::write(&meta_path, json.as_bytes())
+            .await
+            .context("Failed to write run metadata")
+    }
+
+    async fn append_log(&self, job_id: Uuid, run_id: Uuid, data: &[u8]) -> Result<()> {
+        self.append_bytes(job_id, run_id, data).await?;
+        Ok(())
+    }
+
+    async fn append_output(
+        &self,
+        job_id: Uuid,
+        run_id: Uuid,
+        stream: OutputStream,
+        data: &[u8],
+    ) -> Result<()> {
+        let offset = self.append_bytes(job_id, run_id, data).await?;
+        if stream == OutputStream::Stderr && !data.is_empty() {
+            use tokio::io::AsyncWriteExt;
+            let mut index = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.stderr_index_path(job_id, run_id))
+                .await
+                .context("Failed to open stderr index")?;
+            index
+                .write_all(format!("{} {}\n", offset, data.len()).as_bytes())
+                .await
+                .context("Failed to append to stderr index")?;
+            index
+                .flush()
+                .await
+                .context("Failed to flush stderr index")?;
+        }
+        Ok(())
+    }
+
+    async fn read_log(&self, job_id: Uuid, run_id: Uuid, tail: Option<usize>) -> Result<String> {
+        let Some(n) = tail else {
+            let content = self.read_log_bytes(job_id, run_id).await?;
+            return Ok(String::from_utf8_lossy(&content.unwrap_or_default()).into_owned());
+        };
+
+        let Some(reader) = self.open_run_log(job_id, run_id).await? else {
+            return Ok(String::new());
+        };
+        tokio::task::spawn_blocking(move || tail_reader(reader, n))
+            .await
+            .context("Log read task failed")?
+    }
+
+    async fn read_output(
+        &self,
+        job_id: Uuid,
+        run_id: Uuid,
+        stream: OutputStream,
+        tail: Option<usize>,
+    ) -> Result<String> {
+        let Some(content) = self.read_log_bytes(job_id, run_id).await? else {
+            return Ok(String::new());
+        };
+        let ranges = self.stderr_ranges(job_id, run_id).await?;
+
+        // Walk the stderr ranges in order; everything between them is stdout.
+        let mut selected = Vec::new();
+        let mut pos = 0;
+        for (offset, len) in ranges {
+            let start = offset.min(content.len());
+            let end = offset.saturating_add(len).min(content.len());
+            if stream == OutputStream::Stdout {
+                selected.extend_from_slice(&content[pos.min(start)..start]);
+            } else {
+                selected.extend_from_slice(&content[start..end]);
             }
+            pos = pos.max(end);
+        }
+        if stream == OutputStream::Stdout {
+            selected.extend_from_slice(&content[pos.min(content.len())..]);
         }
 
+        let text = String::from_utf8_lossy(&selected);
+        match tail {
+            Some(n) => Ok(tail_lines(&text, n)),
+            None => Ok(text.into_owned()),
+        }
+    }
+
+    async fn list_runs(
+        &self,
+        job_id: Uuid,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<JobRun>, usize)> {
+        let mut runs = self.read_runs(job_id).await?;
+
+        // Sort by started_at descending
+        runs.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+
+        let total = runs.len();
+
+        // Apply offset and limit
+        let paginated: Vec<JobRun> = runs.into_iter().skip(offset).take(limit).collect();
+
+        Ok((paginated, total))
+    }
+
+    async fn find_run(&self, run_id: Uuid) -> Result<Option<JobRun>> {
+        let Some(index) = &self.run_index else {
+            return Ok(None);
+        };
+        // Every run is indexed before its metadata is written, so a run
+        // missing from the index does not exist.
+        let Some(job_id) = index.lock().await.runs.get(&run_id).copied() else {
+            return Ok(None);
+        };
+
+        // The index may still list runs whose files were removed outside
+        // the store, such as by the orphaned log cleanup.
+        let meta_path = self.meta_path(job_id, run_id);
+        if !meta_path.exists() {
+            return Ok(None);
+        }
+        let content = tokio::fs::read_to_string(&meta_path)
+            .await
+            .context("Failed to read run metadata")?;
+        let run = serde_json::from_str(&content).context("Failed to parse run metadata")?;
+        Ok(Some(run))
+    }
+
+    async fn query_runs(
+        &self,
+        query: &RunQuery,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<JobRun>, usize)> {
+        let job_ids = match (query.job_id, &query.job_ids) {
+            (Some(job_id), _) => vec![job_id],
+            (None, Some(job_ids)) => job_ids.clone(),
+            (None, None) => self.job_ids().await?,
+        };
+
+        let mut runs = Vec::new();
+        for job_id in job_ids {
+            runs.extend(self.read_runs(job_id).await?);
+        }
+        Ok(query.page(runs, limit, offset))
+    }
+
+    async fn cleanup(&self, job_id: Uuid, max_files: usize) -> Result<()> {
+        let mut runs = self.read_runs(job_id).await?;
+
         if runs.len() <= max_files {
             return Ok(());
         }
 
         // Sort by started_at ascending (oldest first)
-        runs.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        runs.sort_by_key(|r| r.started_at);
+
+        let to_remove: Vec<Uuid> = runs
+            .iter()
+            .take(runs.len() - max_files)
+            .map(|r| r.run_id)
+            .collect();
+        self.remove_runs(job_id, &to_remove).await
+    }
 
-        let to_remove = runs.len() - max_files;
-        for run in runs.iter().take(to_remove) {
-            let meta_path = self.meta_path(job_id, run.run_id);
-            let log_path = self.log_path(job_id, run.run_id);
+    async fn remove_runs(&self, job_id: Uuid, run_ids: &[Uuid]) -> Result<()> {
+        for run_id in run_ids {
+            self.remove_run_files(job_id, *run_id).await?;
+        }
 
-            if meta_path.exists() {
-                tokio::fs::remove_file(&meta_path)
-                    .await
-                    .context("Failed to remove old meta file")?;
-            }
-            if log_path.exists() {
-                tokio::fs::remove_file(&log_path)
-                    .await
-                    .context("Failed to remove old log file")?;
-            }
+        if let Some(index) = &self.run_index {
+            self.unindex_runs(&mut *index.lock().await, run_ids).await?;
         }
 
         Ok(())
     }
+
+    async fn forget_job(&self, job_id: Uuid) -> Result<()> {
+        let Some(index) = &self.run_index else {
+            return Ok(());
+        };
+        let mut index = index.lock().await;
+        let run_ids: Vec<Uuid> = index
+            .runs
+            .iter()
+            .filter(|(_, indexed_job)| **indexed_job == job_id)
+            .map(|(run_id, _)| *run_id)
+            .collect();
+        self.unindex_runs(&mut index, &run_ids).await
+    }
+
+    async fn run_disk_usage(&self, run: &JobRun) -> Result<u64> {
+        self.run_files_size(run.job_id, run.run_id).await
+    }
+
+    async fn compress_finished(&self, run: &JobRun) {
+        if self.compression == LogCompression::None || !is_finished(run) {
+            return;
+        }
+        if let Err(e) = self
+            .compress_log(run.job_id, run.run_id, self.compression)
+            .await
+        {
+            tracing::warn!("Failed to compress the log of run {}: {:#}", run.run_id, e);
+        }
+    }
+
+    async fn compress_logs(&self, compression: LogCompression) -> Result<CompressedLogs> {
+        let mut runs = Vec::new();
+        for job_id in self.job_ids().await? {
+            runs.extend(self.read_runs(job_id).await?);
+        }
+        self.compress_run_logs(&runs, compression).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::RunStatus;
-    use chrono::Utc;
+    use crate::models::{RunStatus, TriggerSource};
+    use crate::storage::RunCursor;
+    use chrono::{DateTime, Utc};
     use tempfile::TempDir;
 
     fn make_job_run(job_id: Uuid) -> JobRun {
@@ -231,6 +921,12 @@ mod tests {
             log_size_bytes: 0,
             error: None,
             trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
         }
     }
 
@@ -307,6 +1003,76 @@ mod tests {
         assert_eq!(content, "line 1\nline 2\nline 3\n");
     }
 
+    #[tokio::test]
+    async fn test_append_output_keeps_interleaving_and_filters_streams() {
+        let (store, _tmp, job_id) = setup_store().await;
+        let run = make_job_run(job_id);
+        store.create_run(&run).await.expect("create run");
+
+        store
+            .append_log(job_id, run.run_id, b"$ cmd\n")
+            .await
+            .expect("append");
+        for (stream, data) in [
+            (OutputStream::Stdout, &b"out 1\n"[..]),
+            (OutputStream::Stderr, &b"err 1\n"[..]),
+            (OutputStream::Stdout, &b"out 2\n"[..]),
+            (OutputStream::Stderr, &b"err 2\n"[..]),
+        ] {
+            store
+                .append_output(job_id, run.run_id, stream, data)
+                .await
+                .expect("append output");
+        }
+
+        let combined = store
+            .read_log(job_id, run.run_id, None)
+            .await
+            .expect("read");
+        assert_eq!(combined, "$ cmd\nout 1\nerr 1\nout 2\nerr 2\n");
+
+        let stdout = store
+            .read_output(job_id, run.run_id, OutputStream::Stdout, None)
+            .await
+            .expect("read stdout");
+        assert_eq!(stdout, "$ cmd\nout 1\nout 2\n");
+
+        let stderr = store
+            .read_output(job_id, run.run_id, OutputStream::Stderr, None)
+            .await
+            .expect("read stderr");
+        assert_eq!(stderr, "err 1\nerr 2\n");
+
+        let stderr_tail = store
+            .read_output(job_id, run.run_id, OutputStream::Stderr, Some(1))
+            .await
+            .expect("read stderr tail");
+        assert_eq!(stderr_tail, "err 2");
+    }
+
+    #[tokio::test]
+    async fn test_read_output_without_stderr_index() {
+        let (store, _tmp, job_id) = setup_store().await;
+        let run = make_job_run(job_id);
+        store.create_run(&run).await.expect("create run");
+        store
+            .append_output(job_id, run.run_id, OutputStream::Stdout, b"only stdout\n")
+            .await
+            .expect("append");
+
+        assert!(!store.stderr_index_path(job_id, run.run_id).exists());
+        let stdout = store
+            .read_output(job_id, run.run_id, OutputStream::Stdout, None)
+            .await
+            .expect("read");
+        assert_eq!(stdout, "only stdout\n");
+        let stderr = store
+            .read_output(job_id, run.run_id, OutputStream::Stderr, None)
+            .await
+            .expect("read");
+        assert!(stderr.is_empty());
+    }
+
     #[tokio::test]
     async fn test_read_log_nonexistent_returns_empty() {
         let (store, _tmp, job_id) = setup_store().await;
@@ -466,6 +1232,225 @@ mod tests {
         assert_eq!(total, 3);
     }
 
+    #[tokio::test]
+    async fn test_find_run_uses_persistent_index() {
+        let (store, tmp, job_id) = setup_store().await;
+        let first = make_job_run(job_id);
+        let second = make_job_run(job_id);
+        store.create_run(&first).await.expect("create run");
+        store.create_run(&second).await.expect("create run");
+
+        assert_eq!(
+            store.find_run(first.run_id).await.expect("find"),
+            Some(first.clone())
+        );
+        assert!(store
+            .find_run(Uuid::now_v7())
+            .await
+            .expect("find")
+            .is_none());
+
+        // The index survives a restart, and is rebuilt if it goes missing.
+        let reopened = FsLogStore::new(tmp.path().to_path_buf())
+            .await
+            .expect("reopen");
+        assert!(reopened
+            .find_run(second.run_id)
+            .await
+            .expect("find")
+            .is_some());
+        std::fs::remove_file(tmp.path().join("logs").join("runs.idx")).expect("remove index");
+        let rebuilt = FsLogStore::new(tmp.path().to_path_buf())
+            .await
+            .expect("rebuild");
+        assert!(rebuilt
+            .find_run(first.run_id)
+            .await
+            .expect("find")
+            .is_some());
+
+        // Runs removed by cleanup leave the index, through a tombstone
+        // rather than a rewrite.
+        rebuilt.cleanup(job_id, 1).await.expect("cleanup");
+        let index =
+            std::fs::read_to_string(tmp.path().join("logs").join("runs.idx")).expect("read index");
+        assert_eq!(
+            index.lines().last(),
+            Some(format!("-{}", first.run_id).as_str())
+        );
+        let reopened = FsLogStore::new(tmp.path().to_path_buf())
+            .await
+            .expect("reopen");
+        assert!(reopened
+            .find_run(first.run_id)
+            .await
+            .expect("find")
+            .is_none());
+
+        // A run whose metadata was written without an index entry is not
+        // looked for on disk.
+        let unindexed = make_job_run(job_id);
+        std::fs::write(
+            tmp.path()
+                .join("logs")
+                .join(job_id.to_string())
+                .join(format!("{}.meta.json", unindexed.run_id)),
+            serde_json::to_string(&unindexed).unwrap(),
+        )
+        .expect("write metadata");
+        assert!(rebuilt
+            .find_run(unindexed.run_id)
+            .await
+            .expect("find")
+            .is_none());
+
+        // Forgetting a deleted job drops its runs from the index.
+        rebuilt.forget_job(job_id).await.expect("forget job");
+        assert!(rebuilt
+            .find_run(second.run_id)
+            .await
+            .expect("find")
+            .is_none());
+        let reopened = FsLogStore::new(tmp.path().to_path_buf())
+            .await
+            .expect("reopen");
+        assert!(reopened.run_index.unwrap().into_inner().runs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_index_is_compacted_once_mostly_stale() {
+        let (store, tmp, job_id) = setup_store().await;
+        for _ in 0..RUN_INDEX_COMPACT_MIN_LINES {
+            let run = make_job_run(job_id);
+            store.create_run(&run).await.expect("create run");
+            store.cleanup(job_id, 1).await.expect("cleanup");
+        }
+
+        let lines = std::fs::read_to_string(tmp.path().join("logs").join("runs.idx"))
+            .expect("read index")
+            .lines()
+            .count();
+        assert!(
+            lines < RUN_INDEX_COMPACT_MIN_LINES,
+            "index has {} lines for 1 run",
+            lines
+        );
+        let reopened = FsLogStore::new(tmp.path().to_path_buf())
+            .await
+            .expect("reopen");
+        assert_eq!(reopened.run_index.unwrap().into_inner().runs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_query_runs_across_jobs() {
+        let (store, _tmp, job_id) = setup_store().await;
+        let other_job = Uuid::now_v7();
+
+        let mut failed = make_job_run(job_id);
+        failed.status = RunStatus::Failed;
+        let running = make_job_run(job_id);
+        let mut other_failed = make_job_run(other_job);
+        other_failed.status = RunStatus::Failed;
+        for run in [&failed, &running, &other_failed] {
+            store.create_run(run).await.expect("create run");
+        }
+
+        let query = RunQuery {
+            status: Some(RunStatus::Failed),
+            ..Default::default()
+        };
+        let (runs, total) = store.query_runs(&query, 10, 0).await.expect("query");
+        assert_eq!(total, 2);
+        assert_eq!(runs, [other_failed, failed.clone()]);
+
+        let query = RunQuery {
+            job_id: Some(job_id),
+            started_before: Some(running.started_at),
+            ..Default::default()
+        };
+        let (runs, _) = store.query_runs(&query, 10, 0).await.expect("query");
+        assert_eq!(runs, [failed]);
+    }
+
+    #[tokio::test]
+    async fn test_query_runs_filters_and_cursor() {
+        let (store, _tmp, job_id) = setup_store().await;
+
+        let mut runs = Vec::new();
+        for exit_code in [0, 1, 1] {
+            let mut run = make_job_run(job_id);
+            run.status = RunStatus::Completed;
+            run.exit_code = Some(exit_code);
+            run.finished_at = Some(run.started_at + chrono::Duration::seconds(1));
+            run.trigger = Some(TriggerSource::Schedule);
+            store.create_run(&run).await.expect("create run");
+            runs.push(run);
+        }
+        let mut failed = make_job_run(job_id);
+        failed.status = RunStatus::Failed;
+        failed.error = Some("PTY spawn failed: No such file".to_string());
+        store.create_run(&failed).await.expect("create run");
+
+        let query = RunQuery {
+            exit_code: Some(1),
+            trigger: Some(TriggerSource::Schedule),
+            ..Default::default()
+        };
+        let (page, total) = store.query_runs(&query, 1, 0).await.expect("query");
+        assert_eq!((total, page.len()), (2, 1));
+        assert_eq!(page[0], runs[2]);
+
+        let next = RunQuery {
+            before: Some(RunCursor::after(&page[0])),
+            ..query
+        };
+        let (page, total) = store.query_runs(&next, 1, 0).await.expect("query");
+        assert_eq!((total, page.len()), (2, 1));
+        assert_eq!(page[0], runs[1]);
+
+        let query = RunQuery {
+            finished_after: Some(runs[0].finished_at.unwrap()),
+            ..Default::default()
+        };
+        let (_, total) = store.query_runs(&query, 10, 0).await.expect("query");
+        assert_eq!(total, 3);
+
+        let query = RunQuery {
+            error_contains: Some("spawn FAILED".to_string()),
+            ..Default::default()
+        };
+        let (page, _) = store.query_runs(&query, 10, 0).await.expect("query");
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0], failed);
+    }
+
+    #[tokio::test]
+    async fn test_query_runs_cursor_within_a_microsecond() {
+        let (store, _tmp, job_id) = setup_store().await;
+        // Two runs in the same microsecond, the later one with the lower id.
+        let base = DateTime::from_timestamp(1_700_000_000, 1_000).unwrap();
+        let mut low = make_job_run(job_id);
+        let mut high = make_job_run(job_id);
+        low.started_at = base + chrono::Duration::nanoseconds(500);
+        high.started_at = base;
+        store.create_run(&low).await.expect("create run");
+        store.create_run(&high).await.expect("create run");
+
+        let mut seen = Vec::new();
+        let mut query = RunQuery::default();
+        loop {
+            let (page, total) = store.query_runs(&query, 1, 0).await.expect("query");
+            assert_eq!(total, 2);
+            let Some(run) = page.into_iter().next() else {
+                break;
+            };
+            let cursor = RunCursor::after(&run).to_string();
+            query.before = Some(cursor.parse().expect("parse cursor"));
+            seen.push(run.run_id);
+        }
+        assert_eq!(seen, [high.run_id, low.run_id]);
+    }
+
     #[tokio::test]
     async fn test_cleanup_removes_log_files_too() {
         let (store, tmp, job_id) = setup_store().await;
@@ -476,6 +1461,12 @@ mod tests {
             .append_log(job_id, run.run_id, b"data\n")
             .await
             .expect("append");
+        store
+            .append_output(job_id, run.run_id, OutputStream::Stderr, b"oops\n")
+            .await
+            .expect("append stderr");
+        let index_path = store.stderr_index_path(job_id, run.run_id);
+        assert!(index_path.exists());
 
         // Verify log file exists
         let log_path = tmp
@@ -495,6 +1486,271 @@ mod tests {
 
         // Old log file should be gone
         assert!(!log_path.exists(), "Old log file should have been removed");
+        assert!(
+            !index_path.exists(),
+            "Old stderr index should have been removed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_runs_and_disk_usage() {
+        let (store, _tmp, job_id) = setup_store().await;
+        let kept = make_job_run(job_id);
+        let removed = make_job_run(job_id);
+        for run in [&kept, &removed] {
+            store.create_run(run).await.unwrap();
+            store
+                .append_output(job_id, run.run_id, OutputStream::Stderr, b"oops\n")
+                .await
+                .unwrap();
+        }
+
+        let meta_len = std::fs::metadata(store.meta_path(job_id, removed.run_id))
+            .unwrap()
+            .len();
+        let usage = store.run_disk_usage(&removed).await.unwrap();
+        assert!(
+            usage > meta_len + 5,
+            "usage {} should cover every file",
+            usage
+        );
+
+        store.remove_runs(job_id, &[removed.run_id]).await.unwrap();
+        assert!(!store.log_path(job_id, removed.run_id).exists());
+        assert!(!store.stderr_index_path(job_id, removed.run_id).exists());
+        assert!(store.find_run(removed.run_id).await.unwrap().is_none());
+        assert_eq!(store.run_disk_usage(&removed).await.unwrap(), 0);
+
+        let (runs, _) = store.list_runs(job_id, 10, 0).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].run_id, kept.run_id);
+        assert!(store.find_run(kept.run_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_finished_runs_are_compressed_and_read_transparently() {
+        for compression in LogCompression::FORMATS {
+            let (store, _tmp, job_id) = setup_store().await;
+            let store = store.with_compression(compression);
+            let mut run = make_job_run(job_id);
+            store.create_run(&run).await.unwrap();
+            for i in 0..50 {
+                let (stream, line) = if i % 10 == 9 {
+                    (OutputStream::Stderr, format!("warning {}\n", i))
+                } else {
+                    (OutputStream::Stdout, format!("line {}\n", i))
+                };
+                store
+                    .append_output(job_id, run.run_id, stream, line.as_bytes())
+                    .await
+                    .unwrap();
+            }
+            let full = store.read_log(job_id, run.run_id, None).await.unwrap();
+            let tail = store.read_log(job_id, run.run_id, Some(3)).await.unwrap();
+            let stderr = store
+                .read_output(job_id, run.run_id, OutputStream::Stderr, None)
+                .await
+                .unwrap();
+
+            // Still running: the log stays plain.
+            store.update_run(&run).await.unwrap();
+            store.compress_finished(&run).await;
+            assert!(store.log_path(job_id, run.run_id).exists());
+
+            // Saving the finished run leaves compressing to its own call.
+            run.status = RunStatus::Completed;
+            run.finished_at = Some(Utc::now());
+            store.update_run(&run).await.unwrap();
+            assert!(store.log_path(job_id, run.run_id).exists());
+            store.compress_finished(&run).await;
+            let archive = store.archive_path(job_id, run.run_id, compression.extension().unwrap());
+            assert!(archive.exists(), "{} archive should exist", compression);
+            assert!(!store.log_path(job_id, run.run_id).exists());
+
+            assert_eq!(
+                store.read_log(job_id, run.run_id, None).await.unwrap(),
+                full
+            );
+            assert_eq!(
+                store.read_log(job_id, run.run_id, Some(3)).await.unwrap(),
+                tail
+            );
+            assert_eq!(tail, "line 47\nline 48\nwarning 49");
+            assert_eq!(
+                store
+                    .read_output(job_id, run.run_id, OutputStream::Stderr, None)
+                    .await
+                    .unwrap(),
+                stderr
+            );
+            assert!(
+                store.run_disk_usage(&run).await.unwrap()
+                    >= std::fs::metadata(&archive).unwrap().len()
+            );
+
+            // Output written after compression reopens the log, so stderr
+            // offsets still point into the whole log.
+            store
+                .append_output(job_id, run.run_id, OutputStream::Stdout, b"late\n")
+                .await
+                .unwrap();
+            store
+                .append_output(job_id, run.run_id, OutputStream::Stderr, b"late warning\n")
+                .await
+                .unwrap();
+            assert!(!archive.exists());
+            assert_eq!(
+                store.read_log(job_id, run.run_id, None).await.unwrap(),
+                format!("{}late\nlate warning\n", full)
+            );
+            assert_eq!(
+                store
+                    .read_output(job_id, run.run_id, OutputStream::Stderr, None)
+                    .await
+                    .unwrap(),
+                format!("{}late warning\n", stderr)
+            );
+            store.compress_finished(&run).await;
+            assert!(archive.exists());
+
+            store.remove_runs(job_id, &[run.run_id]).await.unwrap();
+            assert!(!archive.exists());
+            assert!(!store.log_path(job_id, run.run_id).exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logs_read_while_compressing_hold_the_output_once() {
+        let (store, _tmp, job_id) = setup_store().await;
+        let store = Arc::new(store.with_compression(LogCompression::Gzip));
+        let mut run = make_job_run(job_id);
+        store.create_run(&run).await.unwrap();
+        let full = "some output\n".repeat(20_000);
+        store
+            .append_log(job_id, run.run_id, full.as_bytes())
+            .await
+            .unwrap();
+        run.status = RunStatus::Completed;
+        store.update_run(&run).await.unwrap();
+
+        let compress = tokio::spawn({
+            let store = Arc::clone(&store);
+            let run = run.clone();
+            async move { store.compress_finished(&run).await }
+        });
+        while !compress.is_finished() {
+            assert_eq!(
+                store.read_log(job_id, run.run_id, None).await.unwrap(),
+                full
+            );
+        }
+        compress.await.unwrap();
+
+        // A compression interrupted before removing the plain log leaves two
+        // copies; the plain one is read, and replaced on the next append.
+        let archive = store.archive_path(job_id, run.run_id, "gz");
+        assert!(archive.exists());
+        std::fs::write(store.log_path(job_id, run.run_id), &full).unwrap();
+        assert_eq!(
+            store.read_log(job_id, run.run_id, None).await.unwrap(),
+            full
+        );
+        store
+            .append_log(job_id, run.run_id, b"late\n")
+            .await
+            .unwrap();
+        assert!(!archive.exists());
+        assert_eq!(
+            store.read_log(job_id, run.run_id, None).await.unwrap(),
+            format!("{}late\n", full)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_runs_removed_while_compressing_leave_no_files() {
+        let (store, tmp, job_id) = setup_store().await;
+        let store = Arc::new(store.with_compression(LogCompression::Gzip));
+        let mut run = make_job_run(job_id);
+        store.create_run(&run).await.unwrap();
+        store
+            .append_log(
+                job_id,
+                run.run_id,
+                "some output\n".repeat(20_000).as_bytes(),
+            )
+            .await
+            .unwrap();
+        run.status = RunStatus::Completed;
+        store.update_run(&run).await.unwrap();
+
+        let compress = tokio::spawn({
+            let store = Arc::clone(&store);
+            let run = run.clone();
+            async move { store.compress_finished(&run).await }
+        });
+        store.remove_runs(job_id, &[run.run_id]).await.unwrap();
+        compress.await.unwrap();
+
+        let left: Vec<_> = std::fs::read_dir(tmp.path().join("logs").join(job_id.to_string()))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert!(left.is_empty(), "files left behind: {:?}", left);
+    }
+
+    #[tokio::test]
+    async fn test_compress_logs_converts_existing_logs() {
+        let (store, _tmp, job_id) = setup_store().await;
+        let mut finished = make_job_run(job_id);
+        let running = make_job_run(job_id);
+        for run in [&finished, &running] {
+            store.create_run(run).await.unwrap();
+            store
+                .append_log(job_id, run.run_id, "hello world\n".repeat(100).as_bytes())
+                .await
+                .unwrap();
+        }
+        finished.status = RunStatus::Failed;
+        finished.finished_at = Some(Utc::now());
+        store.update_run(&finished).await.unwrap();
+        assert!(
+            store.log_path(job_id, finished.run_id).exists(),
+            "a store without compression keeps plain logs"
+        );
+
+        let compressed = store.compress_logs(LogCompression::Gzip).await.unwrap();
+        assert_eq!(compressed.runs, 1);
+        assert_eq!(compressed.bytes_before, 1200);
+        assert!(compressed.bytes_after < compressed.bytes_before);
+        assert!(store.archive_path(job_id, finished.run_id, "gz").exists());
+        assert!(store.log_path(job_id, running.run_id).exists());
+        assert_eq!(
+            store
+                .read_log(job_id, finished.run_id, Some(1))
+                .await
+                .unwrap(),
+            "hello world"
+        );
+
+        // Already compressed logs are left alone.
+        let again = store.compress_logs(LogCompression::Zstd).await.unwrap();
+        assert_eq!(again, CompressedLogs::default());
+        assert!(!store.archive_path(job_id, finished.run_id, "zst").exists());
+    }
+
+    #[test]
+    fn test_tail_reader_matches_tail_lines() {
+        for content in ["", "a", "a\n", "a\nb\nc", "a\r\nb\r\n\nc\n", "\n\n"] {
+            for n in 0..5 {
+                assert_eq!(
+                    tail_reader(content.as_bytes(), n).unwrap(),
+                    tail_lines(content, n),
+                    "content {:?}, n {}",
+                    content,
+                    n
+                );
+            }
+        }
     }
 
     #[tokio::test]
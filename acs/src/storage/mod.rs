@@ -1,11 +1,19 @@
+pub mod calendars;
 pub mod jobs;
 pub mod logs;
+pub mod sqlite;
+pub mod workflows;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::Serialize;
 use uuid::Uuid;
 
-use crate::models::{Job, JobRun, JobUpdate, NewJob};
+use crate::models::{
+    Calendar, Job, JobRun, JobUpdate, LogCompression, NewJob, NewWorkflow, OutputStream, RunStatus,
+    TriggerSource, Workflow, WorkflowRun, WorkflowUpdate,
+};
 
 #[async_trait]
 pub trait JobStore: Send + Sync {
@@ -13,8 +21,203 @@ pub trait JobStore: Send + Sync {
     async fn get_job(&self, id: Uuid) -> Result<Option<Job>>;
     async fn find_by_name(&self, name: &str) -> Result<Option<Job>>;
     async fn create_job(&self, new: NewJob) -> Result<Job>;
+    /// Create a job defined by `source_file` in the daemon's `jobs_dir`.
+    /// Stores that can should record the file along with the job; the
+    /// default creates the job, then sets its `source_file`.
+    async fn create_file_job(&self, new: NewJob, source_file: String) -> Result<Job> {
+        let job = self.create_job(new).await?;
+        let update = JobUpdate {
+            source_file: Some(Some(source_file)),
+            ..Default::default()
+        };
+        self.update_job(job.id, update).await
+    }
     async fn update_job(&self, id: Uuid, update: JobUpdate) -> Result<Job>;
     async fn delete_job(&self, id: Uuid) -> Result<()>;
+    /// Replace the definition of job `id` with `new`, keeping its id,
+    /// creation time and run metadata. Unlike `update_job`, fields that
+    /// `new` leaves unset are cleared.
+    async fn replace_job(&self, id: Uuid, new: NewJob) -> Result<Job>;
+
+    /// Apply several updates, returning each one's outcome in order. The
+    /// outer error means the changes could not be saved. Stores that can
+    /// should save them all at once; the default updates one job at a time.
+    async fn update_jobs(&self, updates: Vec<(Uuid, JobUpdate)>) -> Result<Vec<Result<Job>>> {
+        let mut results = Vec::with_capacity(updates.len());
+        for (id, update) in updates {
+            results.push(self.update_job(id, update).await);
+        }
+        Ok(results)
+    }
+
+    /// Delete several jobs, returning each one's outcome in order, like
+    /// `update_jobs`.
+    async fn delete_jobs(&self, ids: &[Uuid]) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            results.push(self.delete_job(*id).await);
+        }
+        Ok(results)
+    }
+}
+
+/// Named calendars that jobs reference by name.
+#[async_trait]
+pub trait CalendarStore: Send + Sync {
+    async fn list_calendars(&self) -> Result<Vec<Calendar>>;
+    async fn get_calendar(&self, name: &str) -> Result<Option<Calendar>>;
+    async fn create_calendar(&self, calendar: Calendar) -> Result<Calendar>;
+    /// Replace the calendar with the same name.
+    async fn update_calendar(&self, calendar: Calendar) -> Result<Calendar>;
+    async fn delete_calendar(&self, name: &str) -> Result<()>;
+}
+
+/// Workflows and the records of their runs.
+#[async_trait]
+pub trait WorkflowStore: Send + Sync {
+    async fn list_workflows(&self) -> Result<Vec<Workflow>>;
+    async fn get_workflow(&self, id: Uuid) -> Result<Option<Workflow>>;
+    async fn find_workflow_by_name(&self, name: &str) -> Result<Option<Workflow>>;
+    async fn create_workflow(&self, new: NewWorkflow) -> Result<Workflow>;
+    async fn update_workflow(&self, id: Uuid, update: WorkflowUpdate) -> Result<Workflow>;
+    /// Delete a workflow together with its runs.
+    async fn delete_workflow(&self, id: Uuid) -> Result<()>;
+    /// Create or replace a workflow run record.
+    async fn save_workflow_run(&self, run: &WorkflowRun) -> Result<()>;
+    async fn get_workflow_run(
+        &self,
+        workflow_id: Uuid,
+        run_id: Uuid,
+    ) -> Result<Option<WorkflowRun>>;
+    /// A page of a workflow's runs, newest first, and the total count.
+    async fn list_workflow_runs(
+        &self,
+        workflow_id: Uuid,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<WorkflowRun>, usize)>;
+    /// Keep only the `max_runs` newest runs of a workflow.
+    async fn cleanup_workflow_runs(&self, workflow_id: Uuid, max_runs: usize) -> Result<()>;
+}
+
+/// Which runs [`LogStore::query_runs`] returns. Unset fields match any run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunQuery {
+    pub job_id: Option<Uuid>,
+    /// Only runs of one of these jobs.
+    pub job_ids: Option<Vec<Uuid>>,
+    pub status: Option<RunStatus>,
+    pub exit_code: Option<i32>,
+    pub trigger: Option<TriggerSource>,
+    /// Only runs that started at or after this time.
+    pub started_after: Option<DateTime<Utc>>,
+    /// Only runs that started before this time.
+    pub started_before: Option<DateTime<Utc>>,
+    /// Only runs that finished at or after this time.
+    pub finished_after: Option<DateTime<Utc>>,
+    /// Only runs that finished before this time.
+    pub finished_before: Option<DateTime<Utc>>,
+    /// Only runs whose error contains this text, ignoring ASCII case.
+    pub error_contains: Option<String>,
+    /// Only runs that come after this cursor, newest first. Unlike the
+    /// other fields it does not narrow the total count.
+    pub before: Option<RunCursor>,
+}
+
+impl RunQuery {
+    /// Whether the run matches every field except `before`.
+    pub fn matches(&self, run: &JobRun) -> bool {
+        self.job_id.is_none_or(|id| run.job_id == id)
+            && self
+                .job_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&run.job_id))
+            && self.status.as_ref().is_none_or(|s| run.status == *s)
+            && self.exit_code.is_none_or(|c| run.exit_code == Some(c))
+            && self.trigger.is_none_or(|t| run.trigger_source() == t)
+            && self.started_after.is_none_or(|t| run.started_at >= t)
+            && self.started_before.is_none_or(|t| run.started_at < t)
+            && self
+                .finished_after
+                .is_none_or(|t| run.finished_at.is_some_and(|f| f >= t))
+            && self
+                .finished_before
+                .is_none_or(|t| run.finished_at.is_some_and(|f| f < t))
+            && self.error_contains.as_ref().is_none_or(|text| {
+                run.error
+                    .as_ref()
+                    .is_some_and(|e| e.to_ascii_lowercase().contains(&text.to_ascii_lowercase()))
+            })
+    }
+
+    /// A page of the matching `runs`, newest first, and the total count.
+    pub(crate) fn page(
+        &self,
+        runs: impl IntoIterator<Item = JobRun>,
+        limit: usize,
+        offset: usize,
+    ) -> (Vec<JobRun>, usize) {
+        let mut runs: Vec<JobRun> = runs.into_iter().filter(|r| self.matches(r)).collect();
+        runs.sort_by_key(|r| std::cmp::Reverse(RunCursor::position(r)));
+        let total = runs.len();
+        let page = runs
+            .into_iter()
+            .filter(|r| self.before.as_ref().is_none_or(|c| c.precedes(r)))
+            .skip(offset)
+            .take(limit)
+            .collect();
+        (page, total)
+    }
+}
+
+/// A position in a newest-first list of runs: the start time and id of the
+/// last run of a page. Written as `<started_at micros>:<run_id>`, so runs
+/// are ordered by their start time to the microsecond.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunCursor {
+    pub started_at: DateTime<Utc>,
+    pub run_id: Uuid,
+}
+
+impl RunCursor {
+    pub fn after(run: &JobRun) -> Self {
+        Self {
+            started_at: run.started_at.trunc_subsecs(6),
+            run_id: run.run_id,
+        }
+    }
+
+    /// Whether `run` comes after the cursor, newest first.
+    pub fn precedes(&self, run: &JobRun) -> bool {
+        Self::position(run) < (self.started_at.timestamp_micros(), self.run_id)
+    }
+
+    /// The sort key of `run` in a list that cursors page through.
+    pub(crate) fn position(run: &JobRun) -> (i64, Uuid) {
+        (run.started_at.timestamp_micros(), run.run_id)
+    }
+}
+
+impl std::fmt::Display for RunCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.started_at.timestamp_micros(), self.run_id)
+    }
+}
+
+impl std::str::FromStr for RunCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid cursor '{}'", s);
+        let (micros, run_id) = s.split_once(':').ok_or_else(invalid)?;
+        let started_at = micros
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let run_id = Uuid::parse_str(run_id).map_err(|_| invalid())?;
+        Ok(Self { started_at, run_id })
+    }
 }
 
 #[async_trait]
@@ -23,11 +226,92 @@ pub trait LogStore: Send + Sync {
     async fn update_run(&self, run: &JobRun) -> Result<()>;
     async fn append_log(&self, job_id: Uuid, run_id: Uuid, data: &[u8]) -> Result<()>;
     async fn read_log(&self, job_id: Uuid, run_id: Uuid, tail: Option<usize>) -> Result<String>;
+    /// Append process output tagged with the stream it came from. Stores that
+    /// don't track streams fall back to the combined log.
+    async fn append_output(
+        &self,
+        job_id: Uuid,
+        run_id: Uuid,
+        stream: OutputStream,
+        data: &[u8],
+    ) -> Result<()> {
+        let _ = stream;
+        self.append_log(job_id, run_id, data).await
+    }
+    /// Read only the output of one stream, in the order it was written.
+    /// Anything appended through `append_log` counts as stdout. Stores that
+    /// don't track streams return the combined log for stdout and nothing
+    /// for stderr.
+    async fn read_output(
+        &self,
+        job_id: Uuid,
+        run_id: Uuid,
+        stream: OutputStream,
+        tail: Option<usize>,
+    ) -> Result<String> {
+        match stream {
+            OutputStream::Stdout => self.read_log(job_id, run_id, tail).await,
+            OutputStream::Stderr => Ok(String::new()),
+        }
+    }
     async fn list_runs(
         &self,
         job_id: Uuid,
         limit: usize,
         offset: usize,
     ) -> Result<(Vec<JobRun>, usize)>;
+    /// Look up a run by its id alone, without knowing its job.
+    async fn find_run(&self, run_id: Uuid) -> Result<Option<JobRun>>;
+    /// A page of the runs matching `query`, newest first, and the total
+    /// count. The default only supports queries for given jobs' runs.
+    async fn query_runs(
+        &self,
+        query: &RunQuery,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<JobRun>, usize)> {
+        let job_ids = match (query.job_id, &query.job_ids) {
+            (Some(job_id), _) => vec![job_id],
+            (None, Some(job_ids)) => job_ids.clone(),
+            (None, None) => anyhow::bail!("This log store can only query the runs of given jobs"),
+        };
+        let mut runs = Vec::new();
+        for job_id in job_ids {
+            runs.extend(self.list_runs(job_id, usize::MAX, 0).await?.0);
+        }
+        Ok(query.page(runs, limit, offset))
+    }
     async fn cleanup(&self, job_id: Uuid, max_files: usize) -> Result<()>;
+    /// Delete the given runs of a job, both their records and their output.
+    async fn remove_runs(&self, job_id: Uuid, run_ids: &[Uuid]) -> Result<()>;
+    /// Stop finding the runs of a deleted job by run id. The default does
+    /// nothing.
+    async fn forget_job(&self, _job_id: Uuid) -> Result<()> {
+        Ok(())
+    }
+    /// The bytes a run takes on disk. The default counts its output only.
+    async fn run_disk_usage(&self, run: &JobRun) -> Result<u64> {
+        Ok(run.log_size_bytes)
+    }
+    /// Compress the log of `run` with the store's `log_compression`, if it
+    /// has finished. Kept out of `update_run` so a run's final record is
+    /// announced without waiting on it. Failures are logged, since the
+    /// plain log is still there to read. The default does nothing.
+    async fn compress_finished(&self, _run: &JobRun) {}
+    /// Compress the logs of finished runs that are still plain text. The
+    /// default cannot compress logs.
+    async fn compress_logs(&self, _compression: LogCompression) -> Result<CompressedLogs> {
+        anyhow::bail!("This log store cannot compress logs")
+    }
+}
+
+/// The logs converted by [`LogStore::compress_logs`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CompressedLogs {
+    /// The number of logs compressed.
+    pub runs: usize,
+    /// Their combined size before compression.
+    pub bytes_before: u64,
+    /// Their combined size after compression.
+    pub bytes_after: u64,
 }
@@ -0,0 +1,989 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use uuid::Uuid;
+
+use crate::errors::AcsError;
+use crate::models::job::validate_new_job;
+use crate::models::{Job, JobRun, JobUpdate, LogCompression, NewJob, OutputStream, RunStatus};
+use crate::storage::jobs::{apply_update, new_job, JsonJobStore};
+use crate::storage::logs::FsLogStore;
+use crate::storage::{CompressedLogs, JobStore, LogStore, RunQuery};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS jobs (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS runs (
+        run_id TEXT PRIMARY KEY,
+        job_id TEXT NOT NULL,
+        status TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        finished_at INTEGER,
+        exit_code INTEGER,
+        trigger_source TEXT,
+        error TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS runs_by_job ON runs (job_id, started_at);
+    CREATE INDEX IF NOT EXISTS runs_by_status ON runs (status, started_at);
+    CREATE INDEX IF NOT EXISTS runs_by_time ON runs (started_at);
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+/// Set in `meta` once `jobs.json` and `logs/` have been imported.
+const IMPORTED_KEY: &str = "imported_files";
+
+/// A SQLite database (`acs.db`) shared by [`SqliteJobStore`] and
+/// [`SqliteLogStore`]. Statements run on the blocking thread pool, one at a
+/// time.
+#[derive(Clone)]
+pub struct Database {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl Database {
+    /// Open (or create) the database at `path` and bring its schema up to
+    /// date.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut conn = Connection::open(path)
+            .with_context(|| format!("Failed to open database {}", path.display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create database schema")?;
+        add_run_filter_columns(&mut conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Run `f` inside a transaction, committing it if `f` succeeds.
+    async fn transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Transaction) -> Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Database connection lock poisoned"))?;
+            let tx = conn.transaction()?;
+            let result = f(&tx)?;
+            tx.commit().context("Failed to commit transaction")?;
+            Ok(result)
+        })
+        .await
+        .context("Database task failed")?
+    }
+}
+
+/// Add the columns that run queries filter on to a `runs` table created
+/// before they existed, filling them in from each run's data.
+fn add_run_filter_columns(conn: &mut Connection) -> Result<()> {
+    let has_columns = conn
+        .prepare("SELECT 1 FROM pragma_table_info('runs') WHERE name = 'trigger_source'")?
+        .exists([])?;
+    if has_columns {
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+    tx.execute_batch(
+        "ALTER TABLE runs ADD COLUMN finished_at INTEGER;
+         ALTER TABLE runs ADD COLUMN exit_code INTEGER;
+         ALTER TABLE runs ADD COLUMN trigger_source TEXT;
+         ALTER TABLE runs ADD COLUMN error TEXT;",
+    )
+    .context("Failed to add run columns")?;
+    let runs = {
+        let mut stmt = tx.prepare("SELECT data FROM runs")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let mut runs = Vec::new();
+        for data in rows {
+            let run: JobRun =
+                serde_json::from_str(&data?).context("Failed to parse run metadata")?;
+            runs.push(run);
+        }
+        runs
+    };
+    for run in &runs {
+        save_run(&tx, run)?;
+    }
+    tx.commit().context("Failed to commit transaction")?;
+    Ok(())
+}
+
+fn name_taken(tx: &Transaction, name: &str, except: Option<Uuid>) -> Result<bool> {
+    let except = except.map(|id| id.to_string()).unwrap_or_default();
+    let taken = tx
+        .query_row(
+            "SELECT 1 FROM jobs WHERE name = ?1 AND id <> ?2",
+            params![name, except],
+            |_| Ok(()),
+        )
+        .optional()?;
+    Ok(taken.is_some())
+}
+
+fn conflict(name: &str) -> anyhow::Error {
+    AcsError::Conflict(format!("A job with name '{}' already exists", name)).into()
+}
+
+fn not_found(id: Uuid) -> anyhow::Error {
+    AcsError::NotFound(format!("Job with id '{}' not found", id)).into()
+}
+
+fn load_job(tx: &Transaction, id: Uuid) -> Result<Option<Job>> {
+    let data: Option<String> = tx
+        .query_row(
+            "SELECT data FROM jobs WHERE id = ?1",
+            [id.to_string()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    data.map(|d| serde_json::from_str(&d).context("Failed to parse stored job"))
+        .transpose()
+}
+
+fn save_job(tx: &Transaction, job: &Job) -> Result<()> {
+    let data = serde_json::to_string(job).context("Failed to serialize job")?;
+    tx.execute(
+        "INSERT INTO jobs (id, name, data) VALUES (?1, ?2, ?3)
+         ON CONFLICT (id) DO UPDATE SET name = excluded.name, data = excluded.data",
+        params![job.id.to_string(), job.name, data],
+    )?;
+    Ok(())
+}
+
+fn update_one(tx: &Transaction, id: Uuid, update: JobUpdate) -> Result<Job> {
+    let mut job = load_job(tx, id)?.ok_or_else(|| not_found(id))?;
+    if let Some(name) = &update.name {
+        if name_taken(tx, name, Some(id))? {
+            return Err(conflict(name));
+        }
+    }
+    let job = apply_update(std::slice::from_mut(&mut job), id, update)?;
+    save_job(tx, &job)?;
+    Ok(job)
+}
+
+fn delete_one(tx: &Transaction, id: Uuid) -> Result<()> {
+    match tx.execute("DELETE FROM jobs WHERE id = ?1", [id.to_string()])? {
+        0 => Err(not_found(id)),
+        _ => Ok(()),
+    }
+}
+
+/// Jobs kept in the `jobs` table, one row per job. Unlike `JsonJobStore`,
+/// an update writes only the job that changed.
+pub struct SqliteJobStore {
+    db: Database,
+}
+
+impl SqliteJobStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Validate and add a new job, defined by `source_file` if given.
+    async fn insert_job(&self, new: NewJob, source_file: Option<String>) -> Result<Job> {
+        validate_new_job(&new)?;
+        self.db
+            .transaction(move |tx| {
+                if name_taken(tx, &new.name, None)? {
+                    return Err(conflict(&new.name));
+                }
+                let job = Job {
+                    source_file,
+                    ..new_job(new)
+                };
+                save_job(tx, &job)?;
+                Ok(job)
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl JobStore for SqliteJobStore {
+    async fn list_jobs(&self) -> Result<Vec<Job>> {
+        self.db
+            .transaction(|tx| {
+                let mut stmt = tx.prepare("SELECT data FROM jobs ORDER BY rowid")?;
+                let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+                let mut jobs = Vec::new();
+                for data in rows {
+                    jobs.push(serde_json::from_str(&data?).context("Failed to parse stored job")?);
+                }
+                Ok(jobs)
+            })
+            .await
+    }
+
+    async fn get_job(&self, id: Uuid) -> Result<Option<Job>> {
+        self.db.transaction(move |tx| load_job(tx, id)).await
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Job>> {
+        let name = name.to_string();
+        self.db
+            .transaction(move |tx| {
+                let data: Option<String> = tx
+                    .query_row("SELECT data FROM jobs WHERE name = ?1", [name], |row| {
+                        row.get(0)
+                    })
+                    .optional()?;
+                data.map(|d| serde_json::from_str(&d).context("Failed to parse stored job"))
+                    .transpose()
+            })
+            .await
+    }
+
+    async fn create_job(&self, new: NewJob) -> Result<Job> {
+        self.insert_job(new, None).await
+    }
+
+    async fn create_file_job(&self, new: NewJob, source_file: String) -> Result<Job> {
+        self.insert_job(new, Some(source_file)).await
+    }
+
+    async fn update_job(&self, id: Uuid, update: JobUpdate) -> Result<Job> {
+        self.db
+            .transaction(move |tx| update_one(tx, id, update))
+            .await
+    }
+
+    async fn delete_job(&self, id: Uuid) -> Result<()> {
+        self.db.transaction(move |tx| delete_one(tx, id)).await
+    }
+
+    async fn replace_job(&self, id: Uuid, new: NewJob) -> Result<Job> {
+        validate_new_job(&new)?;
+        self.db
+            .transaction(move |tx| {
+                if name_taken(tx, &new.name, Some(id))? {
+                    return Err(conflict(&new.name));
+                }
+                let mut job = load_job(tx, id)?.ok_or_else(|| not_found(id))?;
+                job.redefine(new);
+                save_job(tx, &job)?;
+                Ok(job)
+            })
+            .await
+    }
+
+    async fn update_jobs(&self, updates: Vec<(Uuid, JobUpdate)>) -> Result<Vec<Result<Job>>> {
+        self.db
+            .transaction(move |tx| {
+                Ok(updates
+                    .into_iter()
+                    .map(|(id, update)| update_one(tx, id, update))
+                    .collect())
+            })
+            .await
+    }
+
+    async fn delete_jobs(&self, ids: &[Uuid]) -> Result<Vec<Result<()>>> {
+        let ids = ids.to_vec();
+        self.db
+            .transaction(move |tx| Ok(ids.into_iter().map(|id| delete_one(tx, id)).collect()))
+            .await
+    }
+}
+
+fn status_name(status: &RunStatus) -> String {
+    format!("{:?}", status)
+}
+
+fn save_run(tx: &Transaction, run: &JobRun) -> Result<()> {
+    let data = serde_json::to_string(run).context("Failed to serialize run metadata")?;
+    tx.execute(
+        "INSERT INTO runs (run_id, job_id, status, started_at, finished_at, exit_code,
+                           trigger_source, error, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT (run_id) DO UPDATE SET
+             job_id = excluded.job_id, status = excluded.status,
+             started_at = excluded.started_at, finished_at = excluded.finished_at,
+             exit_code = excluded.exit_code, trigger_source = excluded.trigger_source,
+             error = excluded.error, data = excluded.data",
+        params![
+            run.run_id.to_string(),
+            run.job_id.to_string(),
+            status_name(&run.status),
+            run.started_at.timestamp_micros(),
+            run.finished_at.map(|t| t.timestamp_micros()),
+            run.exit_code,
+            run.trigger_source().as_str(),
+            run.error,
+            data
+        ],
+    )?;
+    Ok(())
+}
+
+/// Run records kept in the `runs` table, indexed by job, status and start
+/// time. Output stays in the per-run log files of `FsLogStore`.
+pub struct SqliteLogStore {
+    db: Database,
+    output: FsLogStore,
+}
+
+impl SqliteLogStore {
+    /// Create a store keeping run records in `db` and output under
+    /// `data_dir/logs/`.
+    pub async fn new(db: Database, data_dir: PathBuf) -> Result<Self> {
+        Ok(Self {
+            db,
+            output: FsLogStore::for_output(data_dir).await?,
+        })
+    }
+
+    /// Compress the log of each run once it finishes.
+    pub fn with_compression(mut self, compression: LogCompression) -> Self {
+        self.output = self.output.with_compression(compression);
+        self
+    }
+
+    /// Delete the records of runs whose job is not in `job_ids`, returning
+    /// how many there were. Their log files are left to
+    /// `cleanup_orphaned_logs`.
+    pub async fn remove_orphaned_runs(&self, job_ids: HashSet<Uuid>) -> Result<usize> {
+        self.db
+            .transaction(move |tx| {
+                let mut stmt = tx.prepare("SELECT DISTINCT job_id FROM runs")?;
+                let orphans: Vec<String> = stmt
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<_>>()?;
+                let mut removed = 0;
+                for job_id in orphans {
+                    if Uuid::parse_str(&job_id).is_ok_and(|id| job_ids.contains(&id)) {
+                        continue;
+                    }
+                    removed += tx.execute("DELETE FROM runs WHERE job_id = ?1", [job_id])?;
+                }
+                Ok(removed)
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl LogStore for SqliteLogStore {
+    async fn create_run(&self, run: &JobRun) -> Result<()> {
+        let run = run.clone();
+        self.db.transaction(move |tx| save_run(tx, &run)).await
+    }
+
+    async fn update_run(&self, run: &JobRun) -> Result<()> {
+        let saved = run.clone();
+        self.db.transaction(move |tx| save_run(tx, &saved)).await
+    }
+
+    async fn append_log(&self, job_id: Uuid, run_id: Uuid, data: &[u8]) -> Result<()> {
+        self.output.append_log(job_id, run_id, data).await
+    }
+
+    async fn read_log(&self, job_id: Uuid, run_id: Uuid, tail: Option<usize>) -> Result<String> {
+        self.output.read_log(job_id, run_id, tail).await
+    }
+
+    async fn append_output(
+        &self,
+        job_id: Uuid,
+        run_id: Uuid,
+        stream: OutputStream,
+        data: &[u8],
+    ) -> Result<()> {
+        self.output
+            .append_output(job_id, run_id, stream, data)
+            .await
+    }
+
+    async fn read_output(
+        &self,
+        job_id: Uuid,
+        run_id: Uuid,
+        stream: OutputStream,
+        tail: Option<usize>,
+    ) -> Result<String> {
+        self.output.read_output(job_id, run_id, stream, tail).await
+    }
+
+    async fn list_runs(
+        &self,
+        job_id: Uuid,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<JobRun>, usize)> {
+        let query = RunQuery {
+            job_id: Some(job_id),
+            ..Default::default()
+        };
+        self.query_runs(&query, limit, offset).await
+    }
+
+    async fn find_run(&self, run_id: Uuid) -> Result<Option<JobRun>> {
+        self.db
+            .transaction(move |tx| {
+                let data: Option<String> = tx
+                    .query_row(
+                        "SELECT data FROM runs WHERE run_id = ?1",
+                        [run_id.to_string()],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                data.map(|d| serde_json::from_str(&d).context("Failed to parse run metadata"))
+                    .transpose()
+            })
+            .await
+    }
+
+    async fn query_runs(
+        &self,
+        query: &RunQuery,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<JobRun>, usize)> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        if let Some(job_id) = query.job_id {
+            conditions.push("job_id = ?".to_string());
+            values.push(job_id.to_string().into());
+        }
+        if let Some(job_ids) = &query.job_ids {
+            conditions.push(format!(
+                "job_id IN ({})",
+                vec!["?"; job_ids.len()].join(", ")
+            ));
+            values.extend(job_ids.iter().map(|id| id.to_string().into()));
+        }
+        if let Some(status) = &query.status {
+            conditions.push("status = ?".to_string());
+            values.push(status_name(status).into());
+        }
+        if let Some(exit_code) = query.exit_code {
+            conditions.push("exit_code = ?".to_string());
+            values.push(exit_code.into());
+        }
+        if let Some(trigger) = query.trigger {
+            conditions.push("trigger_source = ?".to_string());
+            values.push(trigger.as_str().to_string().into());
+        }
+        let ranges = [
+            ("started_at >= ?", query.started_after),
+            ("started_at < ?", query.started_before),
+            ("finished_at >= ?", query.finished_after),
+            ("finished_at < ?", query.finished_before),
+        ];
+        for (condition, time) in ranges {
+            if let Some(time) = time {
+                conditions.push(condition.to_string());
+                values.push(time.timestamp_micros().into());
+            }
+        }
+        if let Some(text) = &query.error_contains {
+            conditions.push("instr(lower(error), lower(?)) > 0".to_string());
+            values.push(text.clone().into());
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let mut page_values = values.clone();
+        let page_filter = match query.before {
+            Some(cursor) => {
+                let micros = cursor.started_at.timestamp_micros();
+                page_values.extend([
+                    micros.into(),
+                    micros.into(),
+                    cursor.run_id.to_string().into(),
+                ]);
+                let after_cursor = "(started_at < ? OR (started_at = ? AND run_id < ?))";
+                if conditions.is_empty() {
+                    format!("WHERE {}", after_cursor)
+                } else {
+                    format!("{} AND {}", filter, after_cursor)
+                }
+            }
+            None => filter.clone(),
+        };
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let offset = i64::try_from(offset).unwrap_or(i64::MAX);
+
+        self.db
+            .transaction(move |tx| {
+                let total: i64 = tx.query_row(
+                    &format!("SELECT COUNT(*) FROM runs {}", filter),
+                    params_from_iter(values.iter()),
+                    |row| row.get(0),
+                )?;
+
+                let mut stmt = tx.prepare(&format!(
+                    "SELECT data FROM runs {} ORDER BY started_at DESC, run_id DESC
+                     LIMIT {} OFFSET {}",
+                    page_filter, limit, offset
+                ))?;
+                let rows = stmt.query_map(params_from_iter(page_values.iter()), |row| {
+                    row.get::<_, String>(0)
+                })?;
+                let mut runs = Vec::new();
+                for data in rows {
+                    runs.push(
+                        serde_json::from_str(&data?).context("Failed to parse run metadata")?,
+                    );
+                }
+                Ok((runs, total as usize))
+            })
+            .await
+    }
+
+    async fn cleanup(&self, job_id: Uuid, max_files: usize) -> Result<()> {
+        let keep = i64::try_from(max_files).unwrap_or(i64::MAX);
+        let removed = self
+            .db
+            .transaction(move |tx| {
+                let mut stmt = tx.prepare(
+                    "SELECT run_id FROM runs WHERE job_id = ?1
+                     ORDER BY started_at DESC, run_id DESC LIMIT -1 OFFSET ?2",
+                )?;
+                let run_ids: Vec<String> = stmt
+                    .query_map(params![job_id.to_string(), keep], |row| row.get(0))?
+                    .collect::<rusqlite::Result<_>>()?;
+                for run_id in &run_ids {
+                    tx.execute("DELETE FROM runs WHERE run_id = ?1", [run_id])?;
+                }
+                Ok(run_ids)
+            })
+            .await?;
+
+        for run_id in removed {
+            if let Ok(run_id) = Uuid::parse_str(&run_id) {
+                self.output.remove_run_files(job_id, run_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_runs(&self, job_id: Uuid, run_ids: &[Uuid]) -> Result<()> {
+        let ids: Vec<String> = run_ids.iter().map(Uuid::to_string).collect();
+        let job = job_id.to_string();
+        self.db
+            .transaction(move |tx| {
+                for run_id in &ids {
+                    tx.execute(
+                        "DELETE FROM runs WHERE run_id = ?1 AND job_id = ?2",
+                        params![run_id, job],
+                    )?;
+                }
+                Ok(())
+            })
+            .await?;
+
+        for run_id in run_ids {
+            self.output.remove_run_files(job_id, *run_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn forget_job(&self, job_id: Uuid) -> Result<()> {
+        let job = job_id.to_string();
+        self.db
+            .transaction(move |tx| {
+                tx.execute("DELETE FROM runs WHERE job_id = ?1", [job])?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn run_disk_usage(&self, run: &JobRun) -> Result<u64> {
+        self.output.run_files_size(run.job_id, run.run_id).await
+    }
+
+    async fn compress_finished(&self, run: &JobRun) {
+        self.output.compress_finished(run).await;
+    }
+
+    async fn compress_logs(&self, compression: LogCompression) -> Result<CompressedLogs> {
+        let (runs, _) = self.query_runs(&RunQuery::default(), usize::MAX, 0).await?;
+        self.output.compress_run_logs(&runs, compression).await
+    }
+}
+
+/// Import `jobs.json` and the `.meta.json` run records under `logs/` into
+/// `db`, once. Returns the number of jobs and runs imported, or `None` if
+/// an earlier start already did. `jobs.json` is renamed to
+/// `jobs.json.imported` so it is not mistaken for the live job list; the
+/// run records and log files stay where they are.
+pub async fn import_files(db: &Database, data_dir: &Path) -> Result<Option<(usize, usize)>> {
+    let imported = db
+        .transaction(|tx| {
+            Ok(tx
+                .query_row("SELECT 1 FROM meta WHERE key = ?1", [IMPORTED_KEY], |_| {
+                    Ok(())
+                })
+                .optional()?
+                .is_some())
+        })
+        .await?;
+    if imported {
+        return Ok(None);
+    }
+
+    let jobs_path = data_dir.join("jobs.json");
+    let jobs = if jobs_path.exists() {
+        JsonJobStore::new(data_dir.to_path_buf())
+            .await?
+            .list_jobs()
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    let logs = FsLogStore::for_output(data_dir.to_path_buf()).await?;
+    let mut runs = Vec::new();
+    for job_id in logs.job_ids().await? {
+        runs.extend(logs.read_runs(job_id).await?);
+    }
+
+    let counts = (jobs.len(), runs.len());
+    db.transaction(move |tx| {
+        for job in &jobs {
+            save_job(tx, job)?;
+        }
+        for run in &runs {
+            save_run(tx, run)?;
+        }
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)",
+            params![IMPORTED_KEY, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    })
+    .await?;
+
+    if jobs_path.exists() {
+        tokio::fs::rename(&jobs_path, data_dir.join("jobs.json.imported"))
+            .await
+            .context("Failed to rename imported jobs.json")?;
+    }
+
+    Ok(Some(counts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TriggerSource;
+    use crate::storage::RunCursor;
+    use chrono::{Duration, Utc};
+    use tempfile::TempDir;
+
+    fn new_job_named(name: &str) -> NewJob {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "schedule": "*/5 * * * *",
+            "execution": {"type": "ShellCommand", "value": "echo hello"}
+        }))
+        .unwrap()
+    }
+
+    fn make_run(job_id: Uuid, status: RunStatus, minutes_ago: i64) -> JobRun {
+        JobRun {
+            run_id: Uuid::now_v7(),
+            job_id,
+            started_at: Utc::now() - Duration::minutes(minutes_ago),
+            finished_at: None,
+            status,
+            exit_code: None,
+            log_size_bytes: 0,
+            error: None,
+            trigger_params: None,
+            signal: None,
+            attempt: 1,
+            parent_run_id: None,
+            scheduled_for: None,
+            catch_up: false,
+            trigger: None,
+        }
+    }
+
+    fn open(tmp: &TempDir) -> Database {
+        Database::open(&tmp.path().join("acs.db")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_job_store_crud() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteJobStore::new(open(&tmp));
+
+        let a = store.create_job(new_job_named("a")).await.unwrap();
+        let b = store.create_job(new_job_named("b")).await.unwrap();
+        let err = store.create_job(new_job_named("a")).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AcsError>(),
+            Some(AcsError::Conflict(_))
+        ));
+
+        let update = JobUpdate {
+            name: Some("b".to_string()),
+            ..Default::default()
+        };
+        assert!(store.update_job(a.id, update).await.is_err());
+        let update = JobUpdate {
+            enabled: Some(false),
+            ..Default::default()
+        };
+        store.update_job(a.id, update).await.unwrap();
+
+        let mut replacement = new_job_named("a2");
+        replacement.timeout_secs = 30;
+        store.replace_job(a.id, replacement).await.unwrap();
+
+        let jobs = store.list_jobs().await.unwrap();
+        assert_eq!(
+            jobs.iter().map(|j| j.name.as_str()).collect::<Vec<_>>(),
+            ["a2", "b"],
+            "jobs keep their creation order"
+        );
+        assert_eq!(jobs[0].id, a.id);
+        assert_eq!(jobs[0].timeout_secs, 30);
+        assert!(jobs[0].enabled, "replace_job resets the definition");
+
+        store.delete_job(b.id).await.unwrap();
+        assert!(store.find_by_name("b").await.unwrap().is_none());
+        assert!(store.delete_job(b.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_runs_and_cleanup() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteLogStore::new(open(&tmp), tmp.path().to_path_buf())
+            .await
+            .unwrap();
+        let (job_a, job_b) = (Uuid::now_v7(), Uuid::now_v7());
+        let old = make_run(job_a, RunStatus::Failed, 60);
+        let mut recent = make_run(job_a, RunStatus::Running, 5);
+        let other = make_run(job_b, RunStatus::Failed, 10);
+        for run in [&old, &recent, &other] {
+            store.create_run(run).await.unwrap();
+        }
+        recent.status = RunStatus::Completed;
+        store.update_run(&recent).await.unwrap();
+        store
+            .append_log(job_a, old.run_id, b"old output\n")
+            .await
+            .unwrap();
+
+        let (runs, total) = store.list_runs(job_a, 10, 0).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(runs, [recent.clone(), old.clone()]);
+
+        let failed = RunQuery {
+            status: Some(RunStatus::Failed),
+            ..Default::default()
+        };
+        let (runs, total) = store.query_runs(&failed, 1, 0).await.unwrap();
+        assert_eq!((runs, total), (vec![other.clone()], 2));
+
+        let last_half_hour = RunQuery {
+            started_after: Some(Utc::now() - Duration::minutes(30)),
+            ..Default::default()
+        };
+        let (runs, _) = store.query_runs(&last_half_hour, 10, 0).await.unwrap();
+        assert_eq!(runs, [recent.clone(), other.clone()]);
+
+        assert_eq!(store.find_run(old.run_id).await.unwrap(), Some(old.clone()));
+        assert_eq!(store.run_disk_usage(&old).await.unwrap(), 11);
+        store.cleanup(job_a, 1).await.unwrap();
+        assert!(store.find_run(old.run_id).await.unwrap().is_none());
+        let (runs, _) = store.list_runs(job_a, 10, 0).await.unwrap();
+        assert_eq!(runs, [recent.clone()]);
+        assert_eq!(store.read_log(job_a, old.run_id, None).await.unwrap(), "");
+        assert_eq!(store.run_disk_usage(&old).await.unwrap(), 0);
+
+        store.remove_runs(job_a, &[recent.run_id]).await.unwrap();
+        assert!(store.find_run(recent.run_id).await.unwrap().is_none());
+
+        let removed = store
+            .remove_orphaned_runs(HashSet::from([job_a]))
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_compresses_finished_run_logs() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteLogStore::new(open(&tmp), tmp.path().to_path_buf())
+            .await
+            .unwrap()
+            .with_compression(LogCompression::Zstd);
+        let job_id = Uuid::now_v7();
+        let mut run = make_run(job_id, RunStatus::Running, 5);
+        let queued = make_run(job_id, RunStatus::Queued, 1);
+        for run in [&run, &queued] {
+            store.create_run(run).await.unwrap();
+            store
+                .append_log(job_id, run.run_id, b"output\n")
+                .await
+                .unwrap();
+        }
+
+        run.status = RunStatus::Completed;
+        store.update_run(&run).await.unwrap();
+        store.compress_finished(&run).await;
+        let job_dir = tmp.path().join("logs").join(job_id.to_string());
+        assert!(job_dir.join(format!("{}.log.zst", run.run_id)).exists());
+        assert_eq!(
+            store.read_log(job_id, run.run_id, None).await.unwrap(),
+            "output\n"
+        );
+
+        // Queued and running runs are never compressed.
+        let compressed = store.compress_logs(LogCompression::Gzip).await.unwrap();
+        assert_eq!(compressed, CompressedLogs::default());
+        assert!(job_dir.join(format!("{}.log", queued.run_id)).exists());
+    }
+
+    #[tokio::test]
+    async fn test_query_runs_filters_and_cursor() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteLogStore::new(open(&tmp), tmp.path().to_path_buf())
+            .await
+            .unwrap();
+        let (job_a, job_b) = (Uuid::now_v7(), Uuid::now_v7());
+        let mut runs = Vec::new();
+        for (job_id, exit_code, minutes_ago) in [(job_a, 1, 30), (job_a, 0, 20), (job_b, 1, 10)] {
+            let mut run = make_run(job_id, RunStatus::Completed, minutes_ago);
+            run.exit_code = Some(exit_code);
+            run.finished_at = Some(run.started_at + Duration::minutes(1));
+            run.scheduled_for = Some(run.started_at);
+            store.create_run(&run).await.unwrap();
+            runs.push(run);
+        }
+        let mut failed = make_run(job_b, RunStatus::Failed, 5);
+        failed.error = Some("PTY spawn failed".to_string());
+        failed.trigger = Some(TriggerSource::Upstream);
+        store.create_run(&failed).await.unwrap();
+
+        let query = RunQuery {
+            job_ids: Some(vec![job_a, job_b]),
+            exit_code: Some(1),
+            trigger: Some(TriggerSource::Schedule),
+            ..Default::default()
+        };
+        let (page, total) = store.query_runs(&query, 1, 0).await.unwrap();
+        assert_eq!((page.clone(), total), (vec![runs[2].clone()], 2));
+        let next = RunQuery {
+            before: Some(RunCursor::after(&page[0])),
+            ..query
+        };
+        let (page, total) = store.query_runs(&next, 1, 0).await.unwrap();
+        assert_eq!((page, total), (vec![runs[0].clone()], 2));
+
+        let query = RunQuery {
+            finished_before: Some(runs[1].finished_at.unwrap()),
+            ..Default::default()
+        };
+        let (page, _) = store.query_runs(&query, 10, 0).await.unwrap();
+        assert_eq!(page, [runs[0].clone()]);
+
+        let query = RunQuery {
+            error_contains: Some("SPAWN".to_string()),
+            trigger: Some(TriggerSource::Upstream),
+            ..Default::default()
+        };
+        let (page, _) = store.query_runs(&query, 10, 0).await.unwrap();
+        assert_eq!(page, [failed]);
+    }
+
+    #[tokio::test]
+    async fn test_open_adds_run_filter_columns() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("acs.db");
+        let mut run = make_run(Uuid::now_v7(), RunStatus::Completed, 5);
+        run.exit_code = Some(3);
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE runs (
+                     run_id TEXT PRIMARY KEY,
+                     job_id TEXT NOT NULL,
+                     status TEXT NOT NULL,
+                     started_at INTEGER NOT NULL,
+                     data TEXT NOT NULL
+                 );",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO runs VALUES (?1, ?2, 'Completed', ?3, ?4)",
+                params![
+                    run.run_id.to_string(),
+                    run.job_id.to_string(),
+                    run.started_at.timestamp_micros(),
+                    serde_json::to_string(&run).unwrap()
+                ],
+            )
+            .unwrap();
+        }
+
+        let store = SqliteLogStore::new(Database::open(&path).unwrap(), tmp.path().to_path_buf())
+            .await
+            .unwrap();
+        let query = RunQuery {
+            exit_code: Some(3),
+            trigger: Some(TriggerSource::Manual),
+            ..Default::default()
+        };
+        let (runs, _) = store.query_runs(&query, 10, 0).await.unwrap();
+        assert_eq!(runs, [run]);
+    }
+
+    #[tokio::test]
+    async fn test_import_files_runs_once() {
+        let tmp = TempDir::new().unwrap();
+        let json_jobs = JsonJobStore::new(tmp.path().to_path_buf()).await.unwrap();
+        let job = json_jobs.create_job(new_job_named("legacy")).await.unwrap();
+        let fs_logs = FsLogStore::new(tmp.path().to_path_buf()).await.unwrap();
+        let run = make_run(job.id, RunStatus::Completed, 1);
+        fs_logs.create_run(&run).await.unwrap();
+        fs_logs
+            .append_log(job.id, run.run_id, b"hi\n")
+            .await
+            .unwrap();
+
+        let db = open(&tmp);
+        assert_eq!(import_files(&db, tmp.path()).await.unwrap(), Some((1, 1)));
+        assert!(!tmp.path().join("jobs.json").exists());
+        assert!(tmp.path().join("jobs.json.imported").exists());
+        assert_eq!(import_files(&db, tmp.path()).await.unwrap(), None);
+
+        let jobs = SqliteJobStore::new(db.clone());
+        assert_eq!(jobs.get_job(job.id).await.unwrap(), Some(job.clone()));
+        let logs = SqliteLogStore::new(db, tmp.path().to_path_buf())
+            .await
+            .unwrap();
+        let (runs, _) = logs.list_runs(job.id, 10, 0).await.unwrap();
+        assert_eq!(runs.len(), 1, "run records are imported");
+        assert_eq!(runs[0], run);
+        assert_eq!(
+            logs.read_log(job.id, run.run_id, None).await.unwrap(),
+            "hi\n"
+        );
+    }
+}
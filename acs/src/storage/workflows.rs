@@ -0,0 +1,437 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::errors::AcsError;
+use crate::models::workflow::{validate_graph, validate_new_workflow, validate_workflow_update};
+use crate::models::{NewWorkflow, Workflow, WorkflowRun, WorkflowUpdate};
+use crate::storage::WorkflowStore;
+
+/// Workflows kept in `workflows.json`, and their runs as one file each under
+/// `workflow-runs/{workflow_id}/{run_id}.json`.
+pub struct JsonWorkflowStore {
+    file_path: PathBuf,
+    runs_dir: PathBuf,
+    cache: RwLock<Vec<Workflow>>,
+}
+
+impl JsonWorkflowStore {
+    /// Create a new JsonWorkflowStore, loading existing data from disk if
+    /// present.
+    ///
+    /// If `workflows.json` is corrupted, creates a backup at
+    /// `workflows.json.bak`, logs a warning, and starts with no workflows.
+    pub async fn new(data_dir: PathBuf) -> Result<Self> {
+        let runs_dir = data_dir.join("workflow-runs");
+        tokio::fs::create_dir_all(&runs_dir)
+            .await
+            .context("Failed to create workflow runs directory")?;
+
+        let file_path = data_dir.join("workflows.json");
+
+        let workflows = if file_path.exists() {
+            let content = tokio::fs::read_to_string(&file_path)
+                .await
+                .context("Failed to read workflows.json")?;
+            match serde_json::from_str::<Vec<Workflow>>(&content) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    tracing::warn!(
+                        "workflows.json is corrupted ({}), creating backup and starting empty",
+                        e
+                    );
+                    let backup_path = data_dir.join("workflows.json.bak");
+                    if let Err(backup_err) = tokio::fs::copy(&file_path, &backup_path).await {
+                        tracing::error!(
+                            "Failed to create backup of corrupted workflows.json: {}",
+                            backup_err
+                        );
+                    }
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            file_path,
+            runs_dir,
+            cache: RwLock::new(workflows),
+        })
+    }
+
+    /// Atomically write the workflows cache to disk.
+    async fn persist(&self, workflows: &[Workflow]) -> Result<()> {
+        let tmp_path = self.file_path.with_extension("json.tmp");
+
+        let json =
+            serde_json::to_string_pretty(workflows).context("Failed to serialize workflows")?;
+
+        tokio::fs::write(&tmp_path, json.as_bytes())
+            .await
+            .context("Failed to write temporary workflows file")?;
+
+        tokio::fs::rename(&tmp_path, &self.file_path)
+            .await
+            .context("Failed to rename temporary workflows file")?;
+
+        Ok(())
+    }
+
+    fn workflow_runs_dir(&self, workflow_id: Uuid) -> PathBuf {
+        self.runs_dir.join(workflow_id.to_string())
+    }
+
+    /// All runs of a workflow, newest first.
+    async fn read_runs(&self, workflow_id: Uuid) -> Result<Vec<WorkflowRun>> {
+        let dir = self.workflow_runs_dir(workflow_id);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut runs = Vec::new();
+        let mut entries = tokio::fs::read_dir(&dir)
+            .await
+            .context("Failed to read workflow runs directory")?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let content = tokio::fs::read_to_string(&path)
+                .await
+                .context("Failed to read workflow run")?;
+            match serde_json::from_str::<WorkflowRun>(&content) {
+                Ok(run) => runs.push(run),
+                Err(e) => {
+                    tracing::warn!("Skipping malformed workflow run {:?}: {}", path, e);
+                }
+            }
+        }
+        runs.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+        Ok(runs)
+    }
+}
+
+#[async_trait]
+impl WorkflowStore for JsonWorkflowStore {
+    async fn list_workflows(&self) -> Result<Vec<Workflow>> {
+        Ok(self.cache.read().await.clone())
+    }
+
+    async fn get_workflow(&self, id: Uuid) -> Result<Option<Workflow>> {
+        let cache = self.cache.read().await;
+        Ok(cache.iter().find(|w| w.id == id).cloned())
+    }
+
+    async fn find_workflow_by_name(&self, name: &str) -> Result<Option<Workflow>> {
+        let cache = self.cache.read().await;
+        Ok(cache.iter().find(|w| w.name == name).cloned())
+    }
+
+    async fn create_workflow(&self, new: NewWorkflow) -> Result<Workflow> {
+        validate_new_workflow(&new)?;
+
+        let mut cache = self.cache.write().await;
+        if cache.iter().any(|w| w.name == new.name) {
+            return Err(AcsError::Conflict(format!(
+                "A workflow with name '{}' already exists",
+                new.name
+            ))
+            .into());
+        }
+
+        let now = Utc::now();
+        let workflow = Workflow {
+            id: Uuid::now_v7(),
+            name: new.name,
+            schedule: new.schedule,
+            enabled: new.enabled,
+            nodes: new.nodes,
+            edges: new.edges,
+            created_at: now,
+            updated_at: now,
+            next_run_at: None,
+        };
+
+        cache.push(workflow.clone());
+        self.persist(&cache).await?;
+
+        Ok(workflow)
+    }
+
+    async fn update_workflow(&self, id: Uuid, update: WorkflowUpdate) -> Result<Workflow> {
+        validate_workflow_update(&update)?;
+
+        let mut cache = self.cache.write().await;
+        let idx = cache
+            .iter()
+            .position(|w| w.id == id)
+            .ok_or_else(|| AcsError::NotFound(format!("Workflow with id '{}' not found", id)))?;
+
+        if let Some(ref new_name) = update.name {
+            if cache.iter().any(|w| w.name == *new_name && w.id != id) {
+                return Err(AcsError::Conflict(format!(
+                    "A workflow with name '{}' already exists",
+                    new_name
+                ))
+                .into());
+            }
+        }
+
+        let mut workflow = cache[idx].clone();
+        if let Some(name) = update.name {
+            workflow.name = name;
+        }
+        if let Some(schedule) = update.schedule {
+            workflow.schedule = schedule;
+        }
+        if let Some(enabled) = update.enabled {
+            workflow.enabled = enabled;
+        }
+        if let Some(nodes) = update.nodes {
+            workflow.nodes = nodes;
+        }
+        if let Some(edges) = update.edges {
+            workflow.edges = edges;
+        }
+        validate_graph(&workflow.nodes, &workflow.edges)?;
+        workflow.updated_at = Utc::now();
+
+        cache[idx] = workflow.clone();
+        self.persist(&cache).await?;
+
+        Ok(workflow)
+    }
+
+    async fn delete_workflow(&self, id: Uuid) -> Result<()> {
+        let mut cache = self.cache.write().await;
+        let idx = cache
+            .iter()
+            .position(|w| w.id == id)
+            .ok_or_else(|| AcsError::NotFound(format!("Workflow with id '{}' not found", id)))?;
+
+        cache.remove(idx);
+        self.persist(&cache).await?;
+
+        let dir = self.workflow_runs_dir(id);
+        if dir.exists() {
+            if let Err(e) = tokio::fs::remove_dir_all(&dir).await {
+                tracing::warn!("Failed to remove runs of workflow {}: {}", id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn save_workflow_run(&self, run: &WorkflowRun) -> Result<()> {
+        let dir = self.workflow_runs_dir(run.workflow_id);
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .context("Failed to create workflow runs directory")?;
+
+        let path = dir.join(format!("{}.json", run.id));
+        let tmp_path = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(run).context("Failed to serialize workflow run")?;
+        tokio::fs::write(&tmp_path, json.as_bytes())
+            .await
+            .context("Failed to write temporary workflow run file")?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .context("Failed to rename temporary workflow run file")?;
+
+        Ok(())
+    }
+
+    async fn get_workflow_run(
+        &self,
+        workflow_id: Uuid,
+        run_id: Uuid,
+    ) -> Result<Option<WorkflowRun>> {
+        let path = self
+            .workflow_runs_dir(workflow_id)
+            .join(format!("{}.json", run_id));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .context("Failed to read workflow run")?;
+        Ok(Some(
+            serde_json::from_str(&content).context("Failed to parse workflow run")?,
+        ))
+    }
+
+    async fn list_workflow_runs(
+        &self,
+        workflow_id: Uuid,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<WorkflowRun>, usize)> {
+        let runs = self.read_runs(workflow_id).await?;
+        let total = runs.len();
+        Ok((runs.into_iter().skip(offset).take(limit).collect(), total))
+    }
+
+    async fn cleanup_workflow_runs(&self, workflow_id: Uuid, max_runs: usize) -> Result<()> {
+        let runs = self.read_runs(workflow_id).await?;
+        let dir = self.workflow_runs_dir(workflow_id);
+        for run in runs.iter().skip(max_runs) {
+            let path = dir.join(format!("{}.json", run.id));
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                tracing::warn!("Failed to remove workflow run {:?}: {}", path, e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::workflow::{EdgeCondition, WorkflowEdge, WorkflowNode};
+    use crate::models::{Schedule, WorkflowRunStatus};
+    use tempfile::TempDir;
+
+    fn make_new_workflow(name: &str) -> NewWorkflow {
+        NewWorkflow {
+            name: name.to_string(),
+            schedule: Schedule::Manual,
+            enabled: true,
+            nodes: vec![
+                WorkflowNode {
+                    name: "build".to_string(),
+                    job: "build-job".to_string(),
+                },
+                WorkflowNode {
+                    name: "deploy".to_string(),
+                    job: "deploy-job".to_string(),
+                },
+            ],
+            edges: vec![WorkflowEdge {
+                from: "build".to_string(),
+                to: "deploy".to_string(),
+                condition: EdgeCondition::OnSuccess,
+            }],
+        }
+    }
+
+    async fn setup_store() -> (JsonWorkflowStore, TempDir) {
+        let tmp_dir = TempDir::new().expect("create temp dir");
+        let store = JsonWorkflowStore::new(tmp_dir.path().to_path_buf())
+            .await
+            .expect("create store");
+        (store, tmp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_workflow_crud_persists() {
+        let (store, tmp) = setup_store().await;
+        let created = store
+            .create_workflow(make_new_workflow("release"))
+            .await
+            .unwrap();
+
+        let update = WorkflowUpdate {
+            enabled: Some(false),
+            edges: Some(vec![]),
+            ..Default::default()
+        };
+        let updated = store.update_workflow(created.id, update).await.unwrap();
+        assert!(!updated.enabled);
+        assert!(updated.edges.is_empty());
+
+        let reloaded = JsonWorkflowStore::new(tmp.path().to_path_buf())
+            .await
+            .unwrap();
+        assert_eq!(
+            reloaded.find_workflow_by_name("release").await.unwrap(),
+            Some(updated)
+        );
+
+        reloaded.delete_workflow(created.id).await.unwrap();
+        assert!(reloaded.get_workflow(created.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_workflow_errors() {
+        let (store, _tmp) = setup_store().await;
+        let created = store
+            .create_workflow(make_new_workflow("release"))
+            .await
+            .unwrap();
+
+        let err = store
+            .create_workflow(make_new_workflow("release"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        // The merged graph is validated: dropping a node an edge uses fails.
+        let update = WorkflowUpdate {
+            nodes: Some(make_new_workflow("x").nodes[..1].to_vec()),
+            ..Default::default()
+        };
+        let err = store.update_workflow(created.id, update).await.unwrap_err();
+        assert!(err.to_string().contains("unknown node 'deploy'"));
+
+        assert!(store.delete_workflow(Uuid::now_v7()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_workflow_runs_listed_newest_first_and_cleaned_up() {
+        let (store, tmp) = setup_store().await;
+        let workflow = store
+            .create_workflow(make_new_workflow("release"))
+            .await
+            .unwrap();
+
+        let start = Utc::now();
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            let mut run = WorkflowRun::new(&workflow, None, start + chrono::Duration::seconds(i));
+            run.status = WorkflowRunStatus::Succeeded;
+            store.save_workflow_run(&run).await.unwrap();
+            ids.push(run.id);
+        }
+
+        let (runs, total) = store.list_workflow_runs(workflow.id, 2, 0).await.unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(runs[0].id, ids[2]);
+        assert_eq!(runs[1].id, ids[1]);
+
+        store.cleanup_workflow_runs(workflow.id, 1).await.unwrap();
+        let (runs, total) = store.list_workflow_runs(workflow.id, 10, 0).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(runs[0].id, ids[2]);
+        assert!(store
+            .get_workflow_run(workflow.id, ids[0])
+            .await
+            .unwrap()
+            .is_none());
+
+        store.delete_workflow(workflow.id).await.unwrap();
+        assert!(!tmp
+            .path()
+            .join("workflow-runs")
+            .join(workflow.id.to_string())
+            .exists());
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_workflows_json_recovers_empty() {
+        let tmp_dir = TempDir::new().unwrap();
+        std::fs::write(tmp_dir.path().join("workflows.json"), "{ not json").unwrap();
+
+        let store = JsonWorkflowStore::new(tmp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        assert!(store.list_workflows().await.unwrap().is_empty());
+        assert!(tmp_dir.path().join("workflows.json.bak").exists());
+    }
+}
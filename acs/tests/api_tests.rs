@@ -8,9 +8,14 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use agent_cron_scheduler::daemon::events::JobEvent;
-use agent_cron_scheduler::models::{DaemonConfig, Job, JobRun, JobUpdate, NewJob};
+use agent_cron_scheduler::daemon::scheduler::{compute_next_run, SystemClock};
+use agent_cron_scheduler::daemon::workflows::WorkflowEngine;
+use agent_cron_scheduler::models::{
+    Calendar, DaemonConfig, Job, JobRun, JobUpdate, NewJob, NewWorkflow, Workflow, WorkflowRun,
+    WorkflowUpdate,
+};
 use agent_cron_scheduler::server::{self, AppState};
-use agent_cron_scheduler::storage::{JobStore, LogStore};
+use agent_cron_scheduler::storage::{CalendarStore, JobStore, LogStore, WorkflowStore};
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -62,19 +67,38 @@ impl JobStore for InMemoryJobStore {
         let job = Job {
             id: Uuid::now_v7(),
             name: new.name,
-            schedule: new.schedule,
+            schedule: new.schedule.with_timezone(new.timezone),
+            jitter_secs: new.jitter_secs,
             execution: new.execution,
             enabled: new.enabled,
-            timezone: new.timezone,
             working_dir: new.working_dir,
             env_vars: new.env_vars,
             timeout_secs: new.timeout_secs,
             log_environment: new.log_environment,
+            concurrency_policy: new.concurrency_policy,
+            retry: new.retry,
+            catchup: new.catchup,
+            include_calendars: new.include_calendars,
+            exclude_calendars: new.exclude_calendars,
+            start_at: new.start_at,
+            end_at: new.end_at,
+            max_runs: new.max_runs,
+            on_success: new.on_success,
+            on_failure: new.on_failure,
+            forward_output_lines: new.forward_output_lines,
+            pool: new.pool,
+            priority: new.priority,
+            tags: new.tags,
+            labels: new.labels,
+            log_retention: Default::default(),
+            scheduled_runs: 0,
             created_at: now,
             updated_at: now,
             last_run_at: None,
             last_exit_code: None,
+            source_file: None,
             next_run_at: None,
+            last_scheduled_at: None,
         };
         jobs.push(job.clone());
         Ok(job)
@@ -89,7 +113,7 @@ impl JobStore for InMemoryJobStore {
             job.name = n;
         }
         if let Some(s) = update.schedule {
-            job.schedule = s;
+            job.schedule = s.replacing(&job.schedule, update.timezone);
         }
         if let Some(x) = update.execution {
             job.execution = x;
@@ -97,9 +121,6 @@ impl JobStore for InMemoryJobStore {
         if let Some(e) = update.enabled {
             job.enabled = e;
         }
-        if let Some(t) = update.timezone {
-            job.timezone = Some(t);
-        }
         if let Some(w) = update.working_dir {
             job.working_dir = Some(w);
         }
@@ -112,6 +133,15 @@ impl JobStore for InMemoryJobStore {
         job.updated_at = Utc::now();
         Ok(job.clone())
     }
+    async fn replace_job(&self, id: Uuid, new: NewJob) -> anyhow::Result<Job> {
+        let mut jobs = self.jobs.write().await;
+        let job = jobs
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| anyhow::anyhow!("not found"))?;
+        job.redefine(new);
+        Ok(job.clone())
+    }
     async fn delete_job(&self, id: Uuid) -> anyhow::Result<()> {
         let mut jobs = self.jobs.write().await;
         let len_before = jobs.len();
@@ -123,6 +153,157 @@ impl JobStore for InMemoryJobStore {
     }
 }
 
+struct InMemoryCalendarStore {
+    calendars: RwLock<Vec<Calendar>>,
+}
+
+#[async_trait]
+impl CalendarStore for InMemoryCalendarStore {
+    async fn list_calendars(&self) -> anyhow::Result<Vec<Calendar>> {
+        Ok(self.calendars.read().await.clone())
+    }
+    async fn get_calendar(&self, name: &str) -> anyhow::Result<Option<Calendar>> {
+        let calendars = self.calendars.read().await;
+        Ok(calendars.iter().find(|c| c.name == name).cloned())
+    }
+    async fn create_calendar(&self, calendar: Calendar) -> anyhow::Result<Calendar> {
+        let mut calendars = self.calendars.write().await;
+        if calendars.iter().any(|c| c.name == calendar.name) {
+            return Err(anyhow::anyhow!("Conflict: already exists"));
+        }
+        calendars.push(calendar.clone());
+        Ok(calendar)
+    }
+    async fn update_calendar(&self, calendar: Calendar) -> anyhow::Result<Calendar> {
+        let mut calendars = self.calendars.write().await;
+        let existing = calendars
+            .iter_mut()
+            .find(|c| c.name == calendar.name)
+            .ok_or_else(|| anyhow::anyhow!("not found"))?;
+        *existing = calendar.clone();
+        Ok(calendar)
+    }
+    async fn delete_calendar(&self, name: &str) -> anyhow::Result<()> {
+        let mut calendars = self.calendars.write().await;
+        let len_before = calendars.len();
+        calendars.retain(|c| c.name != name);
+        if calendars.len() == len_before {
+            return Err(anyhow::anyhow!("not found"));
+        }
+        Ok(())
+    }
+}
+
+struct InMemoryWorkflowStore {
+    workflows: RwLock<Vec<Workflow>>,
+    runs: RwLock<Vec<WorkflowRun>>,
+}
+
+#[async_trait]
+impl WorkflowStore for InMemoryWorkflowStore {
+    async fn list_workflows(&self) -> anyhow::Result<Vec<Workflow>> {
+        Ok(self.workflows.read().await.clone())
+    }
+    async fn get_workflow(&self, id: Uuid) -> anyhow::Result<Option<Workflow>> {
+        let workflows = self.workflows.read().await;
+        Ok(workflows.iter().find(|w| w.id == id).cloned())
+    }
+    async fn find_workflow_by_name(&self, name: &str) -> anyhow::Result<Option<Workflow>> {
+        let workflows = self.workflows.read().await;
+        Ok(workflows.iter().find(|w| w.name == name).cloned())
+    }
+    async fn create_workflow(&self, new: NewWorkflow) -> anyhow::Result<Workflow> {
+        let mut workflows = self.workflows.write().await;
+        if workflows.iter().any(|w| w.name == new.name) {
+            return Err(anyhow::anyhow!("Conflict: already exists"));
+        }
+        let now = Utc::now();
+        let workflow = Workflow {
+            id: Uuid::now_v7(),
+            name: new.name,
+            schedule: new.schedule,
+            enabled: new.enabled,
+            nodes: new.nodes,
+            edges: new.edges,
+            created_at: now,
+            updated_at: now,
+            next_run_at: None,
+        };
+        workflows.push(workflow.clone());
+        Ok(workflow)
+    }
+    async fn update_workflow(&self, id: Uuid, update: WorkflowUpdate) -> anyhow::Result<Workflow> {
+        let mut workflows = self.workflows.write().await;
+        let workflow = workflows
+            .iter_mut()
+            .find(|w| w.id == id)
+            .ok_or_else(|| anyhow::anyhow!("not found"))?;
+        if let Some(name) = update.name {
+            workflow.name = name;
+        }
+        if let Some(schedule) = update.schedule {
+            workflow.schedule = schedule;
+        }
+        if let Some(enabled) = update.enabled {
+            workflow.enabled = enabled;
+        }
+        if let Some(nodes) = update.nodes {
+            workflow.nodes = nodes;
+        }
+        if let Some(edges) = update.edges {
+            workflow.edges = edges;
+        }
+        Ok(workflow.clone())
+    }
+    async fn delete_workflow(&self, id: Uuid) -> anyhow::Result<()> {
+        self.workflows.write().await.retain(|w| w.id != id);
+        self.runs.write().await.retain(|r| r.workflow_id != id);
+        Ok(())
+    }
+    async fn save_workflow_run(&self, run: &WorkflowRun) -> anyhow::Result<()> {
+        let mut runs = self.runs.write().await;
+        runs.retain(|r| r.id != run.id);
+        runs.push(run.clone());
+        Ok(())
+    }
+    async fn get_workflow_run(
+        &self,
+        workflow_id: Uuid,
+        run_id: Uuid,
+    ) -> anyhow::Result<Option<WorkflowRun>> {
+        let runs = self.runs.read().await;
+        Ok(runs
+            .iter()
+            .find(|r| r.workflow_id == workflow_id && r.id == run_id)
+            .cloned())
+    }
+    async fn list_workflow_runs(
+        &self,
+        workflow_id: Uuid,
+        limit: usize,
+        offset: usize,
+    ) -> anyhow::Result<(Vec<WorkflowRun>, usize)> {
+        let mut runs: Vec<WorkflowRun> = self
+            .runs
+            .read()
+            .await
+            .iter()
+            .filter(|r| r.workflow_id == workflow_id)
+            .cloned()
+            .collect();
+        runs.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+        let total = runs.len();
+        Ok((runs.into_iter().skip(offset).take(limit).collect(), total))
+    }
+    async fn cleanup_workflow_runs(
+        &self,
+        _workflow_id: Uuid,
+        _max_runs: usize,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
 struct InMemoryLogStore;
 
 #[async_trait]
@@ -152,9 +333,17 @@ impl LogStore for InMemoryLogStore {
     ) -> anyhow::Result<(Vec<JobRun>, usize)> {
         Ok((vec![], 0))
     }
+    async fn find_run(&self, _run_id: Uuid) -> anyhow::Result<Option<JobRun>> {
+        Ok(None)
+    }
+
     async fn cleanup(&self, _job_id: Uuid, _max_files: usize) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn remove_runs(&self, _job_id: Uuid, _run_ids: &[Uuid]) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -163,9 +352,32 @@ impl LogStore for InMemoryLogStore {
 
 async fn spawn_test_server() -> (String, tokio::task::JoinHandle<()>) {
     let (event_tx, _) = broadcast::channel::<JobEvent>(4096);
+    let job_store: Arc<dyn JobStore> = Arc::new(InMemoryJobStore::new());
+    let log_store: Arc<dyn LogStore> = Arc::new(InMemoryLogStore);
+    let workflow_store: Arc<dyn WorkflowStore> = Arc::new(InMemoryWorkflowStore {
+        workflows: RwLock::new(Vec::new()),
+        runs: RwLock::new(Vec::new()),
+    });
+
+    // Accept workflow dispatches without running anything.
+    let (dispatch_tx, mut dispatch_rx) = tokio::sync::mpsc::channel(64);
+    tokio::spawn(async move { while dispatch_rx.recv().await.is_some() {} });
+    let workflow_engine = Arc::new(WorkflowEngine::new(
+        Arc::clone(&workflow_store),
+        Arc::clone(&job_store),
+        Arc::clone(&log_store),
+        Arc::new(SystemClock),
+        dispatch_tx,
+        10,
+    ));
+
     let state = Arc::new(AppState {
-        job_store: Arc::new(InMemoryJobStore::new()),
-        log_store: Arc::new(InMemoryLogStore),
+        job_store,
+        log_store,
+        calendar_store: Arc::new(InMemoryCalendarStore {
+            calendars: RwLock::new(Vec::new()),
+        }),
+        workflow_store,
         event_tx,
         scheduler_notify: Arc::new(Notify::new()),
         config: Arc::new(DaemonConfig::default()),
@@ -173,6 +385,9 @@ async fn spawn_test_server() -> (String, tokio::task::JoinHandle<()>) {
         active_runs: Arc::new(RwLock::new(HashMap::new())),
         shutdown_tx: None,
         dispatch_tx: None,
+        workflow_engine: Some(workflow_engine),
+        jobs_dir_errors: None,
+        run_queue: Default::default(),
     });
 
     let router = server::create_router(state);
@@ -329,6 +544,37 @@ async fn test_update_job_via_http() {
     assert_eq!(json["name"], "updated-name");
 }
 
+#[tokio::test]
+async fn test_update_schedule_expression_keeps_timezone_via_http() {
+    let (base_url, _handle) = spawn_test_server().await;
+    let client = reqwest::Client::new();
+
+    let mut body = new_job_json("berlin-job");
+    body["timezone"] = serde_json::json!("Europe/Berlin");
+    let created: serde_json::Value = client
+        .post(format!("{}/api/jobs", base_url))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let job_id = created["id"].as_str().unwrap();
+
+    let resp = client
+        .patch(format!("{}/api/jobs/{}", base_url, job_id))
+        .json(&serde_json::json!({"schedule": "0 9 * * *"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let json: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(json["schedule"]["expr"], "0 9 * * *");
+    assert_eq!(json["schedule"]["tz"], "Europe/Berlin");
+}
+
 #[tokio::test]
 async fn test_delete_job_via_http() {
     let (base_url, _handle) = spawn_test_server().await;
@@ -509,3 +755,373 @@ async fn test_sse_connection() {
         Err(e) => assert!(e.is_timeout(), "Expected timeout, got: {}", e),
     }
 }
+
+#[tokio::test]
+async fn test_extended_cron_schedule() {
+    let (base_url, _handle) = spawn_test_server().await;
+    let client = reqwest::Client::new();
+
+    // Seconds field: next_run_at lands on a 30-second boundary.
+    let job = serde_json::json!({
+        "name": "half-minutely",
+        "schedule": "*/30 * * * * *",
+        "execution": {
+            "type": "ShellCommand",
+            "value": "echo"
+        }
+    });
+    let resp = client
+        .post(format!("{}/api/jobs", base_url))
+        .json(&job)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let resp = client
+        .get(format!("{}/api/jobs/half-minutely", base_url))
+        .send()
+        .await
+        .unwrap();
+    let json: serde_json::Value = resp.json().await.unwrap();
+    let next: chrono::DateTime<chrono::Utc> =
+        json["next_run_at"].as_str().unwrap().parse().unwrap();
+    assert_eq!(chrono::Timelike::second(&next) % 30, 0);
+    assert_eq!(chrono::Timelike::nanosecond(&next), 0);
+
+    // Validation errors name the offending field.
+    let bad_job = serde_json::json!({
+        "name": "bad-hour",
+        "schedule": "0 25 * * *",
+        "execution": {
+            "type": "ShellCommand",
+            "value": "echo"
+        }
+    });
+    let resp = client
+        .post(format!("{}/api/jobs", base_url))
+        .json(&bad_job)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let json: serde_json::Value = resp.json().await.unwrap();
+    assert!(json["message"]
+        .as_str()
+        .unwrap()
+        .contains("hour field '25'"));
+}
+
+#[tokio::test]
+async fn test_typed_schedules() {
+    let (base_url, _handle) = spawn_test_server().await;
+    let client = reqwest::Client::new();
+
+    let create = |name: &str, schedule: serde_json::Value| {
+        let job = serde_json::json!({
+            "name": name,
+            "schedule": schedule,
+            "execution": {
+                "type": "ShellCommand",
+                "value": "echo"
+            }
+        });
+        client
+            .post(format!("{}/api/jobs", base_url))
+            .json(&job)
+            .send()
+    };
+
+    // Interval: next_run_at falls on a whole multiple of the interval.
+    let resp = create(
+        "quarter-hourly",
+        serde_json::json!({"type": "Every", "interval": "15m"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 201);
+    let json: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(json["schedule"]["interval"], "15m");
+    let resp = client
+        .get(format!("{}/api/jobs/quarter-hourly", base_url))
+        .send()
+        .await
+        .unwrap();
+    let json: serde_json::Value = resp.json().await.unwrap();
+    let next: chrono::DateTime<chrono::Utc> =
+        json["next_run_at"].as_str().unwrap().parse().unwrap();
+    assert_eq!(next.timestamp() % 900, 0);
+
+    // Manual: never scheduled, so no next_run_at.
+    let resp = create("on-demand", serde_json::json!({"type": "Manual"}))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let resp = client
+        .get(format!("{}/api/jobs/on-demand", base_url))
+        .send()
+        .await
+        .unwrap();
+    let json: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(json["schedule"]["type"], "Manual");
+    assert!(json["next_run_at"].is_null());
+
+    // At: the timestamp is the only run; it must be in the future.
+    let resp = create(
+        "one-off",
+        serde_json::json!({"type": "At", "timestamp": "2099-11-01T09:00:00Z"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 201);
+    let resp = client
+        .get(format!("{}/api/jobs/one-off", base_url))
+        .send()
+        .await
+        .unwrap();
+    let json: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(json["next_run_at"], "2099-11-01T09:00:00Z");
+    let resp = create(
+        "too-late",
+        serde_json::json!({"type": "At", "timestamp": "2001-01-01T00:00:00Z"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_jitter_shifts_next_run_at() {
+    let (base_url, _handle) = spawn_test_server().await;
+    let client = reqwest::Client::new();
+
+    let job = serde_json::json!({
+        "name": "spread-out",
+        "schedule": "H * * * *",
+        "jitter_secs": 59,
+        "execution": {
+            "type": "ShellCommand",
+            "value": "echo"
+        }
+    });
+    let resp = client
+        .post(format!("{}/api/jobs", base_url))
+        .json(&job)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let resp = client
+        .get(format!("{}/api/jobs/spread-out", base_url))
+        .send()
+        .await
+        .unwrap();
+    let json: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(json["jitter_secs"], 59);
+    let job: Job = serde_json::from_value(json.clone()).unwrap();
+    let next: chrono::DateTime<chrono::Utc> =
+        json["next_run_at"].as_str().unwrap().parse().unwrap();
+    // The hashed minute plus the jitter offset, both stable for the job.
+    assert_eq!(
+        chrono::Timelike::second(&next) as u64,
+        job.jitter_offset_secs()
+    );
+    assert_eq!(
+        Some(next),
+        compute_next_run(&job, next - chrono::Duration::seconds(1)).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_calendars_crud_and_job_references() {
+    let (base_url, _handle) = spawn_test_server().await;
+    let client = reqwest::Client::new();
+
+    let calendar = serde_json::json!({
+        "name": "always-closed",
+        "windows": [{"cron": "0 0 * * *", "duration": "1d"}]
+    });
+    let resp = client
+        .post(format!("{}/api/calendars", base_url))
+        .json(&calendar)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let resp = client
+        .post(format!("{}/api/calendars", base_url))
+        .json(&calendar)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+    let resp = client
+        .post(format!("{}/api/calendars", base_url))
+        .json(&serde_json::json!({"name": "bad", "windows": [{"cron": "nope", "duration": "1h"}]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Jobs may only reference calendars that exist.
+    let mut job = new_job_json("gated");
+    job["exclude_calendars"] = serde_json::json!(["missing"]);
+    let resp = client
+        .post(format!("{}/api/jobs", base_url))
+        .json(&job)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    job["exclude_calendars"] = serde_json::json!(["always-closed"]);
+    let resp = client
+        .post(format!("{}/api/jobs", base_url))
+        .json(&job)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+
+    // Every occurrence is excluded, so there is no next run.
+    let resp = client
+        .get(format!("{}/api/jobs/gated", base_url))
+        .send()
+        .await
+        .unwrap();
+    let json: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(
+        json["exclude_calendars"],
+        serde_json::json!(["always-closed"])
+    );
+    assert!(json["next_run_at"].is_null());
+
+    // A referenced calendar cannot be deleted.
+    let resp = client
+        .delete(format!("{}/api/calendars/always-closed", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+
+    let resp = client
+        .get(format!("{}/api/calendars/always-closed", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client
+        .get(format!("{}/api/calendars/nope", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_workflows_crud_trigger_and_runs() {
+    let (base_url, _handle) = spawn_test_server().await;
+    let client = reqwest::Client::new();
+
+    for name in ["extract", "load", "alert"] {
+        let resp = client
+            .post(format!("{}/api/jobs", base_url))
+            .json(&new_job_json(name))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+    }
+
+    let workflow = serde_json::json!({
+        "name": "etl",
+        "schedule": {"type": "Every", "interval": "1h"},
+        "nodes": [
+            {"name": "extract", "job": "extract"},
+            {"name": "load", "job": "load"},
+            {"name": "alert", "job": "alert"}
+        ],
+        "edges": [
+            {"from": "extract", "to": "load"},
+            {"from": "extract", "to": "alert", "condition": "on_failure"},
+            {"from": "load", "to": "alert", "condition": "on_failure"}
+        ]
+    });
+    let resp = client
+        .post(format!("{}/api/workflows", base_url))
+        .json(&workflow)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: serde_json::Value = resp.json().await.unwrap();
+    assert!(created["next_run_at"].is_string());
+    assert_eq!(created["edges"][0]["condition"], "on_success");
+
+    let resp = client
+        .post(format!("{}/api/workflows", base_url))
+        .json(&workflow)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+
+    let resp = client
+        .patch(format!("{}/api/workflows/etl", base_url))
+        .json(&serde_json::json!({"enabled": false}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(updated["enabled"], false);
+    assert!(updated["next_run_at"].is_null());
+
+    // Only the root node starts; the others wait for its outcome.
+    let resp = client
+        .post(format!("{}/api/workflows/etl/trigger", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 202);
+    let run: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(run["status"], "Running");
+    assert_eq!(run["nodes"][0]["status"], "Running");
+    assert!(run["nodes"][0]["run_id"].is_string());
+    assert_eq!(run["nodes"][1]["status"], "Pending");
+
+    let resp = client
+        .get(format!("{}/api/workflows/etl/runs", base_url))
+        .send()
+        .await
+        .unwrap();
+    let runs: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(runs["total"], 1);
+    assert_eq!(runs["runs"][0]["id"], run["id"]);
+
+    let resp = client
+        .get(format!(
+            "{}/api/workflows/{}/runs/{}",
+            base_url,
+            created["id"].as_str().unwrap(),
+            run["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .delete(format!("{}/api/workflows/etl", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client
+        .get(format!("{}/api/workflows/etl", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 use agent_cron_scheduler::daemon::events::JobEvent;
 use agent_cron_scheduler::daemon::executor::Executor;
-use agent_cron_scheduler::models::{DaemonConfig, ExecutionType, Job};
+use agent_cron_scheduler::models::{DaemonConfig, ExecutionType, Job, Schedule};
 use agent_cron_scheduler::pty::MockPtySpawner;
 use agent_cron_scheduler::storage::logs::FsLogStore;
 use agent_cron_scheduler::storage::LogStore;
@@ -22,19 +22,38 @@ fn make_job(name: &str) -> Job {
     Job {
         id: Uuid::now_v7(),
         name: name.to_string(),
-        schedule: "* * * * *".to_string(),
+        schedule: Schedule::cron("* * * * *"),
         execution: ExecutionType::ShellCommand("echo hello".to_string()),
         enabled: true,
-        timezone: None,
+        jitter_secs: 0,
         working_dir: None,
         env_vars: None,
         timeout_secs: 0,
         log_environment: false,
+        concurrency_policy: Default::default(),
+        retry: Default::default(),
+        catchup: Default::default(),
+        include_calendars: Vec::new(),
+        exclude_calendars: Vec::new(),
+        start_at: None,
+        end_at: None,
+        max_runs: None,
+        on_success: Vec::new(),
+        on_failure: Vec::new(),
+        forward_output_lines: None,
+        pool: None,
+        priority: 0,
+        tags: Vec::new(),
+        labels: Default::default(),
+        log_retention: Default::default(),
+        scheduled_runs: 0,
         created_at: now,
         updated_at: now,
         last_run_at: None,
         last_exit_code: None,
+        source_file: None,
         next_run_at: None,
+        last_scheduled_at: None,
     }
 }
 
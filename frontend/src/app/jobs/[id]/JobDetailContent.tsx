@@ -16,7 +16,7 @@ import { LogViewer } from "@/components/logs/LogViewer";
 import { useToast } from "@/components/ui/Toast";
 import { useSSEEvents } from "@/hooks/useSSE";
 import { api } from "@/lib/api";
-import { formatDate, formatBytes } from "@/lib/format";
+import { formatDate, formatBytes, formatSchedule } from "@/lib/format";
 
 export function JobDetailContent() {
   const params = useParams();
@@ -127,7 +127,7 @@ export function JobDetailContent() {
             onBackground="neutral-weak"
             style={{ fontFamily: "var(--font-code)" }}
           >
-            {job.schedule}
+            {formatSchedule(job.schedule)}
           </Text>
         </Column>
         <Row gap="8">
@@ -181,10 +181,10 @@ export function JobDetailContent() {
               {job.execution.value}
             </code>
           </Column>
-          {job.timezone && (
+          {job.schedule.type === "Cron" && job.schedule.tz && (
             <Column gap="2">
               <Text variant="label-default-xs" onBackground="neutral-weak">Timezone</Text>
-              <Text variant="body-default-s">{job.timezone}</Text>
+              <Text variant="body-default-s">{job.schedule.tz}</Text>
             </Column>
           )}
           {job.working_dir && (
@@ -8,7 +8,7 @@ import { JobForm } from "@/components/jobs/JobForm";
 import { Spinner } from "@/components/ui/Spinner";
 import { api } from "@/lib/api";
 import { useToast } from "@/components/ui/Toast";
-import type { NewJob } from "@/lib/types";
+import type { JobUpdate } from "@/lib/types";
 
 export function EditJobContent() {
   const params = useParams();
@@ -17,7 +17,7 @@ export function EditJobContent() {
   const { job, loading, error } = useJob(id);
   const { addToast } = useToast();
 
-  const handleSubmit = async (data: NewJob) => {
+  const handleSubmit = async (data: JobUpdate) => {
     try {
       await api.updateJob(id, data);
       addToast("Job updated successfully", "success");
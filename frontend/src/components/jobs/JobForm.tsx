@@ -7,16 +7,19 @@ import {
   JobDetailsFields, ScheduleFields, RuntimeFields, EnvVarsFields,
 } from "./FormSections";
 import { useJobFormState } from "./useJobFormState";
-import type { Job, NewJob } from "@/lib/types";
+import type { Job, JobUpdate, NewJob } from "@/lib/types";
 
-interface JobFormProps {
-  job?: Job | null;
+// A new job is submitted whole; an existing one as the changes to it.
+type JobFormProps = {
   title: string;
-  onSubmit: (data: NewJob) => Promise<void>;
   submitLabel?: string;
-}
+} & (
+  | { job?: undefined; onSubmit: (data: NewJob) => Promise<void> }
+  | { job: Job; onSubmit: (data: JobUpdate) => Promise<void> }
+);
 
-export function JobForm({ job, title, onSubmit, submitLabel = "Save" }: JobFormProps) {
+export function JobForm(props: JobFormProps) {
+  const { job, title, submitLabel = "Save" } = props;
   const s = useJobFormState(job);
   const [tab, setTab] = useState<"basic" | "advanced">("basic");
 
@@ -25,7 +28,8 @@ export function JobForm({ job, title, onSubmit, submitLabel = "Save" }: JobFormP
     if (!s.validate()) return;
     s.setSubmitting(true);
     try {
-      await onSubmit(s.buildData());
+      if (props.job) await props.onSubmit(s.buildUpdate());
+      else await props.onSubmit(s.buildData());
     } catch {
       // Error handled by caller
     } finally {
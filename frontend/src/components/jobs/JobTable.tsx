@@ -9,7 +9,7 @@ import { Button } from "@/components/ui/Button";
 import { Toggle } from "@/components/ui/Toggle";
 import { Modal } from "@/components/ui/Modal";
 import { EmptyState } from "@/components/ui/EmptyState";
-import { formatDate } from "@/lib/format";
+import { formatDate, formatSchedule } from "@/lib/format";
 
 type SortField = "name" | "last_run_at";
 type SortDir = "asc" | "desc";
@@ -158,7 +158,7 @@ export function JobTable({
                   </Link>
                 </td>
                 <td style={{ fontFamily: "var(--font-code)", fontSize: "var(--font-size-body-xs)" }}>
-                  {job.schedule}
+                  {formatSchedule(job.schedule)}
                 </td>
                 <td>
                   {job.execution.type === "ShellCommand" ? "Shell" : "Script"}
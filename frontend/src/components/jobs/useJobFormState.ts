@@ -2,7 +2,14 @@
 
 import { useState, useEffect } from "react";
 import { validateCron } from "@/lib/cron";
-import type { Job, NewJob } from "@/lib/types";
+import type { Job, JobUpdate, NewJob } from "@/lib/types";
+
+// The form edits cron schedules; other schedule types start out blank and
+// are kept as they are unless the user enters a cron expression.
+const cronExpr = (job?: Job | null) =>
+  job?.schedule.type === "Cron" ? job.schedule.expr : "";
+const cronTz = (job?: Job | null) =>
+  job?.schedule.type === "Cron" ? job.schedule.tz ?? "" : "";
 
 export function useJobFormState(job?: Job | null) {
   const [name, setName] = useState(job?.name ?? "");
@@ -10,8 +17,9 @@ export function useJobFormState(job?: Job | null) {
     job?.execution.type ?? "ShellCommand"
   );
   const [execValue, setExecValue] = useState(job?.execution.value ?? "");
-  const [schedule, setSchedule] = useState(job?.schedule ?? "*/5 * * * *");
-  const [timezone, setTimezone] = useState(job?.timezone ?? "");
+  const [schedule, setSchedule] = useState(job ? cronExpr(job) : "*/5 * * * *");
+  const [timezone, setTimezone] = useState(cronTz(job));
+  const [scheduleEdited, setScheduleEdited] = useState(false);
   const [workingDir, setWorkingDir] = useState(job?.working_dir ?? "");
   const [envVars, setEnvVars] = useState<Record<string, string>>(
     job?.env_vars ?? {}
@@ -30,8 +38,9 @@ export function useJobFormState(job?: Job | null) {
       setName(job.name);
       setExecType(job.execution.type);
       setExecValue(job.execution.value);
-      setSchedule(job.schedule);
-      setTimezone(job.timezone ?? "");
+      setSchedule(cronExpr(job));
+      setTimezone(cronTz(job));
+      setScheduleEdited(false);
       setWorkingDir(job.working_dir ?? "");
       setEnvVars(job.env_vars ?? {});
       setTimeoutSecs(job.timeout_secs);
@@ -40,14 +49,28 @@ export function useJobFormState(job?: Job | null) {
     }
   }, [job]);
 
+  const editSchedule = (value: string) => {
+    setSchedule(value);
+    setScheduleEdited(true);
+  };
+  const editTimezone = (value: string) => {
+    setTimezone(value);
+    setScheduleEdited(true);
+  };
+
+  // An existing job keeps its schedule until the user edits it.
+  const keepsSchedule = !!job && !scheduleEdited;
+
   const validate = (): boolean => {
     setSubmitted(true);
     const newErrors: Record<string, string> = {};
     if (!name.trim()) newErrors.name = "Name is required";
     if (!execValue.trim()) newErrors.execValue = "Command or script path is required";
-    if (!schedule.trim()) newErrors.schedule = "Schedule is required";
-    const cronErr = validateCron(schedule);
-    if (cronErr) newErrors.schedule = cronErr;
+    if (!keepsSchedule) {
+      if (!schedule.trim()) newErrors.schedule = "Schedule is required";
+      const cronErr = validateCron(schedule);
+      if (cronErr) newErrors.schedule = cronErr;
+    }
     if (timeoutSecs < 1) newErrors.timeout = "Timeout must be at least 1 second";
     setErrors(newErrors);
     return Object.keys(newErrors).length === 0;
@@ -59,18 +82,25 @@ export function useJobFormState(job?: Job | null) {
   const buildData = (): NewJob => {
     const data: NewJob = {
       name: name.trim(),
-      schedule: schedule.trim(),
+      schedule: job && keepsSchedule ? job.schedule : schedule.trim(),
       execution: { type: execType, value: execValue.trim() },
       enabled,
       timeout_secs: timeoutSecs,
       log_environment: logEnvironment,
     };
-    if (timezone) data.timezone = timezone;
+    if (timezone && !keepsSchedule) data.timezone = timezone;
     if (workingDir) data.working_dir = workingDir;
     if (Object.keys(envVars).length > 0) data.env_vars = envVars;
     return data;
   };
 
+  // The changes to an existing job; the schedule is only sent if edited.
+  const buildUpdate = (): JobUpdate => {
+    const { schedule: newSchedule, timezone: newTimezone, ...update } = buildData();
+    if (keepsSchedule) return update;
+    return { ...update, schedule: newSchedule, timezone: newTimezone };
+  };
+
   const hasAdvancedFields = !!(
     job?.working_dir ||
     job?.log_environment ||
@@ -82,8 +112,8 @@ export function useJobFormState(job?: Job | null) {
     name, setName,
     execType, setExecType,
     execValue, setExecValue,
-    schedule, setSchedule,
-    timezone, setTimezone,
+    schedule, setSchedule: editSchedule,
+    timezone, setTimezone: editTimezone,
     workingDir, setWorkingDir,
     envVars, setEnvVars,
     timeoutSecs, setTimeoutSecs,
@@ -91,7 +121,7 @@ export function useJobFormState(job?: Job | null) {
     enabled, setEnabled,
     errors: visibleErrors,
     submitting, setSubmitting,
-    validate, buildData,
+    validate, buildData, buildUpdate,
     hasAdvancedFields,
   };
 }
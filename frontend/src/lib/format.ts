@@ -1,3 +1,18 @@
+import type { Schedule } from "@/lib/types";
+
+export function formatSchedule(schedule: Schedule): string {
+  switch (schedule.type) {
+    case "Cron":
+      return schedule.tz ? `${schedule.expr} (${schedule.tz})` : schedule.expr;
+    case "Every":
+      return `every ${schedule.interval}`;
+    case "At":
+      return `once at ${new Date(schedule.timestamp).toLocaleString()}`;
+    case "Manual":
+      return "manual";
+  }
+}
+
 export function formatUptime(seconds: number): string {
   const days = Math.floor(seconds / 86400);
   const hours = Math.floor((seconds % 86400) / 3600);
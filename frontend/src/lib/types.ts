@@ -1,10 +1,15 @@
+export type Schedule =
+  | { type: "Cron"; expr: string; tz?: string }
+  | { type: "Every"; interval: string; anchor?: string }
+  | { type: "At"; timestamp: string }
+  | { type: "Manual" };
+
 export interface Job {
   id: string;
   name: string;
-  schedule: string;
+  schedule: Schedule;
   execution: { type: "ShellCommand" | "ScriptFile"; value: string };
   enabled: boolean;
-  timezone: string | null;
   working_dir: string | null;
   env_vars: Record<string, string> | null;
   timeout_secs: number;
@@ -18,7 +23,7 @@ export interface Job {
 
 export interface NewJob {
   name: string;
-  schedule: string;
+  schedule: Schedule | string;
   execution: { type: "ShellCommand" | "ScriptFile"; value: string };
   enabled?: boolean;
   timezone?: string;
@@ -30,7 +35,7 @@ export interface NewJob {
 
 export interface JobUpdate {
   name?: string;
-  schedule?: string;
+  schedule?: Schedule | string;
   execution?: { type: "ShellCommand" | "ScriptFile"; value: string };
   enabled?: boolean;
   timezone?: string | null;